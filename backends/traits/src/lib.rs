@@ -145,7 +145,7 @@ use async_std::sync::{Arc, RwLock};
 use async_trait::async_trait;
 use std::convert::TryFrom;
 use zenoh::net::Sample;
-use zenoh::{Properties, Selector, Value, ZError, ZResult};
+use zenoh::{Properties, Selector, Timestamp, Value, ZError, ZErrorKind, ZResult};
 
 pub mod utils;
 
@@ -196,6 +196,52 @@ pub trait Storage: Send + Sync {
     /// Function called for each incoming query matching this storage's PathExpression.
     /// This storage should reply with data matching the query calling [`Query::reply()`].
     async fn on_query(&mut self, query: Query) -> ZResult<()>;
+
+    /// Function called for each incoming increment request (see [`zenoh::Workspace::increment()`])
+    /// on a path matching this storage's PathExpression.
+    /// This storage should atomically add `delta` to the integer value stored for `path`
+    /// (`0` if none), store the result with the given `timestamp` and return it.
+    ///
+    /// The default implementation returns an error, meaning the storage doesn't support increments.
+    async fn on_increment(
+        &mut self,
+        path: &str,
+        _delta: i64,
+        _timestamp: Timestamp,
+    ) -> ZResult<i64> {
+        Err(ZError::new(
+            ZErrorKind::Other {
+                descr: format!("Storage doesn't support increment (on {})", path),
+            },
+            file!(),
+            line!(),
+            None,
+        ))
+    }
+
+    /// Function called for each incoming append request (see [`zenoh::Workspace::append()`])
+    /// on a path matching this storage's PathExpression.
+    /// This storage should atomically append `element` (a [`zenoh::Value::Json`]) to the JSON array
+    /// stored for `path` (storing `[element]` if none), store the result with the given `timestamp`
+    /// and return the resulting array. If the stored value is not a JSON array,
+    /// it should be left unchanged and an error returned.
+    ///
+    /// The default implementation returns an error, meaning the storage doesn't support appends.
+    async fn on_append(
+        &mut self,
+        path: &str,
+        _element: Value,
+        _timestamp: Timestamp,
+    ) -> ZResult<Vec<Value>> {
+        Err(ZError::new(
+            ZErrorKind::Other {
+                descr: format!("Storage doesn't support append (on {})", path),
+            },
+            file!(),
+            line!(),
+            None,
+        ))
+    }
 }
 
 /// An interceptor allowing to modify the data pushed into a storage before it's actually stored.
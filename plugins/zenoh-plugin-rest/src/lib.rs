@@ -83,6 +83,11 @@ fn value_to_json(value: Value) -> String {
         Json(s) => s,
         Integer(i) => format!(r#"{}"#, i),
         Float(f) => format!(r#"{}"#, f),
+        Reference { path, encoding } => serde_json::json!({
+            "reference": path.as_str(),
+            "encoding": encoding::to_string(encoding),
+        })
+        .to_string(),
     }
 }
 
@@ -290,7 +295,7 @@ async fn query(req: Request<(Arc<Session>, String)>) -> tide::Result<Response> {
             },
         ))
     } else {
-        let resource = path_to_resource(selector.path_expr.as_str(), &req.state().1);
+        let resource = path_to_resource(selector.path_expr.included(), &req.state().1);
         let consolidation = if selector.has_time_range() {
             QueryConsolidation::none()
         } else {
@@ -16,12 +16,17 @@ use async_trait::async_trait;
 use log::{debug, trace, warn};
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::time::{Duration, Instant};
 use zenoh::net::utils::resource_name;
 use zenoh::net::Sample;
-use zenoh::{utils, ChangeKind, Properties, Timestamp, Value, ZResult};
+use zenoh::{
+    utils, Change, ChangeKind, Path, Properties, Selector, Timestamp, Value, ZError, ZErrorKind,
+    ZResult, PROP_TIME,
+};
 use zenoh_backend_traits::*;
 use zenoh_util::collections::{Timed, TimedEvent, TimedHandle, Timer};
+use zenoh_util::zerror;
 
 pub fn create_backend(_unused: Properties) -> ZResult<Box<dyn Backend>> {
     // For now admin status is static and only contains a PROP_BACKEND_TYPE entry
@@ -233,22 +238,193 @@ impl Storage for MemoryStorage {
 
     async fn on_query(&mut self, query: Query) -> ZResult<()> {
         trace!("on_query for {}", query.res_name());
+        // The memory storage keeps no history: a time-travel query (_at property)
+        // or a history query (_time property) is answered with the latest values,
+        // and a warning is logged if some are more recent than the requested time
+        // or out of the requested range.
+        let selector = Selector::try_from(&query)?;
+        let at = selector.at()?;
+        let time_range = selector.time_range()?;
+        // paging: only the values after the `_after` path, up to `_limit` values in paths order
+        let limit = selector.limit()?;
+        let after = selector.after()?;
+        let mut warned = false;
+        let mut check_at = |ts: &Timestamp| {
+            if let Some(at) = &at {
+                if !warned && ts > at {
+                    warn!(
+                        "Memory storage has no history: replying latest values to query on {} with _at={}",
+                        query.res_name(),
+                        at
+                    );
+                    warned = true;
+                }
+            }
+            if let Some(time_range) = &time_range {
+                if !warned && !time_range.contains(ts) {
+                    warn!(
+                        "Memory storage has no history: replying latest values to query on {} with _time={}",
+                        query.res_name(),
+                        selector.properties.get(PROP_TIME).unwrap()
+                    );
+                    warned = true;
+                }
+            }
+        };
         if !query.res_name().contains('*') {
-            if let Some(Present { sample, ts: _ }) = self.map.read().await.get(query.res_name()) {
+            let in_page = limit != Some(0)
+                && after
+                    .as_ref()
+                    .map_or(true, |after| query.res_name() > after.as_str());
+            if !in_page {
+                return Ok(());
+            }
+            if let Some(Present { sample, ts }) = self.map.read().await.get(query.res_name()) {
+                check_at(ts);
                 query.reply(sample.clone()).await;
             }
         } else {
-            for (_, stored_value) in self.map.read().await.iter() {
-                if let Present { sample, ts: _ } = stored_value {
-                    if resource_name::intersect(query.res_name(), &sample.res_name) {
-                        let s: Sample = sample.clone();
-                        query.reply(s).await;
+            let map = self.map.read().await;
+            let mut matching: Vec<(&Sample, &Timestamp)> = map
+                .values()
+                .filter_map(|stored_value| match stored_value {
+                    Present { sample, ts }
+                        if resource_name::intersect(query.res_name(), &sample.res_name)
+                            && after.as_ref().map_or(true, |after| {
+                                sample.res_name.as_str() > after.as_str()
+                            }) =>
+                    {
+                        Some((sample, ts))
                     }
-                }
+                    _ => None,
+                })
+                .collect();
+            if let Some(limit) = limit {
+                matching.sort_by(|(s1, _), (s2, _)| s1.res_name.cmp(&s2.res_name));
+                matching.truncate(limit);
+            }
+            for (sample, ts) in matching {
+                check_at(ts);
+                let s: Sample = sample.clone();
+                query.reply(s).await;
             }
         }
         Ok(())
     }
+
+    async fn on_increment(&mut self, path: &str, delta: i64, timestamp: Timestamp) -> ZResult<i64> {
+        trace!("on_increment for {} by {}", path, delta);
+        let mut map = self.map.write().await;
+        let current = match map.get(path) {
+            Some(Present { sample, ts }) => {
+                if ts >= &timestamp {
+                    return zerror!(ZErrorKind::Other {
+                        descr: format!("Increment on {} dropped: out-of-date", path)
+                    });
+                }
+                match Value::from_sample(sample, true)? {
+                    Some(Value::Integer(i)) => i,
+                    Some(v) => {
+                        return zerror!(ZErrorKind::Other {
+                            descr: format!(
+                                "Stored value for {} is not an integer (encoding: {})",
+                                path,
+                                v.encoding_descr()
+                            )
+                        })
+                    }
+                    None => 0,
+                }
+            }
+            _ => 0,
+        };
+        let result = match current.checked_add(delta) {
+            Some(result) => result,
+            None => {
+                return zerror!(ZErrorKind::Other {
+                    descr: format!("Increment on {} overflows", path)
+                })
+            }
+        };
+        let sample = Change::new(
+            Path::try_from(path)?,
+            Some(Value::Integer(result)),
+            timestamp,
+            ChangeKind::Put,
+        )
+        .into_sample();
+        if let Some(Removed {
+            ts: _,
+            cleanup_handle,
+        }) = map.insert(
+            path.to_string(),
+            Present {
+                sample,
+                ts: timestamp,
+            },
+        ) {
+            // cancel timed cleanup
+            cleanup_handle.defuse();
+        }
+        Ok(result)
+    }
+
+    async fn on_append(
+        &mut self,
+        path: &str,
+        element: Value,
+        timestamp: Timestamp,
+    ) -> ZResult<Vec<Value>> {
+        trace!("on_append for {}", path);
+        let mut map = self.map.write().await;
+        let mut array = match map.get(path) {
+            Some(Present { sample, ts }) => {
+                if ts >= &timestamp {
+                    return zerror!(ZErrorKind::Other {
+                        descr: format!("Append on {} dropped: out-of-date", path)
+                    });
+                }
+                match Value::from_sample(sample, true)? {
+                    Some(value) => match value.as_array() {
+                        Ok(array) => array,
+                        Err(_) => {
+                            return zerror!(ZErrorKind::Other {
+                                descr: format!(
+                                    "Stored value for {} is not a JSON array (encoding: {})",
+                                    path,
+                                    value.encoding_descr()
+                                )
+                            })
+                        }
+                    },
+                    None => vec![],
+                }
+            }
+            _ => vec![],
+        };
+        array.push(element);
+        let sample = Change::new(
+            Path::try_from(path)?,
+            Some(Value::from(array.clone())),
+            timestamp,
+            ChangeKind::Put,
+        )
+        .into_sample();
+        if let Some(Removed {
+            ts: _,
+            cleanup_handle,
+        }) = map.insert(
+            path.to_string(),
+            Present {
+                sample,
+                ts: timestamp,
+            },
+        ) {
+            // cancel timed cleanup
+            cleanup_handle.defuse();
+        }
+        Ok(array)
+    }
 }
 
 impl Drop for MemoryStorage {
@@ -271,3 +447,58 @@ impl Timed for TimedCleanup {
         self.map.write().await.remove(&self.path);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_std::task;
+
+    #[test]
+    fn increment() {
+        task::block_on(async {
+            let mut storage = MemoryStorage::new(Properties::default()).await.unwrap();
+            let out_of_date = utils::new_reception_timestamp();
+            let counter = "/test/counter";
+
+            // a missing value counts as 0
+            let result = storage
+                .on_increment(counter, 5, utils::new_reception_timestamp())
+                .await;
+            assert_eq!(result.unwrap(), 5);
+            let result = storage
+                .on_increment(counter, -2, utils::new_reception_timestamp())
+                .await;
+            assert_eq!(result.unwrap(), 3);
+
+            // the failed increments leave the value unchanged
+            assert!(storage.on_increment(counter, 1, out_of_date).await.is_err());
+            let result = storage
+                .on_increment(counter, i64::MAX, utils::new_reception_timestamp())
+                .await;
+            assert!(result.is_err());
+            let result = storage
+                .on_increment(counter, 1, utils::new_reception_timestamp())
+                .await;
+            assert_eq!(result.unwrap(), 4);
+
+            // a non-integer value can't be incremented
+            let ts = utils::new_reception_timestamp();
+            let sample = Change::new(
+                Path::try_from("/test/name").unwrap(),
+                Some(Value::StringUtf8("bob".to_string())),
+                ts,
+                ChangeKind::Put,
+            )
+            .into_sample();
+            storage
+                .map
+                .write()
+                .await
+                .insert("/test/name".to_string(), Present { sample, ts });
+            let result = storage
+                .on_increment("/test/name", 1, utils::new_reception_timestamp())
+                .await;
+            assert!(result.is_err());
+        });
+    }
+}
@@ -18,11 +18,18 @@ use futures::select;
 use futures::stream::StreamExt;
 use futures::FutureExt;
 use log::{debug, error, trace, warn};
+use std::collections::HashMap;
+use std::convert::TryFrom;
 use zenoh::net::{
-    queryable, QueryConsolidation, QueryTarget, Reliability, SubInfo, SubMode, Target,
+    data_kind, encoding, queryable, CongestionControl, DataInfo, QueryConsolidation, QueryTarget,
+    Reliability, Sample, SubInfo, SubMode, Target,
+};
+use zenoh::{
+    utils, Change, ChangeKind, DeltaDecoder, Path, PathExpr, Selector, Value, Workspace, ZError,
+    ZErrorKind, ZResult, Zenoh, PROP_INCREMENT,
 };
-use zenoh::{Path, PathExpr, ZResult, Zenoh};
 use zenoh_backend_traits::{IncomingDataInterceptor, OutgoingDataInterceptor, Query};
+use zenoh_util::zerror2;
 
 pub(crate) async fn start_storage(
     mut storage: Box<dyn zenoh_backend_traits::Storage>,
@@ -46,7 +53,7 @@ pub(crate) async fn start_storage(
         };
         let mut storage_sub = match workspace
             .session()
-            .declare_subscriber(&path_expr.to_string().into(), &sub_info)
+            .declare_subscriber(&path_expr.included().into(), &sub_info)
             .await
         {
             Ok(storage_sub) => storage_sub,
@@ -65,7 +72,7 @@ pub(crate) async fn start_storage(
         let mut replies = match workspace
             .session()
             .query(
-                &path_expr.to_string().into(),
+                &path_expr.included().into(),
                 "?(starttime=0)",
                 query_target,
                 QueryConsolidation::none(),
@@ -108,7 +115,7 @@ pub(crate) async fn start_storage(
         // answer to queries on path_expr
         let mut storage_queryable = match workspace
             .session()
-            .declare_queryable(&path_expr.to_string().into(), queryable::STORAGE)
+            .declare_queryable(&path_expr.included().into(), queryable::STORAGE)
             .await
         {
             Ok(storage_queryable) => storage_queryable,
@@ -118,6 +125,8 @@ pub(crate) async fn start_storage(
             }
         };
 
+        // the decoders of the values put by DeltaPublishers, per path
+        let mut delta_decoders: HashMap<String, DeltaDecoder> = HashMap::new();
         loop {
             select!(
                 // on get request on storage_admin
@@ -127,11 +136,16 @@ pub(crate) async fn start_storage(
                 },
                 // on sample for path_expr
                 sample = storage_sub.receiver().next().fuse() => {
+                    // Store the values put by DeltaPublishers rather than their frames
+                    let sample = match delta_decode(&mut delta_decoders, &admin_path, sample.unwrap()) {
+                        Some(sample) => sample,
+                        None => continue,
+                    };
                     // Call incoming data interceptor (if any)
                     let sample = if let Some(ref interceptor) = in_interceptor {
-                        interceptor.read().await.on_sample(sample.unwrap()).await
+                        interceptor.read().await.on_sample(sample).await
                     } else {
-                        sample.unwrap()
+                        sample
                     };
                     // Call storage
                     if let Err(e) = storage.on_sample(sample).await {
@@ -141,11 +155,21 @@ pub(crate) async fn start_storage(
                 // on query on path_expr
                 query = storage_queryable.receiver().next().fuse() => {
                     let q = query.unwrap();
-                    // wrap zenoh::net::Query in zenoh_backend_traits::Query
-                    // with outgoing interceptor
-                    let query = Query::new(q, out_interceptor.clone());
-                    if let Err(e) = storage.on_query(query).await {
-                        warn!("Storage {} raised an error receiving a query: {}", admin_path, e);
+                    let selector = Selector::try_from(&q).ok();
+                    let increment = selector.as_ref().and_then(|s| s.properties.get(PROP_INCREMENT).cloned());
+                    let append = selector.as_ref().and_then(|s| s.append().transpose());
+                    match (increment, append) {
+                        // increment and append requests are applied by the storage itself to be atomic
+                        (Some(delta), _) => on_increment(&mut storage, &workspace, &admin_path, q, &delta).await,
+                        (None, Some(element)) => on_append(&mut storage, &workspace, &admin_path, q, element).await,
+                        (None, None) => {
+                            // wrap zenoh::net::Query in zenoh_backend_traits::Query
+                            // with outgoing interceptor
+                            let query = Query::new(q, out_interceptor.clone());
+                            if let Err(e) = storage.on_query(query).await {
+                                warn!("Storage {} raised an error receiving a query: {}", admin_path, e);
+                            }
+                        }
                     }
                 },
                 // on storage handle drop
@@ -159,3 +183,170 @@ pub(crate) async fn start_storage(
 
     Ok(tx)
 }
+
+// Returns the sample with its value reconstructed if it's a frame of a DeltaPublisher,
+// or None if it must not be stored (a delta missing its previous frame, or a malformed frame).
+fn delta_decode(
+    decoders: &mut HashMap<String, DeltaDecoder>,
+    admin_path: &Path,
+    mut sample: Sample,
+) -> Option<Sample> {
+    let info = match sample.data_info.as_mut() {
+        Some(info) if info.encoding == Some(encoding::APP_DELTA) => info,
+        _ => return Some(sample),
+    };
+    let decoder = decoders.entry(sample.res_name.clone()).or_default();
+    match decoder.decode(sample.payload.clone()) {
+        Ok(Some(value)) => {
+            let (encoding, payload) = value.encode();
+            info.encoding = Some(encoding);
+            sample.payload = payload;
+            Some(sample)
+        }
+        Ok(None) => {
+            trace!(
+                "Storage {} ignores a delta for {} until the next keyframe",
+                admin_path,
+                sample.res_name
+            );
+            None
+        }
+        Err(e) => {
+            warn!(
+                "Storage {} received an invalid delta for {}: {}",
+                admin_path, sample.res_name, e
+            );
+            None
+        }
+    }
+}
+
+async fn on_increment(
+    storage: &mut Box<dyn zenoh_backend_traits::Storage>,
+    workspace: &Workspace<'_>,
+    admin_path: &Path,
+    query: zenoh::net::Query,
+    delta: &str,
+) {
+    let path = match Path::try_from(query.res_name.as_str()) {
+        Ok(path) => path,
+        Err(e) => {
+            warn!(
+                "Storage {} received an invalid increment: {}",
+                admin_path, e
+            );
+            return;
+        }
+    };
+    let result = match delta.parse::<i64>() {
+        Ok(delta) => {
+            let timestamp = workspace
+                .session()
+                .hlc()
+                .map(|hlc| hlc.new_timestamp())
+                .unwrap_or_else(utils::new_reception_timestamp);
+            storage.on_increment(path.as_str(), delta, timestamp).await
+        }
+        Err(e) => Err(zerror2!(
+            ZErrorKind::Other {
+                descr: format!("Invalid increment delta: {}", delta)
+            },
+            e
+        )),
+    };
+    // reply with the new value, or with the error description
+    let value = match result {
+        Ok(i) => {
+            // notify the subscribers and the other storages of the new value
+            if let Err(e) = workspace.put(&path, Value::Integer(i)).await {
+                warn!(
+                    "Storage {} failed to publish increment on {}: {}",
+                    admin_path, path, e
+                );
+            }
+            Value::Integer(i)
+        }
+        Err(e) => {
+            warn!(
+                "Storage {} raised an error receiving an increment: {}",
+                admin_path, e
+            );
+            Value::StringUtf8(e.to_string())
+        }
+    };
+    let change = Change::new(
+        path,
+        Some(value),
+        utils::new_reception_timestamp(),
+        ChangeKind::Put,
+    );
+    query.reply_async(change.into_sample()).await;
+}
+
+async fn on_append(
+    storage: &mut Box<dyn zenoh_backend_traits::Storage>,
+    workspace: &Workspace<'_>,
+    admin_path: &Path,
+    query: zenoh::net::Query,
+    element: ZResult<Value>,
+) {
+    let path = match Path::try_from(query.res_name.as_str()) {
+        Ok(path) => path,
+        Err(e) => {
+            warn!("Storage {} received an invalid append: {}", admin_path, e);
+            return;
+        }
+    };
+    let timestamp = workspace
+        .session()
+        .hlc()
+        .map(|hlc| hlc.new_timestamp())
+        .unwrap_or_else(utils::new_reception_timestamp);
+    let result = match element {
+        Ok(element) => storage.on_append(path.as_str(), element, timestamp).await,
+        Err(e) => Err(e),
+    };
+    // reply with the new length, or with the error description
+    let value = match result {
+        Ok(array) => {
+            // notify the subscribers and the other storages of the whole array,
+            // with the timestamp of the stored one
+            let len = array.len();
+            let (encoding, payload) = Value::from(array).encode();
+            let mut info = DataInfo::new();
+            info.kind = Some(data_kind::PUT);
+            info.encoding = Some(encoding);
+            info.timestamp = Some(timestamp);
+            if let Err(e) = workspace
+                .session()
+                .write_with_info(
+                    &path.as_str().into(),
+                    payload,
+                    info,
+                    CongestionControl::Drop,
+                )
+                .await
+            {
+                warn!(
+                    "Storage {} failed to publish append on {}: {}",
+                    admin_path, path, e
+                );
+            }
+            Value::Integer(len as i64)
+        }
+        Err(e) => {
+            warn!(
+                "Storage {} raised an error receiving an append: {}",
+                admin_path, e
+            );
+            Value::StringUtf8(e.to_string())
+        }
+    };
+    let change = Change::new(
+        path,
+        Some(value),
+        utils::new_reception_timestamp(),
+        ChangeKind::Put,
+    );
+    query.reply_async(change.into_sample()).await;
+}
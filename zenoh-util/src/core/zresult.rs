@@ -41,6 +41,9 @@ pub enum ZErrorKind {
     InvalidSession {
         descr: String,
     },
+    NoRouter {
+        descr: String,
+    },
     InvalidPath {
         path: String,
     },
@@ -57,6 +60,7 @@ pub enum ZErrorKind {
         descr: String,
     },
     Timeout {},
+    Cancelled {},
     UnkownResourceId {
         rid: String,
     },
@@ -66,6 +70,10 @@ pub enum ZErrorKind {
     ValueDecodingFailed {
         descr: String,
     },
+    ValueTooLarge {
+        size: usize,
+        max: usize,
+    },
     TranscodingFailed {
         origin_encoding: String,
         target_encoding: String,
@@ -73,6 +81,12 @@ pub enum ZErrorKind {
     SharedMemoryError {
         descr: String,
     },
+    Quic {
+        descr: String,
+    },
+    StorageError {
+        descr: String,
+    },
 }
 
 impl fmt::Display for ZErrorKind {
@@ -98,6 +112,7 @@ impl fmt::Display for ZErrorKind {
             ZErrorKind::InvalidReference { descr } => write!(f, "Invalid Reference ({})", descr),
             ZErrorKind::InvalidResolution { descr } => write!(f, "Invalid Resolution ({})", descr),
             ZErrorKind::InvalidSession { descr } => write!(f, "Invalid Session ({})", descr),
+            ZErrorKind::NoRouter { descr } => write!(f, "No router reachable ({})", descr),
             ZErrorKind::InvalidPath { path } => write!(f, "Invalid Path ({})", path),
             ZErrorKind::InvalidPathExpr { path } => write!(f, "Invalid PathExpr ({})", path),
             ZErrorKind::InvalidSelector { selector } => {
@@ -106,6 +121,7 @@ impl fmt::Display for ZErrorKind {
             ZErrorKind::IoError { descr } => write!(f, "IO error ({})", descr),
             ZErrorKind::Other { descr } => write!(f, "zenoh error: ({})", descr),
             ZErrorKind::Timeout {} => write!(f, "Timeout"),
+            ZErrorKind::Cancelled {} => write!(f, "Cancelled"),
             ZErrorKind::UnkownResourceId { rid } => write!(f, "Unkown ResourceId ({})", rid),
             ZErrorKind::ValueEncodingFailed { descr } => {
                 write!(f, "Failed to encode Value ({})", descr)
@@ -113,6 +129,9 @@ impl fmt::Display for ZErrorKind {
             ZErrorKind::ValueDecodingFailed { descr } => {
                 write!(f, "Failed to decode Value ({})", descr)
             }
+            ZErrorKind::ValueTooLarge { size, max } => {
+                write!(f, "Value too large ({} bytes, maximum is {})", size, max)
+            }
             ZErrorKind::TranscodingFailed {
                 origin_encoding,
                 target_encoding,
@@ -122,6 +141,8 @@ impl fmt::Display for ZErrorKind {
                 origin_encoding, target_encoding
             ),
             ZErrorKind::SharedMemoryError { descr } => write!(f, "Shared Memory error ({})", descr),
+            ZErrorKind::Quic { descr } => write!(f, "QUIC error ({})", descr),
+            ZErrorKind::StorageError { descr } => write!(f, "Storage error ({})", descr),
         }
     }
 }
@@ -230,13 +230,19 @@ mod consts {
     pub const ZN_OPEN_INCOMING_PENDING_STR: &str = "open_pending";
     pub const ZN_OPEN_INCOMING_PENDING_DEFAULT: &str = "1024";
 
-    /// Configures the peer ID.
+    /// Configures the peer ID, instead of a random one.
     /// String key : `"peer_id"`.
-    /// Accepted values : `<UUID>`.
+    /// Accepted values : `<hex string>` of at most 16 bytes (e.g. a UUID, possibly with `'-'` characters).
+    /// Default value : a random UUID.
+    ///
+    /// A deterministic ID allows to correlate a node across its restarts (e.g. in logs or metrics).
+    /// Its uniqueness is not enforced: two nodes using the same ID in a same system will disrupt the routing
+    /// and the sessions between them.
     pub const ZN_PEER_ID_KEY: u64 = 0x68;
     pub const ZN_PEER_ID_STR: &str = "peer_id";
 
-    /// Configures the batch size.
+    /// Configures the batch size: the maximum number of bytes of the messages sent in a single write on a link
+    /// (bounded by the link MTU).
     /// String key : `"batch_size"`.
     /// Accepted values : `<unsigned 16-bit integer>`.
     /// Default value : `65535`.
@@ -307,10 +313,222 @@ mod consts {
     pub const ZN_MULTICAST_IPV6_ADDRESS_KEY: u64 = 0x77;
     pub const ZN_MULTICAST_IPV6_ADDRESS_STR: &str = "multicast_ipv6_address";
     pub const ZN_MULTICAST_IPV6_ADDRESS_DEFAULT: &str = "[ff24::224]:7447";
+
+    /// The maximum size (in bytes) of an encoded value received by the zenoh API.
+    /// Larger values are rejected on their declared length, before being decoded: the `Workspace` operations
+    /// collecting the replies of a query (e.g. `get_collect()`) fail with a `ValueTooLarge` error, and the
+    /// streams of `Data` and `Change` drop them with a warning (their `into_try_stream()` return the error).
+    /// String key : `"max_value_size"`.
+    /// Accepted values : `<unsigned integer>`.
+    /// Default value : `"67108864"` (64 MiB).
+    pub const ZN_MAX_VALUE_SIZE_KEY: u64 = 0x78;
+    pub const ZN_MAX_VALUE_SIZE_STR: &str = "max_value_size";
+    pub const ZN_MAX_VALUE_SIZE_DEFAULT: &str = "67108864";
+
+    /// The maximum size (in bytes) of an encoded value put via the zenoh API.
+    /// A put of a larger value fails with an error.
+    /// String key : `"max_put_size"`.
+    /// Accepted values : `<unsigned integer>`.
+    /// Default value : `"67108864"` (64 MiB).
+    pub const ZN_MAX_PUT_SIZE_KEY: u64 = 0x79;
+    pub const ZN_MAX_PUT_SIZE_STR: &str = "max_put_size";
+    pub const ZN_MAX_PUT_SIZE_DEFAULT: &str = "67108864";
+
+    /// The time (in milliseconds) after which an internal task (transport link, scouting)
+    /// that made no progress is considered as stalled.
+    /// String key : `"stall_threshold"`.
+    /// Accepted values : `<unsigned integer>`.
+    /// Default value : `"30000"`.
+    pub const ZN_STALL_THRESHOLD_KEY: u64 = 0x7A;
+    pub const ZN_STALL_THRESHOLD_STR: &str = "stall_threshold";
+    pub const ZN_STALL_THRESHOLD_DEFAULT: &str = "30000";
+
+    /// Indicates if a watchdog logging the stalled internal tasks must be started.
+    /// String key : `"watchdog"`.
+    /// Accepted values : `"true"`, `"false"`.
+    /// Default value : `"false"`.
+    pub const ZN_WATCHDOG_KEY: u64 = 0x7B;
+    pub const ZN_WATCHDOG_STR: &str = "watchdog";
+    pub const ZN_WATCHDOG_DEFAULT: &str = "false";
+
+    /// The local IP address to bind the outbound TCP and TLS links to (e.g. to use a specific
+    /// network interface on a multi-homed host). It must be an address of the local host.
+    /// Only the links established towards the configured or scouted peers and routers are concerned:
+    /// the listeners are bound to the address of their own locator, independently of this setting.
+    /// String key : `"bind_address"`.
+    /// Accepted values : `<IP address>` (ex: `"192.168.1.10"`).
+    /// Default value : None (the local address is chosen by the OS routing).
+    pub const ZN_BIND_ADDRESS_KEY: u64 = 0x7C;
+    pub const ZN_BIND_ADDRESS_STR: &str = "bind_address";
+
+    /// Indicates if the startup must fail when a configured listener can't be opened.
+    /// If `"false"`, the session starts anyway and the failures are reported as warnings
+    /// (see `Zenoh::startup_warnings()`).
+    /// String key : `"strict_startup"`.
+    /// Accepted values : `"true"`, `"false"`.
+    /// Default value : `"true"`.
+    pub const ZN_STRICT_STARTUP_KEY: u64 = 0x7D;
+    pub const ZN_STRICT_STARTUP_STR: &str = "strict_startup";
+    pub const ZN_STRICT_STARTUP_DEFAULT: &str = "true";
+
+    /// The maximum number of queries concurrently processed by all the queryables (e.g. evals) of a session.
+    /// A query is processed until all the queryables it was dispatched to have finished to reply.
+    /// Above this limit, the queries are queued (see [`ZN_MAX_QUEUED_QUERIES_KEY`]).
+    /// String key : `"max_concurrent_queries"`.
+    /// Accepted values : `<unsigned integer>` (`"0"` means unlimited).
+    /// Default value : `"0"`.
+    pub const ZN_MAX_CONCURRENT_QUERIES_KEY: u64 = 0x7E;
+    pub const ZN_MAX_CONCURRENT_QUERIES_STR: &str = "max_concurrent_queries";
+    pub const ZN_MAX_CONCURRENT_QUERIES_DEFAULT: &str = "0";
+
+    /// The maximum number of queries waiting for the processing of others when [`ZN_MAX_CONCURRENT_QUERIES_KEY`]
+    /// is reached. Above this limit, the queries are rejected with an overload reply.
+    /// String key : `"max_queued_queries"`.
+    /// Accepted values : `<unsigned integer>`.
+    /// Default value : `"0"`.
+    pub const ZN_MAX_QUEUED_QUERIES_KEY: u64 = 0x7F;
+    pub const ZN_MAX_QUEUED_QUERIES_STR: &str = "max_queued_queries";
+    pub const ZN_MAX_QUEUED_QUERIES_DEFAULT: &str = "0";
+
+    /// The transport protocols allowed for both the listeners and the outgoing links (e.g. to enforce
+    /// the use of encrypted links only). A listener or a peer whose locator has another protocol is rejected
+    /// at startup, as well as any scouted or gossiped locator of another protocol. An unknown protocol is rejected.
+    /// String key : `"enabled_transports"`.
+    /// Accepted values : `<protocol>[,<protocol>]*` (ex: `"tls,quic"`).
+    /// Default value : None (all the protocols are enabled).
+    pub const ZN_ENABLED_TRANSPORTS_KEY: u64 = 0x80;
+    pub const ZN_ENABLED_TRANSPORTS_STR: &str = "enabled_transports";
+
+    /// The maximum egress bandwidth of each unicast link, in bytes per second.
+    /// When exceeded, the sending on the link is delayed (see `TransportUnicast::get_links_tx_rate()`).
+    /// String key : `"max_egress_bandwidth"`.
+    /// Accepted values : `<unsigned integer>` (`0` for no limit).
+    /// Default value : `"0"`.
+    pub const ZN_MAX_EGRESS_BANDWIDTH_KEY: u64 = 0x81;
+    pub const ZN_MAX_EGRESS_BANDWIDTH_STR: &str = "max_egress_bandwidth";
+    pub const ZN_MAX_EGRESS_BANDWIDTH_DEFAULT: &str = "0";
+
+    /// The maximum time, in microseconds, an incomplete batch waits for more messages before being sent
+    /// (see [`ZN_BATCH_SIZE_KEY`]). By default, a batch is sent as soon as the link is available, which minimizes
+    /// the latency. Waiting allows to send more messages per write when publishing at a high rate (e.g. a kHz
+    /// sensor), improving the throughput at the cost of up to this latency for each message.
+    /// The messages of the `Control` and `RealTime` priorities are never delayed: they trigger the sending
+    /// of their batch (see `Workspace::put_flush_now()`).
+    /// String key : `"batch_timeout"`.
+    /// Accepted values : `<unsigned integer>` (`0` to not wait).
+    /// Default value : `"0"`.
+    pub const ZN_BATCH_TIMEOUT_KEY: u64 = 0x82;
+    pub const ZN_BATCH_TIMEOUT_STR: &str = "batch_timeout";
+    pub const ZN_BATCH_TIMEOUT_DEFAULT: &str = "0";
+
+    /// The path of a watched file listing the locators of peers to connect to, one per line
+    /// (the empty lines and the lines starting with `#` are ignored).
+    /// String key : `"peers_file"`.
+    /// Accepted values : `<file path>`.
+    /// Default value : None.
+    pub const ZN_PEERS_FILE_KEY: u64 = 0x83;
+    pub const ZN_PEERS_FILE_STR: &str = "peers_file";
+
+    /// The maximum drift, in milliseconds, of the timestamps of the received data ahead of the local clock
+    /// (see [`ZN_ADD_TIMESTAMP_KEY`]). The timestamps further ahead are clamped (see `Zenoh::clock_skews()`).
+    /// String key : `"max_clock_drift"`.
+    /// Accepted values : `<unsigned integer>` (`0` for no bound).
+    /// Default value : `"0"`.
+    pub const ZN_MAX_CLOCK_DRIFT_KEY: u64 = 0x84;
+    pub const ZN_MAX_CLOCK_DRIFT_STR: &str = "max_clock_drift";
+    pub const ZN_MAX_CLOCK_DRIFT_DEFAULT: &str = "0";
+
+    /// The path of the keyring file used by the Workspaces to encrypt and decrypt the values end-to-end
+    /// (see `Keyring::from_file()` in the zenoh API for its format).
+    /// String key : `"keyring"`.
+    /// Accepted values : `<file path>`.
+    /// Default value : None.
+    pub const ZN_KEYRING_KEY: u64 = 0x85;
+    pub const ZN_KEYRING_STR: &str = "keyring";
+
+    /// The default encoding of the values put with `Workspace::put_typed()` in the zenoh API, serialized
+    /// from any `serde::Serialize` type. It can be overridden per Workspace or per put.
+    /// `"cbor"` requires the `cbor` feature of zenoh: without it, the creation of a Workspace fails.
+    /// String key : `"typed_encoding"`.
+    /// Accepted values : `"json"`, `"cbor"`.
+    /// Default value : `"json"`.
+    pub const ZN_TYPED_ENCODING_KEY: u64 = 0x86;
+    pub const ZN_TYPED_ENCODING_STR: &str = "typed_encoding";
+    pub const ZN_TYPED_ENCODING_DEFAULT: &str = "json";
+
+    /// The size in bytes of the chunks of the values put with `Workspace::put_stream()` in the zenoh API,
+    /// and thus the memory used to send or read such value (see `Data::value_reader()`), whatever its total size.
+    /// It must not exceed the maximum size of a put (see [`ZN_MAX_PUT_SIZE_KEY`]).
+    /// String key : `"stream_chunk_size"`.
+    /// Accepted values : `<unsigned integer>`.
+    /// Default value : `"65536"`.
+    pub const ZN_STREAM_CHUNK_SIZE_KEY: u64 = 0x87;
+    pub const ZN_STREAM_CHUNK_SIZE_STR: &str = "stream_chunk_size";
+    pub const ZN_STREAM_CHUNK_SIZE_DEFAULT: &str = "65536";
 }
 
 pub use consts::*;
 
+/// The string keys of all the configuration properties recognized by zenoh.
+///
+/// See [`Properties::validate_known()`](super::Properties::validate_known).
+pub const ZN_KNOWN_KEYS: &[&str] = &[
+    ZN_MODE_STR,
+    ZN_PEER_STR,
+    ZN_LISTENER_STR,
+    ZN_USER_STR,
+    ZN_PASSWORD_STR,
+    ZN_MULTICAST_SCOUTING_STR,
+    ZN_MULTICAST_INTERFACE_STR,
+    ZN_MULTICAST_IPV4_ADDRESS_STR,
+    ZN_SCOUTING_TIMEOUT_STR,
+    ZN_SCOUTING_DELAY_STR,
+    ZN_ADD_TIMESTAMP_STR,
+    ZN_LINK_STATE_STR,
+    ZN_USER_PASSWORD_DICTIONARY_STR,
+    ZN_PEERS_AUTOCONNECT_STR,
+    ZN_TLS_SERVER_PRIVATE_KEY_STR,
+    ZN_TLS_SERVER_CERTIFICATE_STR,
+    ZN_TLS_ROOT_CA_CERTIFICATE_STR,
+    ZN_SHM_STR,
+    ZN_ROUTERS_AUTOCONNECT_MULTICAST_STR,
+    ZN_ROUTERS_AUTOCONNECT_GOSSIP_STR,
+    ZN_LOCAL_ROUTING_STR,
+    ZN_JOIN_SUBSCRIPTIONS_STR,
+    ZN_JOIN_PUBLICATIONS_STR,
+    ZN_LINK_LEASE_STR,
+    ZN_LINK_KEEP_ALIVE_STR,
+    ZN_SEQ_NUM_RESOLUTION_STR,
+    ZN_OPEN_TIMEOUT_STR,
+    ZN_OPEN_INCOMING_PENDING_STR,
+    ZN_PEER_ID_STR,
+    ZN_BATCH_SIZE_STR,
+    ZN_MAX_SESSIONS_STR,
+    ZN_MAX_LINKS_STR,
+    ZN_VERSION_STR,
+    ZN_QOS_STR,
+    ZN_JOIN_INTERVAL_STR,
+    ZN_DEFRAG_BUFF_SIZE_STR,
+    ZN_LINK_RX_BUFF_SIZE_STR,
+    ZN_MULTICAST_IPV6_ADDRESS_STR,
+    ZN_MAX_VALUE_SIZE_STR,
+    ZN_MAX_PUT_SIZE_STR,
+    ZN_STALL_THRESHOLD_STR,
+    ZN_WATCHDOG_STR,
+    ZN_BIND_ADDRESS_STR,
+    ZN_STRICT_STARTUP_STR,
+    ZN_MAX_CONCURRENT_QUERIES_STR,
+    ZN_MAX_QUEUED_QUERIES_STR,
+    ZN_ENABLED_TRANSPORTS_STR,
+    ZN_MAX_EGRESS_BANDWIDTH_STR,
+    ZN_BATCH_TIMEOUT_STR,
+    ZN_PEERS_FILE_STR,
+    ZN_MAX_CLOCK_DRIFT_STR,
+    ZN_KEYRING_STR,
+    ZN_TYPED_ENCODING_STR,
+    ZN_STREAM_CHUNK_SIZE_STR,
+];
+
 pub type ConfigProperties = IntKeyProperties<ConfigTranscoder>;
 
 pub struct ConfigTranscoder;
@@ -356,6 +574,22 @@ impl KeyTranscoder for ConfigTranscoder {
             ZN_DEFRAG_BUFF_SIZE_STR => Some(ZN_DEFRAG_BUFF_SIZE_KEY),
             ZN_LINK_RX_BUFF_SIZE_STR => Some(ZN_LINK_RX_BUFF_SIZE_KEY),
             ZN_MULTICAST_IPV6_ADDRESS_STR => Some(ZN_MULTICAST_IPV6_ADDRESS_KEY),
+            ZN_MAX_VALUE_SIZE_STR => Some(ZN_MAX_VALUE_SIZE_KEY),
+            ZN_MAX_PUT_SIZE_STR => Some(ZN_MAX_PUT_SIZE_KEY),
+            ZN_STALL_THRESHOLD_STR => Some(ZN_STALL_THRESHOLD_KEY),
+            ZN_WATCHDOG_STR => Some(ZN_WATCHDOG_KEY),
+            ZN_BIND_ADDRESS_STR => Some(ZN_BIND_ADDRESS_KEY),
+            ZN_STRICT_STARTUP_STR => Some(ZN_STRICT_STARTUP_KEY),
+            ZN_MAX_CONCURRENT_QUERIES_STR => Some(ZN_MAX_CONCURRENT_QUERIES_KEY),
+            ZN_MAX_QUEUED_QUERIES_STR => Some(ZN_MAX_QUEUED_QUERIES_KEY),
+            ZN_ENABLED_TRANSPORTS_STR => Some(ZN_ENABLED_TRANSPORTS_KEY),
+            ZN_MAX_EGRESS_BANDWIDTH_STR => Some(ZN_MAX_EGRESS_BANDWIDTH_KEY),
+            ZN_BATCH_TIMEOUT_STR => Some(ZN_BATCH_TIMEOUT_KEY),
+            ZN_PEERS_FILE_STR => Some(ZN_PEERS_FILE_KEY),
+            ZN_MAX_CLOCK_DRIFT_STR => Some(ZN_MAX_CLOCK_DRIFT_KEY),
+            ZN_KEYRING_STR => Some(ZN_KEYRING_KEY),
+            ZN_TYPED_ENCODING_STR => Some(ZN_TYPED_ENCODING_KEY),
+            ZN_STREAM_CHUNK_SIZE_STR => Some(ZN_STREAM_CHUNK_SIZE_KEY),
             _ => None,
         }
     }
@@ -396,6 +630,7 @@ impl KeyTranscoder for ConfigTranscoder {
             ZN_OPEN_INCOMING_PENDING_KEY => Some(ZN_OPEN_INCOMING_PENDING_STR.to_string()),
             ZN_PEER_ID_KEY => Some(ZN_PEER_ID_STR.to_string()),
             ZN_BATCH_SIZE_KEY => Some(ZN_BATCH_SIZE_STR.to_string()),
+            ZN_MAX_SESSIONS_KEY => Some(ZN_MAX_SESSIONS_STR.to_string()),
             ZN_MAX_LINKS_KEY => Some(ZN_MAX_LINKS_STR.to_string()),
             ZN_VERSION_KEY => Some(ZN_VERSION_STR.to_string()),
             ZN_QOS_KEY => Some(ZN_QOS_STR.to_string()),
@@ -403,6 +638,22 @@ impl KeyTranscoder for ConfigTranscoder {
             ZN_DEFRAG_BUFF_SIZE_KEY => Some(ZN_DEFRAG_BUFF_SIZE_STR.to_string()),
             ZN_LINK_RX_BUFF_SIZE_KEY => Some(ZN_LINK_RX_BUFF_SIZE_STR.to_string()),
             ZN_MULTICAST_IPV6_ADDRESS_KEY => Some(ZN_MULTICAST_IPV6_ADDRESS_STR.to_string()),
+            ZN_MAX_VALUE_SIZE_KEY => Some(ZN_MAX_VALUE_SIZE_STR.to_string()),
+            ZN_MAX_PUT_SIZE_KEY => Some(ZN_MAX_PUT_SIZE_STR.to_string()),
+            ZN_STALL_THRESHOLD_KEY => Some(ZN_STALL_THRESHOLD_STR.to_string()),
+            ZN_WATCHDOG_KEY => Some(ZN_WATCHDOG_STR.to_string()),
+            ZN_BIND_ADDRESS_KEY => Some(ZN_BIND_ADDRESS_STR.to_string()),
+            ZN_STRICT_STARTUP_KEY => Some(ZN_STRICT_STARTUP_STR.to_string()),
+            ZN_MAX_CONCURRENT_QUERIES_KEY => Some(ZN_MAX_CONCURRENT_QUERIES_STR.to_string()),
+            ZN_MAX_QUEUED_QUERIES_KEY => Some(ZN_MAX_QUEUED_QUERIES_STR.to_string()),
+            ZN_ENABLED_TRANSPORTS_KEY => Some(ZN_ENABLED_TRANSPORTS_STR.to_string()),
+            ZN_MAX_EGRESS_BANDWIDTH_KEY => Some(ZN_MAX_EGRESS_BANDWIDTH_STR.to_string()),
+            ZN_BATCH_TIMEOUT_KEY => Some(ZN_BATCH_TIMEOUT_STR.to_string()),
+            ZN_PEERS_FILE_KEY => Some(ZN_PEERS_FILE_STR.to_string()),
+            ZN_MAX_CLOCK_DRIFT_KEY => Some(ZN_MAX_CLOCK_DRIFT_STR.to_string()),
+            ZN_KEYRING_KEY => Some(ZN_KEYRING_STR.to_string()),
+            ZN_TYPED_ENCODING_KEY => Some(ZN_TYPED_ENCODING_STR.to_string()),
+            ZN_STREAM_CHUNK_SIZE_KEY => Some(ZN_STREAM_CHUNK_SIZE_STR.to_string()),
             _ => None,
         }
     }
@@ -192,6 +192,79 @@ impl fmt::Display for Properties {
     }
 }
 
+impl Properties {
+    /// Returns the keys of those properties that are not in `known`, each with the closest known key
+    /// as a suggestion (if any is close enough). Keys are compared case-insensitively.
+    ///
+    /// Typically used with [`ZN_KNOWN_KEYS`](config::ZN_KNOWN_KEYS) to warn about typos in
+    /// a configuration given by a user.
+    pub fn validate_known(&self, known: &[&str]) -> Vec<UnknownKey> {
+        let mut unknown: Vec<UnknownKey> = self
+            .0
+            .keys()
+            .filter(|k| !known.iter().any(|known| known.eq_ignore_ascii_case(k)))
+            .map(|k| UnknownKey {
+                key: k.clone(),
+                suggestion: closest_key(&k.to_lowercase(), known),
+            })
+            .collect();
+        unknown.sort_by(|a, b| a.key.cmp(&b.key));
+        unknown
+    }
+}
+
+/// A property key that is not recognized, as returned by [`Properties::validate_known()`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownKey {
+    /// the unknown key.
+    pub key: String,
+    /// the known key the closest to the unknown one, if any.
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for UnknownKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.suggestion {
+            Some(s) => write!(f, "unknown key '{}' (did you mean '{}'?)", self.key, s),
+            None => write!(f, "unknown key '{}'", self.key),
+        }
+    }
+}
+
+// Returns the known key with the smallest edit distance to `key`,
+// if this distance is at most a third of the key's length (or 1 for short keys).
+fn closest_key(key: &str, known: &[&str]) -> Option<String> {
+    known
+        .iter()
+        .map(|k| (edit_distance(key, &k.to_lowercase()), k))
+        .filter(|(d, _)| *d <= 1.max(key.chars().count() / 3))
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, k)| k.to_string())
+}
+
+// Damerau-Levenshtein distance (optimal string alignment variant).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    d[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
 impl fmt::Debug for Properties {
     /// Format the Properties as a string, using `'='` for key/value separator
     /// and `';'` for separator between each keys/values.
@@ -302,6 +375,24 @@ impl TryFrom<&std::path::Path> for Properties {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_validate_known() {
+        let props = Properties::from("mode=peer;mdoe=client;PEER=tcp/127.0.0.1:7447;foo=bar");
+        assert_eq!(
+            props.validate_known(config::ZN_KNOWN_KEYS),
+            vec![
+                UnknownKey {
+                    key: "foo".to_string(),
+                    suggestion: None
+                },
+                UnknownKey {
+                    key: "mdoe".to_string(),
+                    suggestion: Some("mode".to_string())
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_properties() {
         assert!(Properties::from("").0.is_empty());
@@ -0,0 +1,70 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::sync::Arc;
+use event_listener::Event;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+struct CancellationState {
+    cancelled: AtomicBool,
+    event: Event,
+}
+
+/// A token allowing to cancel some in-flight operations (e.g. on application shutdown).
+///
+/// The token can be cloned and shared between several operations: calling [`cancel()`](Self::cancel)
+/// on any clone cancels all of them. Once cancelled, a token remains cancelled.
+#[derive(Clone)]
+pub struct CancellationToken {
+    state: Arc<CancellationState>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        CancellationToken {
+            state: Arc::new(CancellationState {
+                cancelled: AtomicBool::new(false),
+                event: Event::new(),
+            }),
+        }
+    }
+
+    /// Cancels the operations using this token.
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::Release);
+        self.state.event.notify(usize::MAX);
+    }
+
+    /// Returns true if this token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Waits until this token is cancelled.
+    pub async fn cancelled(&self) {
+        while !self.is_cancelled() {
+            let listener = self.state.event.listen();
+            // re-check once listening, not to miss a cancellation in between
+            if self.is_cancelled() {
+                return;
+            }
+            listener.await;
+        }
+    }
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -112,6 +112,20 @@ macro_rules! zreceiver{
     }
 }
 
+// The default `accept` of the transcoders declared with `ztranscoder!`, accepting all the sources.
+// It's overridden by an `accept` method implemented by the transcoder type (inherent methods take precedence).
+#[doc(hidden)]
+pub trait AcceptAll<Src> {
+    fn accept(&self, _src: &Src) -> bool {
+        true
+    }
+}
+
+impl<T, Src> AcceptAll<Src> for T {}
+
+// The transcoder type must implement `transcode(&self, Src) -> ZResult<Dst>`, the sources failing to be transcoded
+// being dropped with a warning. It may also implement `accept(&self, &Src) -> bool`, the sources not accepted being
+// silently skipped (all the sources are accepted by default).
 #[macro_export]
 macro_rules! ztranscoder {
     (
@@ -127,6 +141,9 @@ macro_rules! ztranscoder {
             )*
         }
     ) => {
+        #[allow(unused_imports)]
+        use $crate::sync::channel::AcceptAll as _;
+
         $(#[$meta])*
         $vis struct $dstrcv$(<$( $lt ),+>)? {
             receiver: $srcrcv,
@@ -139,7 +156,11 @@ macro_rules! ztranscoder {
         impl$(<$( $lt ),+>)? Receiver<$dsttype> for $dstrcv$(<$( $lt ),+>)? {
             fn recv(&self) -> Result<$dsttype, RecvError> {
                 loop {
-                    match self.transcode(self.receiver.recv()?) {
+                    let src = self.receiver.recv()?;
+                    if !Self::accept(self, &src) {
+                        continue;
+                    }
+                    match self.transcode(src) {
                         Err(err) => warn!("Received an invalid {} (drop it): {}", stringify!($srctype), err),
                         Ok(data) => return Ok(data),
                     }
@@ -148,7 +169,11 @@ macro_rules! ztranscoder {
 
             fn try_recv(&self) -> Result<$dsttype, TryRecvError> {
                 loop {
-                    match self.transcode(self.receiver.try_recv()?) {
+                    let src = self.receiver.try_recv()?;
+                    if !Self::accept(self, &src) {
+                        continue;
+                    }
+                    match self.transcode(src) {
                         Err(err) => warn!("Received an invalid {} (drop it): {}", stringify!($srctype), err),
                         Ok(data) => return Ok(data),
                     }
@@ -157,7 +182,11 @@ macro_rules! ztranscoder {
 
             fn recv_timeout(&self, timeout: Duration) -> Result<$dsttype, RecvTimeoutError> {
                 loop {
-                    match self.transcode(self.receiver.recv_timeout(timeout)?) {
+                    let src = self.receiver.recv_timeout(timeout)?;
+                    if !Self::accept(self, &src) {
+                        continue;
+                    }
+                    match self.transcode(src) {
                         Err(err) => warn!("Received an invalid {} (drop it): {}", stringify!($srctype), err),
                         Ok(data) => return Ok(data),
                     }
@@ -166,7 +195,11 @@ macro_rules! ztranscoder {
 
             fn recv_deadline(&self, deadline: Instant) -> Result<$dsttype, RecvTimeoutError> {
                 loop {
-                    match self.transcode(self.receiver.recv_deadline(deadline)?) {
+                    let src = self.receiver.recv_deadline(deadline)?;
+                    if !Self::accept(self, &src) {
+                        continue;
+                    }
+                    match self.transcode(src) {
                         Err(err) => warn!("Received an invalid {} (drop it): {}", stringify!($srctype), err),
                         Ok(data) => return Ok(data),
                     }
@@ -179,16 +212,21 @@ macro_rules! ztranscoder {
 
             #[inline(always)]
             fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
-                match self.receiver.poll_next(cx) {
-                    Poll::Ready(Some(src)) => match self.transcode(src) {
-                        Ok(dst) => Poll::Ready(Some(dst)),
-                        Err(err) => {
-                            warn!("Received an invalid Reply (drop it): {}", err);
-                            Poll::Pending
+                // loop over the skipped and invalid sources, as returning Pending for them wouldn't wake the task
+                loop {
+                    match self.receiver.poll_next(cx) {
+                        Poll::Ready(Some(src)) => {
+                            if !Self::accept(&*self, &src) {
+                                continue;
+                            }
+                            match self.transcode(src) {
+                                Ok(dst) => return Poll::Ready(Some(dst)),
+                                Err(err) => warn!("Received an invalid {} (drop it): {}", stringify!($srctype), err),
+                            }
                         }
-                    },
-                    Poll::Ready(None) => Poll::Ready(None),
-                    Poll::Pending => Poll::Pending,
+                        Poll::Ready(None) => return Poll::Ready(None),
+                        Poll::Pending => return Poll::Pending,
+                    }
                 }
             }
         }
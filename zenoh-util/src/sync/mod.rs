@@ -18,6 +18,8 @@ use std::task::{Context, Poll};
 
 pub mod backoff;
 pub use backoff::*;
+pub mod cancel;
+pub use cancel::*;
 pub mod channel;
 pub mod condition;
 pub use condition::*;
@@ -47,10 +47,13 @@ fn criterion_benchmark(c: &mut Criterion) {
                         Default::default(),
                         uhlc::ID::new(16, [1u8; uhlc::ID::MAX_SIZE]),
                     )),
+                    checksum: None,
+                    monotonic_stamp: None,
                     source_id: Some(PeerId::new(16, [0u8; PeerId::MAX_SIZE])),
                     source_sn: Some(12345),
                     first_router_id: Some(PeerId::new(16, [0u8; PeerId::MAX_SIZE])),
                     first_router_sn: Some(12345),
+                    message_id: None,
                 });
 
                 let msg = ZenohMessage::make_data(
@@ -100,10 +103,13 @@ fn criterion_benchmark(c: &mut Criterion) {
             Default::default(),
             uhlc::ID::new(16, [0u8; uhlc::ID::MAX_SIZE]),
         )),
+        checksum: None,
+        monotonic_stamp: None,
         source_id: Some(PeerId::new(16, [0u8; PeerId::MAX_SIZE])),
         source_sn: Some(12345),
         first_router_id: Some(PeerId::new(16, [0u8; PeerId::MAX_SIZE])),
         first_router_sn: Some(12345),
+        message_id: None,
     });
     let payload = ZBuf::from(vec![0; 1024]);
     let channel = Channel::default();
@@ -0,0 +1,60 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+#[macro_use]
+extern crate criterion;
+
+use criterion::{black_box, Criterion};
+
+use zenoh::net::encoding;
+use zenoh::net::ZBuf;
+use zenoh::{LazyValue, Value};
+
+// Simulates a subscriber receiving a batch of values but only inspecting 1 out of `ratio` of them.
+fn criterion_benchmark(c: &mut Criterion) {
+    let batch = 1_000;
+    let size = [64, 1_024, 8_192];
+    for s in size.iter() {
+        let json = format!("{{\"data\":\"{}\"}}", "x".repeat(*s));
+        let payloads: Vec<ZBuf> = (0..batch)
+            .map(|_| ZBuf::from(json.as_bytes().to_vec()))
+            .collect();
+
+        for ratio in [1, 10, 100].iter() {
+            c.bench_function(format!("{} eager_decode 1/{}", s, ratio).as_str(), |b| {
+                b.iter(|| {
+                    for (i, p) in payloads.iter().enumerate() {
+                        let value = Value::decode(encoding::APP_JSON, p.clone()).unwrap();
+                        if i % ratio == 0 {
+                            black_box(&value);
+                        }
+                    }
+                })
+            });
+
+            c.bench_function(format!("{} lazy_decode 1/{}", s, ratio).as_str(), |b| {
+                b.iter(|| {
+                    for (i, p) in payloads.iter().enumerate() {
+                        let mut value = LazyValue::new(encoding::APP_JSON, p.clone());
+                        if i % ratio == 0 {
+                            black_box(value.value().unwrap());
+                        }
+                    }
+                })
+            });
+        }
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,64 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+#[macro_use]
+extern crate criterion;
+
+use criterion::{black_box, Criterion};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use zenoh::Path;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let s = "/org/eclipse/building/be/floor/1/office/2/sensors/temperature";
+    let path = Path::try_from(s).unwrap();
+    let string = s.to_string();
+
+    c.bench_function("path_clone", |b| {
+        b.iter(|| {
+            black_box(path.clone());
+        })
+    });
+    c.bench_function("string_clone", |b| {
+        b.iter(|| {
+            black_box(string.clone());
+        })
+    });
+
+    let paths: Vec<Path> = (0..1_000)
+        .map(|i| Path::try_from(format!("{}/{}", s, i)).unwrap())
+        .collect();
+    c.bench_function("path_index_1000", |b| {
+        b.iter(|| {
+            let mut index: HashMap<Path, usize> = HashMap::with_capacity(paths.len());
+            for (i, p) in paths.iter().enumerate() {
+                index.insert(p.clone(), i);
+            }
+            black_box(index);
+        })
+    });
+    let strings: Vec<String> = paths.iter().map(|p| p.to_string()).collect();
+    c.bench_function("string_index_1000", |b| {
+        b.iter(|| {
+            let mut index: HashMap<String, usize> = HashMap::with_capacity(strings.len());
+            for (i, s) in strings.iter().enumerate() {
+                index.insert(s.clone(), i);
+            }
+            black_box(index);
+        })
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,46 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+#[macro_use]
+extern crate criterion;
+
+use criterion::{black_box, Criterion};
+
+use zenoh::net::encoding;
+use zenoh::{BufferPool, Value};
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let size = [64, 1_024, 8_192, 65_536];
+    for s in size.iter() {
+        let data = vec![0u8; *s];
+        c.bench_function(format!("{} value_from_vec", s).as_str(), |b| {
+            b.iter(|| {
+                let value = Value::from(data.clone());
+                black_box(value.encode());
+            })
+        });
+
+        let pool = BufferPool::new(16, *s);
+        c.bench_function(format!("{} value_from_pooled_buffer", s).as_str(), |b| {
+            b.iter(|| {
+                let mut buf = pool.take();
+                buf.write(&data).unwrap();
+                let value = Value::from_pooled_buffer(encoding::APP_OCTET_STREAM, buf);
+                black_box(value.encode());
+            })
+        });
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
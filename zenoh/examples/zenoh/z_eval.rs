@@ -64,7 +64,7 @@ async fn main() {
                     Some(Data {
                         path: _,
                         value: Value::StringUtf8(s),
-                        timestamp: _,
+                        ..
                     }) => name = s,
                     Some(_) => println!("Failed to get name from '{}' : not a UTF-8 String", name),
                     None => println!("Failed to get name from '{}' : not found", name),
@@ -76,9 +76,22 @@ async fn main() {
                 );
             }
         }
+        // The requester can ask for a JSON value rather than a string, specifying the accepted encodings
+        // in the selector. For example: "/zenoh/example/eval?(_accept=application/json)"
         let s = format!("Eval from {}", name);
-        println!(r#"   >> Returning string: "{}""#, s);
-        get_request.reply_async(path.clone(), s.into()).await;
+        match get_request.best_match(&["text/plain", "application/json"]) {
+            Some("application/json") => {
+                let json = serde_json::json!({ "eval": s }).to_string();
+                println!("   >> Returning JSON: {}", json);
+                get_request
+                    .reply_async(path.clone(), Value::Json(json))
+                    .await;
+            }
+            _ => {
+                println!(r#"   >> Returning string: "{}""#, s);
+                get_request.reply_async(path.clone(), s.into()).await;
+            }
+        }
     }
 
     get_stream.close().await.unwrap();
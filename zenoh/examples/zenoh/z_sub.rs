@@ -90,6 +90,9 @@ fn parse_args() -> (Properties, String) {
     } else {
         Properties::default()
     };
+    for unknown in config.validate_known(net::config::ZN_KNOWN_KEYS) {
+        println!("Warning: {} in configuration", unknown);
+    }
     for key in ["mode", "peer", "listener"].iter() {
         if let Some(value) = args.values_of(key) {
             config.insert(key.to_string(), value.collect::<Vec<&str>>().join(","));
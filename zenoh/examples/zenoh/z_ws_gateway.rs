@@ -0,0 +1,103 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use clap::{App, Arg};
+use futures::prelude::*;
+use futures::select;
+use std::convert::{TryFrom, TryInto};
+use zenoh::*;
+
+// A zenoh router accepting the browser clients on a WebSocket locator (build with `--features transport_ws`).
+// The browser clients put, subscribe and get through it as any other zenoh application;
+// the changes on the selector are printed to show what they publish.
+#[async_std::main]
+async fn main() {
+    // initiate logging
+    env_logger::init();
+
+    let (config, selector) = parse_args();
+
+    println!("New zenoh router...");
+    let zenoh = Zenoh::new(config.into()).await.unwrap();
+
+    println!("New workspace...");
+    let workspace = zenoh.workspace(None).await.unwrap();
+
+    println!("Subscribe to {}'...\n", selector);
+    let mut change_stream = workspace
+        .subscribe(&selector.try_into().unwrap())
+        .await
+        .unwrap();
+
+    let mut stdin = async_std::io::stdin();
+    let mut input = [0u8];
+    loop {
+        select!(
+            change = change_stream.next().fuse() => {
+                let change = change.unwrap();
+                println!(
+                    ">> [Gateway] received {:?} for {} : {:?} with timestamp {}",
+                    change.kind,
+                    change.path,
+                    change.value,
+                    change.timestamp
+                )
+            }
+
+            _ = stdin.read_exact(&mut input).fuse() => {
+                if input[0] == b'q' {break}
+            }
+        );
+    }
+
+    change_stream.close().await.unwrap();
+    zenoh.close().await.unwrap();
+}
+
+fn parse_args() -> (Properties, String) {
+    let args = App::new("zenoh WebSocket gateway example")
+        .arg(
+            Arg::from_usage("-l, --listener=[LOCATOR]...   'Locators to listen on.'")
+                .default_value("ws/0.0.0.0:7448"),
+        )
+        .arg(Arg::from_usage(
+            "-e, --peer=[LOCATOR]...  'Peer locators used to initiate the zenoh session.'",
+        ))
+        .arg(Arg::from_usage(
+            "-c, --config=[FILE]      'A configuration file.'",
+        ))
+        .arg(
+            Arg::from_usage("-s, --selector=[selector] 'The selection of resources to print'")
+                .default_value("/demo/example/**"),
+        )
+        .get_matches();
+
+    let mut config = if let Some(conf_file) = args.value_of("config") {
+        Properties::try_from(std::path::Path::new(conf_file)).unwrap()
+    } else {
+        Properties::default()
+    };
+    for unknown in config.validate_known(net::config::ZN_KNOWN_KEYS) {
+        println!("Warning: {} in configuration", unknown);
+    }
+    config.insert("mode".to_string(), "router".to_string());
+    for key in ["peer", "listener"].iter() {
+        if let Some(value) = args.values_of(key) {
+            config.insert(key.to_string(), value.collect::<Vec<&str>>().join(","));
+        }
+    }
+
+    let selector = args.value_of("selector").unwrap().to_string();
+
+    (config, selector)
+}
@@ -0,0 +1,1879 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+
+//! Some adapters for the [`Stream`]s of [`Change`] returned by [`Workspace::subscribe()`](crate::Workspace::subscribe).
+//!
+//! See [`ChangeStreamExt`].
+
+use crate::delta::DeltaDecoder;
+use crate::net::encoding::APP_DELTA;
+use crate::net::protocol::core::Timestamp;
+use crate::net::{PeerId, ZInt};
+use crate::{Change, ChangeEvent, ChangeKind, Data, Path, Value};
+use async_std::pin::Pin;
+use async_std::task::{Context, Poll};
+use futures_lite::stream::Stream;
+use log::{trace, warn};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+type Timer = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+fn timer(deadline: Instant) -> Timer {
+    Box::pin(async_std::task::sleep(
+        deadline.saturating_duration_since(Instant::now()),
+    ))
+}
+
+/// Some extensions to the [`Stream`]s of [`Change`].
+pub trait ChangeStreamExt: Stream<Item = Change> + Sized {
+    /// Coalesces the changes received for a same path within `window` into their net effect.
+    ///
+    /// When a first change is received for a path, it's retained during `window`.
+    /// All the changes received on the same path during this window replace the retained one
+    /// if they have a more recent timestamp (a put followed by a delete results in a delete,
+    /// a delete followed by a put results in a put).
+    /// At the end of the window, only the retained change is emitted.
+    ///
+    /// The changes emitted at the same time are ordered by timestamp. Note that the changes on different
+    /// paths are emitted at the end of their respective windows, and thus can be emitted out of their
+    /// timestamps order.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use std::time::Duration;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut change_stream = workspace
+    ///     .subscribe(&"/demo/example/**".try_into().unwrap())
+    ///     .await
+    ///     .unwrap()
+    ///     .net_effect(Duration::from_millis(100));
+    /// while let Some(change) = change_stream.next().await {
+    ///     println!(">> {:?} for {}", change.kind, change.path)
+    /// }
+    /// # })
+    /// ```
+    fn net_effect(self, window: Duration) -> NetEffect<Self> {
+        NetEffect::new(self, window)
+    }
+
+    /// Remaps the path of each change starting with the `from` prefix, replacing this prefix with `to`
+    /// (e.g. to see the changes on `/raw/**` as changes on `/view/**`). See [`PathRemap`] for the details.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut change_stream = workspace
+    ///     .subscribe(&"/raw/**".try_into().unwrap())
+    ///     .await
+    ///     .unwrap()
+    ///     .remap(path("/raw"), path("/view"));
+    /// while let Some(change) = change_stream.next().await {
+    ///     // change.path starts with "/view"
+    ///     println!(">> {:?} for {}", change.kind, change.path)
+    /// }
+    /// # })
+    /// ```
+    fn remap(self, from: Path, to: Path) -> Remap<Self> {
+        Remap {
+            stream: self,
+            remap: PathRemap::new(from, to),
+        }
+    }
+
+    /// Returns a stream of [`ChangeEvent`], where a [`ChangeEvent::Heartbeat`] is inserted each time
+    /// no change has been received during `interval`.
+    ///
+    /// The heartbeats are local, in-stream signals: they're generated by this stream and don't involve
+    /// any network traffic. This allows a consumer to know that nothing changed during an interval
+    /// without a separate timer.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use std::time::Duration;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut events = workspace
+    ///     .subscribe(&"/demo/example/**".try_into().unwrap())
+    ///     .await
+    ///     .unwrap()
+    ///     .heartbeat(Duration::from_secs(1));
+    /// while let Some(event) = events.next().await {
+    ///     match event {
+    ///         ChangeEvent::Change(change) => println!(">> {:?} for {}", change.kind, change.path),
+    ///         ChangeEvent::Heartbeat => println!(">> no change for 1s"),
+    ///         ChangeEvent::Gap | ChangeEvent::SequenceGap { .. } => (),
+    ///     }
+    /// }
+    /// # })
+    /// ```
+    fn heartbeat(self, interval: Duration) -> Heartbeat<Self> {
+        Heartbeat {
+            stream: self,
+            interval,
+            timer: timer(Instant::now() + interval),
+        }
+    }
+
+    /// Maintains the latest value of each path and, on each change, yields the aggregate of all those values.
+    ///
+    /// The aggregate is recomputed by folding all the latest values with `fold`, starting from `init`,
+    /// in the order of their paths. A deleted path is removed from the aggregate.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// // the maximum of the distances measured by a set of sensors
+    /// let mut max_stream = workspace
+    ///     .subscribe(&"/demo/sensors/*/distance".try_into().unwrap())
+    ///     .await
+    ///     .unwrap()
+    ///     .reduce(None, |max: Option<f64>, _path, value| match value {
+    ///         Value::Float(f) => Some(max.map_or(*f, |max| max.max(*f))),
+    ///         _ => max,
+    ///     });
+    /// while let Some(max) = max_stream.next().await {
+    ///     println!(">> max distance: {:?}", max)
+    /// }
+    /// # })
+    /// ```
+    fn reduce<A, F>(self, init: A, fold: F) -> Reduce<Self, A, F>
+    where
+        A: Clone,
+        F: FnMut(A, &Path, &Value) -> A,
+    {
+        Reduce {
+            stream: self,
+            init,
+            fold,
+            latest: BTreeMap::new(),
+        }
+    }
+
+    /// Delivers the changes of each path in the order of their timestamps, even if they're received out of order.
+    ///
+    /// Each change is buffered during `delay` to give a chance to the changes with an older timestamp on the same
+    /// path to be received. The changes of a path are then delivered in timestamp order. The changes on different
+    /// paths are not reordered between them (this is cheaper than a global resequencing).
+    /// A change with a timestamp older than or equal to the one of the last change delivered for its path
+    /// (i.e. received too late, or a duplicate) is dropped.
+    ///
+    /// The buffering is bounded: at most `max_per_path` changes are buffered per path, the oldest buffered change
+    /// being delivered without waiting for the end of its delay when this maximum is exceeded.  
+    /// As there is no way to know if a change is missing, a change missing forever (e.g. lost by the transport)
+    /// doesn't block the next ones: they're delivered once their delay elapsed, and the missing change would be
+    /// dropped if received afterwards.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use std::time::Duration;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut change_stream = workspace
+    ///     .subscribe(&"/demo/example/**".try_into().unwrap())
+    ///     .await
+    ///     .unwrap()
+    ///     .ordered_per_path(Duration::from_millis(50), 16);
+    /// while let Some(change) = change_stream.next().await {
+    ///     println!(">> {:?} for {} at {}", change.kind, change.path, change.timestamp)
+    /// }
+    /// # })
+    /// ```
+    fn ordered_per_path(self, delay: Duration, max_per_path: usize) -> OrderedPerPath<Self> {
+        OrderedPerPath {
+            stream: self,
+            delay,
+            max_per_path: max_per_path.max(1),
+            pending: HashMap::new(),
+            delivered: HashMap::new(),
+            ready: VecDeque::new(),
+            timer: None,
+            terminated: false,
+        }
+    }
+
+    /// Tracks the paths having a value (i.e. put and not deleted since) and their estimated memory size,
+    /// calling `callback` when their count exceeds `count_threshold` or their size exceeds `bytes_threshold`.
+    ///
+    /// The callback is called once per crossing of a threshold, not for each change: it's called again only
+    /// after the count (or size) went back below the threshold and then exceeded it again.
+    /// The estimated size of a path is the length of the path plus the length of its value's payload
+    /// (as it would be encoded). The changes are passed through unchanged.  
+    /// This is purely local bookkeeping, e.g. to detect a runaway cardinality (such as a publisher creating
+    /// unbounded unique paths) in a subscriber maintaining a local map of all the matching paths.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut change_stream = workspace
+    ///     .subscribe(&"/demo/example/**".try_into().unwrap())
+    ///     .await
+    ///     .unwrap()
+    ///     .on_watermark(10_000, 64 * 1024 * 1024, |watermark| {
+    ///         println!("Too many paths: {} paths for {} bytes", watermark.paths, watermark.bytes)
+    ///     });
+    /// while let Some(change) = change_stream.next().await {
+    ///     println!(">> {:?} for {}", change.kind, change.path)
+    /// }
+    /// # })
+    /// ```
+    fn on_watermark<F>(
+        self,
+        count_threshold: usize,
+        bytes_threshold: usize,
+        callback: F,
+    ) -> OnWatermark<Self, F>
+    where
+        F: FnMut(Watermark),
+    {
+        OnWatermark {
+            stream: self,
+            count_threshold,
+            bytes_threshold,
+            callback,
+            sizes: HashMap::new(),
+            watermark: Watermark::default(),
+            count_exceeded: false,
+            bytes_exceeded: false,
+        }
+    }
+
+    /// Reconstructs the values put by [`DeltaPublisher`](crate::DeltaPublisher)s from their keyframes and deltas.
+    ///
+    /// The values with the [`APP_DELTA`] encoding are replaced with the reconstructed values, all the other
+    /// changes are passed through unchanged. The previous value of each path is kept to apply the deltas.
+    /// A delta that can't be applied (because its previous value was not received, e.g. when subscribing
+    /// mid-stream or after a lost frame) is dropped, as well as the following ones until the next keyframe.
+    /// A delete removes the previous value of its path.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut change_stream = workspace
+    ///     .subscribe(&"/demo/example/**".try_into().unwrap())
+    ///     .await
+    ///     .unwrap()
+    ///     .delta_decode();
+    /// while let Some(change) = change_stream.next().await {
+    ///     println!(">> {:?} for {} : {:?}", change.kind, change.path, change.value)
+    /// }
+    /// # })
+    /// ```
+    fn delta_decode(self) -> DeltaDecode<Self> {
+        DeltaDecode {
+            stream: self,
+            decoders: HashMap::new(),
+        }
+    }
+
+    /// Keeps the last `n` changes received for each path, accessible with [`History::for_path()`].
+    ///
+    /// The changes are passed through unchanged. The history of a path includes its last change,
+    /// i.e. the one just returned by the stream, and the deletions (a deleted path keeps its history).
+    /// This allows for instance to compare a new value with the previous one without maintaining a ring
+    /// buffer per path in the consumer.  
+    /// Note that the memory used grows with the number of distinct paths matched by the subscription
+    /// (up to `n` changes for each of them), and is never released while the stream is alive:
+    /// prefer a small `n` and a selective path expression if the paths are numerous or unbounded.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut change_stream = workspace
+    ///     .subscribe(&"/demo/example/**".try_into().unwrap())
+    ///     .await
+    ///     .unwrap()
+    ///     .history(2);
+    /// while let Some(change) = change_stream.next().await {
+    ///     if let [previous, _] = change_stream.for_path(&change.path) {
+    ///         println!(">> {} changed from {:?} to {:?}", change.path, previous.value, change.value)
+    ///     }
+    /// }
+    /// # })
+    /// ```
+    fn history(self, n: usize) -> History<Self> {
+        History {
+            stream: self,
+            n,
+            changes: HashMap::new(),
+        }
+    }
+
+    /// Replaces the JSON values with their part referenced by the JSON pointer `ptr` (see [`Value::json_pointer()`]),
+    /// delivering a change only when this part differs from the one previously delivered for the same path.
+    ///
+    /// This avoids the wakeups and the processing of the changes of the other parts of large JSON documents.
+    /// The part is delivered as a [`Value::Json`] in a [`ChangeKind::Put`] change (without checksum, as it
+    /// applies to the whole value).
+    ///
+    /// The part is absent when the pointer doesn't reference any part of the value, when the value is not JSON
+    /// or when the pointer is invalid. A change where the part is absent, as well as a deletion of the path,
+    /// is delivered as a [`ChangeKind::Delete`] change if a part was previously delivered for the path,
+    /// and dropped otherwise.  
+    /// A [`ChangeKind::Patch`] is applied as a JSON merge patch (see [RFC 7386](https://tools.ietf.org/html/rfc7386))
+    /// to the part previously delivered for the path: a patch which doesn't contain the part is dropped (the part
+    /// is unchanged), a `null` removes the part, and an object is merged into the previous part. If no part was
+    /// previously delivered for the path, the patch is applied to an empty part.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut change_stream = workspace
+    ///     .subscribe(&"/demo/robots/*/state".try_into().unwrap())
+    ///     .await
+    ///     .unwrap()
+    ///     .json_field("/battery/level");
+    /// while let Some(change) = change_stream.next().await {
+    ///     println!(">> battery of {} : {:?}", change.path, change.value)
+    /// }
+    /// # })
+    /// ```
+    fn json_field(self, ptr: &str) -> JsonField<Self> {
+        JsonField {
+            stream: self,
+            ptr: ptr.to_string(),
+            delivered: HashMap::new(),
+        }
+    }
+
+    /// Returns a stream of [`ChangeEvent`], where a [`ChangeEvent::SequenceGap`] is inserted before a change
+    /// when the sequence number attached by its publisher (see
+    /// [`Workspace::put_with_sequence()`](crate::Workspace::put_with_sequence)) skips some numbers.
+    ///
+    /// This gives visibility into the losses (e.g. of a best-effort subscription) without requiring reliability.
+    /// The sequence numbers are tracked per publisher, identified by its zenoh id. The changes without sequence
+    /// number are delivered as is.
+    ///
+    /// The sequence numbers are compared with a wrapping arithmetic: the number following `u64::MAX` is `0`,
+    /// and a number is ahead of the last received one if it's less than `2^63` after it. A number not ahead of
+    /// the last received one (e.g. a duplicate, or a publisher restarting its sequence) doesn't report any gap,
+    /// and the sequence of the publisher continues from this number.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut events = workspace
+    ///     .subscribe(&"/demo/example/**".try_into().unwrap())
+    ///     .await
+    ///     .unwrap()
+    ///     .detect_gaps();
+    /// while let Some(event) = events.next().await {
+    ///     match event {
+    ///         ChangeEvent::Change(change) => println!(">> {:?} for {}", change.kind, change.path),
+    ///         ChangeEvent::SequenceGap { source, from, to } => {
+    ///             println!(">> missed changes {} to {} from {}", from, to, source)
+    ///         }
+    ///         ChangeEvent::Gap | ChangeEvent::Heartbeat => (),
+    ///     }
+    /// }
+    /// # })
+    /// ```
+    fn detect_gaps(self) -> DetectGaps<Self> {
+        DetectGaps {
+            stream: self,
+            last: HashMap::new(),
+            pending: None,
+        }
+    }
+
+    /// Drops the changes with a message id (see
+    /// [`Workspace::put_with_message_id()`](crate::Workspace::put_with_message_id)) already received among
+    /// the last `window` message ids, e.g. the duplicates replayed after a reconnection of an at-least-once
+    /// subscription. The changes without message id are delivered as is.
+    ///
+    /// The memory is bounded to `window` message ids: a duplicate received after `window` other message ids
+    /// is delivered again. The window must cover the number of changes which can be replayed. This is a
+    /// best-effort de-duplication for an exactly-once-ish processing, not a transaction log: the ids are lost
+    /// when the subscription is closed, and a duplicate received by another subscriber isn't detected.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut changes = workspace
+    ///     .subscribe(&"/demo/example/orders/**".try_into().unwrap())
+    ///     .await
+    ///     .unwrap()
+    ///     .dedup_by_id(10_000);
+    /// while let Some(change) = changes.next().await {
+    ///     println!(">> {:?} for {} ({:?})", change.kind, change.path, change.message_id)
+    /// }
+    /// # })
+    /// ```
+    fn dedup_by_id(self, window: usize) -> DedupById<Self> {
+        DedupById {
+            stream: self,
+            window,
+            seen: HashSet::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Maintains the latest value of each path and, at each `interval`, yields a [`Snapshot`] of all those values
+    /// (even if none changed since the previous one), instead of the individual changes.
+    ///
+    /// This simplifies the rendering of a whole subtree (e.g. in a UI), at the cost of bandwidth.
+    /// A deleted path is removed from the snapshots. For a path receiving several changes, the one with the most
+    /// recent timestamp is kept. The snapshots can be seeded with the values already existing before the
+    /// subscription with [`SnapshotOnTick::seed()`] (see [`Workspace::subscribe_snapshot()`](crate::Workspace::subscribe_snapshot)).
+    ///
+    /// Note that the latest value of every path ever received is kept in memory until the path is deleted,
+    /// and that each snapshot is a copy of all of them: both the memory and the processing at each tick
+    /// grow linearly with the number of paths (and with the size of their values). For a large or unbounded
+    /// number of paths, prefer [`reduce()`](ChangeStreamExt::reduce) to compute an aggregate, and
+    /// [`on_watermark()`](ChangeStreamExt::on_watermark) to monitor the number of paths.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use std::time::Duration;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut snapshots = workspace
+    ///     .subscribe(&"/demo/example/**".try_into().unwrap())
+    ///     .await
+    ///     .unwrap()
+    ///     .snapshot_on_tick(Duration::from_millis(100));
+    /// while let Some(snapshot) = snapshots.next().await {
+    ///     for (path, data) in &snapshot.values {
+    ///         println!(">> {} : {:?}", path, data.value)
+    ///     }
+    /// }
+    /// # })
+    /// ```
+    fn snapshot_on_tick(self, interval: Duration) -> SnapshotOnTick<Self> {
+        SnapshotOnTick {
+            stream: self,
+            interval,
+            timer: timer(Instant::now() + interval),
+            values: BTreeMap::new(),
+        }
+    }
+}
+
+impl<S: Stream<Item = Change>> ChangeStreamExt for S {}
+
+/// A [`Stream`] coalescing the changes of a same path within a time window.
+///
+/// See [`ChangeStreamExt::net_effect()`].
+pub struct NetEffect<S> {
+    stream: S,
+    window: Duration,
+    pending: HashMap<Path, (Instant, Change)>,
+    ready: VecDeque<Change>,
+    timer: Option<Timer>,
+    terminated: bool,
+}
+
+impl<S> NetEffect<S> {
+    fn new(stream: S, window: Duration) -> Self {
+        NetEffect {
+            stream,
+            window,
+            pending: HashMap::new(),
+            ready: VecDeque::new(),
+            timer: None,
+            terminated: false,
+        }
+    }
+
+    /// Returns the underlying stream (e.g. to close the subscription), dropping the pending changes.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    fn retain(&mut self, change: Change) {
+        match self.pending.get_mut(&change.path) {
+            Some((_, retained)) => {
+                if change.timestamp > retained.timestamp {
+                    *retained = change;
+                }
+            }
+            None => {
+                let deadline = Instant::now() + self.window;
+                self.pending.insert(change.path.clone(), (deadline, change));
+            }
+        }
+    }
+
+    fn release(&mut self, now: Option<Instant>) {
+        let expired: Vec<Path> = self
+            .pending
+            .iter()
+            .filter(|(_, (deadline, _))| now.map_or(true, |now| *deadline <= now))
+            .map(|(path, _)| path.clone())
+            .collect();
+        let mut changes: Vec<Change> = expired
+            .iter()
+            .filter_map(|path| self.pending.remove(path).map(|(_, change)| change))
+            .collect();
+        changes.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        self.ready.extend(changes);
+    }
+}
+
+impl<S: Stream<Item = Change> + Unpin> Stream for NetEffect<S> {
+    type Item = Change;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        while !this.terminated {
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(Some(change)) => this.retain(change),
+                Poll::Ready(None) => {
+                    this.terminated = true;
+                    this.release(None);
+                }
+                Poll::Pending => break,
+            }
+        }
+        loop {
+            this.release(Some(Instant::now()));
+            if let Some(change) = this.ready.pop_front() {
+                return Poll::Ready(Some(change));
+            }
+            let next_deadline = match this.pending.values().map(|(deadline, _)| *deadline).min() {
+                Some(deadline) => deadline,
+                None if this.terminated => return Poll::Ready(None),
+                None => {
+                    this.timer = None;
+                    return Poll::Pending;
+                }
+            };
+            let timer = this.timer.get_or_insert_with(|| timer(next_deadline));
+            match timer.as_mut().poll(cx) {
+                Poll::Ready(()) => this.timer = None,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// The remapping of the [`Path`]s starting with a prefix to another prefix.
+///
+/// The prefixes are matched on whole segments: with `/raw` as `from` prefix, `/raw` and `/raw/a/b`
+/// are remapped (to `/view` and `/view/a/b` with `/view` as `to` prefix), but `/rawdata` is not.
+/// The paths not starting with the `from` prefix are left unchanged.
+///
+/// The remapping is reversed by [`PathRemap::reverse()`]. Note that if some paths not starting with `from`
+/// do start with `to`, reversing is ambiguous: such paths are remapped back as if they started with `from`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PathRemap {
+    from: Path,
+    to: Path,
+}
+
+impl PathRemap {
+    pub fn new(from: Path, to: Path) -> Self {
+        PathRemap { from, to }
+    }
+
+    /// Returns the prefix being replaced.
+    pub fn from(&self) -> &Path {
+        &self.from
+    }
+
+    /// Returns the replacing prefix.
+    pub fn to(&self) -> &Path {
+        &self.to
+    }
+
+    /// Returns the reverse remapping, from `to` to `from`.
+    pub fn reverse(&self) -> PathRemap {
+        PathRemap::new(self.to.clone(), self.from.clone())
+    }
+
+    /// Returns `path` with the `from` prefix replaced with `to`, or a copy of `path` if it doesn't start
+    /// with `from`.
+    pub fn apply(&self, path: &Path) -> Path {
+        match path.as_str().strip_prefix(self.from.as_str()) {
+            Some(rest)
+                if rest.is_empty()
+                    || rest.starts_with('/')
+                    || self.from.as_str().ends_with('/') =>
+            {
+                Path::new(&format!("{}/{}", self.to, rest)).unwrap_or_else(|_| path.clone())
+            }
+            _ => path.clone(),
+        }
+    }
+}
+
+/// A [`Stream`] remapping the paths of the changes with a [`PathRemap`].
+///
+/// See [`ChangeStreamExt::remap()`].
+pub struct Remap<S> {
+    stream: S,
+    remap: PathRemap,
+}
+
+impl<S> Remap<S> {
+    /// Returns the remapping applied by this stream (e.g. to reverse it).
+    pub fn remapping(&self) -> &PathRemap {
+        &self.remap
+    }
+
+    /// Returns the underlying stream (e.g. to close the subscription).
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S: Stream<Item = Change> + Unpin> Stream for Remap<S> {
+    type Item = Change;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.stream).poll_next(cx) {
+            Poll::Ready(Some(mut change)) => {
+                change.path = this.remap.apply(&change.path);
+                Poll::Ready(Some(change))
+            }
+            other => other,
+        }
+    }
+}
+
+/// A [`Stream`] of [`ChangeEvent`] inserting a [`ChangeEvent::Heartbeat`] when no change is received
+/// during an interval.
+///
+/// See [`ChangeStreamExt::heartbeat()`].
+pub struct Heartbeat<S> {
+    stream: S,
+    interval: Duration,
+    timer: Timer,
+}
+
+impl<S> Heartbeat<S> {
+    /// Returns the underlying stream (e.g. to close the subscription).
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S: Stream<Item = Change> + Unpin> Stream for Heartbeat<S> {
+    type Item = ChangeEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.stream).poll_next(cx) {
+            Poll::Ready(Some(change)) => {
+                this.timer = timer(Instant::now() + this.interval);
+                return Poll::Ready(Some(ChangeEvent::Change(change)));
+            }
+            Poll::Ready(None) => return Poll::Ready(None),
+            Poll::Pending => (),
+        }
+        match this.timer.as_mut().poll(cx) {
+            Poll::Ready(()) => {
+                this.timer = timer(Instant::now() + this.interval);
+                Poll::Ready(Some(ChangeEvent::Heartbeat))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// A [`Stream`] of the aggregate of the latest values of each path.
+///
+/// See [`ChangeStreamExt::reduce()`].
+pub struct Reduce<S, A, F> {
+    stream: S,
+    init: A,
+    fold: F,
+    latest: BTreeMap<Path, Value>,
+}
+
+impl<S, A, F> Reduce<S, A, F> {
+    /// Returns the latest value of each path taken into account in the aggregate.
+    pub fn latest(&self) -> &BTreeMap<Path, Value> {
+        &self.latest
+    }
+
+    /// Returns the underlying stream (e.g. to close the subscription).
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S, A, F> Stream for Reduce<S, A, F>
+where
+    S: Stream<Item = Change> + Unpin,
+    A: Clone + Unpin,
+    F: FnMut(A, &Path, &Value) -> A + Unpin,
+{
+    type Item = A;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.stream).poll_next(cx) {
+            Poll::Ready(Some(change)) => {
+                match (change.kind, change.value) {
+                    (ChangeKind::Delete, _) | (_, None) => {
+                        this.latest.remove(&change.path);
+                    }
+                    (_, Some(value)) => {
+                        this.latest.insert(change.path, value);
+                    }
+                }
+                let fold = &mut this.fold;
+                let aggregate = this
+                    .latest
+                    .iter()
+                    .fold(this.init.clone(), |acc, (path, value)| {
+                        fold(acc, path, value)
+                    });
+                Poll::Ready(Some(aggregate))
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// A [`Stream`] delivering the changes of each path in the order of their timestamps.
+///
+/// See [`ChangeStreamExt::ordered_per_path()`].
+pub struct OrderedPerPath<S> {
+    stream: S,
+    delay: Duration,
+    max_per_path: usize,
+    // the buffered changes of each path, by timestamp, with their delivery deadline
+    pending: HashMap<Path, BTreeMap<Timestamp, (Instant, Change)>>,
+    // the timestamp of the last change delivered for each path
+    delivered: HashMap<Path, Timestamp>,
+    ready: VecDeque<Change>,
+    timer: Option<Timer>,
+    terminated: bool,
+}
+
+impl<S> OrderedPerPath<S> {
+    /// Returns the underlying stream (e.g. to close the subscription), dropping the buffered changes.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    fn buffer(&mut self, change: Change) {
+        if let Some(last) = self.delivered.get(&change.path) {
+            if change.timestamp <= *last {
+                log::trace!(
+                    "Drop change for {} received too late ({} <= {})",
+                    change.path,
+                    change.timestamp,
+                    last
+                );
+                return;
+            }
+        }
+        let path = change.path.clone();
+        let buffered = self.pending.entry(path.clone()).or_default();
+        let deadline = Instant::now() + self.delay;
+        buffered
+            .entry(change.timestamp.clone())
+            .or_insert((deadline, change));
+        if buffered.len() > self.max_per_path {
+            let oldest = buffered.keys().next().cloned().unwrap();
+            self.deliver(&path, &oldest);
+        }
+    }
+
+    // Delivers the buffered changes of `path` up to the `until` timestamp (included).
+    fn deliver(&mut self, path: &Path, until: &Timestamp) {
+        if let Some(buffered) = self.pending.get_mut(path) {
+            let later = buffered.split_off(until);
+            let mut changes = std::mem::replace(buffered, later);
+            if let Some(until_change) = buffered.remove(until) {
+                changes.insert(until.clone(), until_change);
+            }
+            if buffered.is_empty() {
+                self.pending.remove(path);
+            }
+            if let Some(last) = changes.keys().next_back() {
+                self.delivered.insert(path.clone(), last.clone());
+            }
+            self.ready
+                .extend(changes.into_iter().map(|(_, (_, change))| change));
+        }
+    }
+
+    fn release(&mut self, now: Option<Instant>) {
+        // for each path, the most recent expired change (the older ones are delivered with it)
+        let expired: Vec<(Path, Timestamp)> = self
+            .pending
+            .iter()
+            .filter_map(|(path, buffered)| {
+                buffered
+                    .iter()
+                    .rev()
+                    .find(|(_, (deadline, _))| now.map_or(true, |now| *deadline <= now))
+                    .map(|(timestamp, _)| (path.clone(), timestamp.clone()))
+            })
+            .collect();
+        for (path, timestamp) in expired {
+            self.deliver(&path, &timestamp);
+        }
+    }
+}
+
+impl<S: Stream<Item = Change> + Unpin> Stream for OrderedPerPath<S> {
+    type Item = Change;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        while !this.terminated {
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(Some(change)) => this.buffer(change),
+                Poll::Ready(None) => {
+                    this.terminated = true;
+                    this.release(None);
+                }
+                Poll::Pending => break,
+            }
+        }
+        loop {
+            this.release(Some(Instant::now()));
+            if let Some(change) = this.ready.pop_front() {
+                return Poll::Ready(Some(change));
+            }
+            let next_deadline = match this
+                .pending
+                .values()
+                .flat_map(|buffered| buffered.values().map(|(deadline, _)| *deadline))
+                .min()
+            {
+                Some(deadline) => deadline,
+                None if this.terminated => return Poll::Ready(None),
+                None => {
+                    this.timer = None;
+                    return Poll::Pending;
+                }
+            };
+            let timer = this.timer.get_or_insert_with(|| timer(next_deadline));
+            match timer.as_mut().poll(cx) {
+                Poll::Ready(()) => this.timer = None,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// The count and estimated memory size of the paths tracked by [`ChangeStreamExt::on_watermark()`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Watermark {
+    /// The number of paths having a value.
+    pub paths: usize,
+    /// The estimated size in bytes of those paths and values.
+    pub bytes: usize,
+}
+
+/// A [`Stream`] tracking the count and size of the paths having a value, calling a callback when they
+/// exceed some thresholds.
+///
+/// See [`ChangeStreamExt::on_watermark()`].
+pub struct OnWatermark<S, F> {
+    stream: S,
+    count_threshold: usize,
+    bytes_threshold: usize,
+    callback: F,
+    sizes: HashMap<Path, usize>,
+    watermark: Watermark,
+    count_exceeded: bool,
+    bytes_exceeded: bool,
+}
+
+impl<S, F> OnWatermark<S, F> {
+    /// Returns the current count and estimated size of the tracked paths.
+    pub fn watermark(&self) -> Watermark {
+        self.watermark
+    }
+
+    /// Returns the underlying stream (e.g. to close the subscription).
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S, F: FnMut(Watermark)> OnWatermark<S, F> {
+    fn track(&mut self, change: &Change) {
+        let size = match (&change.kind, &change.value) {
+            (ChangeKind::Delete, _) | (_, None) => None,
+            (_, Some(value)) => Some(change.path.as_str().len() + estimated_size(value)),
+        };
+        let previous = match size {
+            Some(size) => self.sizes.insert(change.path.clone(), size),
+            None => self.sizes.remove(&change.path),
+        };
+        self.watermark.paths = self.sizes.len();
+        self.watermark.bytes = self.watermark.bytes + size.unwrap_or(0) - previous.unwrap_or(0);
+
+        let count_exceeded = self.watermark.paths > self.count_threshold;
+        let bytes_exceeded = self.watermark.bytes > self.bytes_threshold;
+        let crossed =
+            (count_exceeded && !self.count_exceeded) || (bytes_exceeded && !self.bytes_exceeded);
+        self.count_exceeded = count_exceeded;
+        self.bytes_exceeded = bytes_exceeded;
+        if crossed {
+            (self.callback)(self.watermark);
+        }
+    }
+}
+
+// Returns the length of the payload of a Value as it would be encoded, without encoding it.
+fn estimated_size(value: &Value) -> usize {
+    match value {
+        Value::Raw(_, buf) => buf.len(),
+        Value::Custom {
+            encoding_descr,
+            data,
+        } => encoding_descr.len() + 1 + data.len(),
+        Value::StringUtf8(s) | Value::Json(s) => s.len(),
+        Value::Properties(p) => p.iter().map(|(k, v)| k.len() + v.len() + 2).sum(),
+        Value::Integer(_) | Value::Float(_) => std::mem::size_of::<i64>(),
+        Value::Reference { path, .. } => std::mem::size_of::<ZInt>() + path.as_str().len() + 1,
+    }
+}
+
+impl<S, F> Stream for OnWatermark<S, F>
+where
+    S: Stream<Item = Change> + Unpin,
+    F: FnMut(Watermark) + Unpin,
+{
+    type Item = Change;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.stream).poll_next(cx) {
+            Poll::Ready(Some(change)) => {
+                this.track(&change);
+                Poll::Ready(Some(change))
+            }
+            other => other,
+        }
+    }
+}
+
+/// A [`Stream`] reconstructing the values put by [`DeltaPublisher`](crate::DeltaPublisher)s.
+///
+/// See [`ChangeStreamExt::delta_decode()`].
+pub struct DeltaDecode<S> {
+    stream: S,
+    decoders: HashMap<Path, DeltaDecoder>,
+}
+
+impl<S> DeltaDecode<S> {
+    /// Returns the underlying stream (e.g. to close the subscription).
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    // Returns the change with its reconstructed value, or None if it must be dropped.
+    fn decode(&mut self, mut change: Change) -> Option<Change> {
+        match (&change.kind, change.value.take()) {
+            (_, Some(Value::Raw(APP_DELTA, frame))) => {
+                let decoder = self.decoders.entry(change.path.clone()).or_default();
+                match decoder.decode(frame) {
+                    Ok(Some(value)) => {
+                        change.value = Some(value);
+                        Some(change)
+                    }
+                    Ok(None) => {
+                        trace!("Drop delta for {} until the next keyframe", change.path);
+                        None
+                    }
+                    Err(e) => {
+                        warn!("Drop delta for {}: {}", change.path, e);
+                        self.decoders.remove(&change.path);
+                        None
+                    }
+                }
+            }
+            (ChangeKind::Delete, value) => {
+                self.decoders.remove(&change.path);
+                change.value = value;
+                Some(change)
+            }
+            (_, value) => {
+                change.value = value;
+                Some(change)
+            }
+        }
+    }
+}
+
+impl<S: Stream<Item = Change> + Unpin> Stream for DeltaDecode<S> {
+    type Item = Change;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(Some(change)) => {
+                    if let Some(change) = this.decode(change) {
+                        return Poll::Ready(Some(change));
+                    }
+                }
+                other => return other,
+            }
+        }
+    }
+}
+
+/// A [`Stream`] keeping the last changes received for each path.
+///
+/// See [`ChangeStreamExt::history()`].
+pub struct History<S> {
+    stream: S,
+    n: usize,
+    changes: HashMap<Path, Vec<Change>>,
+}
+
+impl<S> History<S> {
+    /// Returns the last changes received for `path`, from the oldest to the most recent
+    /// (empty if no change has been received for `path`).
+    pub fn for_path(&self, path: &Path) -> &[Change] {
+        self.changes.get(path).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Returns the underlying stream (e.g. to close the subscription).
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S: Stream<Item = Change> + Unpin> Stream for History<S> {
+    type Item = Change;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.stream).poll_next(cx) {
+            Poll::Ready(Some(change)) => {
+                if this.n > 0 {
+                    let changes = this.changes.entry(change.path.clone()).or_default();
+                    if changes.len() == this.n {
+                        changes.remove(0);
+                    }
+                    changes.push(change.clone());
+                }
+                Poll::Ready(Some(change))
+            }
+            other => other,
+        }
+    }
+}
+
+/// A [`Stream`] of the changes of a part of JSON values.
+///
+/// See [`ChangeStreamExt::json_field()`].
+pub struct JsonField<S> {
+    stream: S,
+    ptr: String,
+    delivered: HashMap<Path, serde_json::Value>,
+}
+
+impl<S> JsonField<S> {
+    /// Returns the JSON pointer of the part of the values.
+    pub fn pointer(&self) -> &str {
+        &self.ptr
+    }
+
+    /// Returns the underlying stream (e.g. to close the subscription).
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    // Returns the change of the part of the value, or None if it must be dropped.
+    fn select(&mut self, mut change: Change) -> Option<Change> {
+        let part = match (&change.kind, &change.value) {
+            (ChangeKind::Delete, _) | (_, None) => None,
+            (ChangeKind::Patch, Some(value)) => {
+                let patch = value.json_pointer("").ok().flatten()?;
+                patch_part(self.delivered.get(&change.path), &patch, &self.ptr)?
+            }
+            (_, Some(value)) => value.json_pointer(&self.ptr).ok().flatten(),
+        };
+        match part {
+            Some(part) => {
+                if self.delivered.get(&change.path) == Some(&part) {
+                    return None;
+                }
+                self.delivered.insert(change.path.clone(), part.clone());
+                change.kind = ChangeKind::Put;
+                change.value = Some(Value::from(part));
+            }
+            None => {
+                self.delivered.remove(&change.path)?;
+                change.kind = ChangeKind::Delete;
+                change.value = None;
+            }
+        }
+        change.checksum = None;
+        Some(change)
+    }
+}
+
+// Returns the part referenced by `ptr` of a JSON document whose part was `previous`, once the JSON merge
+// patch `patch` applied to it (see RFC 7386): None if the patch doesn't modify the part, Some(None) if the
+// part is removed.
+fn patch_part(
+    previous: Option<&serde_json::Value>,
+    patch: &serde_json::Value,
+    ptr: &str,
+) -> Option<Option<serde_json::Value>> {
+    let tokens: Vec<&str> = ptr.split('/').skip(1).collect();
+    let mut patch = patch;
+    for (i, token) in tokens.iter().enumerate() {
+        match patch {
+            serde_json::Value::Object(members) => {
+                let token = token.replace("~1", "/").replace("~0", "~");
+                patch = members.get(&token)?;
+            }
+            // a non-object replaces the document at this level (a null removes it)
+            serde_json::Value::Null => return Some(None),
+            _ => {
+                return Some(
+                    patch
+                        .pointer(&["/", &tokens[i..].join("/")].concat())
+                        .cloned(),
+                )
+            }
+        }
+    }
+    if patch.is_null() {
+        return Some(None);
+    }
+    let mut part = previous.cloned().unwrap_or(serde_json::Value::Null);
+    merge_patch(&mut part, patch);
+    Some(Some(part))
+}
+
+// Applies the JSON merge `patch` to `target` (see RFC 7386).
+fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    match patch {
+        serde_json::Value::Object(members) => {
+            if !target.is_object() {
+                *target = serde_json::Value::Object(serde_json::Map::new());
+            }
+            let target = target.as_object_mut().unwrap();
+            for (key, value) in members {
+                if value.is_null() {
+                    target.remove(key);
+                } else {
+                    merge_patch(
+                        target.entry(key.clone()).or_insert(serde_json::Value::Null),
+                        value,
+                    );
+                }
+            }
+        }
+        _ => *target = patch.clone(),
+    }
+}
+
+impl<S: Stream<Item = Change> + Unpin> Stream for JsonField<S> {
+    type Item = Change;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(Some(change)) => {
+                    if let Some(change) = this.select(change) {
+                        return Poll::Ready(Some(change));
+                    }
+                }
+                other => return other,
+            }
+        }
+    }
+}
+
+/// A [`Stream`] of [`ChangeEvent`] inserting a [`ChangeEvent::SequenceGap`] when the sequence numbers
+/// of a publisher skip some numbers.
+///
+/// See [`ChangeStreamExt::detect_gaps()`].
+pub struct DetectGaps<S> {
+    stream: S,
+    last: HashMap<PeerId, u64>,
+    pending: Option<Change>,
+}
+
+impl<S> DetectGaps<S> {
+    /// Returns the underlying stream (e.g. to close the subscription).
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    // Returns the gap before this change, if any.
+    fn track(&mut self, change: &Change) -> Option<ChangeEvent> {
+        let (source, sn) = change.sequence?;
+        let last = self.last.insert(source, sn)?;
+        let from = last.wrapping_add(1);
+        let missed = sn.wrapping_sub(from);
+        if missed == 0 || missed >= 1 << 63 {
+            if missed != 0 {
+                trace!(
+                    "Sequence of {} continues from {} after {}",
+                    source,
+                    sn,
+                    last
+                );
+            }
+            return None;
+        }
+        Some(ChangeEvent::SequenceGap {
+            source,
+            from,
+            to: sn.wrapping_sub(1),
+        })
+    }
+}
+
+impl<S: Stream<Item = Change> + Unpin> Stream for DetectGaps<S> {
+    type Item = ChangeEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if let Some(change) = this.pending.take() {
+            return Poll::Ready(Some(ChangeEvent::Change(change)));
+        }
+        match Pin::new(&mut this.stream).poll_next(cx) {
+            Poll::Ready(Some(change)) => match this.track(&change) {
+                Some(gap) => {
+                    this.pending = Some(change);
+                    Poll::Ready(Some(gap))
+                }
+                None => Poll::Ready(Some(ChangeEvent::Change(change))),
+            },
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// A [`Stream`] of [`Change`] dropping the changes with a message id already received among the last ones.
+///
+/// See [`ChangeStreamExt::dedup_by_id()`].
+pub struct DedupById<S> {
+    stream: S,
+    window: usize,
+    seen: HashSet<Uuid>,
+    // the message ids in `seen`, from the oldest
+    order: VecDeque<Uuid>,
+}
+
+impl<S> DedupById<S> {
+    /// Returns the underlying stream (e.g. to close the subscription).
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    // Returns true if this change's message id was already received.
+    fn is_duplicate(&mut self, change: &Change) -> bool {
+        let id = match change.message_id {
+            Some(id) => id,
+            None => return false,
+        };
+        if self.seen.contains(&id) {
+            trace!("Drop duplicate change {} for {}", id, change.path);
+            return true;
+        }
+        if self.window > 0 {
+            if self.order.len() == self.window {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.seen.remove(&oldest);
+                }
+            }
+            self.seen.insert(id);
+            self.order.push_back(id);
+        }
+        false
+    }
+}
+
+impl<S: Stream<Item = Change> + Unpin> Stream for DedupById<S> {
+    type Item = Change;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(Some(change)) => {
+                    if !this.is_duplicate(&change) {
+                        return Poll::Ready(Some(change));
+                    }
+                }
+                other => return other,
+            }
+        }
+    }
+}
+
+/// The latest value of each path, returned by [`SnapshotOnTick`].
+#[derive(Clone, Debug, Default)]
+pub struct Snapshot {
+    /// The latest value of each path, in the order of the paths.
+    pub values: BTreeMap<Path, Data>,
+}
+
+/// A [`Stream`] of [`Snapshot`] of the latest values of each path, returned at each interval.
+///
+/// See [`ChangeStreamExt::snapshot_on_tick()`].
+pub struct SnapshotOnTick<S> {
+    stream: S,
+    interval: Duration,
+    timer: Timer,
+    values: BTreeMap<Path, Data>,
+}
+
+impl<S> SnapshotOnTick<S> {
+    /// Seeds the snapshots with some existing values (e.g. the ones returned by [`Workspace::get()`](crate::Workspace::get)).
+    /// A value is ignored if a more recent one was already received for its path.
+    pub fn seed(mut self, values: impl IntoIterator<Item = Data>) -> Self {
+        for data in values {
+            match self.values.get(&data.path) {
+                Some(latest) if latest.timestamp > data.timestamp => (),
+                _ => {
+                    self.values.insert(data.path.clone(), data);
+                }
+            }
+        }
+        self
+    }
+
+    /// Returns the underlying stream (e.g. to close the subscription).
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    fn apply(&mut self, change: Change) {
+        if matches!(self.values.get(&change.path), Some(latest) if latest.timestamp > change.timestamp)
+        {
+            trace!("Ignore outdated change for {} in snapshot", change.path);
+            return;
+        }
+        match (change.kind, change.value) {
+            (ChangeKind::Delete, _) | (_, None) => {
+                self.values.remove(&change.path);
+            }
+            (_, Some(value)) => {
+                self.values.insert(
+                    change.path.clone(),
+                    Data {
+                        path: change.path,
+                        value,
+                        timestamp: change.timestamp,
+                        stale: false,
+                    },
+                );
+            }
+        }
+    }
+}
+
+impl<S: Stream<Item = Change> + Unpin> Stream for SnapshotOnTick<S> {
+    type Item = Snapshot;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(Some(change)) => this.apply(change),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => break,
+            }
+        }
+        match this.timer.as_mut().poll(cx) {
+            Poll::Ready(()) => {
+                this.timer = timer(Instant::now() + this.interval);
+                Poll::Ready(Some(Snapshot {
+                    values: this.values.clone(),
+                }))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::protocol::core::{Timestamp, TimestampId};
+    use crate::{path, ChangeKind, Value};
+    use futures::prelude::*;
+    use std::convert::TryFrom;
+
+    fn change(path: &str, time: u64, kind: ChangeKind) -> Change {
+        Change::new(
+            Path::try_from(path).unwrap(),
+            match kind {
+                ChangeKind::Delete => None,
+                _ => Some(Value::Integer(time as i64)),
+            },
+            Timestamp::new(
+                uhlc::NTP64(time),
+                TimestampId::new(1, [0u8; TimestampId::MAX_SIZE]),
+            ),
+            kind,
+        )
+    }
+
+    #[test]
+    fn net_effect_coalesces_put_and_delete() {
+        async_std::task::block_on(async {
+            let changes = vec![
+                change("/a", 1, ChangeKind::Put),
+                change("/b", 2, ChangeKind::Delete),
+                change("/a", 3, ChangeKind::Delete),
+                change("/b", 4, ChangeKind::Put),
+                change("/a", 0, ChangeKind::Put),
+            ];
+            let result: Vec<Change> = stream::iter(changes)
+                .net_effect(Duration::from_millis(10))
+                .collect()
+                .await;
+            assert_eq!(result.len(), 2);
+            assert_eq!(result[0].path.as_str(), "/a");
+            assert_eq!(result[0].kind, ChangeKind::Delete);
+            assert_eq!(result[1].path.as_str(), "/b");
+            assert_eq!(result[1].kind, ChangeKind::Put);
+        });
+    }
+
+    #[test]
+    fn remap_paths() {
+        let remap = PathRemap::new(path("/raw"), path("/view"));
+        assert_eq!(remap.apply(&path("/raw")), path("/view"));
+        assert_eq!(remap.apply(&path("/raw/a/b")), path("/view/a/b"));
+        assert_eq!(remap.apply(&path("/rawdata/a")), path("/rawdata/a"));
+        assert_eq!(remap.apply(&path("/other/a")), path("/other/a"));
+        assert_eq!(remap.reverse().apply(&path("/view/a/b")), path("/raw/a/b"));
+
+        let root = PathRemap::new(path("/"), path("/view"));
+        assert_eq!(root.apply(&path("/a/b")), path("/view/a/b"));
+
+        async_std::task::block_on(async {
+            let result: Vec<Change> = stream::iter(vec![
+                change("/raw/a", 1, ChangeKind::Put),
+                change("/other/b", 2, ChangeKind::Put),
+            ])
+            .remap(path("/raw"), path("/view"))
+            .collect()
+            .await;
+            assert_eq!(result[0].path, path("/view/a"));
+            assert_eq!(result[1].path, path("/other/b"));
+        });
+    }
+
+    #[test]
+    fn heartbeat_when_idle() {
+        async_std::task::block_on(async {
+            let changes = vec![change("/a", 1, ChangeKind::Put)];
+            let events: Vec<ChangeEvent> = stream::iter(changes)
+                .chain(stream::pending())
+                .heartbeat(Duration::from_millis(10))
+                .take(3)
+                .collect()
+                .await;
+            assert!(matches!(&events[0], ChangeEvent::Change(c) if c.path.as_str() == "/a"));
+            assert!(matches!(events[1], ChangeEvent::Heartbeat));
+            assert!(matches!(events[2], ChangeEvent::Heartbeat));
+        });
+    }
+
+    #[test]
+    fn detect_gaps_per_source() {
+        async_std::task::block_on(async {
+            let source1 = PeerId::new(1, [1u8; PeerId::MAX_SIZE]);
+            let source2 = PeerId::new(1, [2u8; PeerId::MAX_SIZE]);
+            let seq = |time: u64, source: PeerId, sn: u64| Change {
+                sequence: Some((source, sn)),
+                ..change("/a", time, ChangeKind::Put)
+            };
+            let changes = vec![
+                seq(1, source1, 5),
+                seq(2, source2, u64::MAX - 1),
+                seq(3, source1, 6),
+                seq(4, source1, 9),
+                change("/a", 5, ChangeKind::Put),
+                seq(6, source2, 1),
+                seq(7, source1, 2),
+                seq(8, source1, 3),
+            ];
+            let events: Vec<String> = stream::iter(changes)
+                .detect_gaps()
+                .map(|event| match event {
+                    ChangeEvent::Change(c) => format!("{}", c.timestamp.get_time().as_u64()),
+                    ChangeEvent::SequenceGap { source, from, to } => {
+                        format!("gap {}:{}-{}", source.as_slice()[0], from, to)
+                    }
+                    _ => "other".to_string(),
+                })
+                .collect()
+                .await;
+            assert_eq!(
+                events,
+                vec![
+                    "1",
+                    "2",
+                    "3",
+                    "gap 1:7-8",
+                    "4",
+                    "5",
+                    format!("gap 2:{}-0", u64::MAX).as_str(),
+                    "6",
+                    "7",
+                    "8"
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn dedup_by_id_window() {
+        async_std::task::block_on(async {
+            let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+            let msg = |time: u64, id: usize| Change {
+                message_id: Some(ids[id]),
+                ..change("/a", time, ChangeKind::Put)
+            };
+            let changes = vec![
+                msg(1, 0),
+                msg(2, 1),
+                msg(3, 0),
+                change("/a", 4, ChangeKind::Put),
+                change("/a", 5, ChangeKind::Put),
+                msg(6, 2),
+                msg(7, 1),
+                // ids[0] is out of the window of 2 ids (ids[1] and ids[2])
+                msg(8, 0),
+            ];
+            let times: Vec<u64> = stream::iter(changes)
+                .dedup_by_id(2)
+                .map(|c| c.timestamp.get_time().as_u64())
+                .collect()
+                .await;
+            assert_eq!(times, vec![1, 2, 4, 5, 6, 8]);
+        });
+    }
+
+    #[test]
+    fn reduce_latest_values() {
+        async_std::task::block_on(async {
+            let changes = vec![
+                change("/a", 1, ChangeKind::Put),
+                change("/b", 5, ChangeKind::Put),
+                change("/a", 7, ChangeKind::Put),
+                change("/a", 8, ChangeKind::Delete),
+            ];
+            let sums: Vec<i64> = stream::iter(changes)
+                .reduce(0, |sum, _, value| match value {
+                    Value::Integer(i) => sum + i,
+                    _ => sum,
+                })
+                .collect()
+                .await;
+            assert_eq!(sums, vec![1, 6, 12, 5]);
+        });
+    }
+
+    #[test]
+    fn on_watermark_once_per_crossing() {
+        async_std::task::block_on(async {
+            let changes = vec![
+                change("/a", 1, ChangeKind::Put),
+                change("/b", 2, ChangeKind::Put),
+                change("/c", 3, ChangeKind::Put),
+                change("/a", 4, ChangeKind::Put),
+                change("/a", 5, ChangeKind::Delete),
+                change("/b", 6, ChangeKind::Delete),
+                change("/d", 7, ChangeKind::Put),
+                change("/e", 8, ChangeKind::Put),
+            ];
+            let mut alerts = vec![];
+            let mut stream = stream::iter(changes).on_watermark(2, usize::MAX, |w| alerts.push(w));
+            let mut count = 0;
+            while stream.next().await.is_some() {
+                count += 1;
+            }
+            assert_eq!(count, 8);
+            assert_eq!(stream.watermark().paths, 3);
+            drop(stream);
+            // the count exceeds 2 with /c, and again with /e after the deletions of /a and /b
+            assert_eq!(
+                alerts.iter().map(|w| w.paths).collect::<Vec<_>>(),
+                vec![3, 3]
+            );
+        });
+    }
+
+    #[test]
+    fn ordered_per_path_reorders() {
+        async_std::task::block_on(async {
+            let changes = vec![
+                change("/a", 2, ChangeKind::Put),
+                change("/b", 5, ChangeKind::Put),
+                change("/a", 1, ChangeKind::Put),
+                change("/a", 2, ChangeKind::Put),
+                change("/a", 3, ChangeKind::Delete),
+            ];
+            let result: Vec<(String, u64)> = stream::iter(changes)
+                .ordered_per_path(Duration::from_millis(10), 16)
+                .map(|c| (c.path.to_string(), c.timestamp.get_time().as_u64()))
+                .collect()
+                .await;
+            let of = |path: &str| {
+                result
+                    .iter()
+                    .filter(|(p, _)| p == path)
+                    .map(|(_, t)| *t)
+                    .collect::<Vec<u64>>()
+            };
+            assert_eq!(of("/a"), vec![1, 2, 3]);
+            assert_eq!(of("/b"), vec![5]);
+        });
+    }
+
+    #[test]
+    fn ordered_per_path_drops_late_changes() {
+        async_std::task::block_on(async {
+            let changes = vec![
+                change("/a", 2, ChangeKind::Put),
+                change("/a", 3, ChangeKind::Put),
+                change("/a", 1, ChangeKind::Put),
+            ];
+            // with at most 1 buffered change, the change 2 is delivered when the change 3 is received
+            let result: Vec<u64> = stream::iter(changes)
+                .ordered_per_path(Duration::from_secs(10), 1)
+                .map(|c| c.timestamp.get_time().as_u64())
+                .collect()
+                .await;
+            assert_eq!(result, vec![2, 3]);
+        });
+    }
+
+    #[test]
+    fn delta_decode_per_path() {
+        async_std::task::block_on(async {
+            let mut encoder = crate::delta::DeltaEncoder::new(10);
+            let mut put = |path: &str, time: u64, value: &str| {
+                let mut c = change(path, time, ChangeKind::Put);
+                let value = format!("{}{}", "-".repeat(200), value);
+                c.value = Some(encoder.encode(Value::StringUtf8(value)));
+                c
+            };
+            let changes = vec![
+                put("/a", 1, "x"),
+                put("/a", 2, "y"),
+                change("/a", 3, ChangeKind::Delete),
+                // a delta after the delete has no previous value
+                put("/a", 4, "z"),
+                change("/b", 5, ChangeKind::Put),
+            ];
+            let result: Vec<(u64, Option<Value>)> = stream::iter(changes)
+                .delta_decode()
+                .map(|c| (c.timestamp.get_time().as_u64(), c.value))
+                .collect()
+                .await;
+            assert_eq!(result.len(), 4);
+            assert!(matches!(&result[0], (1, Some(Value::StringUtf8(s))) if s.ends_with("-x")));
+            assert!(matches!(&result[1], (2, Some(Value::StringUtf8(s))) if s.ends_with("-y")));
+            assert!(matches!(&result[2], (3, None)));
+            assert!(matches!(&result[3], (5, Some(Value::Integer(5)))));
+        });
+    }
+
+    #[test]
+    fn snapshot_on_tick_latest_values() {
+        async_std::task::block_on(async {
+            let changes = vec![
+                change("/a", 2, ChangeKind::Put),
+                change("/b", 3, ChangeKind::Put),
+                change("/a", 1, ChangeKind::Put),
+                change("/c", 4, ChangeKind::Put),
+                change("/c", 5, ChangeKind::Delete),
+            ];
+            let seed = |path: &str, time: u64| Data {
+                path: Path::try_from(path).unwrap(),
+                value: Value::Integer(0),
+                timestamp: change(path, time, ChangeKind::Put).timestamp,
+                stale: false,
+            };
+            let snapshots: Vec<Snapshot> = stream::iter(changes)
+                .chain(stream::pending())
+                .snapshot_on_tick(Duration::from_millis(10))
+                .seed(vec![seed("/b", 1), seed("/d", 1)])
+                .take(2)
+                .collect()
+                .await;
+            for snapshot in snapshots {
+                let values = snapshot
+                    .values
+                    .iter()
+                    .map(|(path, data)| (path.as_str(), data.value.clone()))
+                    .collect::<Vec<(&str, Value)>>();
+                assert!(matches!(
+                    values.as_slice(),
+                    [
+                        ("/a", Value::Integer(2)),
+                        ("/b", Value::Integer(3)),
+                        ("/d", Value::Integer(0)),
+                    ]
+                ));
+            }
+        });
+    }
+
+    #[test]
+    fn history_per_path() {
+        async_std::task::block_on(async {
+            let changes = vec![
+                change("/a", 1, ChangeKind::Put),
+                change("/a", 2, ChangeKind::Put),
+                change("/b", 3, ChangeKind::Put),
+                change("/a", 4, ChangeKind::Delete),
+            ];
+            let mut stream = stream::iter(changes).history(2);
+            while stream.next().await.is_some() {}
+            let times = |path: &str| {
+                stream
+                    .for_path(&Path::try_from(path).unwrap())
+                    .iter()
+                    .map(|c| c.timestamp.get_time().as_u64())
+                    .collect::<Vec<u64>>()
+            };
+            assert_eq!(times("/a"), vec![2, 4]);
+            assert_eq!(times("/b"), vec![3]);
+            assert!(times("/c").is_empty());
+        });
+    }
+
+    #[test]
+    fn json_field_distinct() {
+        async_std::task::block_on(async {
+            let json = |path: &str, time: u64, kind: ChangeKind, json: &str| Change {
+                value: Some(Value::Json(json.to_string())),
+                ..change(path, time, kind)
+            };
+            let changes = vec![
+                json("/a", 1, ChangeKind::Put, r#"{"x": 1, "y": 1}"#),
+                json("/a", 2, ChangeKind::Put, r#"{"x": 1, "y": 2}"#),
+                json("/b", 3, ChangeKind::Put, r#"{"y": 1}"#),
+                json("/a", 4, ChangeKind::Patch, r#"{"y": 3}"#),
+                json("/a", 5, ChangeKind::Patch, r#"{"x": {"z": 2}}"#),
+                json("/a", 6, ChangeKind::Patch, r#"{"x": {"w": 1}}"#),
+                json("/a", 7, ChangeKind::Patch, r#"{"x": {"z": null}}"#),
+                json("/a", 8, ChangeKind::Patch, r#"{"x": null}"#),
+                json("/a", 9, ChangeKind::Patch, r#"{"x": 2}"#),
+                json("/a", 10, ChangeKind::Put, r#"{"y": 4}"#),
+                change("/a", 11, ChangeKind::Put),
+                change("/b", 12, ChangeKind::Delete),
+                json("/b", 13, ChangeKind::Put, r#"{"x": 3}"#),
+            ];
+            let received: Vec<(u64, ChangeKind, Option<serde_json::Value>)> = stream::iter(changes)
+                .json_field("/x")
+                .map(|c| {
+                    let part = c.value.map(|v| v.json_pointer("").unwrap().unwrap());
+                    (c.timestamp.get_time().as_u64(), c.kind, part)
+                })
+                .collect()
+                .await;
+            assert_eq!(
+                received,
+                vec![
+                    (1, ChangeKind::Put, Some(serde_json::json!(1))),
+                    (5, ChangeKind::Put, Some(serde_json::json!({"z": 2}))),
+                    (
+                        6,
+                        ChangeKind::Put,
+                        Some(serde_json::json!({"z": 2, "w": 1}))
+                    ),
+                    (7, ChangeKind::Put, Some(serde_json::json!({"w": 1}))),
+                    (8, ChangeKind::Delete, None),
+                    (9, ChangeKind::Put, Some(serde_json::json!(2))),
+                    (10, ChangeKind::Delete, None),
+                    (13, ChangeKind::Put, Some(serde_json::json!(3))),
+                ]
+            );
+        });
+    }
+}
@@ -0,0 +1,193 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use crate::net::protocol::proto::Checksum;
+use crate::net::{DataInfo, ZBuf, ZInt};
+use sha2::{Digest, Sha256};
+use std::convert::TryFrom;
+use std::fmt;
+
+/// The algorithms that can be used to compute the checksum attached to a put
+/// (see [`Workspace::put_with_checksum()`](crate::Workspace::put_with_checksum)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumAlgo {
+    /// CRC-32 (IEEE 802.3). Cheap, detects accidental corruption only.
+    Crc32 = 0x01,
+    /// SHA-256. More expensive, also detects tampering of the payload.
+    Sha256 = 0x02,
+}
+
+impl ChecksumAlgo {
+    /// Computes the digest of `payload` with this algorithm.
+    pub fn digest(&self, payload: &ZBuf) -> Vec<u8> {
+        match self {
+            ChecksumAlgo::Crc32 => {
+                let mut crc = !0u32;
+                for slice in payload.as_zslices() {
+                    crc = crc32_update(crc, slice.as_slice());
+                }
+                (!crc).to_be_bytes().to_vec()
+            }
+            ChecksumAlgo::Sha256 => {
+                let mut hasher = Sha256::new();
+                for slice in payload.as_zslices() {
+                    hasher.update(slice.as_slice());
+                }
+                hasher.finalize().to_vec()
+            }
+        }
+    }
+
+    pub(crate) fn checksum(&self, payload: &ZBuf) -> Checksum {
+        Checksum {
+            algo: *self as ZInt,
+            digest: self.digest(payload),
+        }
+    }
+}
+
+impl fmt::Display for ChecksumAlgo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChecksumAlgo::Crc32 => write!(f, "CRC32"),
+            ChecksumAlgo::Sha256 => write!(f, "SHA256"),
+        }
+    }
+}
+
+impl TryFrom<ZInt> for ChecksumAlgo {
+    type Error = IntegrityError;
+
+    fn try_from(algo: ZInt) -> Result<Self, Self::Error> {
+        match algo {
+            0x01 => Ok(ChecksumAlgo::Crc32),
+            0x02 => Ok(ChecksumAlgo::Sha256),
+            _ => Err(IntegrityError::UnknownAlgorithm(algo)),
+        }
+    }
+}
+
+/// The error returned by [`Change::verify_checksum()`](crate::Change::verify_checksum).
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntegrityError {
+    /// No checksum was attached to the change by the publisher.
+    Missing,
+    /// The checksum was computed with an algorithm unknown by this subscriber.
+    UnknownAlgorithm(ZInt),
+    /// The checksum doesn't match the received payload (corruption or tampering).
+    Mismatch(ChecksumAlgo),
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityError::Missing => write!(f, "No checksum attached"),
+            IntegrityError::UnknownAlgorithm(algo) => {
+                write!(f, "Unknown checksum algorithm: {}", algo)
+            }
+            IntegrityError::Mismatch(algo) => write!(f, "{} checksum mismatch", algo),
+        }
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
+/// Verifies the checksum possibly attached in `info` against `payload`.
+/// Returns `None` if there is no attached checksum.
+pub(crate) fn verify(
+    info: Option<&DataInfo>,
+    payload: &ZBuf,
+) -> Option<Result<ChecksumAlgo, IntegrityError>> {
+    info.and_then(|info| info.checksum.as_ref())
+        .map(|checksum| {
+            let algo = ChecksumAlgo::try_from(checksum.algo)?;
+            if algo.digest(payload) == checksum.digest {
+                Ok(algo)
+            } else {
+                Err(IntegrityError::Mismatch(algo))
+            }
+        })
+}
+
+fn crc32_update(mut crc: u32, data: &[u8]) -> u32 {
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum_digests() {
+        let payload = ZBuf::from("123456789".as_bytes());
+        assert_eq!(
+            ChecksumAlgo::Crc32.digest(&payload),
+            vec![0xCB, 0xF4, 0x39, 0x26]
+        );
+        assert_eq!(
+            hex::encode(ChecksumAlgo::Sha256.digest(&payload)),
+            "15e2b0d3c33891ebb0f1ef609ec419420c20e320ce94c65fbc8c3312448eb225"
+        );
+    }
+
+    #[test]
+    fn checksum_verify() {
+        let payload = ZBuf::from("hello".as_bytes());
+        let mut info = DataInfo::new();
+        assert_eq!(verify(Some(&info), &payload), None);
+
+        info.checksum = Some(ChecksumAlgo::Sha256.checksum(&payload));
+        assert_eq!(
+            verify(Some(&info), &payload),
+            Some(Ok(ChecksumAlgo::Sha256))
+        );
+        assert_eq!(
+            verify(Some(&info), &ZBuf::from("hellO".as_bytes())),
+            Some(Err(IntegrityError::Mismatch(ChecksumAlgo::Sha256)))
+        );
+
+        info.checksum.as_mut().unwrap().algo = 0xFF;
+        assert_eq!(
+            verify(Some(&info), &payload),
+            Some(Err(IntegrityError::UnknownAlgorithm(0xFF)))
+        );
+    }
+
+    #[test]
+    fn checksum_republished() {
+        let mut change = crate::Change::new(
+            std::convert::TryFrom::try_from("/a/b").unwrap(),
+            Some(crate::Value::Integer(1)),
+            crate::utils::new_reception_timestamp(),
+            crate::ChangeKind::Put,
+        );
+        change.checksum = Some(Ok(ChecksumAlgo::Crc32));
+        let republished = crate::Change::from_sample(change.clone().into_sample(), true).unwrap();
+        assert_eq!(republished.checksum, Some(Ok(ChecksumAlgo::Crc32)));
+
+        // an invalid checksum is not re-attached
+        change.checksum = Some(Err(IntegrityError::Mismatch(ChecksumAlgo::Crc32)));
+        let republished = crate::Change::from_sample(change.into_sample(), true).unwrap();
+        assert_eq!(republished.checksum, None);
+    }
+}
@@ -0,0 +1,297 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+
+use crate::net::encoding::APP_DELTA;
+use crate::net::{WBuf, ZBuf, ZFuture, ZInt};
+use crate::{Path, Value, Workspace, ZError, ZErrorKind, ZResult};
+use zenoh_util::{zerror, zerror2};
+
+/// The size of the blocks compared by the delta encoding.
+pub const DELTA_BLOCK_SIZE: usize = 64;
+
+const KEYFRAME: ZInt = 0;
+const DELTA: ZInt = 1;
+
+/// A publisher of the successive values of a path, sending either the full payload of a value
+/// (a _keyframe_) or only its differences with the previous value (a _delta_) when it's smaller.
+///
+/// Created by [`Workspace::delta_publisher()`](crate::Workspace::delta_publisher).
+/// The frames are put as [`Value::Raw`] with the [`APP_DELTA`] encoding. The subscribers reconstruct
+/// the values with [`ChangeStreamExt::delta_decode()`](crate::ChangeStreamExt::delta_decode).
+/// The storages reconstruct them too (with a [`DeltaDecoder`]) and store the full values, so that a `get`
+/// returns the values rather than the frames. A delta received by a storage missing the previous frame
+/// is not stored: the storage keeps its previous value until the next keyframe.
+/// A keyframe is sent for the first value, every `keyframe_interval` values (so that a subscriber
+/// joining mid-stream or missing a frame gets the values again), and when the encoding changes.
+///
+/// # Diff algorithm
+/// The payloads are compared per aligned blocks of [`DELTA_BLOCK_SIZE`] bytes: a block of the new payload
+/// identical to the block at the same offset in the previous payload is copied, any other block is sent
+/// as-is. This suits the payloads of a constant layout changing by places (e.g. a slowly changing image),
+/// not the payloads with inserted or removed bytes.
+///
+/// # Frame format
+/// The payload of a frame is made of zenoh integers (_zint_) and bytes:
+/// ```text
+/// zint: kind (0: keyframe, 1: delta)
+/// zint: sequence number (incremented by 1 for each frame)
+/// zint: encoding of the value
+/// keyframe: the payload of the value
+/// delta:    zint: length of the new payload
+///           repeated until the new payload is complete:
+///             zint: number of bytes copied from the previous payload (at the current offset)
+///             zint: number of following literal bytes, and those bytes
+/// ```
+pub struct DeltaPublisher<'a> {
+    workspace: &'a Workspace<'a>,
+    path: Path,
+    encoder: DeltaEncoder,
+}
+
+impl<'a> DeltaPublisher<'a> {
+    pub(crate) fn new(
+        workspace: &'a Workspace<'a>,
+        path: Path,
+        keyframe_interval: usize,
+    ) -> DeltaPublisher<'a> {
+        DeltaPublisher {
+            workspace,
+            path,
+            encoder: DeltaEncoder::new(keyframe_interval),
+        }
+    }
+
+    /// Returns the path of the published values.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Puts `value` into zenoh, as a keyframe or as a delta against the previously put value.
+    pub fn put(&mut self, value: Value) -> impl ZFuture<Output = ZResult<()>> {
+        let frame = self.encoder.encode(value);
+        self.workspace.put(&self.path, frame)
+    }
+}
+
+// The encoding of the successive values of a path as frames (see DeltaPublisher).
+pub(crate) struct DeltaEncoder {
+    keyframe_interval: u64,
+    seq: u64,
+    // the encoding and payload of the previous value
+    reference: Option<(ZInt, Vec<u8>)>,
+}
+
+impl DeltaEncoder {
+    pub(crate) fn new(keyframe_interval: usize) -> DeltaEncoder {
+        DeltaEncoder {
+            keyframe_interval: keyframe_interval.max(1) as u64,
+            seq: 0,
+            reference: None,
+        }
+    }
+
+    // Returns the frame of `value`, as a Value with the APP_DELTA encoding.
+    pub(crate) fn encode(&mut self, value: Value) -> Value {
+        let (encoding, payload) = value.encode();
+        let payload = payload.to_vec();
+        let delta = match &self.reference {
+            Some((ref_encoding, reference))
+                if *ref_encoding == encoding && self.seq % self.keyframe_interval != 0 =>
+            {
+                Some(diff(reference, &payload)).filter(|delta| delta.len() < payload.len())
+            }
+            _ => None,
+        };
+        let mut buf = WBuf::new(64, false);
+        buf.write_zint(if delta.is_some() { DELTA } else { KEYFRAME });
+        buf.write_u64_as_zint(self.seq);
+        buf.write_zint(encoding);
+        buf.write_bytes(delta.as_ref().unwrap_or(&payload));
+        self.seq += 1;
+        self.reference = Some((encoding, payload));
+        Value::Raw(APP_DELTA, buf.into())
+    }
+}
+
+// Returns the delta of `new` against `reference` (see DeltaPublisher).
+fn diff(reference: &[u8], new: &[u8]) -> Vec<u8> {
+    let mut buf = WBuf::new(64, false);
+    buf.write_usize_as_zint(new.len());
+    let mut offset = 0;
+    while offset < new.len() {
+        let same_block = |offset: usize| {
+            let end = (offset + DELTA_BLOCK_SIZE).min(new.len());
+            end <= reference.len() && reference[offset..end] == new[offset..end]
+        };
+        let copy_start = offset;
+        while offset < new.len() && same_block(offset) {
+            offset = (offset + DELTA_BLOCK_SIZE).min(new.len());
+        }
+        let literal_start = offset;
+        while offset < new.len() && !same_block(offset) {
+            offset = (offset + DELTA_BLOCK_SIZE).min(new.len());
+        }
+        buf.write_usize_as_zint(literal_start - copy_start);
+        buf.write_bytes_array(&new[literal_start..offset]);
+    }
+    ZBuf::from(buf).to_vec()
+}
+
+// Returns the payload reconstructed from `reference` and a `delta`.
+fn patch(reference: &[u8], delta: &mut ZBuf) -> Option<Vec<u8>> {
+    let len = delta.read_zint_as_usize()?;
+    if len > reference.len() + delta.readable() {
+        return None;
+    }
+    let mut new = Vec::with_capacity(len);
+    while new.len() < len {
+        let copy = delta.read_zint_as_usize()?;
+        let start = new.len();
+        if copy > len - start || start + copy > reference.len() {
+            return None;
+        }
+        new.extend_from_slice(&reference[start..start + copy]);
+        let literal = delta.read_zint_as_usize()?;
+        if literal > len - new.len() || literal > delta.readable() {
+            return None;
+        }
+        let start = new.len();
+        new.resize(start + literal, 0);
+        if !delta.read_bytes(&mut new[start..]) {
+            return None;
+        }
+    }
+    Some(new)
+}
+
+/// The reconstruction of the values of the frames sent by a [`DeltaPublisher`] for a path.
+///
+/// Used by [`ChangeStreamExt::delta_decode()`](crate::ChangeStreamExt::delta_decode), and by the storages
+/// which store the reconstructed values rather than the frames.
+#[derive(Default)]
+pub struct DeltaDecoder {
+    // the sequence number, encoding and payload of the last reconstructed value
+    reference: Option<(u64, ZInt, Vec<u8>)>,
+}
+
+impl DeltaDecoder {
+    /// Returns the value of a frame (the payload of a [`Value::Raw`] with the [`APP_DELTA`] encoding),
+    /// or `Ok(None)` if it's a delta that can't be applied because its previous value is missing
+    /// (e.g. when joining mid-stream, until the next keyframe).
+    pub fn decode(&mut self, mut frame: ZBuf) -> ZResult<Option<Value>> {
+        let malformed = || {
+            zerror2!(ZErrorKind::ValueDecodingFailed {
+                descr: "Malformed delta Value".to_string()
+            })
+        };
+        let kind = frame.read_zint().ok_or_else(malformed)?;
+        let seq = frame.read_zint_as_u64().ok_or_else(malformed)?;
+        let encoding = frame.read_zint().ok_or_else(malformed)?;
+        let payload = match kind {
+            KEYFRAME => {
+                let mut payload = vec![0u8; frame.readable()];
+                frame.read_bytes(&mut payload);
+                payload
+            }
+            DELTA => match &self.reference {
+                Some((ref_seq, ref_encoding, reference))
+                    if *ref_seq + 1 == seq && *ref_encoding == encoding =>
+                {
+                    patch(reference, &mut frame).ok_or_else(malformed)?
+                }
+                _ => {
+                    // wait for the next keyframe
+                    self.reference = None;
+                    return Ok(None);
+                }
+            },
+            _ => {
+                return zerror!(ZErrorKind::ValueDecodingFailed {
+                    descr: format!("Unknown delta frame kind: {}", kind)
+                })
+            }
+        };
+        let value = Value::decode(encoding, payload.clone().into())?;
+        self.reference = Some((seq, encoding, payload));
+        Ok(Some(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_and_patch() {
+        let reference: Vec<u8> = (0..1000).map(|i| (i % 251) as u8).collect();
+        let mut new = reference.clone();
+        new[10] = 0xff;
+        new[700] = 0xff;
+        new.extend_from_slice(&[1, 2, 3]);
+        let delta = diff(&reference, &new);
+        assert!(delta.len() < 3 * DELTA_BLOCK_SIZE);
+        assert_eq!(patch(&reference, &mut delta.into()), Some(new.clone()));
+
+        // a shorter payload and an empty one
+        let short = &new[..100];
+        assert_eq!(
+            patch(&reference, &mut diff(&reference, short).into()),
+            Some(short.to_vec())
+        );
+        assert_eq!(
+            patch(&reference, &mut diff(&reference, &[]).into()),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn encoder_and_decoder() {
+        let decode = |decoder: &mut DeltaDecoder, frame: &Value| match frame {
+            Value::Raw(APP_DELTA, buf) => decoder.decode(buf.clone()).unwrap(),
+            _ => panic!("Not a delta frame: {:?}", frame),
+        };
+        let values: Vec<Vec<u8>> = (0..6u8)
+            .map(|i| {
+                let mut v = vec![0u8; 1000];
+                v[500] = i;
+                v
+            })
+            .collect();
+        let mut encoder = DeltaEncoder::new(4);
+        let frames: Vec<Value> = values
+            .iter()
+            .map(|v| encoder.encode(Value::from(v.clone())))
+            .collect();
+        let sizes: Vec<usize> = frames.iter().map(|f| f.clone().encode().1.len()).collect();
+        // keyframes at 0 and 4
+        assert!(sizes[0] > 1000 && sizes[4] > 1000);
+        assert!(sizes[1] < 100 && sizes[5] < 100);
+
+        let mut decoder = DeltaDecoder::default();
+        for (frame, value) in frames.iter().zip(values.iter()) {
+            let decoded = decode(&mut decoder, frame).unwrap();
+            assert_eq!(&decoded.encode().1.to_vec(), value);
+        }
+
+        // a decoder joining mid-stream waits for the next keyframe
+        let mut decoder = DeltaDecoder::default();
+        assert!(decode(&mut decoder, &frames[2]).is_none());
+        assert!(decode(&mut decoder, &frames[3]).is_none());
+        assert!(decode(&mut decoder, &frames[4]).is_some());
+        // a lost frame
+        let mut decoder = DeltaDecoder::default();
+        assert!(decode(&mut decoder, &frames[0]).is_some());
+        assert!(decode(&mut decoder, &frames[2]).is_none());
+    }
+}
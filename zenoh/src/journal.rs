@@ -0,0 +1,755 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use crate::net::protocol::core::Timestamp;
+use crate::net::{Sample, ZBuf, ZInt};
+use crate::utils::new_reception_timestamp;
+use crate::{ChangeKind, Path, Value};
+use log::{trace, warn};
+use sha2::{Digest, Sha256};
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use zenoh_util::core::{ZError, ZErrorKind, ZResult};
+use zenoh_util::zerror2;
+
+/// The options of a [`Journal`].
+#[derive(Clone, Debug)]
+pub struct JournalOptions {
+    /// The size in bytes above which the journal file is rotated (64 MiB by default).
+    pub max_file_size: u64,
+    /// The number of rotated files kept, the older ones being removed (8 by default).
+    pub max_files: usize,
+    /// The number of records buffered while the journal file is written (1024 by default).
+    /// When the buffer is full, the new records are dropped rather than blocking the operations.
+    pub buffer_size: usize,
+    /// If `true`, the values are journaled in addition to their hashes, allowing [`Journal::replay()`]
+    /// to feed another store (`false` by default).
+    pub with_values: bool,
+}
+
+impl Default for JournalOptions {
+    fn default() -> Self {
+        JournalOptions {
+            max_file_size: 64 * 1024 * 1024,
+            max_files: 8,
+            buffer_size: 1024,
+            with_values: false,
+        }
+    }
+}
+
+/// Whether a journaled change was issued via the Workspace, or observed by one of its subscriptions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JournalOrigin {
+    Issued,
+    Observed,
+}
+
+impl fmt::Display for JournalOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JournalOrigin::Issued => write!(f, "issued"),
+            JournalOrigin::Observed => write!(f, "observed"),
+        }
+    }
+}
+
+/// A change recorded in a [`Journal`], returned by [`Journal::replay()`].
+#[derive(Clone, Debug)]
+pub struct JournalEntry {
+    /// the sequence number of the record, incremented for each record of the journal.
+    pub seq: u64,
+    /// whether the change was issued or observed.
+    pub origin: JournalOrigin,
+    /// the kind of the change.
+    pub kind: ChangeKind,
+    /// the absolute [`Path`] of the change.
+    pub path: Path,
+    /// the timestamp of the change, as a string.
+    pub timestamp: String,
+    /// the SHA-256 of the encoded value, in hexadecimal. `None` for a `DELETE`.
+    pub value_hash: Option<String>,
+    /// the value, if the journal was opened with [`JournalOptions::with_values`]. `None` for a `DELETE`.
+    pub value: Option<Value>,
+    /// the number of records dropped just before this one, because the buffer was full.
+    pub dropped: u64,
+}
+
+// A change to journal, processed by the writer thread
+struct Record {
+    origin: JournalOrigin,
+    path: String,
+    kind: ChangeKind,
+    encoding: ZInt,
+    payload: Option<ZBuf>,
+    timestamp: Option<Timestamp>,
+}
+
+/// A write-ahead log of the changes issued and observed by a Workspace, for auditing and recovery
+/// (see [`Workspace::with_journal()`](crate::Workspace::with_journal)).
+///
+/// The records are appended to the journal file by a dedicated thread: the operations only push them in a
+/// bounded buffer (see [`JournalOptions::buffer_size`]) and are never blocked by the file I/O. When the buffer
+/// is full, the new records are dropped (and a warning is logged): the next written record tells how many.
+/// The written records are synced to the disk as soon as the buffer is emptied, and the buffered records are
+/// written when the Journal is dropped.
+///
+/// # File format
+/// The journal file contains a JSON object per line, with the fields:
+///  * `"seq"`: the sequence number of the record
+///  * `"origin"`: `"issued"` or `"observed"`
+///  * `"kind"`: `"PUT"`, `"PATCH"` or `"DELETE"`
+///  * `"path"`: the absolute path of the change
+///  * `"timestamp"`: the timestamp of the change (or of its journaling, for an issued change which timestamp
+///    is assigned by the router)
+///  * `"value_hash"`: the SHA-256 of the encoded value, in hexadecimal (not for a `"DELETE"`)
+///  * `"encoding"` and `"payload"`: the encoding id and the base64 encoded value
+///    (only with [`JournalOptions::with_values`], and not for a `"DELETE"`)
+///  * `"dropped"`: the number of records dropped before this one (only if some were dropped)
+///  * `"prev"`: the SHA-256 of the previous line, in hexadecimal (empty for the very first record)
+///
+/// Each record is chained to the previous one by its `"prev"` hash, across the rotated files: modifying,
+/// inserting or removing a record breaks the chain, which is detected by [`Journal::replay()`].
+/// This is tamper-evident, not tamper-proof: someone able to rewrite the whole files can recompute the chain.
+///
+/// A last record torn by a crash while it was written (i.e. not terminated by a newline) is ignored by
+/// [`Journal::replay()`], and removed when the journal is opened again.
+///
+/// # Rotation
+/// When the journal file would exceed [`JournalOptions::max_file_size`], it's renamed with a `.1` suffix,
+/// the previously rotated files being shifted (`.1` to `.2`, etc.), and a new file is started.
+/// Only the [`JournalOptions::max_files`] most recent rotated files are kept.
+/// Opening an existing journal continues its sequence and its chain.
+///
+/// # Examples
+/// ```no_run
+/// # async_std::task::block_on(async {
+/// use zenoh::*;
+///
+/// let journal = Journal::open("/var/log/zenoh/journal.log", JournalOptions::default()).unwrap();
+/// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+/// let workspace = zenoh.workspace(None).await.unwrap().with_journal(journal);
+/// # })
+/// ```
+pub struct Journal {
+    sender: Option<SyncSender<Record>>,
+    dropped: Arc<AtomicU64>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl Journal {
+    /// Opens the journal file at `path` (creating it if needed), to append the records to it.
+    pub fn open(path: impl Into<PathBuf>, options: JournalOptions) -> ZResult<Journal> {
+        let path = path.into();
+        truncate_torn_record(&path)?;
+        // continue the sequence and the chain of an existing journal
+        let last = match last_line(&path)? {
+            Some(line) => Some(line),
+            None => last_line(&rotated(&path, 1))?,
+        };
+        let (seq, prev) = match last {
+            Some(line) => {
+                let json: serde_json::Value = serde_json::from_str(&line).map_err(|e| {
+                    zerror2!(ZErrorKind::IoError {
+                        descr: format!("Invalid last record in journal {}: {}", path.display(), e)
+                    })
+                })?;
+                (json["seq"].as_u64().unwrap_or(0) + 1, sha256_hex(&line))
+            }
+            None => (0, String::new()),
+        };
+        let file = open_append(&path)?;
+        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        let (sender, receiver) = sync_channel(options.buffer_size);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let mut writer = Writer {
+            path,
+            options,
+            file: BufWriter::new(file),
+            size,
+            seq,
+            prev,
+            dropped: dropped.clone(),
+        };
+        let writer = std::thread::Builder::new()
+            .name("zenoh-journal".to_string())
+            .spawn(move || writer.run(receiver))
+            .map_err(|e| {
+                zerror2!(ZErrorKind::IoError {
+                    descr: format!("Failed to start the journal writer: {}", e)
+                })
+            })?;
+        Ok(Journal {
+            sender: Some(sender),
+            dropped,
+            writer: Some(writer),
+        })
+    }
+
+    /// Reads all the records of the journal at `path` (including the rotated files, from the oldest),
+    /// verifying their chain. A torn last record (e.g. after a crash) is ignored with a warning.
+    /// Returns an error if a record was modified, inserted or removed, or if a file can't be read.
+    /// The entries can feed another store (e.g. putting their [`JournalEntry::value`] into zenoh,
+    /// if the journal was written [`JournalOptions::with_values`]).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// for entry in Journal::replay("/var/log/zenoh/journal.log").unwrap() {
+    ///     match (entry.kind, entry.value) {
+    ///         (ChangeKind::Delete, _) => workspace.delete(&entry.path).await.unwrap(),
+    ///         (_, Some(value)) => workspace.put(&entry.path, value).await.unwrap(),
+    ///         (_, None) => (),
+    ///     }
+    /// }
+    /// # })
+    /// ```
+    pub fn replay(path: impl Into<PathBuf>) -> ZResult<Vec<JournalEntry>> {
+        let path = path.into();
+        let mut files = vec![];
+        let mut i = 1;
+        while rotated(&path, i).exists() {
+            files.push(rotated(&path, i));
+            i += 1;
+        }
+        files.reverse();
+        files.push(path);
+
+        let mut entries = vec![];
+        // the oldest record's chain can't be verified (the files before it may have been removed)
+        let mut prev: Option<String> = None;
+        for file in files.iter().filter(|f| f.exists()) {
+            let mut reader = BufReader::new(File::open(file).map_err(|e| io_error(file, e))?);
+            let mut n = 0;
+            while let Some(line) = read_record(&mut reader, file)? {
+                n += 1;
+                let line = match line {
+                    Ok(line) => line,
+                    Err(torn) => {
+                        warn!(
+                            "Journal {} line {}: ignore the torn last record {:?}",
+                            file.display(),
+                            n,
+                            torn
+                        );
+                        break;
+                    }
+                };
+                let entry = parse_record(&line, prev.as_deref()).map_err(|descr| {
+                    zerror2!(ZErrorKind::Other {
+                        descr: format!("Journal {} line {}: {}", file.display(), n, descr)
+                    })
+                })?;
+                prev = Some(sha256_hex(&line));
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    // Pushes a change into the buffer, to be written by the writer thread. Drops it if the buffer is full.
+    pub(crate) fn record(
+        &self,
+        origin: JournalOrigin,
+        path: &str,
+        kind: ChangeKind,
+        encoding: ZInt,
+        payload: Option<&ZBuf>,
+        timestamp: Option<&Timestamp>,
+    ) {
+        let record = Record {
+            origin,
+            path: path.to_string(),
+            kind,
+            encoding,
+            payload: payload.cloned(),
+            timestamp: timestamp.cloned(),
+        };
+        if let Some(sender) = &self.sender {
+            match sender.try_send(record) {
+                Ok(()) => (),
+                Err(TrySendError::Full(record)) => {
+                    if self.dropped.fetch_add(1, Ordering::Relaxed) == 0 {
+                        warn!(
+                            "Journal buffer full: dropping records (first dropped: {} on {})",
+                            record.kind, record.path
+                        );
+                    }
+                }
+                Err(TrySendError::Disconnected(_)) => {
+                    warn!("Journal writer stopped: dropping record on {}", path)
+                }
+            }
+        }
+    }
+
+    // Journals a received Sample
+    pub(crate) fn record_sample(&self, origin: JournalOrigin, sample: &Sample) {
+        let info = sample.data_info.as_ref();
+        let kind = info
+            .and_then(|info| info.kind)
+            .map_or(ChangeKind::Put, ChangeKind::from);
+        let payload = match kind {
+            ChangeKind::Delete => None,
+            _ => Some(&sample.payload),
+        };
+        self.record(
+            origin,
+            &sample.res_name,
+            kind,
+            info.and_then(|info| info.encoding)
+                .unwrap_or(crate::net::encoding::APP_OCTET_STREAM),
+            payload,
+            info.and_then(|info| info.timestamp.as_ref()),
+        )
+    }
+}
+
+impl Drop for Journal {
+    fn drop(&mut self) {
+        // closing the buffer stops the writer once all the buffered records are written
+        drop(self.sender.take());
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+impl fmt::Debug for Journal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Journal")
+    }
+}
+
+// Writes the records into the journal file, in a dedicated thread
+struct Writer {
+    path: PathBuf,
+    options: JournalOptions,
+    file: BufWriter<File>,
+    size: u64,
+    seq: u64,
+    prev: String,
+    dropped: Arc<AtomicU64>,
+}
+
+impl Writer {
+    fn run(&mut self, receiver: Receiver<Record>) {
+        while let Ok(record) = receiver.recv() {
+            self.write(record);
+            // write all the buffered records before flushing
+            while let Ok(record) = receiver.try_recv() {
+                self.write(record);
+            }
+            if let Err(e) = self.sync() {
+                warn!("Failed to write journal {}: {}", self.path.display(), e);
+            }
+        }
+        trace!("Journal {} closed", self.path.display());
+    }
+
+    fn write(&mut self, record: Record) {
+        let mut json = serde_json::Map::new();
+        json.insert("seq".into(), self.seq.into());
+        json.insert("origin".into(), record.origin.to_string().into());
+        json.insert("kind".into(), record.kind.to_string().into());
+        json.insert("path".into(), record.path.into());
+        let timestamp = record.timestamp.unwrap_or_else(new_reception_timestamp);
+        json.insert("timestamp".into(), timestamp.to_string().into());
+        if let Some(payload) = record.payload {
+            let bytes = payload.to_vec();
+            json.insert(
+                "value_hash".into(),
+                hex::encode(Sha256::digest(&bytes)).into(),
+            );
+            if self.options.with_values {
+                json.insert("encoding".into(), record.encoding.into());
+                json.insert("payload".into(), base64::encode(&bytes).into());
+            }
+        }
+        let dropped = self.dropped.swap(0, Ordering::Relaxed);
+        if dropped > 0 {
+            json.insert("dropped".into(), dropped.into());
+        }
+        json.insert("prev".into(), self.prev.clone().into());
+        let line = serde_json::Value::from(json).to_string();
+
+        let len = line.len() as u64 + 1;
+        if self.size > 0 && self.size + len > self.options.max_file_size {
+            if let Err(e) = self.rotate() {
+                warn!("Failed to rotate journal {}: {}", self.path.display(), e);
+            }
+        }
+        match writeln!(self.file, "{}", line) {
+            Ok(()) => {
+                self.size += len;
+                self.seq += 1;
+                self.prev = sha256_hex(&line);
+            }
+            Err(e) => warn!("Failed to write journal {}: {}", self.path.display(), e),
+        }
+    }
+
+    // Flushes the written records, and syncs them to the disk
+    fn sync(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+        self.file.get_ref().sync_data()
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.sync()?;
+        for i in (1..self.options.max_files).rev() {
+            let from = rotated(&self.path, i);
+            if from.exists() {
+                std::fs::rename(from, rotated(&self.path, i + 1))?;
+            }
+        }
+        if self.options.max_files > 0 {
+            std::fs::rename(&self.path, rotated(&self.path, 1))?;
+        } else {
+            std::fs::remove_file(&self.path)?;
+        }
+        self.file = BufWriter::new(
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?,
+        );
+        self.size = 0;
+        trace!("Journal {} rotated", self.path.display());
+        Ok(())
+    }
+}
+
+// The path of the i-th rotated journal file
+fn rotated(path: &std::path::Path, i: usize) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(format!(".{}", i));
+    PathBuf::from(rotated)
+}
+
+fn open_append(path: &std::path::Path) -> ZResult<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| io_error(path, e))
+}
+
+// The last line of a file, if it exists and isn't empty (ignoring a torn last record)
+fn last_line(path: &std::path::Path) -> ZResult<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut reader = BufReader::new(File::open(path).map_err(|e| io_error(path, e))?);
+    let mut last = None;
+    while let Some(Ok(line)) = read_record(&mut reader, path)? {
+        last = Some(line);
+    }
+    Ok(last)
+}
+
+// Reads the next line of a journal file, without its newline. Returns Err with the read bytes (as a lossy string)
+// if the line isn't terminated by a newline, i.e. if it's a record torn by a crash while it was written.
+fn read_record(
+    reader: &mut impl BufRead,
+    path: &std::path::Path,
+) -> ZResult<Option<Result<String, String>>> {
+    let mut bytes = vec![];
+    if reader
+        .read_until(b'\n', &mut bytes)
+        .map_err(|e| io_error(path, e))?
+        == 0
+    {
+        return Ok(None);
+    }
+    if !bytes.ends_with(b"\n") {
+        return Ok(Some(Err(String::from_utf8_lossy(&bytes).into_owned())));
+    }
+    bytes.pop();
+    String::from_utf8(bytes)
+        .map(|line| Some(Ok(line)))
+        .map_err(|e| {
+            io_error(
+                path,
+                std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+            )
+        })
+}
+
+// Removes the torn last record of a journal file, if any, so that the next records are appended after
+// the last complete one
+fn truncate_torn_record(path: &std::path::Path) -> ZResult<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let content = std::fs::read(path).map_err(|e| io_error(path, e))?;
+    if content.is_empty() || content.ends_with(b"\n") {
+        return Ok(());
+    }
+    let len = content
+        .iter()
+        .rposition(|b| *b == b'\n')
+        .map_or(0, |i| i + 1);
+    warn!(
+        "Journal {}: remove the torn last record {:?}",
+        path.display(),
+        String::from_utf8_lossy(&content[len..])
+    );
+    OpenOptions::new()
+        .write(true)
+        .open(path)
+        .and_then(|file| {
+            file.set_len(len as u64)?;
+            file.sync_data()
+        })
+        .map_err(|e| io_error(path, e))
+}
+
+fn io_error(path: &std::path::Path, e: std::io::Error) -> ZError {
+    zerror2!(ZErrorKind::IoError {
+        descr: format!("Journal {}: {}", path.display(), e)
+    })
+}
+
+fn sha256_hex(line: &str) -> String {
+    hex::encode(Sha256::digest(line.as_bytes()))
+}
+
+// Parses a journal line, verifying it's chained to the line which hash is `prev` (if known)
+fn parse_record(line: &str, prev: Option<&str>) -> Result<JournalEntry, String> {
+    let json: serde_json::Value =
+        serde_json::from_str(line).map_err(|e| format!("invalid record ({})", e))?;
+    let field = |name: &str| {
+        json[name]
+            .as_str()
+            .ok_or_else(|| format!("missing field '{}'", name))
+    };
+    if let Some(prev) = prev {
+        if field("prev")? != prev {
+            return Err(
+                "the record isn't chained to the previous one (modified or removed)".into(),
+            );
+        }
+    }
+    let kind = match field("kind")? {
+        "PUT" => ChangeKind::Put,
+        "PATCH" => ChangeKind::Patch,
+        "DELETE" => ChangeKind::Delete,
+        kind => return Err(format!("invalid kind '{}'", kind)),
+    };
+    let origin = match field("origin")? {
+        "issued" => JournalOrigin::Issued,
+        "observed" => JournalOrigin::Observed,
+        origin => return Err(format!("invalid origin '{}'", origin)),
+    };
+    let value = match (json["encoding"].as_u64(), json["payload"].as_str()) {
+        (Some(encoding), Some(payload)) => {
+            let bytes = base64::decode(payload).map_err(|e| format!("invalid payload ({})", e))?;
+            Some(
+                Value::decode(encoding as ZInt, ZBuf::from(bytes))
+                    .map_err(|e| format!("invalid value ({})", e))?,
+            )
+        }
+        _ => None,
+    };
+    Ok(JournalEntry {
+        seq: json["seq"].as_u64().ok_or("missing field 'seq'")?,
+        origin,
+        kind,
+        path: Path::try_from(field("path")?).map_err(|e| e.to_string())?,
+        timestamp: field("timestamp")?.to_string(),
+        value_hash: json["value_hash"].as_str().map(String::from),
+        value,
+        dropped: json["dropped"].as_u64().unwrap_or(0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn journal_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "zenoh-journal-{}-{}.log",
+            name,
+            uuid::Uuid::new_v4()
+        ))
+    }
+
+    fn remove_journal(path: &std::path::Path) {
+        let _ = std::fs::remove_file(path);
+        for i in 1..10 {
+            let _ = std::fs::remove_file(rotated(path, i));
+        }
+    }
+
+    fn put(journal: &Journal, path: &str, value: &str) {
+        journal.record(
+            JournalOrigin::Issued,
+            path,
+            ChangeKind::Put,
+            crate::net::encoding::TEXT_PLAIN,
+            Some(&value.as_bytes().to_vec().into()),
+            None,
+        );
+    }
+
+    #[test]
+    fn journal_replay() {
+        let path = journal_path("replay");
+        let options = JournalOptions {
+            with_values: true,
+            ..Default::default()
+        };
+        let journal = Journal::open(&path, options.clone()).unwrap();
+        put(&journal, "/a/b", "v1");
+        journal.record(
+            JournalOrigin::Observed,
+            "/a/c",
+            ChangeKind::Delete,
+            crate::net::encoding::NONE,
+            None,
+            None,
+        );
+        drop(journal);
+        // reopening continues the sequence and the chain
+        let journal = Journal::open(&path, options).unwrap();
+        put(&journal, "/a/b", "v2");
+        drop(journal);
+
+        let entries = Journal::replay(&path).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            entries.iter().map(|e| e.seq).collect::<Vec<u64>>(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(entries[0].origin, JournalOrigin::Issued);
+        assert_eq!(entries[0].kind, ChangeKind::Put);
+        assert_eq!(entries[0].path.as_str(), "/a/b");
+        assert_eq!(entries[0].value, Some(Value::StringUtf8("v1".to_string())));
+        assert_eq!(
+            entries[0].value_hash.as_deref(),
+            Some(hex::encode(Sha256::digest(b"v1")).as_str())
+        );
+        assert_eq!(entries[1].origin, JournalOrigin::Observed);
+        assert_eq!(entries[1].kind, ChangeKind::Delete);
+        assert!(entries[1].value_hash.is_none() && entries[1].value.is_none());
+        assert_eq!(entries[2].value, Some(Value::StringUtf8("v2".to_string())));
+        remove_journal(&path);
+    }
+
+    #[test]
+    fn journal_rotation() {
+        let path = journal_path("rotation");
+        let journal = Journal::open(
+            &path,
+            JournalOptions {
+                max_file_size: 1024,
+                max_files: 3,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        for i in 0..100 {
+            put(&journal, "/a/b", &i.to_string());
+        }
+        drop(journal);
+
+        assert!(rotated(&path, 3).exists());
+        assert!(!rotated(&path, 4).exists());
+        for i in 1..=3 {
+            assert!(std::fs::metadata(rotated(&path, i)).unwrap().len() <= 1024);
+        }
+        // the oldest records were removed, the others are still chained
+        let entries = Journal::replay(&path).unwrap();
+        assert!(!entries.is_empty() && entries.len() < 100);
+        assert_eq!(entries.last().unwrap().seq, 99);
+        for (e1, e2) in entries.iter().zip(entries.iter().skip(1)) {
+            assert_eq!(e1.seq + 1, e2.seq);
+        }
+        remove_journal(&path);
+    }
+
+    #[test]
+    fn journal_tampering() {
+        let path = journal_path("tampering");
+        let journal = Journal::open(&path, JournalOptions::default()).unwrap();
+        for i in 0..5 {
+            put(&journal, "/a/b", &i.to_string());
+        }
+        drop(journal);
+        assert_eq!(Journal::replay(&path).unwrap().len(), 5);
+        let lines: Vec<String> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect();
+
+        // modified record
+        let mut modified = lines.clone();
+        modified[2] = modified[2].replace("/a/b", "/a/x");
+        std::fs::write(&path, modified.join("\n") + "\n").unwrap();
+        assert!(Journal::replay(&path).is_err());
+
+        // removed record
+        let mut removed = lines.clone();
+        removed.remove(2);
+        std::fs::write(&path, removed.join("\n") + "\n").unwrap();
+        assert!(Journal::replay(&path).is_err());
+
+        // the last record can't be verified by the chain, but the others are
+        let mut truncated = lines;
+        truncated.pop();
+        std::fs::write(&path, truncated.join("\n") + "\n").unwrap();
+        assert_eq!(Journal::replay(&path).unwrap().len(), 4);
+        remove_journal(&path);
+    }
+
+    #[test]
+    fn journal_torn_record() {
+        let path = journal_path("torn");
+        let journal = Journal::open(&path, JournalOptions::default()).unwrap();
+        for i in 0..3 {
+            put(&journal, "/a/b", &i.to_string());
+        }
+        drop(journal);
+        // a crash while writing the 4th record
+        let mut content = std::fs::read(&path).unwrap();
+        content.extend_from_slice(br#"{"seq":3,"origin":"iss"#);
+        std::fs::write(&path, &content).unwrap();
+
+        // the torn record is ignored by replay
+        assert_eq!(Journal::replay(&path).unwrap().len(), 3);
+        // and removed by open, the new records being chained to the last complete one
+        let journal = Journal::open(&path, JournalOptions::default()).unwrap();
+        put(&journal, "/a/b", "3");
+        drop(journal);
+        let entries = Journal::replay(&path).unwrap();
+        assert_eq!(
+            entries.iter().map(|e| e.seq).collect::<Vec<u64>>(),
+            vec![0, 1, 2, 3]
+        );
+        remove_journal(&path);
+    }
+}
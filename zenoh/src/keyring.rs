@@ -0,0 +1,326 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use crate::net::encoding::APP_ENCRYPTED;
+use crate::net::{WBuf, ZBuf};
+use crate::Value;
+use rand::{RngCore, SeedableRng};
+use std::collections::HashMap;
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use zenoh_util::core::{ZError, ZErrorKind, ZResult};
+use zenoh_util::crypto::{hmac, BlockCipher, PseudoRng};
+use zenoh_util::{zerror, zerror2};
+
+/// The size in bytes of the keys of a [`Keyring`]: the first 16 bytes are the AES-128 key encrypting
+/// the values, the last 16 bytes are the HMAC-SHA3-256 key authenticating them.
+pub const KEYRING_KEY_SIZE: usize = 32;
+
+const NONCE_SIZE: usize = BlockCipher::BLOCK_SIZE;
+const MAC_SIZE: usize = 32;
+
+/// A set of named keys to encrypt the [`Value`]s end-to-end, so that the routers and the storages
+/// (and any other node without the key) only see an opaque ciphertext.
+///
+/// An encrypted Value is a [`Value::Raw`] with the [`APP_ENCRYPTED`] encoding. Its payload is made of the key id
+/// and the original encoding (both in clear), followed by a random nonce, the encoded Value encrypted with
+/// AES-128 in CTR mode, and an HMAC-SHA3-256 of all of this (so that a modified payload is rejected).
+/// The routers route it and the storages store it as any other Value, without being able to decrypt it.
+///
+/// A Workspace uses the keyring configured with the `"keyring"` property (see
+/// [`ZN_KEYRING_KEY`](crate::net::config::ZN_KEYRING_KEY)) or set with
+/// [`Workspace::with_keyring()`](crate::Workspace::with_keyring), to encrypt the values put with
+/// [`Workspace::put_encrypted()`](crate::Workspace::put_encrypted) and to decrypt the values it receives
+/// (subscriptions and gets). The values encrypted with a key that the keyring doesn't have are received
+/// as is, still encrypted.
+///
+/// The keys are not exchanged by zenoh: it's up to the application to generate them (e.g. with
+/// [`Keyring::generate_key()`]), to distribute them out of zenoh to the authorized publishers and subscribers
+/// only, and to rotate them (adding a new key id, and removing the old one once not used anymore).
+/// Only the nodes having the key can decrypt the values encrypted with it.
+/// Note that the paths, the timestamps and the sizes of the values are not encrypted.
+///
+/// # Examples
+/// ```
+/// use zenoh::*;
+///
+/// let mut keyring = Keyring::new();
+/// keyring.insert("tenant-a", Keyring::generate_key());
+/// let encrypted = keyring.encrypt("tenant-a", Value::Integer(42)).unwrap();
+/// assert_eq!(encrypted.encoding(), net::encoding::APP_ENCRYPTED);
+/// assert_eq!(keyring.decrypt(&encrypted).unwrap(), Value::Integer(42));
+/// assert!(Keyring::new().decrypt(&encrypted).is_err());
+/// ```
+#[derive(Clone, Default)]
+pub struct Keyring {
+    keys: HashMap<String, [u8; KEYRING_KEY_SIZE]>,
+}
+
+impl Keyring {
+    /// Creates an empty Keyring.
+    pub fn new() -> Keyring {
+        Keyring::default()
+    }
+
+    /// Reads a Keyring from a file, containing a key per line as `<key id>=<key>`, the key being
+    /// [`KEYRING_KEY_SIZE`] bytes in hexadecimal. The empty lines and the lines starting with `#` are ignored.
+    ///
+    /// The keyring file configured with the `"keyring"` property is read when a Workspace is created:
+    /// an unreadable or invalid file fails its creation. Such a file must only be distributed to the
+    /// publishers and subscribers authorized to read the values encrypted with its keys, and be readable
+    /// only by them.
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> ZResult<Keyring> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            zerror2!(ZErrorKind::IoError {
+                descr: format!("Failed to read keyring file {}: {}", path.display(), e)
+            })
+        })?;
+        content.parse()
+    }
+
+    /// Generates a new random key.
+    pub fn generate_key() -> [u8; KEYRING_KEY_SIZE] {
+        let mut key = [0u8; KEYRING_KEY_SIZE];
+        PseudoRng::from_entropy().fill_bytes(&mut key);
+        key
+    }
+
+    /// Adds a key, replacing the key with the same id if any.
+    pub fn insert(&mut self, key_id: &str, key: [u8; KEYRING_KEY_SIZE]) {
+        self.keys.insert(key_id.to_string(), key);
+    }
+
+    /// Removes a key.
+    pub fn remove(&mut self, key_id: &str) {
+        self.keys.remove(key_id);
+    }
+
+    /// Returns true if this Keyring has a key with the id `key_id`.
+    pub fn contains(&self, key_id: &str) -> bool {
+        self.keys.contains_key(key_id)
+    }
+
+    fn key(&self, key_id: &str) -> ZResult<&[u8; KEYRING_KEY_SIZE]> {
+        self.keys.get(key_id).ok_or_else(|| {
+            zerror2!(ZErrorKind::Other {
+                descr: format!("No key '{}' in the keyring", key_id)
+            })
+        })
+    }
+
+    /// Encrypts `value` with the key `key_id`, returning a [`Value::Raw`] with the [`APP_ENCRYPTED`] encoding.  
+    /// Returns an error if this Keyring has no key `key_id`.
+    pub fn encrypt(&self, key_id: &str, value: Value) -> ZResult<Value> {
+        let key = self.key(key_id)?;
+        let (encoding, payload) = value.encode();
+
+        let mut header = WBuf::new(64, false);
+        header.write_string(key_id);
+        header.write_zint(encoding);
+        let mut bytes = ZBuf::from(header).to_vec();
+        let mut nonce = [0u8; NONCE_SIZE];
+        PseudoRng::from_entropy().fill_bytes(&mut nonce);
+        bytes.extend_from_slice(&nonce);
+        bytes.extend(apply_keystream(key, &nonce, payload.to_vec()));
+        let mac = hmac::sign(&key[NONCE_SIZE..], &bytes)?;
+        bytes.extend(mac);
+        Ok(Value::Raw(APP_ENCRYPTED, bytes.into()))
+    }
+
+    /// Decrypts a Value encrypted with [`Keyring::encrypt()`], returning the original Value.  
+    /// Returns an error if `value` is not encrypted, if this Keyring has not the key it's encrypted with,
+    /// or if it has been modified.
+    pub fn decrypt(&self, value: &Value) -> ZResult<Value> {
+        let payload = match value {
+            Value::Raw(APP_ENCRYPTED, payload) => payload,
+            _ => {
+                return zerror!(ZErrorKind::ValueDecodingFailed {
+                    descr: format!(
+                        "Expected an encrypted Value but got a Value with encoding '{}'",
+                        value.encoding_descr()
+                    )
+                })
+            }
+        };
+        let bytes = payload.to_vec();
+        let mut header = ZBuf::from(bytes.clone());
+        let (key_id, encoding) = match (header.read_string(), header.read_zint()) {
+            (Some(key_id), Some(encoding)) if header.readable() >= NONCE_SIZE + MAC_SIZE => {
+                (key_id, encoding)
+            }
+            _ => {
+                return zerror!(ZErrorKind::ValueDecodingFailed {
+                    descr: "Malformed encrypted Value".to_string()
+                })
+            }
+        };
+        let key = self.key(&key_id)?;
+
+        let (signed, mac) = bytes.split_at(bytes.len() - MAC_SIZE);
+        let expected = hmac::sign(&key[NONCE_SIZE..], signed)?;
+        // compare in constant time, not to leak the position of the first difference
+        if expected.len() != mac.len()
+            || expected
+                .iter()
+                .zip(mac)
+                .fold(0, |acc, (a, b)| acc | (a ^ b))
+                != 0
+        {
+            return zerror!(ZErrorKind::ValueDecodingFailed {
+                descr: format!(
+                    "Failed to authenticate a Value encrypted with the key '{}'",
+                    key_id
+                )
+            });
+        }
+        let start = bytes.len() - header.readable();
+        let nonce: [u8; NONCE_SIZE] = signed[start..start + NONCE_SIZE].try_into().unwrap();
+        let data = apply_keystream(key, &nonce, signed[start + NONCE_SIZE..].to_vec());
+        Value::decode(encoding, data.into())
+    }
+}
+
+impl std::str::FromStr for Keyring {
+    type Err = ZError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut keyring = Keyring::new();
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key_id, key) = line
+                .split_once('=')
+                .map(|(id, key)| (id.trim(), key.trim()))
+                .filter(|(id, _)| !id.is_empty())
+                .ok_or_else(|| {
+                    zerror2!(ZErrorKind::Other {
+                        descr: "Invalid keyring line: expected '<key id>=<key>'".to_string()
+                    })
+                })?;
+            let key = hex::decode(key)
+                .ok()
+                .and_then(|key| <[u8; KEYRING_KEY_SIZE]>::try_from(key).ok())
+                .ok_or_else(|| {
+                    zerror2!(ZErrorKind::Other {
+                        descr: format!(
+                            "Invalid key '{}' in keyring: expected {} bytes in hexadecimal",
+                            key_id, KEYRING_KEY_SIZE
+                        )
+                    })
+                })?;
+            keyring.insert(key_id, key);
+        }
+        Ok(keyring)
+    }
+}
+
+impl fmt::Debug for Keyring {
+    // Displays the key ids only, never the keys
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut ids: Vec<&String> = self.keys.keys().collect();
+        ids.sort();
+        f.debug_struct("Keyring").field("keys", &ids).finish()
+    }
+}
+
+// Encrypts or decrypts `data` with AES-128 in CTR mode, the counter starting at `nonce`
+fn apply_keystream(
+    key: &[u8; KEYRING_KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+    mut data: Vec<u8>,
+) -> Vec<u8> {
+    let cipher = BlockCipher::new(key[..NONCE_SIZE].try_into().unwrap());
+    let counter = u128::from_be_bytes(*nonce);
+    let blocks = (data.len() + NONCE_SIZE - 1) / NONCE_SIZE;
+    let mut keystream = Vec::with_capacity(blocks * NONCE_SIZE);
+    for i in 0..blocks {
+        keystream.extend_from_slice(&counter.wrapping_add(i as u128).to_be_bytes());
+    }
+    // the keystream is a multiple of the block size: the cipher adds no padding
+    let keystream = cipher.encrypt(keystream, &mut PseudoRng::from_entropy());
+    for (byte, k) in data.iter_mut().zip(keystream) {
+        *byte ^= k;
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyring_encrypt_decrypt() {
+        let mut keyring = Keyring::new();
+        keyring.insert("k1", Keyring::generate_key());
+        keyring.insert("k2", Keyring::generate_key());
+
+        for value in &[
+            Value::Integer(42),
+            Value::StringUtf8(String::new()),
+            Value::Json(r#"{"secret": "a somewhat longer string than a block"}"#.to_string()),
+            Value::Raw(12345, vec![0u8; 1000].into()),
+        ] {
+            let encrypted = keyring.encrypt("k1", value.clone()).unwrap();
+            assert_eq!(encrypted.encoding(), APP_ENCRYPTED);
+            assert_eq!(keyring.decrypt(&encrypted).unwrap(), *value);
+        }
+
+        // the same value gives different ciphertexts
+        let e1 = keyring.encrypt("k1", Value::Integer(1)).unwrap();
+        let e2 = keyring.encrypt("k1", Value::Integer(1)).unwrap();
+        assert_ne!(e1, e2);
+
+        // unknown key
+        assert!(keyring.encrypt("k3", Value::Integer(1)).is_err());
+        let mut other = Keyring::new();
+        other.insert("k2", Keyring::generate_key());
+        assert!(other.decrypt(&e1).is_err());
+        // same key id, different key
+        let e3 = keyring.encrypt("k2", Value::Integer(1)).unwrap();
+        assert!(other.decrypt(&e3).is_err());
+
+        // modified payload
+        if let Value::Raw(encoding, payload) = e1 {
+            let mut bytes = payload.to_vec();
+            let last = bytes.len() - MAC_SIZE - 1;
+            bytes[last] ^= 0x01;
+            assert!(keyring
+                .decrypt(&Value::Raw(encoding, bytes.into()))
+                .is_err());
+        }
+
+        // not encrypted
+        assert!(keyring.decrypt(&Value::Integer(1)).is_err());
+    }
+
+    #[test]
+    fn keyring_from_str() {
+        let key = "00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff";
+        let keyring: Keyring = format!("# comment\n\nk1={}\n k2 = {} \n", key, key)
+            .parse()
+            .unwrap();
+        assert!(keyring.contains("k1"));
+        assert!(keyring.contains("k2"));
+        assert_eq!(
+            format!("{:?}", keyring),
+            r#"Keyring { keys: ["k1", "k2"] }"#
+        );
+
+        assert!("k1".parse::<Keyring>().is_err());
+        assert!("=00".parse::<Keyring>().is_err());
+        assert!("k1=0011".parse::<Keyring>().is_err());
+        assert!("k1=zz".parse::<Keyring>().is_err());
+    }
+}
@@ -92,7 +92,9 @@ extern crate zenoh_util;
 extern crate async_std;
 extern crate uuid;
 
+use async_std::sync::Arc;
 use log::debug;
+use std::time::Duration;
 
 pub mod net;
 
@@ -109,17 +111,77 @@ pub use path::{path, Path};
 mod pathexpr;
 pub use pathexpr::{pathexpr, PathExpr};
 mod selector;
-pub use selector::{selector, Selector};
+pub use selector::{
+    selector, GetMethod, Selector, TimeRange, Transform, PROP_ACCEPT, PROP_AFTER, PROP_APPEND,
+    PROP_AT, PROP_INCREMENT, PROP_LIMIT, PROP_METHOD, PROP_TARGET, PROP_TIME, PROP_TRANSFORM,
+};
 mod values;
 pub use values::*;
+#[cfg(feature = "arrow")]
+mod value_batch;
+#[cfg(feature = "arrow")]
+pub use value_batch::{ValueBatch, VALUE_COLUMN};
+mod sample;
+pub use sample::Sample;
+mod checksum;
+pub use checksum::{ChecksumAlgo, IntegrityError};
+mod delta;
+pub use delta::{DeltaDecoder, DeltaPublisher, DELTA_BLOCK_SIZE};
+mod value_stream;
+pub use value_stream::{ValueReader, STREAM_CHUNKS_SEGMENT};
+mod keyring;
+pub use keyring::{Keyring, KEYRING_KEY_SIZE};
+mod journal;
+pub use journal::{Journal, JournalEntry, JournalOptions, JournalOrigin};
+mod change_stream;
+pub use change_stream::{
+    ChangeStreamExt, DedupById, DeltaDecode, DetectGaps, Heartbeat, History, JsonField, NetEffect,
+    OnWatermark, OrderedPerPath, PathRemap, Reduce, Remap, Snapshot, SnapshotOnTick, Watermark,
+};
 
 // pub mod config;
+pub mod sync;
 pub mod utils;
 
+/// The errors of the zenoh API.
+///
+/// All the operations return a [`ZResult`], whose [`ZError`] gives the cause of a failure
+/// via [`ZError::get_kind()`]. A caller can `match` on this [`ZErrorKind`] to tell the causes apart:
+///  * [`ZErrorKind::InvalidPath`], [`ZErrorKind::InvalidPathExpr`] and [`ZErrorKind::InvalidSelector`]
+///    carry the offending string
+///  * [`ZErrorKind::NoRouter`], [`ZErrorKind::InvalidLocator`], [`ZErrorKind::InvalidLink`] and
+///    [`ZErrorKind::IoError`] are transport failures (e.g. when opening the session)
+///  * [`ZErrorKind::Timeout`] and [`ZErrorKind::Cancelled`] for an operation which didn't complete
+///  * [`ZErrorKind::StorageError`] for an operation rejected by a storage (e.g. [`Workspace::increment()`])
+///  * [`ZErrorKind::ValueEncodingFailed`], [`ZErrorKind::ValueDecodingFailed`] and [`ZErrorKind::ValueTooLarge`]
+///    for a [`Value`] which can't be sent or received
+///
+/// The underlying error (e.g. an [`std::io::Error`]) is available via [`std::error::Error::source()`].
+///
+/// # Examples
+/// ```
+/// use zenoh::Path;
+/// use zenoh::error::ZErrorKind;
+/// use std::convert::TryFrom;
+///
+/// match Path::try_from("/demo/*") {
+///     Ok(path) => println!("{}", path),
+///     Err(e) => match e.get_kind() {
+///         ZErrorKind::InvalidPath { path } => println!("invalid path: {}", path),
+///         _ => println!("error: {}", e),
+///     },
+/// }
+/// ```
+pub mod error {
+    pub use crate::net::{ZError, ZErrorKind, ZResult};
+}
+
 pub use net::protocol::core::{Timestamp, TimestampId};
+pub use uuid::Uuid;
 pub use zenoh_util::properties::config::ConfigProperties;
 pub use zenoh_util::properties::Properties;
 pub use zenoh_util::sync::zpinbox;
+pub use zenoh_util::sync::CancellationToken;
 
 /// The zenoh client API.
 pub struct Zenoh {
@@ -176,10 +238,32 @@ impl Zenoh {
     /// let zenoh = Zenoh::new(config.into()).await.unwrap();
     /// # })
     /// ```
+    ///
+    /// # Client mode
+    ///
+    /// In `"client"` mode the zenoh API requires a connection to a router.
+    /// If no router is reachable, this operation fails with a [`ZErrorKind::NoRouter`] error,
+    /// rather than returning a session on which `get` and `subscribe` would silently receive nothing.
+    /// In `"peer"` mode the session is successfully opened even if no other peer is reachable.
     pub fn new(config: ConfigProperties) -> impl ZFuture<Output = ZResult<Zenoh>> {
+        Zenoh::new_with_transports(config, vec![])
+    }
+
+    /// Creates a zenoh API, establishing a zenoh-net session with discovered peers and/or routers,
+    /// and using the given [`TransportFactory`](net::link::TransportFactory)s in addition to (or in place of)
+    /// the built-in transports.
+    ///
+    /// This is for advanced use cases such as testing with an in-memory transport, or simulating
+    /// latency or losses with an instrumented one. A factory implements a protocol (e.g. `"mem"`)
+    /// that can then be used in the `"peer"` and `"listener"` locators (e.g. `"mem/test"`),
+    /// or it replaces a built-in protocol (e.g. `"tcp"`). See [`Zenoh::new()`] for the `config`.
+    pub fn new_with_transports(
+        config: ConfigProperties,
+        transports: Vec<Arc<dyn net::link::TransportFactory>>,
+    ) -> impl ZFuture<Output = ZResult<Zenoh>> {
         zpinbox(async {
             Ok(Zenoh {
-                session: net::open(config).await?,
+                session: net::open_with_transports(config, transports).await?,
             })
         })
     }
@@ -202,6 +286,91 @@ impl Zenoh {
         &self.session
     }
 
+    /// Returns the PeerId of this zenoh instance, as an hexadecimal string.  
+    /// This is a random id, unless configured with [`ZN_PEER_ID_KEY`](net::config::ZN_PEER_ID_KEY)
+    /// (e.g. to correlate a node across its restarts). Note that the uniqueness of a configured id is not
+    /// enforced: two nodes with a same id in a same system will disrupt the routing between them.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use zenoh::net::config::*;
+    ///
+    /// let mut config = net::config::default();
+    /// config.insert(ZN_PEER_ID_KEY, "a0b1c2d3e4f5".to_string());
+    /// let zenoh = Zenoh::new(config).await.unwrap();
+    /// assert_eq!(zenoh.id(), "A0B1C2D3E4F5");
+    /// # })
+    /// ```
+    pub fn id(&self) -> String {
+        self.session.runtime.get_pid_str()
+    }
+
+    /// Returns the nodes traversed by a `get` on `selector` to reach the matching queryables (e.g. storages
+    /// or evals), as known by the routing tables of this zenoh instance. Each [`Hop`](net::Hop) tells the node
+    /// forwarding to it and its distance, the responders being flagged. Only the path expression of
+    /// `selector` is considered.  
+    /// Like a traceroute for the zenoh routing, but computed locally without sending any message:
+    /// e.g. to understand why a `get` unexpectedly returns nothing. A client only knows its router.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// for hop in zenoh.trace_route(&"/demo/example/**".try_into().unwrap()).unwrap() {
+    ///     println!("{} -> {} ({} hops, responder: {})", hop.from, hop.pid, hop.distance, hop.responder);
+    /// }
+    /// # })
+    /// ```
+    pub fn trace_route(&self, selector: &Selector) -> ZResult<Vec<net::Hop>> {
+        self.session
+            .trace_route(&net::ResKey::from(selector.path_expr.included()))
+    }
+
+    /// Updates at runtime the configuration of this zenoh instance, without restart.  
+    /// The keys of `config` whose value differs from the current configuration are applied if they're
+    /// hot-reloadable, the other ones being ignored. The keys missing in `config` are considered as set to
+    /// their default value. The returned [`ReloadReport`](net::runtime::ReloadReport) lists the applied keys
+    /// and the keys requiring a restart.
+    ///
+    /// The hot-reloadable keys, in peer and router modes only, are:
+    ///  * [`ZN_PEER_KEY`](net::config::ZN_PEER_KEY): the added peers are connected, retrying in background as at
+    ///    startup (their connection failures are reported by [`Zenoh::startup_warnings()`]).
+    ///  * [`ZN_LISTENER_KEY`](net::config::ZN_LISTENER_KEY): the added listeners are opened.
+    ///
+    /// Removing a peer or a listener requires a restart, as changing any other key does.
+    /// Note that the log level is not part of the configuration: it is set with the `RUST_LOG` environment variable.
+    ///
+    /// Returns an error if a peer or listener is not a valid locator or has a protocol disabled by
+    /// [`ZN_ENABLED_TRANSPORTS_KEY`](net::config::ZN_ENABLED_TRANSPORTS_KEY) (and then nothing is applied),
+    /// or if a listener can't be opened.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use zenoh::net::config::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::peer()).await.unwrap();
+    /// let mut config = net::config::peer();
+    /// config.insert(ZN_PEER_KEY, "tcp/127.0.0.1:7447".to_string());
+    /// let report = zenoh.reload_config(&config).await.unwrap();
+    /// assert_eq!(report.applied, vec![ZN_PEER_STR.to_string()]);
+    /// # })
+    /// ```
+    pub fn reload_config(
+        &self,
+        config: &ConfigProperties,
+    ) -> impl ZFuture<Output = ZResult<net::runtime::ReloadReport>> {
+        let runtime = self.session.runtime.clone();
+        let config = config.clone();
+        zpinbox(async move { runtime.reload_config(&config).await })
+    }
+
     /// Returns the PeerId of the zenoh router this zenoh API is connected to (if any).
     /// This calls [Session::info()](net::Session::info) and returns the first router pid from
     /// the ZN_INFO_ROUTER_PID_KEY property.
@@ -216,6 +385,88 @@ impl Zenoh {
         )
     }
 
+    /// Returns the [`Health`](net::health::Health) of the internal tasks of zenoh (transport links, scouting),
+    /// allowing to detect if some of them are stalled (e.g. a session that is alive but where nothing flows).  
+    /// The threshold above which a task without progress is considered as stalled is configured with
+    /// [`ZN_STALL_THRESHOLD_KEY`](net::config::ZN_STALL_THRESHOLD_KEY). A watchdog periodically logging
+    /// the stalled tasks can also be enabled with [`ZN_WATCHDOG_KEY`](net::config::ZN_WATCHDOG_KEY).
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let health = zenoh.health();
+    /// for task in health.stalled() {
+    ///     println!("{} stalled for {:?}", task.name, task.since_last_progress);
+    /// }
+    /// # })
+    /// ```
+    pub fn health(&self) -> net::health::Health {
+        net::health::health(net::health::stall_threshold(&self.session.runtime.config))
+    }
+
+    /// Returns the failures of the configured listeners and peers encountered at the startup of zenoh,
+    /// which didn't prevent it to start.
+    ///
+    /// By default, the startup fails if a configured listener can't be opened. With the
+    /// [`ZN_STRICT_STARTUP_KEY`](net::config::ZN_STRICT_STARTUP_KEY) property set to `"false"`, zenoh starts
+    /// anyway in a degraded mode and the failed listeners are reported here.  
+    /// The configured peers that can't be connected at the first attempt are always reported here
+    /// (in peer and router modes they keep being connected in background). Note that as these connections are
+    /// asynchronous, such warnings may be added after the startup.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use zenoh::net::config::*;
+    ///
+    /// let mut config = net::config::default();
+    /// config.insert(ZN_STRICT_STARTUP_KEY, "false".to_string());
+    /// let zenoh = Zenoh::new(config).await.unwrap();
+    /// for warning in zenoh.startup_warnings() {
+    ///     println!("Degraded startup: {}", warning);
+    /// }
+    /// # })
+    /// ```
+    pub fn startup_warnings(&self) -> Vec<net::runtime::ConfigWarning> {
+        self.session.runtime.startup_warnings.get()
+    }
+
+    /// Returns the clock skews detected in the timestamps of the data received by zenoh, per source.
+    ///
+    /// With the [`ZN_MAX_CLOCK_DRIFT_KEY`](net::config::ZN_MAX_CLOCK_DRIFT_KEY) property set (and the data
+    /// timestamped via [`ZN_ADD_TIMESTAMP_KEY`](net::config::ZN_ADD_TIMESTAMP_KEY)), the timestamps further
+    /// ahead of the local clock than this maximum drift are clamped to the local time, and reported here.
+    /// Such a timestamp (e.g. from a sensor with a misconfigured clock) could otherwise win over all the later
+    /// updates from the correctly clocked nodes in a last-writer-wins storage. The data is still delivered,
+    /// rather than rejected, so a misconfigured publisher is degraded but not silenced.
+    ///
+    /// Note that the HLC rejects the timestamps further ahead than its own maximum delta (100 ms by default,
+    /// configured with the `UHLC_MAX_DELTA_MS` environment variable): the maximum drift should be lower
+    /// for those timestamps to be clamped rather than dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use zenoh::net::config::*;
+    ///
+    /// let mut config = net::config::default();
+    /// config.insert(ZN_ADD_TIMESTAMP_KEY, "true".to_string());
+    /// config.insert(ZN_MAX_CLOCK_DRIFT_KEY, "50".to_string());
+    /// let zenoh = Zenoh::new(config).await.unwrap();
+    /// for skew in zenoh.clock_skews() {
+    ///     println!("{} is up to {:?} ahead ({} clamped timestamps)", skew.source, skew.max_skew, skew.clamped);
+    /// }
+    /// # })
+    /// ```
+    pub fn clock_skews(&self) -> Vec<net::runtime::ClockSkew> {
+        self.session.runtime.clock_skews.get()
+    }
+
     /// Creates a [`Workspace`] with an optional [`Path`] as `prefix`.
     /// All relative [`Path`] or [`Selector`] used with this Workspace will be relative to the
     /// specified prefix. Not specifying a prefix is equivalent to specifying "/" as prefix,
@@ -247,6 +498,12 @@ impl Zenoh {
     /// But you may want to use this function to handle errors or
     /// close the session synchronously.
     ///
+    /// Closing on drop is only best-effort: the closure is spawned as a task and the drop waits
+    /// for it at most 1 second (configurable at build time with the `API_CLOSE_ON_DROP_TIMEOUT`
+    /// environment variable, in milliseconds). Its errors are only logged, and if the program exits
+    /// right after the drop the closure may not complete (letting the peers expire this session's
+    /// declarations after the lease period).
+    ///
     /// # Examples
     /// ```
     /// # async_std::task::block_on(async {
@@ -259,6 +516,23 @@ impl Zenoh {
     pub fn close(self) -> impl ZFuture<Output = ZResult<()>> {
         self.session.close()
     }
+
+    /// Closes the zenoh API and the associated zenoh-net session, failing with a [`ZErrorKind::Timeout`]
+    /// error if it's not closed within `timeout`. In such case, the closure continues in background.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::time::Duration;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// zenoh.close_timeout(Duration::from_secs(1)).await.unwrap();
+    /// # })
+    /// ```
+    pub fn close_timeout(self, timeout: Duration) -> impl ZFuture<Output = ZResult<()>> {
+        self.session.close_timeout(timeout)
+    }
 }
 
 impl From<Session> for Zenoh {
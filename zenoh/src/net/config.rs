@@ -54,6 +54,11 @@ pub fn peer() -> ConfigProperties {
 ///
 /// If the given peer locator is not `None`, the returned configuration also contains :
 ///  - `(ZN_PEER_KEY, <peer>)`
+///
+/// Unlike in `'peer'` mode, where a session can be opened without any other reachable peer,
+/// a `'client'` mode session requires a connection to a router: if no router can be reached
+/// (among the configured peers, or via scouting within the scouting timeout) opening the session
+/// fails with a [`ZErrorKind::NoRouter`](crate::net::ZErrorKind::NoRouter) error.
 pub fn client(peer: Option<String>) -> ConfigProperties {
     let mut props = ConfigProperties::default();
     props.insert(ZN_MODE_KEY, "client".to_string());
@@ -0,0 +1,159 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+
+//! Health monitoring of the internal tasks of zenoh (transport links, scouting).
+//!
+//! Each monitored task reports its progress with a cheap heartbeat (an atomic store).
+//! A task is considered as stalled if it didn't report any progress since a threshold
+//! (see [`ZN_STALL_THRESHOLD_KEY`]).
+//! The health is computed on demand with [`health()`], and an optional watchdog periodically
+//! logs the stalled tasks (see [`ZN_WATCHDOG_KEY`](super::config::ZN_WATCHDOG_KEY)).
+//!
+//! Note that the monitored tasks are those of all the zenoh sessions of the process.
+use super::config::{ConfigProperties, ZN_STALL_THRESHOLD_DEFAULT, ZN_STALL_THRESHOLD_KEY};
+use async_std::task;
+use log::warn;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+struct TaskState {
+    name: String,
+    // the time of the last progress, in milliseconds since EPOCH
+    last_progress: AtomicU64,
+}
+
+lazy_static! {
+    static ref EPOCH: Instant = Instant::now();
+    static ref TASKS: Mutex<HashMap<usize, Arc<TaskState>>> = Mutex::new(HashMap::new());
+}
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+static WATCHDOG_STARTED: AtomicBool = AtomicBool::new(false);
+
+#[inline]
+fn now_ms() -> u64 {
+    EPOCH.elapsed().as_millis() as u64
+}
+
+/// A heartbeat reporting the progress of an internal task.
+/// The task is monitored until the heartbeat is dropped.
+pub(crate) struct Heartbeat {
+    id: usize,
+    state: Arc<TaskState>,
+}
+
+impl Heartbeat {
+    pub(crate) fn new(name: String) -> Heartbeat {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let state = Arc::new(TaskState {
+            name,
+            last_progress: AtomicU64::new(now_ms()),
+        });
+        zlock!(TASKS).insert(id, state.clone());
+        Heartbeat { id, state }
+    }
+
+    /// Reports a progress of the task.
+    #[inline]
+    pub(crate) fn beat(&self) {
+        self.state.last_progress.store(now_ms(), Ordering::Relaxed);
+    }
+}
+
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        zlock!(TASKS).remove(&self.id);
+    }
+}
+
+/// The health of an internal task.
+#[derive(Clone, Debug)]
+pub struct TaskHealth {
+    /// the name of the task (e.g. `"tx tcp/127.0.0.1:7447"`).
+    pub name: String,
+    /// the time elapsed since the last progress of the task.
+    pub since_last_progress: Duration,
+}
+
+/// The health of the internal tasks of zenoh, as returned by [`health()`].
+#[derive(Clone, Debug)]
+pub struct Health {
+    /// the threshold above which a task without progress is considered as stalled.
+    pub threshold: Duration,
+    /// the health of all the monitored tasks.
+    pub tasks: Vec<TaskHealth>,
+}
+
+impl Health {
+    /// Returns the tasks that didn't make any progress since the threshold.
+    pub fn stalled(&self) -> impl Iterator<Item = &TaskHealth> {
+        let threshold = self.threshold;
+        self.tasks
+            .iter()
+            .filter(move |t| t.since_last_progress > threshold)
+    }
+
+    /// Returns true if no task is stalled.
+    pub fn is_healthy(&self) -> bool {
+        self.stalled().next().is_none()
+    }
+}
+
+/// Returns the health of the internal tasks, considering as stalled the tasks that didn't
+/// make any progress since `threshold`.
+pub fn health(threshold: Duration) -> Health {
+    let now = now_ms();
+    let mut tasks: Vec<TaskHealth> = zlock!(TASKS)
+        .values()
+        .map(|state| TaskHealth {
+            name: state.name.clone(),
+            since_last_progress: Duration::from_millis(
+                now.saturating_sub(state.last_progress.load(Ordering::Relaxed)),
+            ),
+        })
+        .collect();
+    tasks.sort_by(|a, b| a.name.cmp(&b.name));
+    Health { threshold, tasks }
+}
+
+/// Returns the stall threshold configured in `config` (see [`ZN_STALL_THRESHOLD_KEY`]).
+pub(crate) fn stall_threshold(config: &ConfigProperties) -> Duration {
+    let default = ZN_STALL_THRESHOLD_DEFAULT.parse().unwrap();
+    Duration::from_millis(
+        config
+            .get_or(&ZN_STALL_THRESHOLD_KEY, ZN_STALL_THRESHOLD_DEFAULT)
+            .parse()
+            .unwrap_or(default),
+    )
+}
+
+/// Starts (once per process) a watchdog task logging the stalled tasks every `threshold / 2`.
+pub(crate) fn start_watchdog(threshold: Duration) {
+    if WATCHDOG_STARTED.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    task::spawn(async move {
+        loop {
+            task::sleep(threshold / 2).await;
+            for t in health(threshold).stalled() {
+                warn!(
+                    "Watchdog: task '{}' made no progress for {} ms",
+                    t.name,
+                    t.since_last_progress.as_millis()
+                );
+            }
+        }
+    });
+}
@@ -21,6 +21,8 @@ use super::tls::{LocatorConfigTls, LocatorTls};
 use super::udp::{LocatorConfigUdp, LocatorUdp};
 #[cfg(all(feature = "transport_unixsock-stream", target_family = "unix"))]
 use super::unixsock_stream::{LocatorConfigUnixSocketStream, LocatorUnixSocketStream};
+#[cfg(feature = "transport_ws")]
+use super::ws::{LocatorConfigWs, LocatorWs};
 use std::cmp::PartialEq;
 use std::collections::HashMap;
 use std::fmt;
@@ -46,6 +48,8 @@ pub const STR_TLS: &str = "tls";
 pub const STR_QUIC: &str = "quic";
 #[cfg(all(feature = "transport_unixsock-stream", target_family = "unix"))]
 pub const STR_UNIXSOCK_STREAM: &str = "unixsock-stream";
+#[cfg(feature = "transport_ws")]
+pub const STR_WS: &str = "ws";
 
 // Parsing chars
 pub const PROTO_SEPARATOR: char = '/';
@@ -175,6 +179,10 @@ pub enum LocatorProtocol {
     Quic,
     #[cfg(all(feature = "transport_unixsock-stream", target_family = "unix"))]
     UnixSocketStream,
+    #[cfg(feature = "transport_ws")]
+    Ws,
+    /// A protocol implemented by a [`TransportFactory`](super::TransportFactory).
+    Custom(String),
 }
 
 impl fmt::Display for LocatorProtocol {
@@ -190,6 +198,9 @@ impl fmt::Display for LocatorProtocol {
             LocatorProtocol::Quic => write!(f, "{}", STR_QUIC)?,
             #[cfg(all(feature = "transport_unixsock-stream", target_family = "unix"))]
             LocatorProtocol::UnixSocketStream => write!(f, "{}", STR_UNIXSOCK_STREAM)?,
+            #[cfg(feature = "transport_ws")]
+            LocatorProtocol::Ws => write!(f, "{}", STR_WS)?,
+            LocatorProtocol::Custom(protocol) => write!(f, "{}", protocol)?,
         }
         Ok(())
     }
@@ -238,6 +249,13 @@ impl LocatorConfig {
                 ps.insert(LocatorProtocol::UnixSocketStream, p);
             }
         }
+        #[cfg(feature = "transport_ws")]
+        {
+            let mut res = LocatorConfigWs::from_config(config)?;
+            if let Some(p) = res.take() {
+                ps.insert(LocatorProtocol::Ws, p);
+            }
+        }
         Ok(ps)
     }
 }
@@ -325,6 +343,10 @@ pub enum LocatorAddress {
     Quic(LocatorQuic),
     #[cfg(all(feature = "transport_unixsock-stream", target_family = "unix"))]
     UnixSocketStream(LocatorUnixSocketStream),
+    #[cfg(feature = "transport_ws")]
+    Ws(LocatorWs),
+    /// An address for a protocol implemented by a [`TransportFactory`](super::TransportFactory).
+    Custom(LocatorCustom),
 }
 
 impl FromStr for LocatorAddress {
@@ -349,6 +371,14 @@ impl FromStr for LocatorAddress {
             STR_QUIC => addr.parse().map(LocatorAddress::Quic),
             #[cfg(all(feature = "transport_unixsock-stream", target_family = "unix"))]
             STR_UNIXSOCK_STREAM => addr.parse().map(LocatorAddress::UnixSocketStream),
+            #[cfg(feature = "transport_ws")]
+            STR_WS => addr.parse().map(LocatorAddress::Ws),
+            custom if LocatorCustom::is_valid_protocol(custom) => {
+                Ok(LocatorAddress::Custom(LocatorCustom {
+                    protocol: custom.to_string(),
+                    address: addr.to_string(),
+                }))
+            }
             unknown => {
                 let e = format!(
                     "Invalid locator address: {}. Unknown protocol: {}.",
@@ -373,6 +403,9 @@ impl LocatorAddress {
             LocatorAddress::Quic(..) => LocatorProtocol::Quic,
             #[cfg(all(feature = "transport_unixsock-stream", target_family = "unix"))]
             LocatorAddress::UnixSocketStream(..) => LocatorProtocol::UnixSocketStream,
+            #[cfg(feature = "transport_ws")]
+            LocatorAddress::Ws(..) => LocatorProtocol::Ws,
+            LocatorAddress::Custom(l) => LocatorProtocol::Custom(l.protocol.clone()),
         }
     }
 
@@ -388,6 +421,9 @@ impl LocatorAddress {
             LocatorAddress::Quic(l) => l.is_multicast(),
             #[cfg(all(feature = "transport_unixsock-stream", target_family = "unix"))]
             LocatorAddress::UnixSocketStream(l) => l.is_multicast(),
+            #[cfg(feature = "transport_ws")]
+            LocatorAddress::Ws(l) => l.is_multicast(),
+            LocatorAddress::Custom(..) => false,
         }
     }
 }
@@ -407,7 +443,34 @@ impl fmt::Display for LocatorAddress {
             LocatorAddress::UnixSocketStream(addr) => {
                 write!(f, "{}{}{}", STR_UNIXSOCK_STREAM, PROTO_SEPARATOR, addr)?
             }
+            #[cfg(feature = "transport_ws")]
+            LocatorAddress::Ws(addr) => write!(f, "{}{}{}", STR_WS, PROTO_SEPARATOR, addr)?,
+            LocatorAddress::Custom(l) => {
+                write!(f, "{}{}{}", l.protocol, PROTO_SEPARATOR, l.address)?
+            }
         }
         Ok(())
     }
 }
+
+/*************************************/
+/*          CUSTOM LOCATOR           */
+/*************************************/
+/// The address of a locator for a protocol that is not built in zenoh, but implemented
+/// by a [`TransportFactory`](super::TransportFactory).  
+/// The `address` is not interpreted by zenoh: it's up to the factory's link manager to parse it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LocatorCustom {
+    pub protocol: String,
+    pub address: String,
+}
+
+impl LocatorCustom {
+    // A custom protocol name is made of ASCII alphanumeric characters, '-' or '_'.
+    fn is_valid_protocol(protocol: &str) -> bool {
+        !protocol.is_empty()
+            && protocol
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+}
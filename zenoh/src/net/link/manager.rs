@@ -21,6 +21,8 @@ use super::tls::LinkManagerUnicastTls;
 use super::udp::{LinkManagerMulticastUdp, LinkManagerUnicastUdp};
 #[cfg(all(feature = "transport_unixsock-stream", target_family = "unix"))]
 use super::unixsock_stream::LinkManagerUnicastUnixSocketStream;
+#[cfg(feature = "transport_ws")]
+use super::ws::LinkManagerUnicastWs;
 use super::{EndPoint, LinkMulticast, LinkUnicast, Locator, LocatorProtocol};
 use crate::net::transport::TransportManager;
 use async_std::sync::Arc;
@@ -31,16 +33,64 @@ use zenoh_util::zerror;
 /*************************************/
 /*             UNICAST               */
 /*************************************/
-pub(crate) type LinkManagerUnicast = Arc<dyn LinkManagerUnicastTrait>;
+pub type LinkManagerUnicast = Arc<dyn LinkManagerUnicastTrait>;
+
+/// The manager of the unicast links of a protocol, created by a [`TransportFactory`].
 #[async_trait]
-pub(crate) trait LinkManagerUnicastTrait: Send + Sync {
+pub trait LinkManagerUnicastTrait: Send + Sync {
+    /// Opens a new link to `endpoint`.
     async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast>;
+    /// Starts listening on `endpoint`, returning the actual listening locator
+    /// (e.g. with the port number assigned by the system).  
+    /// Each accepted link must be passed to [`TransportManager::handle_new_link_unicast()`].
     async fn new_listener(&self, endpoint: EndPoint) -> ZResult<Locator>;
+    /// Stops listening on `endpoint`.
     async fn del_listener(&self, endpoint: &EndPoint) -> ZResult<()>;
+    /// Returns the endpoints currently listened on.
     fn get_listeners(&self) -> Vec<EndPoint>;
+    /// Returns the locators on which this manager can be reached.
     fn get_locators(&self) -> Vec<Locator>;
 }
 
+/// A factory of [`LinkManagerUnicast`] for a protocol, allowing to plug a custom transport
+/// (e.g. an in-memory or an instrumented one) into a [`TransportManager`]
+/// (see [`TransportManagerConfigBuilder::transport()`](crate::net::transport::TransportManagerConfigBuilder::transport)).
+///
+/// A factory can implement a new protocol: the locators with this protocol are parsed as
+/// [`LocatorAddress::Custom`](super::LocatorAddress::Custom). It can also replace a built-in protocol
+/// (e.g. to wrap the [`DefaultTransportFactory`] of `"tcp"` and instrument its links).
+pub trait TransportFactory: Send + Sync {
+    /// Returns the protocol handled by this factory (e.g. `"tcp"` for `"tcp/127.0.0.1:7447"`).
+    fn protocol(&self) -> &str;
+    /// Creates the link manager for this protocol. It's called once per [`TransportManager`],
+    /// when the first listener or link with this protocol is created.
+    fn make(&self, manager: TransportManager) -> ZResult<LinkManagerUnicast>;
+}
+
+/// The [`TransportFactory`] of a protocol built in zenoh (e.g. TCP or TLS).  
+/// It's used for the protocols without a custom factory.
+pub struct DefaultTransportFactory {
+    protocol: LocatorProtocol,
+    name: String,
+}
+
+impl DefaultTransportFactory {
+    pub fn new(protocol: LocatorProtocol) -> Self {
+        let name = protocol.to_string();
+        DefaultTransportFactory { protocol, name }
+    }
+}
+
+impl TransportFactory for DefaultTransportFactory {
+    fn protocol(&self) -> &str {
+        &self.name
+    }
+
+    fn make(&self, manager: TransportManager) -> ZResult<LinkManagerUnicast> {
+        LinkManagerBuilderUnicast::make(manager, &self.protocol)
+    }
+}
+
 pub(crate) struct LinkManagerBuilderUnicast;
 
 impl LinkManagerBuilderUnicast {
@@ -61,6 +111,11 @@ impl LinkManagerBuilderUnicast {
             LocatorProtocol::UnixSocketStream => {
                 Ok(Arc::new(LinkManagerUnicastUnixSocketStream::new(manager)))
             }
+            #[cfg(feature = "transport_ws")]
+            LocatorProtocol::Ws => Ok(Arc::new(LinkManagerUnicastWs::new(manager))),
+            LocatorProtocol::Custom(protocol) => zerror!(ZErrorKind::InvalidLocator {
+                descr: format!("No transport factory for {} protocol", protocol)
+            }),
         }
     }
 }
@@ -23,6 +23,8 @@ pub mod tls;
 pub mod udp;
 #[cfg(all(feature = "transport_unixsock-stream", target_family = "unix"))]
 pub mod unixsock_stream;
+#[cfg(feature = "transport_ws")]
+pub mod ws;
 
 use crate::net::protocol::io::{WBuf, ZBuf};
 use crate::net::protocol::proto::TransportMessage;
@@ -30,6 +32,9 @@ use async_std::sync::Arc;
 use async_trait::async_trait;
 pub use endpoint::*;
 pub(crate) use manager::*;
+pub use manager::{
+    DefaultTransportFactory, LinkManagerUnicast, LinkManagerUnicastTrait, TransportFactory,
+};
 use std::cmp::PartialEq;
 use std::fmt;
 use std::hash::{Hash, Hasher};
@@ -38,6 +43,45 @@ use zenoh_util::core::{ZError, ZErrorKind, ZResult};
 
 const WBUF_SIZE: usize = 64;
 
+// Connects a TCP stream to `dst_addr`, binding it first to `bind_addr` if any (see ZN_BIND_ADDRESS_KEY).
+// A bind address of another IP version than `dst_addr` is ignored.
+#[cfg(any(
+    feature = "transport_tcp",
+    feature = "transport_tls",
+    feature = "transport_ws"
+))]
+pub(crate) async fn tcp_connect(
+    dst_addr: std::net::SocketAddr,
+    bind_addr: Option<std::net::IpAddr>,
+) -> std::io::Result<async_std::net::TcpStream> {
+    use socket2::{Domain, Protocol, Socket, Type};
+
+    let bind_addr = match bind_addr {
+        Some(addr) if addr.is_ipv4() == dst_addr.is_ipv4() => addr,
+        Some(addr) => {
+            log::debug!(
+                "Not binding the link to {} to {}: IP version mismatch",
+                dst_addr,
+                addr
+            );
+            return async_std::net::TcpStream::connect(dst_addr).await;
+        }
+        None => return async_std::net::TcpStream::connect(dst_addr).await,
+    };
+    let stream = async_std::task::spawn_blocking(move || {
+        let socket = Socket::new(
+            Domain::for_address(dst_addr),
+            Type::STREAM,
+            Some(Protocol::TCP),
+        )?;
+        socket.bind(&std::net::SocketAddr::new(bind_addr, 0).into())?;
+        socket.connect(&dst_addr.into())?;
+        Ok::<_, std::io::Error>(std::net::TcpStream::from(socket))
+    })
+    .await?;
+    Ok(async_std::net::TcpStream::from(stream))
+}
+
 /*************************************/
 /*            GENERAL                */
 /*************************************/
@@ -99,20 +143,34 @@ impl From<LinkMulticast> for Link {
 /*            UNICAST                */
 /*************************************/
 
+/// A unicast link, created from a [`LinkUnicastTrait`] implementation.
 #[derive(Clone)]
-pub(crate) struct LinkUnicast(Arc<dyn LinkUnicastTrait>);
+pub struct LinkUnicast(Arc<dyn LinkUnicastTrait>);
 
+/// A unicast link of a protocol. Custom transports (see [`TransportFactory`]) implement this trait
+/// for the links returned by their [`LinkManagerUnicastTrait`].
 #[async_trait]
-pub(crate) trait LinkUnicastTrait: Send + Sync {
+pub trait LinkUnicastTrait: Send + Sync {
+    /// Returns the maximum size of a message written on this link.
     fn get_mtu(&self) -> u16;
+    /// Returns the local locator of this link.
     fn get_src(&self) -> Locator;
+    /// Returns the remote locator of this link.
     fn get_dst(&self) -> Locator;
+    /// Returns true if this link neither loses nor reorders messages.
     fn is_reliable(&self) -> bool;
+    /// Returns true if this link is a byte stream (e.g. TCP) rather than message oriented (e.g. UDP).
+    /// On a streamed link each message is prefixed with its length.
     fn is_streamed(&self) -> bool;
+    /// Writes some bytes of `buffer`, returning the number of bytes written.
     async fn write(&self, buffer: &[u8]) -> ZResult<usize>;
+    /// Writes all the bytes of `buffer`.
     async fn write_all(&self, buffer: &[u8]) -> ZResult<()>;
+    /// Reads some bytes into `buffer`, returning the number of bytes read.
     async fn read(&self, buffer: &mut [u8]) -> ZResult<usize>;
+    /// Reads exactly enough bytes to fill `buffer`.
     async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()>;
+    /// Closes this link.
     async fn close(&self) -> ZResult<()>;
 }
 
@@ -11,6 +11,14 @@
 // Contributors:
 //   ADLINK zenoh team, <zenoh@adlink-labs.tech>
 //
+
+//! The QUIC transport, for the `quic/<host>:<port>` locators.
+//!
+//! Built on [quinn](https://docs.rs/quinn) 0.7 and gated by the `transport_quic` feature
+//! (enabled by default). As QUIC always runs over TLS, it uses the same configuration keys
+//! as the TLS transport: a listener requires `tls_server_private_key` and
+//! `tls_server_certificate`, and connecting uses `tls_root_ca_certificate` to verify the server.
+//! A failing handshake is reported as a `ZErrorKind::Quic` error.
 mod endpoint;
 mod unicast;
 
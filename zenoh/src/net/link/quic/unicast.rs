@@ -289,8 +289,8 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
             })?
             .await
             .map_err(|e| {
-                let e = format!("Can not create a new QUIC link bound to {}: {}", host, e);
-                zerror2!(ZErrorKind::InvalidLink { descr: e })
+                let e = format!("QUIC handshake with {} failed: {}", host, e);
+                zerror2!(ZErrorKind::Quic { descr: e })
             })?;
 
         let (send, recv) = quic_conn.connection.open_bi().await.map_err(|e| {
@@ -515,9 +515,9 @@ async fn accept_task(
         })?;
 
         let conn = qc.await.map_err(|e| {
-            let e = format!("QUIC acceptor failed: {:?}", e);
+            let e = format!("QUIC handshake of an incoming connection failed: {}", e);
             log::warn!("{}", e);
-            zerror2!(ZErrorKind::IoError { descr: e })
+            zerror2!(ZErrorKind::Quic { descr: e })
         })?;
 
         Ok(Action::Accept(conn))
@@ -218,7 +218,8 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastTcp {
     async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
         let dst_addr = get_tcp_addr(&endpoint.locator.address).await?;
 
-        let stream = TcpStream::connect(dst_addr).await.map_err(|e| {
+        let bind_addr = self.manager.config.bind_address;
+        let stream = tcp_connect(dst_addr, bind_addr).await.map_err(|e| {
             let e = format!("Can not create a new TCP link bound to {}: {}", dst_addr, e);
             zerror2!(ZErrorKind::Other { descr: e })
         })?;
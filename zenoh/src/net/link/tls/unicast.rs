@@ -275,7 +275,8 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastTls {
         let host: &str = domain.as_ref().into();
 
         // Initialize the TcpStream
-        let tcp_stream = TcpStream::connect(addr).await.map_err(|e| {
+        let bind_addr = self.manager.config.bind_address;
+        let tcp_stream = tcp_connect(addr, bind_addr).await.map_err(|e| {
             let e = format!("Can not create a new TLS link bound to {}: {}", host, e);
             zerror2!(ZErrorKind::Other { descr: e })
         })?;
@@ -0,0 +1,56 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! The WebSocket transport (`ws/<host>:<port>`), enabled with the `transport_ws` feature.
+//!
+//! Each zenoh batch is sent as one binary WebSocket message, so the links are message oriented
+//! (like UDP) but reliable (like TCP). Text, ping and pong messages are not used by zenoh and are ignored.
+//!
+//! Its main purpose is to let browser applications reach a zenoh infrastructure: a router listening
+//! on a `ws/` locator (e.g. `zenohd -l ws/0.0.0.0:7448`, or the `z_ws_gateway` example) accepts
+//! the connections of the browser clients, which open a WebSocket to `ws://<host>:<port>/` and exchange
+//! the zenoh messages as binary messages. Native applications can connect to the same router through
+//! this transport or any other one.
+//!
+//! Note that this crate can't be built for the `wasm32` targets: its runtime relies on the sockets,
+//! threads and timers of the operating system (e.g. for the scouting, the TCP and UDP transports and the
+//! async-std executor). A browser client is a separate implementation of the zenoh protocol speaking
+//! to a router through this transport; the router is what this crate provides.
+#[cfg(target_arch = "wasm32")]
+compile_error!(
+    "zenoh can't be built for wasm32: a browser client must implement the zenoh protocol \
+     over a WebSocket to a router listening on a ws/ locator"
+);
+
+mod endpoint;
+mod unicast;
+
+use super::*;
+pub use endpoint::*;
+pub use unicast::*;
+
+// Default MTU (WebSocket message) in bytes.
+// NOTE: A WebSocket message has no practical size limit. However, a zenoh batch
+//       is limited to 2^16 - 1 bytes (i.e., 65535) on all the transports.
+const WS_MAX_MTU: u16 = u16::MAX;
+
+zconfigurable! {
+    // Default MTU (WebSocket message) in bytes.
+    static ref WS_DEFAULT_MTU: u16 = WS_MAX_MTU;
+    // Amount of time in microseconds to throttle the accept loop upon an error.
+    // Default set to 100 ms.
+    static ref WS_ACCEPT_THROTTLE_TIME: u64 = 100_000;
+    // Amount of time in milliseconds for a connection to complete the WebSocket handshake.
+    // Default set to 10 s.
+    static ref WS_HANDSHAKE_TIMEOUT: u64 = 10_000;
+}
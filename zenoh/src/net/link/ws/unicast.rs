@@ -0,0 +1,458 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use super::*;
+use crate::net::transport::TransportManager;
+use async_std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream};
+use async_std::prelude::FutureExt;
+use async_std::sync::Mutex as AsyncMutex;
+use async_std::task;
+use async_std::task::JoinHandle;
+use async_trait::async_trait;
+use async_tungstenite::tungstenite::Message;
+use async_tungstenite::WebSocketStream;
+use futures::sink::SinkExt;
+use futures::stream::{SplitSink, SplitStream, StreamExt};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use zenoh_util::core::{ZError, ZErrorKind, ZResult};
+use zenoh_util::sync::Signal;
+use zenoh_util::{zasynclock, zerror2, zread, zwrite};
+
+// The reading half of a WebSocket link, with the part of the last received message not read yet
+struct WsReader {
+    stream: SplitStream<WebSocketStream<TcpStream>>,
+    leftover: Vec<u8>,
+    offset: usize,
+}
+
+pub struct LinkUnicastWs {
+    // The writing half of the WebSocket
+    sink: AsyncMutex<SplitSink<WebSocketStream<TcpStream>, Message>>,
+    // The reading half of the WebSocket
+    reader: AsyncMutex<WsReader>,
+    // The source socket address of this link (address used on the local host)
+    src_addr: SocketAddr,
+    // The destination socket address of this link (address used on the remote host)
+    dst_addr: SocketAddr,
+}
+
+impl LinkUnicastWs {
+    fn new(
+        socket: WebSocketStream<TcpStream>,
+        src_addr: SocketAddr,
+        dst_addr: SocketAddr,
+    ) -> LinkUnicastWs {
+        let (sink, stream) = socket.split();
+        LinkUnicastWs {
+            sink: AsyncMutex::new(sink),
+            reader: AsyncMutex::new(WsReader {
+                stream,
+                leftover: vec![],
+                offset: 0,
+            }),
+            src_addr,
+            dst_addr,
+        }
+    }
+}
+
+#[async_trait]
+impl LinkUnicastTrait for LinkUnicastWs {
+    async fn close(&self) -> ZResult<()> {
+        log::trace!("Closing WebSocket link: {}", self);
+        // Send the close message, which closes the underlying TCP socket once acknowledged
+        zasynclock!(self.sink).close().await.map_err(|e| {
+            let e = format!("WebSocket link shutdown {}: {:?}", self, e);
+            log::trace!("{}", e);
+            zerror2!(ZErrorKind::IoError { descr: e })
+        })
+    }
+
+    async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
+        self.write_all(buffer).await?;
+        Ok(buffer.len())
+    }
+
+    async fn write_all(&self, buffer: &[u8]) -> ZResult<()> {
+        zasynclock!(self.sink)
+            .send(Message::Binary(buffer.to_vec()))
+            .await
+            .map_err(|e| {
+                let e = format!("Write error on WebSocket link {}: {}", self, e);
+                log::trace!("{}", e);
+                zerror2!(ZErrorKind::IoError { descr: e })
+            })
+    }
+
+    async fn read(&self, buffer: &mut [u8]) -> ZResult<usize> {
+        let mut guard = zasynclock!(self.reader);
+        let reader = &mut *guard;
+        if reader.offset == reader.leftover.len() {
+            // Wait for the next binary message
+            reader.leftover = loop {
+                match reader.stream.next().await {
+                    Some(Ok(Message::Binary(data))) if !data.is_empty() => break data,
+                    Some(Ok(Message::Close(_))) | None => {
+                        let e = format!("WebSocket link {} closed by the remote end", self);
+                        log::trace!("{}", e);
+                        return zerror!(ZErrorKind::IoError { descr: e });
+                    }
+                    Some(Err(e)) => {
+                        let e = format!("Read error on WebSocket link {}: {}", self, e);
+                        log::trace!("{}", e);
+                        return zerror!(ZErrorKind::IoError { descr: e });
+                    }
+                    // the pings are answered by the WebSocket stream itself
+                    Some(Ok(_)) => continue,
+                }
+            };
+            reader.offset = 0;
+        }
+        let n = buffer.len().min(reader.leftover.len() - reader.offset);
+        buffer[..n].copy_from_slice(&reader.leftover[reader.offset..reader.offset + n]);
+        reader.offset += n;
+        Ok(n)
+    }
+
+    async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()> {
+        let mut read: usize = 0;
+        while read < buffer.len() {
+            let n = self.read(&mut buffer[read..]).await?;
+            read += n;
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn get_src(&self) -> Locator {
+        Locator {
+            address: LocatorAddress::Ws(LocatorWs::SocketAddr(self.src_addr)),
+            metadata: None,
+        }
+    }
+
+    #[inline(always)]
+    fn get_dst(&self) -> Locator {
+        Locator {
+            address: LocatorAddress::Ws(LocatorWs::SocketAddr(self.dst_addr)),
+            metadata: None,
+        }
+    }
+
+    #[inline(always)]
+    fn get_mtu(&self) -> u16 {
+        *WS_DEFAULT_MTU
+    }
+
+    #[inline(always)]
+    fn is_reliable(&self) -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn is_streamed(&self) -> bool {
+        false
+    }
+}
+
+impl fmt::Display for LinkUnicastWs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} => {}", self.src_addr, self.dst_addr)?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for LinkUnicastWs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ws")
+            .field("src", &self.src_addr)
+            .field("dst", &self.dst_addr)
+            .finish()
+    }
+}
+
+/*************************************/
+/*          LISTENER                 */
+/*************************************/
+struct ListenerUnicastWs {
+    endpoint: EndPoint,
+    active: Arc<AtomicBool>,
+    signal: Signal,
+    handle: JoinHandle<ZResult<()>>,
+}
+
+impl ListenerUnicastWs {
+    fn new(
+        endpoint: EndPoint,
+        active: Arc<AtomicBool>,
+        signal: Signal,
+        handle: JoinHandle<ZResult<()>>,
+    ) -> ListenerUnicastWs {
+        ListenerUnicastWs {
+            endpoint,
+            active,
+            signal,
+            handle,
+        }
+    }
+}
+
+pub struct LinkManagerUnicastWs {
+    manager: TransportManager,
+    listeners: Arc<RwLock<HashMap<SocketAddr, ListenerUnicastWs>>>,
+}
+
+impl LinkManagerUnicastWs {
+    pub(crate) fn new(manager: TransportManager) -> Self {
+        Self {
+            manager,
+            listeners: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+}
+
+#[async_trait]
+impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
+    async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
+        let dst_addr = get_ws_addr(&endpoint.locator.address).await?;
+
+        let bind_addr = self.manager.config.bind_address;
+        let stream = tcp_connect(dst_addr, bind_addr).await.map_err(|e| {
+            let e = format!(
+                "Can not create a new WebSocket link bound to {}: {}",
+                dst_addr, e
+            );
+            zerror2!(ZErrorKind::IoError { descr: e })
+        })?;
+
+        let src_addr = stream.local_addr().map_err(|e| {
+            let e = format!(
+                "Can not create a new WebSocket link bound to {}: {}",
+                dst_addr, e
+            );
+            zerror2!(ZErrorKind::InvalidLink { descr: e })
+        })?;
+
+        let dst_addr = stream.peer_addr().map_err(|e| {
+            let e = format!(
+                "Can not create a new WebSocket link bound to {}: {}",
+                dst_addr, e
+            );
+            zerror2!(ZErrorKind::InvalidLink { descr: e })
+        })?;
+
+        // Perform the WebSocket handshake, with the host as given in the locator
+        let url = match &endpoint.locator.address {
+            LocatorAddress::Ws(addr) => format!("ws://{}/", addr),
+            _ => format!("ws://{}/", dst_addr),
+        };
+        let (socket, _) = async_tungstenite::client_async(url, stream)
+            .await
+            .map_err(|e| {
+                let e = format!(
+                    "Can not create a new WebSocket link bound to {}: {}",
+                    dst_addr, e
+                );
+                zerror2!(ZErrorKind::InvalidLink { descr: e })
+            })?;
+
+        let link = Arc::new(LinkUnicastWs::new(socket, src_addr, dst_addr));
+
+        Ok(LinkUnicast(link))
+    }
+
+    async fn new_listener(&self, mut endpoint: EndPoint) -> ZResult<Locator> {
+        let addr = get_ws_addr(&endpoint.locator.address).await?;
+
+        // Bind the TCP socket
+        let socket = TcpListener::bind(addr).await.map_err(|e| {
+            let e = format!("Can not create a new WebSocket listener on {}: {}", addr, e);
+            zerror2!(ZErrorKind::InvalidLink { descr: e })
+        })?;
+
+        let local_addr = socket.local_addr().map_err(|e| {
+            let e = format!("Can not create a new WebSocket listener on {}: {}", addr, e);
+            zerror2!(ZErrorKind::InvalidLink { descr: e })
+        })?;
+
+        // Update the endpoint locator address
+        endpoint.locator.address = LocatorAddress::Ws(LocatorWs::SocketAddr(local_addr));
+
+        // Spawn the accept loop for the listener
+        let active = Arc::new(AtomicBool::new(true));
+        let signal = Signal::new();
+
+        let c_active = active.clone();
+        let c_signal = signal.clone();
+        let c_manager = self.manager.clone();
+        let c_listeners = self.listeners.clone();
+        let c_addr = local_addr;
+        let handle = task::spawn(async move {
+            // Wait for the accept loop to terminate
+            let res = accept_task(socket, c_active, c_signal, c_manager).await;
+            zwrite!(c_listeners).remove(&c_addr);
+            res
+        });
+
+        let locator = endpoint.locator.clone();
+        let listener = ListenerUnicastWs::new(endpoint, active, signal, handle);
+        // Update the list of active listeners on the manager
+        zwrite!(self.listeners).insert(local_addr, listener);
+
+        Ok(locator)
+    }
+
+    async fn del_listener(&self, endpoint: &EndPoint) -> ZResult<()> {
+        let addr = get_ws_addr(&endpoint.locator.address).await?;
+
+        // Stop the listener
+        let listener = zwrite!(self.listeners).remove(&addr).ok_or_else(|| {
+            let e = format!(
+                "Can not delete the WebSocket listener because it has not been found: {}",
+                addr
+            );
+            log::trace!("{}", e);
+            zerror2!(ZErrorKind::InvalidLink { descr: e })
+        })?;
+
+        // Send the stop signal
+        listener.active.store(false, Ordering::Release);
+        listener.signal.trigger();
+        listener.handle.await
+    }
+
+    fn get_listeners(&self) -> Vec<EndPoint> {
+        zread!(self.listeners)
+            .values()
+            .map(|l| l.endpoint.clone())
+            .collect()
+    }
+
+    fn get_locators(&self) -> Vec<Locator> {
+        let mut locators = vec![];
+        let default_ipv4 = Ipv4Addr::new(0, 0, 0, 0);
+        let default_ipv6 = Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0);
+
+        for (key, value) in zread!(self.listeners).iter() {
+            if key.ip() == default_ipv4 || key.ip() == default_ipv6 {
+                match zenoh_util::net::get_local_addresses() {
+                    Ok(ipaddrs) => {
+                        for ipaddr in ipaddrs {
+                            if !ipaddr.is_loopback()
+                                && !ipaddr.is_multicast()
+                                && ipaddr.is_ipv4() == key.is_ipv4()
+                            {
+                                locators.push((
+                                    SocketAddr::new(ipaddr, key.port()),
+                                    value.endpoint.locator.metadata.clone(),
+                                ));
+                            }
+                        }
+                    }
+                    Err(err) => log::error!("Unable to get local addresses : {}", err),
+                }
+            } else {
+                locators.push((*key, value.endpoint.locator.metadata.clone()));
+            }
+        }
+
+        locators
+            .into_iter()
+            .map(|(addr, metadata)| Locator {
+                address: LocatorAddress::Ws(LocatorWs::SocketAddr(addr)),
+                metadata,
+            })
+            .collect()
+    }
+}
+
+async fn accept_task(
+    socket: TcpListener,
+    active: Arc<AtomicBool>,
+    signal: Signal,
+    manager: TransportManager,
+) -> ZResult<()> {
+    enum Action {
+        Accept((TcpStream, SocketAddr)),
+        Stop,
+    }
+
+    async fn accept(socket: &TcpListener) -> ZResult<Action> {
+        let res = socket.accept().await.map_err(|e| {
+            zerror2!(ZErrorKind::IoError {
+                descr: e.to_string()
+            })
+        })?;
+        Ok(Action::Accept(res))
+    }
+
+    async fn stop(signal: Signal) -> ZResult<Action> {
+        signal.wait().await;
+        Ok(Action::Stop)
+    }
+
+    let src_addr = socket.local_addr().map_err(|e| {
+        let e = format!("Can not accept WebSocket connections: {}", e);
+        log::warn!("{}", e);
+        zerror2!(ZErrorKind::IoError { descr: e })
+    })?;
+
+    log::trace!("Ready to accept WebSocket connections on: {:?}", src_addr);
+    while active.load(Ordering::Acquire) {
+        // Wait for incoming connections
+        let (stream, dst_addr) = match accept(&socket).race(stop(signal.clone())).await {
+            Ok(action) => match action {
+                Action::Accept((stream, addr)) => (stream, addr),
+                Action::Stop => break,
+            },
+            Err(e) => {
+                log::warn!("{}. Hint: increase the system open file limit.", e);
+                // Throttle the accept loop upon an error
+                // NOTE: This might be due to various factors. However, the most common case is that
+                //       the process has reached the maximum number of open files in the system.
+                task::sleep(Duration::from_micros(*WS_ACCEPT_THROTTLE_TIME)).await;
+                continue;
+            }
+        };
+
+        log::debug!(
+            "Accepted WebSocket connection on {:?}: {:?}",
+            src_addr,
+            dst_addr
+        );
+        // Perform the WebSocket handshake in a separate task, not to block the accept loop
+        // on a slow or malicious client
+        let c_manager = manager.clone();
+        task::spawn(async move {
+            let handshake = async_tungstenite::accept_async(stream)
+                .timeout(Duration::from_millis(*WS_HANDSHAKE_TIMEOUT))
+                .await;
+            match handshake {
+                Ok(Ok(socket)) => {
+                    // Create the new link object
+                    let link = Arc::new(LinkUnicastWs::new(socket, src_addr, dst_addr));
+                    // Communicate the new link to the initial transport manager
+                    c_manager.handle_new_link_unicast(LinkUnicast(link)).await;
+                }
+                Ok(Err(e)) => log::debug!("WebSocket handshake failed with {}: {}", dst_addr, e),
+                Err(_) => log::debug!("WebSocket handshake timed out with {}", dst_addr),
+            }
+        });
+    }
+
+    Ok(())
+}
@@ -79,6 +79,7 @@ pub mod runtime;
 pub mod transport;
 
 use async_std::net::UdpSocket;
+use async_std::sync::Arc;
 use flume::bounded;
 use futures::prelude::*;
 use log::{debug, trace};
@@ -97,10 +98,16 @@ pub use types::*;
 
 pub mod info;
 
+pub mod health;
+
 #[doc(hidden)]
 mod session;
 pub use session::*;
 
+mod query_limiter;
+pub use query_limiter::{QueryLoad, OVERLOADED_SUFFIX};
+pub use routing::queries::Hop;
+
 pub use protocol::proto::{data_kind, encoding};
 
 pub mod queryable {
@@ -238,7 +245,20 @@ pub fn scout(
 /// # })
 /// ```
 pub fn open(config: ConfigProperties) -> impl ZFuture<Output = ZResult<Session>> {
+    open_with_transports(config, vec![])
+}
+
+/// Open a zenoh-net [Session](Session) using the given [`TransportFactory`](link::TransportFactory)s
+/// in addition to (or in place of) the built-in transports.
+///
+/// The transports of the protocols implemented by `transports` (e.g. an in-memory or an instrumented transport)
+/// are created by their factory. The other protocols use the built-in transports.
+/// See [`open()`] for the `config`.
+pub fn open_with_transports(
+    config: ConfigProperties,
+    transports: Vec<Arc<dyn link::TransportFactory>>,
+) -> impl ZFuture<Output = ZResult<Session>> {
     debug!("Zenoh Rust API {}", GIT_VERSION);
     debug!("Config: {:?}", &config);
-    Session::new(config)
+    Session::new(config, transports)
 }
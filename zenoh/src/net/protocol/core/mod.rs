@@ -228,20 +228,27 @@ impl From<uuid::Uuid> for PeerId {
     }
 }
 
+// Parses an hexadecimal string of at most MAX_SIZE bytes (e.g. a UUID, possibly with '-' characters).
 impl FromStr for PeerId {
     type Err = ZError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let id = s.parse::<Uuid>().map_err(|e| {
+        // filter-out '-' characters (in case s has UUID format)
+        let s = s.replace('-', "");
+        let vec = hex::decode(&s).map_err(|e| {
             zerror2!(ZErrorKind::ValueDecodingFailed {
-                descr: e.to_string()
+                descr: format!("Invalid id: {} - {}", s, e)
             })
         })?;
-        let pid = PeerId {
-            size: 16,
-            id: *id.as_bytes(),
-        };
-        Ok(pid)
+        let size = vec.len();
+        if size > PeerId::MAX_SIZE {
+            return zerror!(ZErrorKind::ValueDecodingFailed {
+                descr: format!("Invalid id size: {} ({} bytes max)", size, PeerId::MAX_SIZE)
+            });
+        }
+        let mut id = [0u8; PeerId::MAX_SIZE];
+        id[..size].copy_from_slice(vec.as_slice());
+        Ok(PeerId::new(size, id))
     }
 }
 
@@ -451,6 +458,9 @@ pub mod queryable {
     pub const ALL_KINDS: super::ZInt = 0x01;
     pub const STORAGE: super::ZInt = 0x02;
     pub const EVAL: super::ZInt = 0x04;
+    // flag ORed into the kind of an eval that replies the complete value of each path it matches,
+    // and whose replies are preferred over the others by a `PreferComplete` consolidation
+    pub const COMPLETE: super::ZInt = 0x08;
 }
 
 #[derive(Debug, Clone, PartialEq, Copy)]
@@ -16,6 +16,7 @@ use super::shm::{SharedMemoryBuf, SharedMemoryReader};
 use super::ZSlice;
 #[cfg(feature = "zero-copy")]
 use super::ZSliceBuffer;
+use bytes::Bytes;
 use std::fmt;
 use std::io;
 use std::io::IoSlice;
@@ -549,8 +550,11 @@ impl fmt::Debug for ZBuf {
                     match $slice.buf {
                         ZSliceBuffer::NetSharedBuffer(_) => write!(f, " BUF:")?,
                         ZSliceBuffer::NetOwnedBuffer(_) => write!(f, " BUF:")?,
+                        ZSliceBuffer::NetBytesBuffer(_) => write!(f, " BUF:")?,
                         ZSliceBuffer::ShmBuffer(_) => write!(f, " SHM_BUF:")?,
                         ZSliceBuffer::ShmInfo(_) => write!(f, " SHM_INFO:")?,
+                        #[cfg(feature = "mmap")]
+                        ZSliceBuffer::MmapBuffer(_) => write!(f, " MMAP_BUF:")?,
                     }
                 }
                 #[cfg(not(feature = "zero-copy"))]
@@ -667,6 +671,23 @@ impl From<Vec<u8>> for ZBuf {
     }
 }
 
+impl From<Bytes> for ZBuf {
+    fn from(buf: Bytes) -> ZBuf {
+        ZBuf::from(ZSlice::from(buf))
+    }
+}
+
+impl From<&ZBuf> for Bytes {
+    /// Converts the [`ZBuf`][ZBuf] into [`Bytes`], without copy if it's made of a single [`ZSlice`][ZSlice]
+    /// created from [`Bytes`]. Otherwise the content is copied.
+    fn from(zbuf: &ZBuf) -> Self {
+        match zbuf.zslices_num() {
+            1 => Bytes::from(zbuf.get_zslice(0).unwrap()),
+            _ => Bytes::from(zbuf.to_vec()),
+        }
+    }
+}
+
 impl From<&[u8]> for ZBuf {
     fn from(slice: &[u8]) -> ZBuf {
         ZBuf::from(slice.to_vec())
@@ -13,6 +13,7 @@
 //
 #[cfg(feature = "zero-copy")]
 use super::{SharedMemoryBuf, SharedMemoryBufInfo, SharedMemoryReader};
+use bytes::Bytes;
 use std::convert::AsRef;
 use std::fmt;
 use std::io::IoSlice;
@@ -33,10 +34,13 @@ use zenoh_util::core::ZResult;
 pub enum ZSliceBuffer {
     NetSharedBuffer(Arc<RecyclingObject<Box<[u8]>>>),
     NetOwnedBuffer(Arc<Vec<u8>>),
+    NetBytesBuffer(Bytes),
     #[cfg(feature = "zero-copy")]
     ShmBuffer(Arc<SharedMemoryBuf>),
     #[cfg(feature = "zero-copy")]
     ShmInfo(Arc<Vec<u8>>),
+    #[cfg(feature = "mmap")]
+    MmapBuffer(Arc<memmap2::Mmap>),
 }
 
 impl ZSliceBuffer {
@@ -44,13 +48,18 @@ impl ZSliceBuffer {
         match self {
             Self::NetSharedBuffer(buf) => buf,
             Self::NetOwnedBuffer(buf) => buf.as_slice(),
+            Self::NetBytesBuffer(buf) => buf,
             #[cfg(feature = "zero-copy")]
             Self::ShmBuffer(buf) => buf.as_slice(),
             #[cfg(feature = "zero-copy")]
             Self::ShmInfo(buf) => buf.as_slice(),
+            #[cfg(feature = "mmap")]
+            Self::MmapBuffer(buf) => buf,
         }
     }
 
+    // Panics for the buffers that are immutable by construction (a Bytes possibly pointing to static data,
+    // or a read-only memory-mapped file): writing to them would fault rather than be a data race.
     #[allow(clippy::missing_safety_doc)]
     #[allow(clippy::mut_from_ref)]
     unsafe fn as_mut_slice(&self) -> &mut [u8] {
@@ -59,12 +68,17 @@ impl ZSliceBuffer {
                 &mut (*(Arc::as_ptr(buf) as *mut RecyclingObject<Box<[u8]>>))
             }
             Self::NetOwnedBuffer(buf) => &mut (*(Arc::as_ptr(buf) as *mut Vec<u8>)),
+            Self::NetBytesBuffer(_) => panic!("A ZSlice backed by a Bytes is immutable"),
             #[cfg(feature = "zero-copy")]
             Self::ShmBuffer(buf) => {
                 (&mut (*(Arc::as_ptr(buf) as *mut SharedMemoryBuf))).as_mut_slice()
             }
             #[cfg(feature = "zero-copy")]
             Self::ShmInfo(buf) => &mut (*(Arc::as_ptr(buf) as *mut Vec<u8>)),
+            #[cfg(feature = "mmap")]
+            Self::MmapBuffer(_) => {
+                panic!("A ZSlice backed by a memory-mapped file is immutable")
+            }
         }
     }
 }
@@ -163,6 +177,19 @@ impl From<Vec<u8>> for ZSliceBuffer {
     }
 }
 
+impl From<Bytes> for ZSliceBuffer {
+    fn from(buf: Bytes) -> Self {
+        Self::NetBytesBuffer(buf)
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl From<Arc<memmap2::Mmap>> for ZSliceBuffer {
+    fn from(buf: Arc<memmap2::Mmap>) -> Self {
+        Self::MmapBuffer(buf)
+    }
+}
+
 impl From<&[u8]> for ZSliceBuffer {
     fn from(buf: &[u8]) -> Self {
         Self::NetOwnedBuffer(buf.to_vec().into())
@@ -248,6 +275,11 @@ impl ZSlice {
     /// This function retrieves a mutable slice from a non-mutable reference.
     /// Mutating the content of the slice without proper syncrhonization is considered
     /// undefined behavior in Rust. To use with extreme caution.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this ZSlice is backed by an immutable buffer: a [`Bytes`](bytes::Bytes)
+    /// or a memory-mapped file (see `Value::from_mmap()`).
     #[allow(clippy::mut_from_ref)]
     #[inline]
     pub unsafe fn as_mut_slice(&self) -> &mut [u8] {
@@ -262,9 +294,13 @@ impl ZSlice {
     #[inline]
     pub fn get_kind(&self) -> ZSliceKind {
         match &self.buf {
-            ZSliceBuffer::NetSharedBuffer(_) | ZSliceBuffer::NetOwnedBuffer(_) => ZSliceKind::Net,
+            ZSliceBuffer::NetSharedBuffer(_)
+            | ZSliceBuffer::NetOwnedBuffer(_)
+            | ZSliceBuffer::NetBytesBuffer(_) => ZSliceKind::Net,
             #[cfg(feature = "zero-copy")]
             ZSliceBuffer::ShmBuffer(_) | ZSliceBuffer::ShmInfo(_) => ZSliceKind::Shm,
+            #[cfg(feature = "mmap")]
+            ZSliceBuffer::MmapBuffer(_) => ZSliceKind::Net,
         }
     }
 
@@ -458,6 +494,23 @@ impl From<Vec<u8>> for ZSlice {
     }
 }
 
+impl From<Bytes> for ZSlice {
+    fn from(buf: Bytes) -> Self {
+        let len = buf.len();
+        Self::new(buf.into(), 0, len)
+    }
+}
+
+impl From<&ZSlice> for Bytes {
+    /// Converts the [`ZSlice`][ZSlice] into [`Bytes`], without copy if it was created from [`Bytes`].
+    fn from(slice: &ZSlice) -> Self {
+        match &slice.buf {
+            ZSliceBuffer::NetBytesBuffer(buf) => buf.slice(slice.start..slice.end),
+            _ => Bytes::copy_from_slice(slice.as_slice()),
+        }
+    }
+}
+
 impl From<&[u8]> for ZSlice {
     fn from(buf: &[u8]) -> Self {
         let len = buf.len();
@@ -21,7 +21,10 @@ pub mod defaults {
     // +-+-+-+-+-+-+-+-+
     // | v_maj | v_min |
     // +-------+-------+
-    pub const VERSION: u8 = 0x05;
+    // 0x06: DataInfo may carry the checksum, monotonic_stamp and message_id options,
+    //       that a 0x05 peer can't skip. The version is checked when opening a transport,
+    //       so that such options are never sent to a 0x05 peer.
+    pub const VERSION: u8 = 0x06;
 
     // The default sequence number resolution takes 4 bytes on the wire.
     // Given the VLE encoding of ZInt, 4 bytes result in 28 useful bits.
@@ -63,7 +66,7 @@ pub mod encoding {
     use zenoh_util::zerror;
 
     lazy_static! {
-    static ref MIMES: [Mime; 20] = [
+    static ref MIMES: [Mime; 28] = [
         /*  0 */ Mime::from_str("application/octet-stream").unwrap(),
         /*  1 */ Mime::from_str("application/custom").unwrap(), // non iana standard
         /*  2 */ Mime::from_str("text/plain").unwrap(),
@@ -84,6 +87,14 @@ pub mod encoding {
         /* 17 */ Mime::from_str("image/jpeg").unwrap(),
         /* 18 */ Mime::from_str("image/png").unwrap(),
         /* 19 */ Mime::from_str("image/gif").unwrap(),
+        /* 20 */ Mime::from_str("application/multipart").unwrap(), // non iana standard
+        /* 21 */ Mime::from_str("application/decimal").unwrap(), // non iana standard
+        /* 22 */ Mime::from_str("application/delta").unwrap(), // non iana standard
+        /* 23 */ Mime::from_str("application/toml").unwrap(),
+        /* 24 */ Mime::from_str("application/reference").unwrap(), // non iana standard
+        /* 25 */ Mime::from_str("application/encrypted").unwrap(), // non iana standard
+        /* 26 */ Mime::from_str("application/cbor").unwrap(),
+        /* 27 */ Mime::from_str("application/stream").unwrap(), // non iana standard
     ];
     }
 
@@ -127,6 +138,14 @@ pub mod encoding {
             "image/jpeg" => Ok(IMG_JPG),
             "image/png" => Ok(IMG_PNG),
             "image/gif" => Ok(IMG_GIF),
+            "application/multipart" => Ok(APP_MULTIPART),
+            "application/decimal" => Ok(APP_DECIMAL),
+            "application/delta" => Ok(APP_DELTA),
+            "application/toml" => Ok(APP_TOML),
+            "application/reference" => Ok(APP_REFERENCE),
+            "application/encrypted" => Ok(APP_ENCRYPTED),
+            "application/cbor" => Ok(APP_CBOR),
+            "application/stream" => Ok(APP_STREAM),
             s => zerror!(ZErrorKind::Other {
                 descr: format!("Unknown encoding '{}'", s)
             }),
@@ -155,6 +174,21 @@ pub mod encoding {
     pub const IMG_JPG: ZInt = 17;
     pub const IMG_PNG: ZInt = 18;
     pub const IMG_GIF: ZInt = 19;
+    pub const APP_MULTIPART: ZInt = 20;
+    /// A decimal number as an UTF-8 string (see `Value::decimal()` in the zenoh API).
+    pub const APP_DECIMAL: ZInt = 21;
+    /// A frame of a delta encoded Value (see `DeltaPublisher` in the zenoh API).
+    pub const APP_DELTA: ZInt = 22;
+    /// A TOML document (see `Value::from_toml_str()` in the zenoh API).
+    pub const APP_TOML: ZInt = 23;
+    /// A reference to a value stored under another path (see `Value::Reference` in the zenoh API).
+    pub const APP_REFERENCE: ZInt = 24;
+    /// A value encrypted end-to-end, with its original encoding (see `Keyring` in the zenoh API).
+    pub const APP_ENCRYPTED: ZInt = 25;
+    /// A CBOR document (see `Value::from_typed()` in the zenoh API).
+    pub const APP_CBOR: ZInt = 26;
+    /// The manifest of a value put in chunks (see `Workspace::put_stream()` in the zenoh API).
+    pub const APP_STREAM: ZInt = 27;
 
     pub const DEFAULT: ZInt = APP_OCTET_STREAM;
 }
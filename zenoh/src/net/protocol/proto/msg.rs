@@ -208,13 +208,14 @@ pub mod zmsg {
             pub const KIND: ZInt = 1 << 1; // 0x02
             pub const ENCODING: ZInt = 1 << 2; // 0x04
             pub const TIMESTAMP: ZInt = 1 << 3; // 0x08
-                                                // 0x10: Reserved
-                                                // 0x20: Reserved
+            pub const CHECKSUM: ZInt = 1 << 4; // 0x10
+            pub const MONOTONIC: ZInt = 1 << 5; // 0x20
                                                 // 0x40: Reserved
             pub const SRCID: ZInt = 1 << 7; // 0x80
             pub const SRCSN: ZInt = 1 << 8; // 0x100
             pub const RTRID: ZInt = 1 << 9; // 0x200
             pub const RTRSN: ZInt = 1 << 10; // 0x400
+            pub const MSGID: ZInt = 1 << 11; // 0x800
         }
     }
 
@@ -494,14 +495,15 @@ impl Priority {
 /// -  1: Payload kind
 /// -  2: Payload encoding
 /// -  3: Payload timestamp
-/// -  4: Reserved
-/// -  5: Reserved
+/// -  4: Payload checksum
+/// -  5: Payload monotonic_stamp
 /// -  6: Reserved
 /// -  7: Payload source_id
 /// -  8: Payload source_sn
 /// -  9: First router_id
 /// - 10: First router_sn
-/// - 11-63: Reserved
+/// - 11: Payload message_id
+/// - 12-63: Reserved
 ///
 ///  7 6 5 4 3 2 1 0
 /// +-+-+-+---------+
@@ -513,6 +515,10 @@ impl Priority {
 /// +---------------+
 /// ~   timestamp   ~ if options & (1 << 2)
 /// +---------------+
+/// ~   checksum    ~ if options & (1 << 4)
+/// +---------------+
+/// ~monotonic_stamp~ if options & (1 << 5)
+/// +---------------+
 /// ~   source_id   ~ if options & (1 << 7)
 /// +---------------+
 /// ~   source_sn   ~ if options & (1 << 8)
@@ -521,8 +527,10 @@ impl Priority {
 /// +---------------+
 /// ~first_router_sn~ if options & (1 << 10)
 /// +---------------+
+/// ~  message_id   ~ if options & (1 << 11)
+/// +---------------+
 ///
-/// - if options & (1 << 5) then the payload is sliced
+/// - if options & (1 << 0) then the payload is sliced
 ///
 /// ```
 #[derive(Debug, Clone, PartialEq)]
@@ -532,10 +540,13 @@ pub struct DataInfo {
     pub kind: Option<ZInt>,
     pub encoding: Option<ZInt>,
     pub timestamp: Option<Timestamp>,
+    pub checksum: Option<Checksum>,
+    pub monotonic_stamp: Option<ZInt>,
     pub source_id: Option<PeerId>,
     pub source_sn: Option<ZInt>,
     pub first_router_id: Option<PeerId>,
     pub first_router_sn: Option<ZInt>,
+    pub message_id: Option<[u8; 16]>,
 }
 
 impl DataInfo {
@@ -552,10 +563,13 @@ impl Default for DataInfo {
             kind: None,
             encoding: None,
             timestamp: None,
+            checksum: None,
+            monotonic_stamp: None,
             source_id: None,
             source_sn: None,
             first_router_id: None,
             first_router_sn: None,
+            message_id: None,
         }
     }
 }
@@ -576,6 +590,12 @@ impl Options for DataInfo {
         if self.timestamp.is_some() {
             options |= zmsg::data::info::TIMESTAMP;
         }
+        if self.checksum.is_some() {
+            options |= zmsg::data::info::CHECKSUM;
+        }
+        if self.monotonic_stamp.is_some() {
+            options |= zmsg::data::info::MONOTONIC;
+        }
         if self.source_id.is_some() {
             options |= zmsg::data::info::SRCID;
         }
@@ -588,6 +608,9 @@ impl Options for DataInfo {
         if self.first_router_sn.is_some() {
             options |= zmsg::data::info::RTRSN;
         }
+        if self.message_id.is_some() {
+            options |= zmsg::data::info::MSGID;
+        }
         options
     }
 
@@ -609,13 +632,34 @@ impl Options for DataInfo {
             || self.kind.is_some()
             || self.encoding.is_some()
             || self.timestamp.is_some()
+            || self.checksum.is_some()
+            || self.monotonic_stamp.is_some()
             || self.source_id.is_some()
             || self.source_sn.is_some()
             || self.first_router_id.is_some()
             || self.first_router_sn.is_some()
+            || self.message_id.is_some()
     }
 }
 
+/// # Checksum
+///
+/// Checksum of the payload, optionally included in DataInfo
+///
+/// ```text
+///  7 6 5 4 3 2 1 0
+/// +-+-+-+---------+
+/// ~     algo      ~
+/// +---------------+
+/// ~    digest     ~
+/// +---------------+
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checksum {
+    pub algo: ZInt,
+    pub digest: Vec<u8>,
+}
+
 impl PartialOrd for DataInfo {
     fn partial_cmp(&self, other: &DataInfo) -> Option<std::cmp::Ordering> {
         self.timestamp.partial_cmp(&other.timestamp)
@@ -542,6 +542,14 @@ impl ZBuf {
         if imsg::has_option(options, zmsg::data::info::TIMESTAMP) {
             info.timestamp = Some(self.read_timestamp()?);
         }
+        if imsg::has_option(options, zmsg::data::info::CHECKSUM) {
+            let algo = self.read_zint()?;
+            let digest = self.read_bytes_array()?;
+            info.checksum = Some(Checksum { algo, digest });
+        }
+        if imsg::has_option(options, zmsg::data::info::MONOTONIC) {
+            info.monotonic_stamp = Some(self.read_zint()?);
+        }
         if imsg::has_option(options, zmsg::data::info::SRCID) {
             info.source_id = Some(self.read_peerid()?);
         }
@@ -554,6 +562,9 @@ impl ZBuf {
         if imsg::has_option(options, zmsg::data::info::RTRSN) {
             info.first_router_sn = Some(self.read_zint()?);
         }
+        if imsg::has_option(options, zmsg::data::info::MSGID) {
+            info.message_id = Some(self.read_bytes_array()?.try_into().ok()?);
+        }
 
         Some(info)
     }
@@ -372,6 +372,13 @@ impl WBuf {
         if let Some(ts) = info.timestamp.as_ref() {
             zcheck!(self.write_timestamp(ts));
         }
+        if let Some(checksum) = info.checksum.as_ref() {
+            zcheck!(self.write_zint(checksum.algo));
+            zcheck!(self.write_bytes_array(&checksum.digest));
+        }
+        if let Some(stamp) = info.monotonic_stamp {
+            zcheck!(self.write_zint(stamp));
+        }
         if let Some(pid) = info.source_id.as_ref() {
             zcheck!(self.write_peerid(pid));
         }
@@ -384,6 +391,9 @@ impl WBuf {
         if let Some(sn) = info.first_router_sn {
             zcheck!(self.write_zint(sn));
         }
+        if let Some(id) = info.message_id.as_ref() {
+            zcheck!(self.write_bytes_array(id));
+        }
 
         true
     }
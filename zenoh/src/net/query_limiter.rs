@@ -0,0 +1,201 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::sync::Arc;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use zenoh_util::properties::config::*;
+
+/// The suffix of the path of the reply sent to a requester whose query has been rejected
+/// because of the overload of a queryable's session (see [`ZN_MAX_CONCURRENT_QUERIES_KEY`]).
+/// The path of such reply is `/@/<pid>/overloaded` where `<pid>` is the id of the overloaded session.
+/// It's not delivered as a reply by the requester's session, which reports the rejection with
+/// [`ReplyReceiver::is_overloaded()`](super::ReplyReceiver::is_overloaded) instead.
+pub const OVERLOADED_SUFFIX: &str = "/overloaded";
+
+/// The load of the queries received by the queryables of a [`Session`](super::Session),
+/// returned by [`Session::query_load()`](super::Session::query_load).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueryLoad {
+    /// The number of queries being processed (i.e. not yet finally replied).
+    pub active: usize,
+    /// The number of queries waiting for the processing of others to complete.
+    pub queued: usize,
+    /// The total number of queries rejected because of overload.
+    pub rejected: u64,
+}
+
+type Dispatch = Box<dyn FnOnce(QueryPermit) + Send>;
+
+struct LimiterState {
+    active: usize,
+    queue: VecDeque<Dispatch>,
+    rejected: u64,
+}
+
+// Limits the number of queries concurrently processed by the queryables of a Session,
+// across all of them (see ZN_MAX_CONCURRENT_QUERIES_KEY and ZN_MAX_QUEUED_QUERIES_KEY).
+pub(crate) struct QueryLimiter {
+    max_concurrent: usize,
+    max_queued: usize,
+    state: Mutex<LimiterState>,
+}
+
+impl QueryLimiter {
+    pub(crate) fn new(config: &ConfigProperties) -> Arc<QueryLimiter> {
+        let get = |key, default: &str| {
+            config
+                .get_or(&key, default)
+                .parse()
+                .unwrap_or_else(|_| default.parse().unwrap())
+        };
+        Arc::new(QueryLimiter {
+            max_concurrent: get(
+                ZN_MAX_CONCURRENT_QUERIES_KEY,
+                ZN_MAX_CONCURRENT_QUERIES_DEFAULT,
+            ),
+            max_queued: get(ZN_MAX_QUEUED_QUERIES_KEY, ZN_MAX_QUEUED_QUERIES_DEFAULT),
+            state: Mutex::new(LimiterState {
+                active: 0,
+                queue: VecDeque::new(),
+                rejected: 0,
+            }),
+        })
+    }
+
+    // Calls `dispatch` with a permit now if the limit of concurrent queries is not reached, or later if the
+    // query can be queued. The query is processed until the permit is dropped.
+    // Returns false if the query is rejected (and `dispatch` is not called).
+    pub(crate) fn submit(self: &Arc<Self>, dispatch: Dispatch) -> bool {
+        if self.max_concurrent == 0 {
+            dispatch(QueryPermit::unlimited());
+            return true;
+        }
+        {
+            let mut state = zlock!(self.state);
+            if state.active >= self.max_concurrent {
+                if state.queue.len() < self.max_queued {
+                    state.queue.push_back(dispatch);
+                    return true;
+                } else {
+                    state.rejected += 1;
+                    return false;
+                }
+            }
+            state.active += 1;
+        }
+        dispatch(QueryPermit {
+            limiter: Some(self.clone()),
+        });
+        true
+    }
+
+    pub(crate) fn load(&self) -> QueryLoad {
+        let state = zlock!(self.state);
+        QueryLoad {
+            active: state.active,
+            queued: state.queue.len(),
+            rejected: state.rejected,
+        }
+    }
+
+    fn release(self: &Arc<Self>) {
+        let next = {
+            let mut state = zlock!(self.state);
+            let next = state.queue.pop_front();
+            if next.is_none() {
+                state.active -= 1;
+            }
+            next
+        };
+        // the permit is transferred to the next queued query
+        if let Some(dispatch) = next {
+            dispatch(QueryPermit {
+                limiter: Some(self.clone()),
+            });
+        }
+    }
+}
+
+// A query being processed, until dropped.
+pub(crate) struct QueryPermit {
+    limiter: Option<Arc<QueryLimiter>>,
+}
+
+impl QueryPermit {
+    // A permit for a query not accounted by any limiter.
+    pub(crate) fn unlimited() -> QueryPermit {
+        QueryPermit { limiter: None }
+    }
+}
+
+impl Drop for QueryPermit {
+    fn drop(&mut self) {
+        if let Some(limiter) = self.limiter.take() {
+            limiter.release();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(max_concurrent: usize, max_queued: usize) -> Arc<QueryLimiter> {
+        let mut config = ConfigProperties::default();
+        config.insert(ZN_MAX_CONCURRENT_QUERIES_KEY, max_concurrent.to_string());
+        config.insert(ZN_MAX_QUEUED_QUERIES_KEY, max_queued.to_string());
+        QueryLimiter::new(&config)
+    }
+
+    #[test]
+    fn query_limiter() {
+        let limiter = limiter(1, 1);
+        let permits = Arc::new(Mutex::new(vec![]));
+        let submit = |limiter: &Arc<QueryLimiter>| {
+            let permits = permits.clone();
+            limiter.submit(Box::new(move |permit| zlock!(permits).push(permit)))
+        };
+
+        assert!(submit(&limiter));
+        assert!(submit(&limiter));
+        assert!(!submit(&limiter));
+        let expected = QueryLoad {
+            active: 1,
+            queued: 1,
+            rejected: 1,
+        };
+        assert_eq!(limiter.load(), expected);
+
+        // the end of the first query dispatches the queued one
+        let first = zlock!(permits).remove(0);
+        drop(first);
+        assert_eq!(zlock!(permits).len(), 1);
+        assert_eq!(limiter.load().queued, 0);
+        assert_eq!(limiter.load().active, 1);
+
+        zlock!(permits).clear();
+        assert_eq!(limiter.load().active, 0);
+        assert!(submit(&limiter));
+        assert_eq!(limiter.load().active, 1);
+    }
+
+    #[test]
+    fn query_limiter_unlimited() {
+        let limiter = limiter(0, 0);
+        for _ in 0..10 {
+            assert!(limiter.submit(Box::new(|_| ())));
+        }
+        assert_eq!(limiter.load(), QueryLoad::default());
+    }
+}
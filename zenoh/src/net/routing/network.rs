@@ -80,6 +80,8 @@ pub(crate) struct Tree {
     pub(crate) parent: Option<NodeIndex>,
     pub(crate) childs: Vec<NodeIndex>,
     pub(crate) directions: Vec<Option<NodeIndex>>,
+    // the parent of each node in this tree, only kept for the tree rooted at this node (see path_to())
+    pub(crate) parents: Vec<Option<NodeIndex>>,
 }
 
 pub(crate) struct Network {
@@ -120,6 +122,7 @@ impl Network {
                 parent: None,
                 childs: vec![],
                 directions: vec![None],
+                parents: vec![None],
             }],
             graph,
             runtime,
@@ -150,6 +153,24 @@ impl Network {
         self.links.values().find(|link| link.pid == *pid)
     }
 
+    // Returns the nodes traversed by a message sent by this node to `destination` (i.e. the branch of the
+    // tree rooted at this node, as last computed by compute_trees() for the routing), excluding this node
+    // and including `destination`.
+    pub(crate) fn path_to(&self, destination: NodeIndex) -> Option<Vec<NodeIndex>> {
+        let parents = &self.trees.get(self.idx.index())?.parents;
+        let mut path = vec![];
+        let mut current = destination;
+        while current != self.idx {
+            path.push(current);
+            if path.len() > parents.len() {
+                return None;
+            }
+            current = (*parents.get(current.index())?)?;
+        }
+        path.reverse();
+        Some(path)
+    }
+
     #[inline]
     pub(crate) fn get_local_context(&self, context: Option<ZInt>, link_id: usize) -> usize {
         let context = context.unwrap_or(0);
@@ -640,6 +661,7 @@ impl Network {
             parent: None,
             childs: vec![],
             directions: vec![],
+            parents: vec![],
         });
 
         for tree_root_idx in &indexes {
@@ -665,6 +687,9 @@ impl Network {
             }
 
             self.trees[tree_root_idx.index()].parent = path[self.idx.index()];
+            if *tree_root_idx == self.idx {
+                self.trees[tree_root_idx.index()].parents = path.clone();
+            }
 
             for idx in &indexes {
                 if let Some(parent_idx) = path[idx.index()] {
@@ -934,22 +934,29 @@ pub(crate) fn compute_matches_data_routes(tables: &mut Tables, res: &mut Arc<Res
 }
 
 macro_rules! treat_timestamp {
-    ($hlc:expr, $info:expr) => {
+    ($hlc:expr, $clock_skews:expr, $info:expr) => {
         // if an HLC was configured (via Config.add_timestamp),
         // check DataInfo and add a timestamp if there isn't
         match $hlc {
             Some(hlc) => {
                 if let Some(mut data_info) = $info {
                     if let Some(ref ts) = data_info.timestamp {
-                        // Timestamp is present; update HLC with it (possibly raising error if delta exceed)
-                        match hlc.update_with_timestamp(ts) {
-                            Ok(()) => Some(data_info),
-                            Err(e) => {
-                                log::error!(
-                                    "Error treating timestamp for received Data ({}): drop it!",
-                                    e
-                                );
-                                return;
+                        if !$clock_skews.accept(ts) {
+                            // Timestamp too far ahead of the local clock (see ZN_MAX_CLOCK_DRIFT_KEY);
+                            // clamp it with a new one from the local HLC
+                            data_info.timestamp = Some(hlc.new_timestamp());
+                            Some(data_info)
+                        } else {
+                            // Timestamp is present; update HLC with it (possibly raising error if delta exceed)
+                            match hlc.update_with_timestamp(ts) {
+                                Ok(()) => Some(data_info),
+                                Err(e) => {
+                                    log::error!(
+                                        "Error treating timestamp for received Data ({}): drop it!",
+                                        e
+                                    );
+                                    return;
+                                }
                             }
                         }
                     } else {
@@ -1147,7 +1154,7 @@ pub fn route_data(
             let matching_pulls = get_matching_pulls(tables, &res, &prefix, suffix);
 
             if !(route.is_empty() && matching_pulls.is_empty()) {
-                let data_info = treat_timestamp!(&tables.hlc, info);
+                let data_info = treat_timestamp!(&tables.hlc, tables.clock_skews, info);
 
                 if route.len() == 1 && matching_pulls.len() == 0 {
                     send_to_first!(route, face, payload, channel, congestion_control, data_info);
@@ -1190,7 +1197,7 @@ pub fn full_reentrant_route_data(
             let matching_pulls = get_matching_pulls(&tables, &res, &prefix, suffix);
 
             if !(route.is_empty() && matching_pulls.is_empty()) {
-                let data_info = treat_timestamp!(&tables.hlc, info);
+                let data_info = treat_timestamp!(&tables.hlc, tables.clock_skews, info);
 
                 if route.len() == 1 && matching_pulls.len() == 0 {
                     drop(tables);
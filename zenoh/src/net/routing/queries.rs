@@ -28,6 +28,21 @@ use super::network::Network;
 use super::resource::{elect_router, Resource, Route, SessionContext};
 use super::router::Tables;
 
+/// A node traversed by the queries on a resource to reach a queryable (see [`Session::trace_route()`](crate::net::Session::trace_route)).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hop {
+    /// The id of the node.
+    pub pid: PeerId,
+    /// The kind of the node (see [`whatami`](crate::net::protocol::core::whatami)).
+    pub whatami: whatami::Type,
+    /// The id of the node forwarding the queries to this node.
+    pub from: PeerId,
+    /// The number of hops from the local node (0 for the local node itself).
+    pub distance: usize,
+    /// `true` if this node declared a matching queryable, `false` if it only forwards the queries.
+    pub responder: bool,
+}
+
 pub(crate) struct Query {
     src_face: Arc<FaceState>,
     src_qid: ZInt,
@@ -923,6 +938,78 @@ fn compute_query_route(
     Arc::new(route)
 }
 
+fn insert_hops_for_qabls(
+    hops: &mut HashMap<PeerId, Hop>,
+    tables: &Tables,
+    net: &Network,
+    qabls: &HashMap<PeerId, ZInt>,
+) {
+    for qabl in qabls.keys().filter(|qabl| **qabl != tables.pid) {
+        let path = match net.get_idx(qabl).and_then(|idx| net.path_to(idx)) {
+            Some(path) => path,
+            None => {
+                log::trace!("No path to queryable node {} yet", qabl);
+                continue;
+            }
+        };
+        let mut from = tables.pid;
+        for (i, idx) in path.iter().enumerate() {
+            let node = &net.graph[*idx];
+            let hop = hops.entry(node.pid).or_insert_with(|| Hop {
+                pid: node.pid,
+                whatami: node.whatami,
+                from,
+                distance: i + 1,
+                responder: false,
+            });
+            hop.responder |= node.pid == *qabl;
+            from = node.pid;
+        }
+    }
+}
+
+// Returns the nodes traversed by the queries on `res_name` from this node to reach the matching
+// queryables, ordered by distance.
+pub(crate) fn trace_query_route(tables: &Tables, res_name: &str) -> Vec<Hop> {
+    let mut hops: HashMap<PeerId, Hop> = HashMap::new();
+    for mres in Resource::get_matches(tables, res_name) {
+        let mres = match mres.upgrade() {
+            Some(mres) => mres,
+            None => continue,
+        };
+        if let Some(ctx) = mres.context.as_ref() {
+            if tables.whatami == whatami::ROUTER {
+                if let Some(net) = tables.routers_net.as_ref() {
+                    insert_hops_for_qabls(&mut hops, tables, net, &ctx.router_qabls);
+                }
+            }
+            if tables.whatami == whatami::ROUTER || tables.whatami == whatami::PEER {
+                if let Some(net) = tables.peers_net.as_ref() {
+                    insert_hops_for_qabls(&mut hops, tables, net, &ctx.peer_qabls);
+                }
+            }
+        }
+        for context in mres.session_ctxs.values() {
+            if context.qabl.is_some() {
+                let face = &context.face;
+                let local = face.pid == tables.pid;
+                let hop = hops.entry(face.pid).or_insert_with(|| Hop {
+                    pid: face.pid,
+                    whatami: face.whatami,
+                    from: tables.pid,
+                    distance: if local { 0 } else { 1 },
+                    responder: false,
+                });
+                // a client only knows the router it's connected to, not the queryables behind it
+                hop.responder |= local || tables.whatami != whatami::CLIENT;
+            }
+        }
+    }
+    let mut hops: Vec<Hop> = hops.into_values().collect();
+    hops.sort_by(|a, b| (a.distance, a.pid.as_slice()).cmp(&(b.distance, b.pid.as_slice())));
+    hops
+}
+
 pub(crate) fn compute_query_routes(tables: &mut Tables, res: &mut Arc<Resource>) {
     if res.context.is_some() {
         let mut res_mut = res.clone();
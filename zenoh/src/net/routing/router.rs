@@ -18,7 +18,7 @@ use super::protocol::proto::{ZenohBody, ZenohMessage};
 pub use super::pubsub::*;
 pub use super::queries::*;
 pub use super::resource::*;
-use super::runtime::Runtime;
+use super::runtime::{ClockSkews, Runtime};
 use super::transport::{DeMux, Mux, Primitives, TransportPeerEventHandler, TransportUnicast};
 use crate::net::link::Link;
 use async_std::sync::{Arc, Weak};
@@ -36,12 +36,21 @@ zconfigurable! {
     static ref TREES_COMPUTATION_DELAY: u64 = 100;
 }
 
+/// The subscriptions and queryables declared by a face, as reported in the admin space.
+pub(crate) struct FaceDeclarations {
+    pub(crate) pid: PeerId,
+    pub(crate) whatami: WhatAmI,
+    pub(crate) subscribers: Vec<String>,
+    pub(crate) queryables: Vec<(String, ZInt)>,
+}
+
 pub struct Tables {
     pub(crate) pid: PeerId,
     pub(crate) whatami: whatami::Type,
     face_counter: usize,
     #[allow(dead_code)]
     pub(crate) hlc: Option<Arc<HLC>>,
+    pub(crate) clock_skews: Arc<ClockSkews>,
     pub(crate) root_res: Arc<Resource>,
     pub(crate) faces: HashMap<usize, Arc<FaceState>>,
     pub(crate) pull_caches_lock: Mutex<()>,
@@ -63,6 +72,7 @@ impl Tables {
             whatami,
             face_counter: 0,
             hlc,
+            clock_skews: Arc::new(ClockSkews::default()),
             root_res: Resource::root(),
             faces: HashMap::new(),
             pull_caches_lock: Mutex::new(()),
@@ -78,6 +88,30 @@ impl Tables {
         }
     }
 
+    /// Returns the subscriptions and queryables declared by each face of this router.
+    pub(crate) fn faces_declarations(&self) -> Vec<FaceDeclarations> {
+        self.faces
+            .values()
+            .map(|face| FaceDeclarations {
+                pid: face.pid,
+                whatami: face.whatami,
+                subscribers: face.remote_subs.iter().map(|res| res.name()).collect(),
+                queryables: face
+                    .remote_qabls
+                    .iter()
+                    .map(|res| {
+                        let kind = res
+                            .session_ctxs
+                            .get(&face.id)
+                            .and_then(|ctx| ctx.qabl)
+                            .unwrap_or(0);
+                        (res.name(), kind)
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
     #[doc(hidden)]
     pub fn _get_root(&self) -> &Arc<Resource> {
         &self.root_res
@@ -242,10 +276,17 @@ pub struct Router {
 }
 
 impl Router {
-    pub fn new(pid: PeerId, whatami: whatami::Type, hlc: Option<Arc<HLC>>) -> Self {
+    pub fn new(
+        pid: PeerId,
+        whatami: whatami::Type,
+        hlc: Option<Arc<HLC>>,
+        clock_skews: Arc<ClockSkews>,
+    ) -> Self {
+        let mut tables = Tables::new(pid, whatami, hlc);
+        tables.clock_skews = clock_skews;
         Router {
             whatami,
-            tables: Arc::new(RwLock::new(Tables::new(pid, whatami, hlc))),
+            tables: Arc::new(RwLock::new(tables)),
         }
     }
 
@@ -10,16 +10,41 @@
 //
 // Contributors:
 //   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+
+//! The admin space of a zenoh router.
+//!
+//! The admin space is started by the zenoh router (`zenohd`) and is always enabled there
+//! (peers and clients don't expose any admin space). It replies to the queries on
+//! `/@/router/<pid>/**` (where `<pid>` is the router's PeerId) with the following paths:
+//!
+//!  - `/@/router/<pid>` : the router's general information, as JSON:
+//!    `{"pid": <pid>, "version": <version>, "locators": [<locator>], "sessions": [{"peer": <pid>, "links": [<locator>]}], "plugins": [{"name": <name>, "path": <path>}]}`
+//!  - `/@/router/<pid>/linkstate/routers` and `/@/router/<pid>/linkstate/peers` : the routers and peers
+//!    graphs, in [DOT](https://graphviz.org/doc/info/lang.html) format.
+//!  - `/@/router/<pid>/subscribers` : the subscriptions declared by each session connected to the router, as JSON:
+//!    `[{"peer": <pid>, "whatami": <whatami>, "subscribers": [<resource name>]}]`
+//!  - `/@/router/<pid>/queryables` : the queryables (storages and evals) declared by each session connected to
+//!    the router, as JSON:
+//!    `[{"peer": <pid>, "whatami": <whatami>, "queryables": [{"resource": <resource name>, "kind": "storage"|"eval"|<kind>}]}]`
+//!  - `/@/router/<pid>/transports` : the transports of the router with their links, as JSON:
+//!    `[{"peer": <pid>, "whatami": <whatami>, "links": [{"src": <locator>, "dst": <locator>, "mtu": <mtu>, "reliable": <bool>, "streamed": <bool>}]}]`.
+//!    If zenoh is built with the `stats` feature, each transport also has a
+//!    `"stats": {"tx_msgs": <n>, "tx_bytes": <n>, "rx_msgs": <n>, "rx_bytes": <n>}` field.
+//!
+//! `/@/router/local` can be used as an alias of `/@/router/<pid>` by the sessions directly connected
+//! to the router. Thus a monitoring tool can use a `get` on `/@/router/*/subscribers` to collect the
+//! subscriptions of all the routers of the system.
 use super::protocol::{
     core::{
-        queryable::EVAL, rname, Channel, CongestionControl, PeerId, QueryConsolidation,
-        QueryTarget, ResKey, SubInfo, ZInt,
+        queryable::{COMPLETE, EVAL, STORAGE},
+        rname, whatami, Channel, CongestionControl, PeerId, QueryConsolidation, QueryTarget,
+        ResKey, SubInfo, ZInt,
     },
     io::ZBuf,
     proto::{encoding, DataInfo, RoutingContext},
 };
 use super::routing::face::Face;
-use super::transport::Primitives;
+use super::transport::{Primitives, TransportUnicast};
 use super::Runtime;
 use async_std::sync::Arc;
 use async_std::task;
@@ -69,6 +94,18 @@ impl AdminSpace {
             [&root_path, "/linkstate/peers"].concat(),
             Arc::new(Box::new(|context| linkstate_peers_data(context).boxed())),
         );
+        handlers.insert(
+            [&root_path, "/subscribers"].concat(),
+            Arc::new(Box::new(|context| subscribers_data(context).boxed())),
+        );
+        handlers.insert(
+            [&root_path, "/queryables"].concat(),
+            Arc::new(Box::new(|context| queryables_data(context).boxed())),
+        );
+        handlers.insert(
+            [&root_path, "/transports"].concat(),
+            Arc::new(Box::new(|context| transports_data(context).boxed())),
+        );
         let context = Arc::new(AdminContext {
             runtime: runtime.clone(),
             plugins_mgr,
@@ -340,3 +377,115 @@ pub async fn linkstate_peers_data(context: &AdminContext) -> (ZBuf, ZInt) {
         encoding::TEXT_PLAIN,
     )
 }
+
+pub async fn subscribers_data(context: &AdminContext) -> (ZBuf, ZInt) {
+    let declarations = zread!(context.runtime.router.tables).faces_declarations();
+    let json: Vec<serde_json::Value> = declarations
+        .iter()
+        .filter(|decls| !decls.subscribers.is_empty())
+        .map(|decls| {
+            json!({
+                "peer": decls.pid.to_string(),
+                "whatami": whatami::to_string(decls.whatami),
+                "subscribers": decls.subscribers,
+            })
+        })
+        .collect();
+    log::trace!("AdminSpace subscribers_data: {:?}", json);
+    (
+        ZBuf::from(serde_json::Value::from(json).to_string().as_bytes()),
+        encoding::APP_JSON,
+    )
+}
+
+pub async fn queryables_data(context: &AdminContext) -> (ZBuf, ZInt) {
+    let declarations = zread!(context.runtime.router.tables).faces_declarations();
+    let json: Vec<serde_json::Value> = declarations
+        .iter()
+        .filter(|decls| !decls.queryables.is_empty())
+        .map(|decls| {
+            let queryables: Vec<serde_json::Value> = decls
+                .queryables
+                .iter()
+                .map(|(resource, kind)| {
+                    let kind = match *kind {
+                        STORAGE => json!("storage"),
+                        EVAL => json!("eval"),
+                        kind if kind == EVAL | COMPLETE => json!("complete eval"),
+                        kind => json!(kind),
+                    };
+                    json!({ "resource": resource, "kind": kind })
+                })
+                .collect();
+            json!({
+                "peer": decls.pid.to_string(),
+                "whatami": whatami::to_string(decls.whatami),
+                "queryables": queryables,
+            })
+        })
+        .collect();
+    log::trace!("AdminSpace queryables_data: {:?}", json);
+    (
+        ZBuf::from(serde_json::Value::from(json).to_string().as_bytes()),
+        encoding::APP_JSON,
+    )
+}
+
+pub async fn transports_data(context: &AdminContext) -> (ZBuf, ZInt) {
+    let transports: Vec<serde_json::Value> = context
+        .runtime
+        .manager()
+        .get_transports()
+        .iter()
+        .map(|transport| {
+            let links: Vec<serde_json::Value> = transport.get_links().map_or_else(
+                |_| Vec::new(),
+                |links| {
+                    links
+                        .iter()
+                        .map(|link| {
+                            json!({
+                                "src": link.src.to_string(),
+                                "dst": link.dst.to_string(),
+                                "mtu": link.mtu,
+                                "reliable": link.is_reliable,
+                                "streamed": link.is_streamed,
+                            })
+                        })
+                        .collect()
+                },
+            );
+            let mut json = json!({
+                "peer": transport.get_pid().map_or_else(|_| "unavailable".to_string(), |p| p.to_string()),
+                "whatami": transport.get_whatami().map_or_else(|_| "unavailable".to_string(), whatami::to_string),
+                "links": links,
+            });
+            if let Some(stats) = transport_stats(transport) {
+                json["stats"] = stats;
+            }
+            json
+        })
+        .collect();
+    log::trace!("AdminSpace transports_data: {:?}", transports);
+    (
+        ZBuf::from(serde_json::Value::from(transports).to_string().as_bytes()),
+        encoding::APP_JSON,
+    )
+}
+
+#[cfg(feature = "stats")]
+fn transport_stats(transport: &TransportUnicast) -> Option<serde_json::Value> {
+    transport.get_stats().ok().map(|stats| {
+        json!({
+            "tx_msgs": stats.tx_msgs,
+            "tx_bytes": stats.tx_bytes,
+            "rx_msgs": stats.rx_msgs,
+            "rx_bytes": stats.rx_bytes,
+        })
+    })
+}
+
+#[cfg(not(feature = "stats"))]
+fn transport_stats(_transport: &TransportUnicast) -> Option<serde_json::Value> {
+    None
+}
@@ -14,11 +14,12 @@
 mod adminspace;
 pub mod orchestrator;
 
+use super::health;
 use super::link;
-use super::link::{Link, Locator};
+use super::link::{Link, Locator, TransportFactory};
 use super::plugins;
 use super::protocol;
-use super::protocol::core::{whatami, PeerId, WhatAmI};
+use super::protocol::core::{whatami, PeerId, Timestamp, TimestampId, WhatAmI};
 use super::protocol::proto::{ZenohBody, ZenohMessage};
 use super::routing;
 use super::routing::pubsub::full_reentrant_route_data;
@@ -30,12 +31,18 @@ use super::transport::{
 };
 pub use adminspace::AdminSpace;
 use async_std::sync::Arc;
+use event_listener::{Event, EventListener};
 use std::any::Any;
-use uhlc::HLC;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use uhlc::{HLC, NTP64};
 use zenoh_util::core::{ZError, ZErrorKind, ZResult};
 use zenoh_util::properties::config::*;
 use zenoh_util::sync::get_mut_unchecked;
-use zenoh_util::{zerror, zerror2};
+use zenoh_util::{zerror, zerror2, zparse};
 
 pub struct RuntimeState {
     pub pid: PeerId,
@@ -44,6 +51,163 @@ pub struct RuntimeState {
     pub config: ConfigProperties,
     pub manager: TransportManager,
     pub hlc: Option<Arc<HLC>>,
+    pub connection_losses: ConnectionLosses,
+    pub startup_warnings: StartupWarnings,
+    pub clock_skews: Arc<ClockSkews>,
+    // the configuration as updated by Runtime::reload_config()
+    pub(crate) current_config: Mutex<ConfigProperties>,
+    // the peers currently listed in the file of ZN_PEERS_FILE_KEY
+    pub(crate) file_peers: Mutex<Vec<Locator>>,
+    // set and notified by Runtime::close() to stop the background tasks (e.g. the peers file watcher)
+    pub(crate) closed: AtomicBool,
+    pub(crate) closing: Event,
+}
+
+/// Notifies the losses of the connections to remote zenoh nodes (routers or peers).
+///
+/// Note that the subscriptions and queryables of a session are re-declared by the routing
+/// when a connection is re-established, but the data published in between are lost.
+#[derive(Default)]
+pub struct ConnectionLosses {
+    count: AtomicUsize,
+    event: Event,
+}
+
+impl ConnectionLosses {
+    pub(crate) fn notify(&self) {
+        self.count.fetch_add(1, Ordering::AcqRel);
+        self.event.notify(usize::MAX);
+    }
+
+    /// Returns the number of connections lost since the creation of the runtime.
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Acquire)
+    }
+
+    /// Returns a listener notified at the next connection loss.
+    pub fn listen(&self) -> EventListener {
+        self.event.listen()
+    }
+}
+
+/// The skew of the clock of a zenoh node, detected in the timestamps of the data it published
+/// (see [`ZN_MAX_CLOCK_DRIFT_KEY`]).
+#[derive(Clone, Debug)]
+pub struct ClockSkew {
+    /// The id of the HLC that generated the timestamps.
+    pub source: TimestampId,
+    /// The largest advance of those timestamps on the local clock.
+    pub max_skew: Duration,
+    /// The number of timestamps clamped because they exceeded the maximum drift.
+    pub clamped: usize,
+}
+
+/// Bounds the drift of the timestamps of the received data with the local clock
+/// (see [`ZN_MAX_CLOCK_DRIFT_KEY`]), keeping track of the detected skews.
+#[derive(Default)]
+pub struct ClockSkews {
+    max_drift: Option<Duration>,
+    skews: Mutex<HashMap<TimestampId, ClockSkew>>,
+}
+
+impl ClockSkews {
+    pub(crate) fn new(max_drift: Option<Duration>) -> ClockSkews {
+        ClockSkews {
+            max_drift,
+            skews: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Returns false if `timestamp` is further ahead of the local clock than the maximum drift.
+    // A warning is logged for the first clamped timestamp of each source only, the counts being
+    // reported by `get()`.
+    pub(crate) fn accept(&self, timestamp: &Timestamp) -> bool {
+        let max_drift = match self.max_drift {
+            Some(max_drift) => max_drift,
+            None => return true,
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let bound: NTP64 = (now + max_drift).into();
+        if *timestamp.get_time() <= bound {
+            return true;
+        }
+        let now: NTP64 = now.into();
+        // NTP64 is a fixed-point number of seconds, with 32 bits for the fraction
+        let skew = Duration::from_secs_f64(
+            (timestamp.get_time().as_u64() - now.as_u64()) as f64 / (1u64 << 32) as f64,
+        );
+        let source = *timestamp.get_id();
+        let mut skews = zlock!(self.skews);
+        let entry = skews.entry(source).or_insert_with(|| {
+            log::warn!(
+                "Timestamp {} is {:?} ahead of the local clock (more than the max drift of {:?}): clamp it, \
+                and the next ones of {} exceeding the max drift",
+                timestamp,
+                skew,
+                max_drift,
+                source
+            );
+            ClockSkew {
+                source,
+                max_skew: skew,
+                clamped: 0,
+            }
+        });
+        entry.max_skew = entry.max_skew.max(skew);
+        entry.clamped += 1;
+        false
+    }
+
+    /// Returns the skews detected so far, one per source.
+    pub fn get(&self) -> Vec<ClockSkew> {
+        zlock!(self.skews).values().cloned().collect()
+    }
+}
+
+/// A failure of a configured listener or peer at the startup of a runtime, that didn't prevent it to start
+/// (see [`ZN_STRICT_STARTUP_KEY`]).
+#[derive(Clone, Debug)]
+pub struct ConfigWarning {
+    /// The configuration key of the failed element (i.e. [`ZN_LISTENER_STR`], [`ZN_PEER_STR`] or [`ZN_PEERS_FILE_STR`]).
+    pub key: &'static str,
+    /// The failed element (e.g. the locator of the listener).
+    pub value: String,
+    /// The reason of the failure.
+    pub reason: String,
+}
+
+impl fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} failed: {}", self.key, self.value, self.reason)
+    }
+}
+
+/// The report of a configuration reload (see [`Runtime::reload_config()`]).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReloadReport {
+    /// The keys whose new value has been applied.
+    pub applied: Vec<String>,
+    /// The keys whose new value can only be applied by a restart, and which have been ignored.
+    pub restart_required: Vec<String>,
+}
+
+/// The [`ConfigWarning`]s collected at the startup of a runtime.
+#[derive(Default)]
+pub struct StartupWarnings(Mutex<Vec<ConfigWarning>>);
+
+impl StartupWarnings {
+    pub(crate) fn add(&self, key: &'static str, value: String, reason: String) {
+        let warning = ConfigWarning { key, value, reason };
+        log::warn!("Startup warning: {}", warning);
+        zlock!(self.0).push(warning);
+    }
+
+    /// Returns the warnings collected so far.
+    pub fn get(&self) -> Vec<ConfigWarning> {
+        zlock!(self.0).clone()
+    }
 }
 
 #[derive(Clone)]
@@ -61,26 +225,32 @@ impl std::ops::Deref for Runtime {
 
 impl Runtime {
     pub async fn new(version: u8, config: ConfigProperties, id: Option<&str>) -> ZResult<Runtime> {
+        Runtime::new_with_transports(version, config, id, vec![]).await
+    }
+
+    /// Creates a Runtime using the given [`TransportFactory`]s in addition to (or in place of)
+    /// the built-in transports.  
+    /// The PeerId of the Runtime is `id` if specified, or else the one configured with
+    /// [`ZN_PEER_ID_KEY`], or else a random one.
+    pub async fn new_with_transports(
+        version: u8,
+        config: ConfigProperties,
+        id: Option<&str>,
+        transports: Vec<Arc<dyn TransportFactory>>,
+    ) -> ZResult<Runtime> {
         // Make sure to have have enough threads spawned in the async futures executor
         zasync_executor_init!();
 
+        let id = id
+            .map(str::to_string)
+            .or_else(|| config.get(&ZN_PEER_ID_KEY).cloned());
         let pid = if let Some(s) = id {
-            // filter-out '-' characters (in case s has UUID format)
-            let s = s.replace('-', "");
-            let vec = hex::decode(&s).map_err(|e| {
-                zerror2!(ZErrorKind::Other {
-                    descr: format!("Invalid id: {} - {}", s, e)
-                })
-            })?;
-            let size = vec.len();
-            if size > PeerId::MAX_SIZE {
-                return zerror!(ZErrorKind::Other {
-                    descr: format!("Invalid id size: {} ({} bytes max)", size, PeerId::MAX_SIZE)
-                });
-            }
-            let mut id = [0u8; PeerId::MAX_SIZE];
-            id[..size].copy_from_slice(vec.as_slice());
-            PeerId::new(size, id)
+            let pid = s.parse::<PeerId>()?;
+            log::warn!(
+                "Using the user-supplied PID {}: its uniqueness is not enforced, make sure no other zenoh node uses it",
+                pid
+            );
+            pid
         } else {
             PeerId::from(uuid::Uuid::new_v4())
         };
@@ -98,12 +268,25 @@ impl Runtime {
             None
         };
 
-        let router = Arc::new(Router::new(pid, whatami, hlc.clone()));
+        let max_clock_drift: u64 =
+            zparse!(config.get_or(&ZN_MAX_CLOCK_DRIFT_KEY, ZN_MAX_CLOCK_DRIFT_DEFAULT))?;
+        let clock_skews = Arc::new(ClockSkews::new(if max_clock_drift > 0 {
+            Some(Duration::from_millis(max_clock_drift))
+        } else {
+            None
+        }));
+
+        let router = Arc::new(Router::new(pid, whatami, hlc.clone(), clock_skews.clone()));
 
         let handler = Arc::new(RuntimeTransportEventHandler {
             runtime: std::sync::RwLock::new(None),
         });
-        let sm_config = TransportManagerConfig::builder()
+        // the custom transports are registered first, to be known when validating the config
+        let mut sm_config = TransportManagerConfig::builder();
+        for factory in transports {
+            sm_config = sm_config.transport(factory);
+        }
+        let sm_config = sm_config
             .from_config(&config)
             .await?
             .version(version)
@@ -120,10 +303,25 @@ impl Runtime {
                 config: config.clone(),
                 manager: transport_manager,
                 hlc,
+                connection_losses: ConnectionLosses::default(),
+                startup_warnings: StartupWarnings::default(),
+                clock_skews,
+                current_config: Mutex::new(config.clone()),
+                file_peers: Mutex::new(vec![]),
+                closed: AtomicBool::new(false),
+                closing: Event::new(),
             }),
         };
         *handler.runtime.write().unwrap() = Some(runtime.clone());
 
+        if config
+            .get_or(&ZN_WATCHDOG_KEY, ZN_WATCHDOG_DEFAULT)
+            .to_lowercase()
+            == ZN_TRUE
+        {
+            health::start_watchdog(health::stall_threshold(&config));
+        }
+
         let peers_autoconnect = config
             .get_or(&ZN_PEERS_AUTOCONNECT_KEY, ZN_PEERS_AUTOCONNECT_DEFAULT)
             .to_lowercase()
@@ -160,6 +358,8 @@ impl Runtime {
 
     pub async fn close(&self) -> ZResult<()> {
         log::trace!("Runtime::close())");
+        self.closed.store(true, Ordering::Release);
+        self.closing.notify(usize::MAX);
         for session in &mut self.manager().get_transports() {
             session.close().await?;
         }
@@ -248,6 +448,7 @@ impl TransportPeerEventHandler for RuntimeSession {
     }
 
     fn closing(&self) {
+        self.runtime.connection_losses.notify();
         self.sub_event_handler.closing();
         Runtime::closing_session(self);
     }
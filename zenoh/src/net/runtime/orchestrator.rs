@@ -16,14 +16,17 @@ use super::protocol::core::{whatami, PeerId, WhatAmI};
 use super::protocol::io::{WBuf, ZBuf};
 use super::protocol::proto::{Hello, Scout, TransportBody, TransportMessage};
 use super::transport::TransportUnicast;
-use super::{Runtime, RuntimeSession};
+use super::{ReloadReport, Runtime, RuntimeSession};
+use crate::net::health::Heartbeat;
 use async_std::net::UdpSocket;
 use futures::prelude::*;
 use socket2::{Domain, Socket, Type};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 use zenoh_util::core::{ZError, ZErrorKind, ZResult};
 use zenoh_util::properties::config::*;
+use zenoh_util::properties::KeyTranscoder;
 use zenoh_util::zerror;
 
 const RCV_BUF_SIZE: usize = 65536;
@@ -36,6 +39,7 @@ const CONNECTION_RETRY_MAX_PERIOD: u64 = 4000; //ms
 const CONNECTION_RETRY_PERIOD_INCREASE_FACTOR: u64 = 2;
 const ROUTER_DEFAULT_LISTENER: &str = "tcp/0.0.0.0:7447";
 const PEER_DEFAULT_LISTENER: &str = "tcp/0.0.0.0:0";
+const PEERS_FILE_PERIOD: u64 = 1000; //ms
 
 pub enum Loop {
     Continue,
@@ -105,8 +109,17 @@ impl Runtime {
                                     .to_string()
                             })
                         } else {
-                            self.connect_first(&sockets, whatami::ROUTER, &addr, timeout)
+                            match self
+                                .connect_first(&sockets, whatami::ROUTER, &addr, timeout)
                                 .await
+                            {
+                                Err(e) if matches!(e.get_kind(), ZErrorKind::Timeout {}) => {
+                                    zerror!(ZErrorKind::NoRouter {
+                                        descr: format!("no router scouted within {:?}", timeout)
+                                    })
+                                }
+                                res => res,
+                            }
                         }
                     }
                 } else {
@@ -122,13 +135,25 @@ impl Runtime {
                         config: None,
                     };
                     match self.manager().open_transport(endpoint).await {
-                        Ok(_) => return Ok(()),
+                        // in client mode only a router can route our declarations and queries
+                        Ok(transport) => match transport.get_whatami() {
+                            Ok(whatami::ROUTER) => return Ok(()),
+                            Ok(what) => {
+                                log::warn!(
+                                    "{} is a {} and not a router: close the connection",
+                                    locator,
+                                    whatami::to_string(what)
+                                );
+                                let _ = transport.close().await;
+                            }
+                            Err(err) => log::warn!("Unable to connect to {}! {}", locator, err),
+                        },
                         Err(err) => log::warn!("Unable to connect to {}! {}", locator, err),
                     }
                 }
-                log::error!("Unable to connect to any of {:?}! ", peers);
-                zerror!(ZErrorKind::IoError {
-                    descr: "".to_string()
+                log::error!("Unable to connect to a router among {:?}! ", peers);
+                zerror!(ZErrorKind::NoRouter {
+                    descr: format!("unable to connect to a router among {:?}", peers)
                 })
             }
         }
@@ -137,7 +162,10 @@ impl Runtime {
     async fn start_peer(&self) -> ZResult<()> {
         let config = &self.config;
         let listeners = config
-            .get_or(&ZN_LISTENER_KEY, PEER_DEFAULT_LISTENER)
+            .get_or(
+                &ZN_LISTENER_KEY,
+                self.default_listener(PEER_DEFAULT_LISTENER),
+            )
             .split(',')
             .filter_map(|s| match s.trim() {
                 "" => None,
@@ -181,6 +209,7 @@ impl Runtime {
             let this = self.clone();
             async_std::task::spawn(async move { this.peer_connector(peer).await });
         }
+        self.start_peers_file_watcher();
 
         if scouting {
             let ifaces = Runtime::get_interfaces(ifaces);
@@ -217,7 +246,10 @@ impl Runtime {
     async fn start_router(&self) -> ZResult<()> {
         let config = &self.config;
         let listeners = config
-            .get_or(&ZN_LISTENER_KEY, ROUTER_DEFAULT_LISTENER)
+            .get_or(
+                &ZN_LISTENER_KEY,
+                self.default_listener(ROUTER_DEFAULT_LISTENER),
+            )
             .split(',')
             .filter_map(|s| match s.trim() {
                 "" => None,
@@ -258,6 +290,7 @@ impl Runtime {
             let this = self.clone();
             async_std::task::spawn(async move { this.peer_connector(peer).await });
         }
+        self.start_peers_file_watcher();
 
         if scouting {
             let ifaces = Runtime::get_interfaces(ifaces);
@@ -289,7 +322,23 @@ impl Runtime {
         Ok(())
     }
 
+    // Returns the default listener, or none if its protocol is disabled (see ZN_ENABLED_TRANSPORTS_KEY).
+    fn default_listener(&self, default: &'static str) -> &'static str {
+        match default.parse::<Locator>() {
+            Ok(locator) if self.manager().config.check_enabled(&locator).is_err() => {
+                log::info!("No default listener {}: its protocol is disabled", default);
+                ""
+            }
+            _ => default,
+        }
+    }
+
     async fn bind_listeners(&self, listeners: &[Locator]) -> ZResult<()> {
+        let strict = self
+            .config
+            .get_or(&ZN_STRICT_STARTUP_KEY, ZN_STRICT_STARTUP_DEFAULT)
+            .to_lowercase()
+            == ZN_TRUE;
         for listener in listeners {
             let endpoint = EndPoint {
                 locator: listener.clone(),
@@ -297,6 +346,13 @@ impl Runtime {
             };
             match self.manager().add_listener(endpoint).await {
                 Ok(listener) => log::debug!("Listener {} added", listener),
+                Err(err) if !strict => {
+                    self.startup_warnings.add(
+                        ZN_LISTENER_STR,
+                        listener.to_string(),
+                        err.to_string(),
+                    );
+                }
                 Err(err) => {
                     log::error!("Unable to open listener {} : {}", listener, err);
                     return zerror!(
@@ -314,6 +370,240 @@ impl Runtime {
         Ok(())
     }
 
+    fn start_peers_file_watcher(&self) {
+        if let Some(path) = self.config.get(&ZN_PEERS_FILE_KEY) {
+            let path = path.clone();
+            let this = self.clone();
+            async_std::task::spawn(async move { this.peers_file_watcher(path).await });
+        }
+    }
+
+    // Polls the peers file (see ZN_PEERS_FILE_KEY) every second, applying its changes: the peers added to the file
+    // are connected (retrying until connected or removed from the file), and the connections to the peers removed
+    // from the file are closed (unless they're also configured with ZN_PEER_KEY). The invalid locators are ignored
+    // with a warning. A missing or unreadable file is ignored (with a warning) and the current connections are kept
+    // until the file is readable again. The polling stops when the runtime is closed.
+    async fn peers_file_watcher(&self, path: String) {
+        let mut content: Option<String> = None;
+        let mut error: Option<String> = None;
+        loop {
+            // listen before checking the flag, not to miss a close in between
+            let closing = self.closing.listen();
+            if self.closed.load(Ordering::Acquire) {
+                break;
+            }
+            match async_std::fs::read_to_string(&path).await {
+                Ok(new) => {
+                    error = None;
+                    if content.as_ref() != Some(&new) {
+                        log::debug!("Peers file {} changed", path);
+                        self.apply_peers_file(&new).await;
+                        content = Some(new);
+                    }
+                }
+                Err(err) => {
+                    let err = err.to_string();
+                    if error.as_ref() != Some(&err) {
+                        if content.is_none() && error.is_none() {
+                            self.startup_warnings
+                                .add(ZN_PEERS_FILE_STR, path.clone(), err.clone());
+                        } else {
+                            log::warn!("Unable to read peers file {}: {}", path, err);
+                        }
+                        error = Some(err);
+                    }
+                }
+            }
+            if async_std::future::timeout(Duration::from_millis(PEERS_FILE_PERIOD), closing)
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        log::debug!("Stop watching peers file {}", path);
+    }
+
+    // Connects to the peers added to the peers file and disconnects from the removed ones.
+    async fn apply_peers_file(&self, content: &str) {
+        let mut peers: Vec<Locator> = vec![];
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.parse::<Locator>() {
+                Ok(locator) => match self.manager().config.check_enabled(&locator) {
+                    Ok(()) if !peers.contains(&locator) => peers.push(locator),
+                    Ok(()) => (),
+                    Err(err) => log::warn!("Ignore peer {} of peers file: {}", line, err),
+                },
+                Err(err) => log::warn!("Ignore invalid peer {} of peers file: {}", line, err),
+            }
+        }
+        let (added, removed) = {
+            let mut file_peers = zlock!(self.file_peers);
+            let added: Vec<Locator> = peers
+                .iter()
+                .filter(|l| !file_peers.contains(l))
+                .cloned()
+                .collect();
+            let removed: Vec<Locator> = file_peers
+                .iter()
+                .filter(|l| !peers.contains(l))
+                .cloned()
+                .collect();
+            *file_peers = peers;
+            (added, removed)
+        };
+        for peer in added {
+            log::info!("Connect to peer {} added to peers file", peer);
+            let this = self.clone();
+            async_std::task::spawn(async move { this.peer_connector(peer).await });
+        }
+        let configured = self.configured_peers();
+        for peer in removed.into_iter().filter(|l| !configured.contains(l)) {
+            for transport in self.manager().get_transports() {
+                let connected = match transport.get_callback() {
+                    Ok(Some(callback)) => callback
+                        .as_any()
+                        .downcast_ref::<super::RuntimeSession>()
+                        .map_or(false, |session| {
+                            let mut locator = zwrite!(session.locator);
+                            if locator.as_ref() == Some(&peer) {
+                                // not to reconnect once closed
+                                *locator = None;
+                                true
+                            } else {
+                                false
+                            }
+                        }),
+                    _ => false,
+                };
+                if connected {
+                    log::info!("Disconnect from peer {} removed from peers file", peer);
+                    let _ = transport.close().await;
+                }
+            }
+        }
+    }
+
+    // Returns the peers configured with ZN_PEER_KEY (including the ones added by a config reload).
+    fn configured_peers(&self) -> Vec<Locator> {
+        zlock!(self.current_config)
+            .get_or(&ZN_PEER_KEY, "")
+            .split(',')
+            .filter_map(|s| s.trim().parse().ok())
+            .collect()
+    }
+
+    // Returns true if `peer` is still to be connected: it's configured or listed in the peers file.
+    fn is_wanted_peer(&self, peer: &Locator) -> bool {
+        zlock!(self.file_peers).contains(peer) || self.configured_peers().contains(peer)
+    }
+
+    /// Applies the keys of `config` whose value differs from the current configuration, if they're
+    /// hot-reloadable, and returns a [`ReloadReport`]. The keys missing in `config` are considered as set to
+    /// their default value.
+    ///
+    /// The hot-reloadable keys, in peer and router modes only, are:
+    ///  * [`ZN_PEER_KEY`]: the added peers are connected (retrying in background as at startup).
+    ///  * [`ZN_LISTENER_KEY`]: the added listeners are opened.
+    ///
+    /// Removing a peer or a listener requires a restart, as changing any other key does.
+    /// Returns an error if a peer or listener is not a valid locator, or if a listener can't be opened.
+    /// Nothing is applied in both cases: the listeners opened by this reload are closed, and the current
+    /// configuration is left unchanged (so that the same reload can be retried).
+    pub async fn reload_config(&self, config: &ConfigProperties) -> ZResult<ReloadReport> {
+        let parse_locators = |s: &str| -> ZResult<Vec<Locator>> {
+            s.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::parse)
+                .collect()
+        };
+        let mut current = zlock!(self.current_config).clone();
+        let mut keys: Vec<u64> = config.keys().chain(current.keys()).cloned().collect();
+        keys.sort_unstable();
+        keys.dedup();
+
+        let mut report = ReloadReport::default();
+        let mut added_peers = vec![];
+        let mut added_listeners = vec![];
+        for key in keys {
+            if config.get(&key) == current.get(&key) {
+                continue;
+            }
+            let name = ConfigTranscoder::decode(key).unwrap_or_else(|| key.to_string());
+            match key {
+                ZN_PEER_KEY | ZN_LISTENER_KEY if self.whatami != whatami::CLIENT => {
+                    let old = parse_locators(current.get_or(&key, ""))?;
+                    let new = parse_locators(config.get_or(&key, ""))?;
+                    if old.iter().any(|l| !new.contains(l)) {
+                        report.restart_required.push(name);
+                        continue;
+                    }
+                    let added: Vec<Locator> =
+                        new.into_iter().filter(|l| !old.contains(l)).collect();
+                    for locator in &added {
+                        self.manager().config.check_enabled(locator)?;
+                    }
+                    if key == ZN_PEER_KEY {
+                        added_peers.extend(added);
+                    } else {
+                        added_listeners.extend(added);
+                    }
+                    current.insert(key, config.get_or(&key, "").to_string());
+                    report.applied.push(name);
+                }
+                _ => report.restart_required.push(name),
+            }
+        }
+
+        // the new configuration is committed only once all the listeners are opened:
+        // otherwise the listeners opened so far are closed, and a retry applies the same changes again
+        let mut opened: Vec<EndPoint> = vec![];
+        for listener in added_listeners {
+            let endpoint = EndPoint {
+                locator: listener.clone(),
+                config: None,
+            };
+            match self.manager().add_listener(endpoint.clone()).await {
+                Ok(listener) => {
+                    log::info!("Listener {} added by config reload", listener);
+                    opened.push(endpoint);
+                }
+                Err(err) => {
+                    log::error!("Unable to open listener {} : {}", listener, err);
+                    for endpoint in opened {
+                        if let Err(e) = self.manager().del_listener(&endpoint).await {
+                            log::warn!("Unable to close listener {} : {}", endpoint.locator, e);
+                        }
+                    }
+                    return zerror!(
+                        ZErrorKind::IoError {
+                            descr: format!("Unable to open listener {}", listener)
+                        },
+                        err
+                    );
+                }
+            }
+        }
+        *zlock!(self.current_config) = current;
+
+        for peer in added_peers {
+            log::info!("Connect to peer {} added by config reload", peer);
+            let this = self.clone();
+            async_std::task::spawn(async move { this.peer_connector(peer).await });
+        }
+        if !report.restart_required.is_empty() {
+            log::warn!(
+                "Config reload: a restart is required to apply {:?}",
+                report.restart_required
+            );
+        }
+        Ok(report)
+    }
+
     pub fn get_interfaces(names: &str) -> Vec<IpAddr> {
         if names == "auto" {
             let ifaces = zenoh_util::net::get_multicast_interfaces();
@@ -483,13 +773,27 @@ impl Runtime {
 
     async fn peer_connector(&self, peer: Locator) {
         let mut delay = CONNECTION_RETRY_INITIAL_PERIOD;
+        let mut first_attempt = true;
         loop {
+            if !self.is_wanted_peer(&peer) {
+                log::debug!("Stop connecting to peer {}: no longer configured", peer);
+                break;
+            }
             log::trace!("Trying to connect to configured peer {}", peer);
             let endpoint = EndPoint {
                 locator: peer.clone(),
                 config: None,
             };
-            if let Ok(transport) = self.manager().open_transport(endpoint).await {
+            let transport = match self.manager().open_transport(endpoint).await {
+                Err(err) if first_attempt => {
+                    self.startup_warnings
+                        .add(ZN_PEER_STR, peer.to_string(), err.to_string());
+                    Err(err)
+                }
+                res => res,
+            };
+            first_attempt = false;
+            if let Ok(transport) = transport {
                 log::debug!("Successfully connected to configured peer {}", peer);
                 if let Some(orch_transport) = transport
                     .get_callback()
@@ -530,7 +834,9 @@ impl Runtime {
             let mut wbuf = WBuf::new(SEND_BUF_INITIAL_SIZE, false);
             let mut scout = TransportMessage::make_scout(Some(what), true, None);
             wbuf.write_transport_message(&mut scout);
+            let heartbeat = Heartbeat::new(format!("scouting {}", mcast_addr));
             loop {
+                heartbeat.beat();
                 for socket in sockets {
                     log::trace!(
                         "Send {:?} to {} on interface {}",
@@ -12,11 +12,14 @@
 //   ADLINK zenoh team, <zenoh@adlink-labs.tech>
 //
 use super::info::*;
+use super::query_limiter::{QueryLimiter, QueryPermit};
 use super::routing::face::Face;
+use super::routing::queries::trace_query_route;
 use super::*;
 use async_std::sync::Arc;
 use async_std::task;
-use flume::{bounded, Sender};
+use flume::{bounded, Sender, TrySendError};
+use link::TransportFactory;
 use log::{error, trace, warn};
 use protocol::{
     core::{
@@ -29,7 +32,7 @@ use protocol::{
 use runtime::Runtime;
 use std::collections::HashMap;
 use std::fmt;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::RwLock;
 use std::time::Duration;
 use transport::Primitives;
@@ -44,6 +47,7 @@ zconfigurable! {
     static ref API_REPLY_EMISSION_CHANNEL_SIZE: usize = 256;
     static ref API_REPLY_RECEPTION_CHANNEL_SIZE: usize = 256;
     static ref API_OPEN_SESSION_DELAY: u64 = 500;
+    static ref API_CLOSE_ON_DROP_TIMEOUT: u64 = 1000;
 }
 
 pub(crate) struct SessionState {
@@ -61,6 +65,7 @@ pub(crate) struct SessionState {
     local_routing: bool,
     join_subscriptions: Vec<String>,
     join_publications: Vec<String>,
+    query_limiter: Arc<QueryLimiter>,
 }
 
 impl SessionState {
@@ -68,6 +73,7 @@ impl SessionState {
         local_routing: bool,
         join_subscriptions: Vec<String>,
         join_publications: Vec<String>,
+        query_limiter: Arc<QueryLimiter>,
     ) -> SessionState {
         SessionState {
             primitives: None,
@@ -84,6 +90,7 @@ impl SessionState {
             local_routing,
             join_subscriptions,
             join_publications,
+            query_limiter,
         }
     }
 }
@@ -199,7 +206,10 @@ impl Session {
         }
     }
 
-    pub(super) fn new(config: ConfigProperties) -> impl ZFuture<Output = ZResult<Session>> {
+    pub(super) fn new(
+        config: ConfigProperties,
+        transports: Vec<Arc<dyn TransportFactory>>,
+    ) -> impl ZFuture<Output = ZResult<Session>> {
         zpinbox(async {
             let local_routing = config
                 .get_or(&ZN_LOCAL_ROUTING_KEY, ZN_LOCAL_ROUTING_DEFAULT)
@@ -213,7 +223,7 @@ impl Session {
                 Some(s) => s.split(',').map(|s| s.to_string()).collect(),
                 None => vec![],
             };
-            match Runtime::new(0, config.0.into(), None).await {
+            match Runtime::new_with_transports(0, config.0.into(), None, transports).await {
                 Ok(runtime) => {
                     let session = Self::init(
                         runtime,
@@ -231,6 +241,38 @@ impl Session {
         })
     }
 
+    /// Returns the current load of the queries received by the queryables of this session,
+    /// limited by [`ZN_MAX_CONCURRENT_QUERIES_KEY`](config::ZN_MAX_CONCURRENT_QUERIES_KEY)
+    /// and [`ZN_MAX_QUEUED_QUERIES_KEY`](config::ZN_MAX_QUEUED_QUERIES_KEY).
+    pub fn query_load(&self) -> QueryLoad {
+        zread!(self.state).query_limiter.load()
+    }
+
+    /// Returns the nodes traversed by the queries on `resource` from this session to reach the matching
+    /// queryables, ordered by their distance, as currently known by the local routing tables.  
+    /// This is a local, control-plane operation (no message is sent): e.g. to understand why a query
+    /// unexpectedly gets no reply. A client only knows the router it's connected to.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::net::*;
+    ///
+    /// let session = open(config::peer()).await.unwrap();
+    /// for hop in session.trace_route(&"/resource/name".into()).unwrap() {
+    ///     println!("{} -> {} ({} hops, responder: {})", hop.from, hop.pid, hop.distance, hop.responder);
+    /// }
+    /// # })
+    /// ```
+    pub fn trace_route(&self, resource: &ResKey) -> ZResult<Vec<Hop>> {
+        trace!("trace_route({:?})", resource);
+        let resname = zread!(self.state).localkey_to_resname(resource)?;
+        Ok(trace_query_route(
+            &*zread!(self.runtime.router.tables),
+            &resname,
+        ))
+    }
+
     /// Returns the identifier for this session.
     pub fn id(&self) -> impl ZFuture<Output = String> {
         zready(self.runtime.get_pid_str())
@@ -254,6 +296,7 @@ impl Session {
             local_routing,
             join_subscriptions,
             join_publications,
+            QueryLimiter::new(&runtime.config),
         )));
         let session = Session {
             runtime,
@@ -296,6 +339,31 @@ impl Session {
         self.close_alive()
     }
 
+    /// Close the zenoh-net [Session](Session), failing with a [`ZErrorKind::Timeout`] error
+    /// if it's not closed within `timeout`.  
+    /// In such case, the closure continues in background.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::net::*;
+    /// use std::time::Duration;
+    ///
+    /// let session = open(config::peer()).await.unwrap();
+    /// session.close_timeout(Duration::from_secs(1)).await.unwrap();
+    /// # })
+    /// ```
+    pub fn close_timeout(mut self, timeout: Duration) -> impl ZFuture<Output = ZResult<()>> {
+        self.alive = false;
+        let handle = task::spawn(self.close_alive());
+        zpinbox(async move {
+            match async_std::future::timeout(timeout, handle).await {
+                Ok(res) => res,
+                Err(_) => zerror!(ZErrorKind::Timeout {}),
+            }
+        })
+    }
+
     /// Get informations about the zenoh-net [Session](Session).
     ///
     /// # Examples
@@ -563,6 +631,7 @@ impl Session {
             reskey: reskey.clone(),
             resname,
             invoker,
+            paused: AtomicBool::new(false),
         });
         let declared_sub = match state
             .join_subscriptions
@@ -728,6 +797,7 @@ impl Session {
                         reskey: reskey.clone(),
                         resname,
                         invoker: SubscriberInvoker::Sender(sender),
+                        paused: AtomicBool::new(false),
                     });
                     state
                         .local_subscribers
@@ -989,40 +1059,113 @@ impl Session {
         congestion_control: CongestionControl,
     ) -> impl ZFuture<Output = ZResult<()>> {
         trace!("write_ext({:?}, [...])", resource);
-        let state = zread!(self.state);
-        let primitives = state.primitives.as_ref().unwrap().clone();
-        drop(state);
-
         let mut info = protocol::proto::DataInfo::new();
         info.kind = Some(kind);
         info.encoding = Some(encoding);
         info.timestamp = self.runtime.new_timestamp();
+        self.write_with_info(resource, payload, info, congestion_control)
+    }
+
+    /// Write data with an already built [DataInfo](DataInfo).
+    /// This allows to share the same DataInfo (and thus Timestamp) between several writes
+    /// (e.g. a storage notifying a change with the timestamp of the stored value).
+    pub fn write_with_info(
+        &self,
+        resource: &ResKey,
+        payload: ZBuf,
+        info: DataInfo,
+        congestion_control: CongestionControl,
+    ) -> impl ZFuture<Output = ZResult<()>> {
+        self.write_with_info_ext(
+            resource,
+            payload,
+            info,
+            congestion_control,
+            Priority::default(),
+            Reliability::Reliable, // @TODO: need to check subscriptions to determine the right reliability value
+            true,
+        )
+    }
+
+    /// Write data with an already built [DataInfo](DataInfo) on the given `priority` and with the given
+    /// `reliability`, delivering it to the subscribers of this session only if `local_echo` is true.
+    /// Note that the subscribers of this session are reached only by this local delivery
+    /// (the routing never sends back data to the session it comes from).
+    pub(crate) fn write_with_info_ext(
+        &self,
+        resource: &ResKey,
+        payload: ZBuf,
+        info: DataInfo,
+        congestion_control: CongestionControl,
+        priority: Priority,
+        reliability: Reliability,
+        local_echo: bool,
+    ) -> impl ZFuture<Output = ZResult<()>> {
+        trace!(
+            "write_with_info({:?}, [...], priority: {:?}, reliability: {:?}, local_echo: {})",
+            resource,
+            priority,
+            reliability,
+            local_echo
+        );
+        let state = zread!(self.state);
+        let primitives = state.primitives.as_ref().unwrap().clone();
+        drop(state);
+
         let data_info = Some(info);
 
         primitives.send_data(
             resource,
             payload.clone(),
             Channel {
-                priority: Priority::default(),
-                reliability: Reliability::Reliable, // @TODO: need to check subscriptions to determine the right reliability value
+                priority,
+                reliability,
             },
             congestion_control,
             data_info.clone(),
             None,
         );
-        self.handle_data(true, resource, data_info, payload);
+        if local_echo {
+            self.handle_data(true, resource, data_info, payload);
+        }
         zready(Ok(()))
     }
 
+    /// Transmits the messages of `priority` waiting for the batch timeout, without waiting for it.
+    pub(crate) fn flush(&self, priority: Priority) {
+        self.runtime.manager().flush(priority);
+    }
+
+    /// Returns a new [Timestamp](uhlc::Timestamp) from the HLC of this session's runtime, if any.
+    pub(crate) fn new_timestamp(&self) -> Option<uhlc::Timestamp> {
+        self.runtime.new_timestamp()
+    }
+
+    /// Returns a new [Timestamp](uhlc::Timestamp) from the HLC of this session's runtime,
+    /// or from the system time with this session's id if the runtime has no HLC.
+    pub(crate) fn new_timestamp_or_system_time(&self) -> uhlc::Timestamp {
+        self.new_timestamp().unwrap_or_else(|| {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap();
+            uhlc::Timestamp::new(now.into(), uhlc::ID::from(&self.runtime.pid))
+        })
+    }
+
     #[inline]
     fn invoke_subscriber(
-        invoker: &SubscriberInvoker,
+        sub: &SubscriberState,
         res_name: String,
         payload: ZBuf,
         data_info: Option<DataInfo>,
     ) {
-        match invoker {
+        let paused = sub.paused.load(Ordering::Relaxed);
+        match &sub.invoker {
             SubscriberInvoker::Handler(handler) => {
+                if paused {
+                    trace!("Subscriber {} paused: drop Sample for {}", sub.id, res_name);
+                    return;
+                }
                 let handler = &mut *zwrite!(handler);
                 handler(Sample {
                     res_name,
@@ -1031,11 +1174,22 @@ impl Session {
                 });
             }
             SubscriberInvoker::Sender(sender) => {
-                if let Err(e) = sender.send(Sample {
+                let sample = Sample {
                     res_name,
                     payload,
                     data_info,
-                }) {
+                };
+                if paused {
+                    // While paused, the channel buffers the samples up to its capacity.
+                    // Further samples are dropped rather than blocking the session.
+                    if let Err(TrySendError::Full(sample)) = sender.try_send(sample) {
+                        trace!(
+                            "Subscriber {} paused and full: drop Sample for {}",
+                            sub.id,
+                            sample.res_name
+                        );
+                    }
+                } else if let Err(e) = sender.send(sample) {
                     error!("SubscriberInvoker error: {}", e);
                 }
             }
@@ -1049,12 +1203,12 @@ impl Session {
                 Some(res) => {
                     if !local && res.subscribers.len() == 1 {
                         let sub = res.subscribers.get(0).unwrap();
-                        Session::invoke_subscriber(&sub.invoker, res.name.clone(), payload, info);
+                        Session::invoke_subscriber(sub, res.name.clone(), payload, info);
                     } else {
                         if !local || state.local_routing {
                             for sub in &res.subscribers {
                                 Session::invoke_subscriber(
-                                    &sub.invoker,
+                                    sub,
                                     res.name.clone(),
                                     payload.clone(),
                                     info.clone(),
@@ -1064,7 +1218,7 @@ impl Session {
                         if local {
                             for sub in &res.local_subscribers {
                                 Session::invoke_subscriber(
-                                    &sub.invoker,
+                                    sub,
                                     res.name.clone(),
                                     payload.clone(),
                                     info.clone(),
@@ -1084,7 +1238,7 @@ impl Session {
                         for sub in state.subscribers.values() {
                             if rname::matches(&sub.resname, &resname) {
                                 Session::invoke_subscriber(
-                                    &sub.invoker,
+                                    sub,
                                     resname.clone(),
                                     payload.clone(),
                                     info.clone(),
@@ -1096,7 +1250,7 @@ impl Session {
                         for sub in state.local_subscribers.values() {
                             if rname::matches(&sub.resname, &resname) {
                                 Session::invoke_subscriber(
-                                    &sub.invoker,
+                                    sub,
                                     resname.clone(),
                                     payload.clone(),
                                     info.clone(),
@@ -1154,18 +1308,33 @@ impl Session {
         predicate: &str,
         target: QueryTarget,
         consolidation: QueryConsolidation,
+    ) -> impl ZFuture<Output = ZResult<ReplyReceiver>> {
+        self.query_ext(resource, predicate, target, consolidation, None)
+    }
+
+    /// Query data, accepting only the replies from `replier` if specified
+    /// (the replies from other repliers are dropped).
+    pub(crate) fn query_ext(
+        &self,
+        resource: &ResKey,
+        predicate: &str,
+        target: QueryTarget,
+        consolidation: QueryConsolidation,
+        replier: Option<PeerId>,
     ) -> impl ZFuture<Output = ZResult<ReplyReceiver>> {
         trace!(
-            "query({:?}, {:?}, {:?}, {:?})",
+            "query({:?}, {:?}, {:?}, {:?}, {:?})",
             resource,
             predicate,
             target,
-            consolidation
+            consolidation,
+            replier
         );
         let mut state = zwrite!(self.state);
         let qid = state.qid_counter.fetch_add(1, Ordering::SeqCst);
         let (rep_sender, rep_receiver) = bounded(*API_REPLY_RECEPTION_CHANNEL_SIZE);
         let nb_final = if state.local_routing { 2 } else { 1 };
+        let overloaded = Arc::new(AtomicBool::new(false));
         trace!("Register query {} (nb_final = {})", qid, nb_final);
         state.queries.insert(
             qid,
@@ -1178,6 +1347,8 @@ impl Session {
                     None
                 },
                 rep_sender,
+                replier,
+                overloaded: overloaded.clone(),
             },
         );
 
@@ -1196,7 +1367,7 @@ impl Session {
             self.handle_query(true, resource, predicate, qid, target, consolidation);
         }
 
-        zready(Ok(ReplyReceiver::new(rep_receiver)))
+        zready(Ok(ReplyReceiver::new(overloaded, rep_receiver)))
     }
 
     fn handle_query(
@@ -1208,7 +1379,7 @@ impl Session {
         target: QueryTarget,
         _consolidation: QueryConsolidation,
     ) {
-        let (primitives, resname, kinds_and_senders) = {
+        let (primitives, resname, kinds_and_senders, query_limiter) = {
             let state = zread!(self.state);
             match state.reskey_to_resname(reskey, local) {
                 Ok(resname) => {
@@ -1238,6 +1409,7 @@ impl Session {
                         state.primitives.as_ref().unwrap().clone(),
                         resname,
                         kinds_and_senders,
+                        state.query_limiter.clone(),
                     )
                 }
                 Err(err) => {
@@ -1248,53 +1420,99 @@ impl Session {
         };
 
         let predicate = predicate.to_string();
-        let (rep_sender, rep_receiver) = bounded(*API_REPLY_EMISSION_CHANNEL_SIZE);
-
         let pid = self.runtime.pid; // @TODO build/use prebuilt specific pid
 
-        for (kind, req_sender) in kinds_and_senders {
-            let _ = req_sender.send(Query {
-                res_name: resname.clone(),
-                predicate: predicate.clone(),
-                replies_sender: RepliesSender {
-                    kind,
-                    sender: rep_sender.clone(),
-                },
-            });
-        }
-        drop(rep_sender); // all senders need to be dropped for the channel to close
+        let no_queryable = kinds_and_senders.is_empty();
+        let this = self.clone();
+        let reply_primitives = primitives.clone();
+        let dispatch = move |permit: QueryPermit| {
+            let (rep_sender, rep_receiver) = bounded(*API_REPLY_EMISSION_CHANNEL_SIZE);
+
+            for (kind, req_sender) in kinds_and_senders {
+                let _ = req_sender.send(Query {
+                    res_name: resname.clone(),
+                    predicate: predicate.clone(),
+                    replies_sender: RepliesSender {
+                        kind,
+                        sender: rep_sender.clone(),
+                    },
+                });
+            }
+            drop(rep_sender); // all senders need to be dropped for the channel to close
 
-        // router is not re-entrant
+            // router is not re-entrant
 
-        if local {
-            let this = self.clone();
             task::spawn(async move {
                 while let Some((kind, sample)) = rep_receiver.stream().next().await {
-                    this.send_reply_data(
-                        qid,
-                        kind,
-                        pid,
-                        ResKey::RName(sample.res_name),
-                        sample.data_info,
-                        sample.payload,
-                    );
+                    if local {
+                        this.send_reply_data(
+                            qid,
+                            kind,
+                            pid,
+                            ResKey::RName(sample.res_name),
+                            sample.data_info,
+                            sample.payload,
+                        );
+                    } else {
+                        reply_primitives.send_reply_data(
+                            qid,
+                            kind,
+                            pid,
+                            ResKey::RName(sample.res_name),
+                            sample.data_info,
+                            sample.payload,
+                        );
+                    }
                 }
-                this.send_reply_final(qid);
-            });
-        } else {
-            task::spawn(async move {
-                while let Some((kind, sample)) = rep_receiver.stream().next().await {
-                    primitives.send_reply_data(
-                        qid,
-                        kind,
-                        pid,
-                        ResKey::RName(sample.res_name),
-                        sample.data_info,
-                        sample.payload,
-                    );
+                // the query is processed once all the queryables have finished to reply
+                drop(permit);
+                if local {
+                    this.send_reply_final(qid);
+                } else {
+                    reply_primitives.send_reply_final(qid);
                 }
-                primitives.send_reply_final(qid);
             });
+        };
+
+        let dispatched = if no_queryable {
+            dispatch(QueryPermit::unlimited());
+            true
+        } else {
+            query_limiter.submit(Box::new(dispatch))
+        };
+        if dispatched {
+            return;
+        }
+
+        warn!(
+            "Reject query on {} because of overload: {:?}",
+            reskey,
+            query_limiter.load()
+        );
+        let mut info = DataInfo::new();
+        info.encoding = Some(encoding::STRING);
+        let res_name = format!("/@/{}{}", pid, OVERLOADED_SUFFIX);
+        let payload = ZBuf::from("Too many concurrent queries".as_bytes());
+        if local {
+            self.send_reply_data(
+                qid,
+                queryable::EVAL,
+                pid,
+                ResKey::RName(res_name),
+                Some(info),
+                payload,
+            );
+            self.send_reply_final(qid);
+        } else {
+            primitives.send_reply_data(
+                qid,
+                queryable::EVAL,
+                pid,
+                ResKey::RName(res_name),
+                Some(info),
+                payload,
+            );
+            primitives.send_reply_final(qid);
         }
     }
 
@@ -1428,6 +1646,23 @@ impl Primitives for Session {
         };
         match state.queries.get_mut(&qid) {
             Some(query) => {
+                if matches!(&query.replier, Some(pid) if *pid != replier_id) {
+                    trace!(
+                        "Drop ReplyData for query {} from non-targeted replier {}",
+                        qid,
+                        replier_id
+                    );
+                    return;
+                }
+                // an overload rejection is reported on the ReplyReceiver rather than delivered as a reply
+                if res_name == format!("/@/{}{}", replier_id, OVERLOADED_SUFFIX) {
+                    warn!(
+                        "Query {} rejected by {} because of overload: the replies are incomplete",
+                        qid, replier_id
+                    );
+                    query.overloaded.store(true, Ordering::Relaxed);
+                    return;
+                }
                 let new_reply = Reply {
                     data: Sample {
                         res_name,
@@ -1545,9 +1780,23 @@ impl Primitives for Session {
 }
 
 impl Drop for Session {
+    // Best-effort closure: the closure is spawned as a task and awaited for at most
+    // API_CLOSE_ON_DROP_TIMEOUT ms, so dropping never blocks indefinitely.
     fn drop(&mut self) {
         if self.alive {
-            let _ = self.clone().close_alive().wait();
+            let close = self.clone().close_alive();
+            let (sender, receiver) = bounded(1);
+            task::spawn(async move {
+                let _ = sender.send(close.await);
+            });
+            match receiver.recv_timeout(Duration::from_millis(*API_CLOSE_ON_DROP_TIMEOUT)) {
+                Ok(Ok(())) => (),
+                Ok(Err(e)) => warn!("Error closing the Session on drop: {}", e),
+                Err(_) => warn!(
+                    "Session not closed on drop within {} ms: the closure continues in background",
+                    *API_CLOSE_ON_DROP_TIMEOUT
+                ),
+            }
         }
     }
 }
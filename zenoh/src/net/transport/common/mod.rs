@@ -16,5 +16,6 @@ pub(crate) mod conduit;
 pub(crate) mod defragmentation;
 pub(crate) mod pipeline;
 pub(crate) mod seq_num;
+pub(crate) mod throttle;
 
 use super::protocol;
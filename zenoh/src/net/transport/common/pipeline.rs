@@ -35,7 +35,7 @@ use std::fmt;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Condvar, Mutex, MutexGuard};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use zenoh_util::sync::{Condition as AsyncCondvar, ConditionWaiter as AsyncCondvarWaiter};
 use zenoh_util::zlock;
 
@@ -91,6 +91,10 @@ struct StageIn {
     inner: VecDeque<SerializationBatch>,
     bytes_topull: Arc<[AtomicUsize]>,
     fragbuf: Option<WBuf>,
+    // When the first message was serialized on the current batch
+    first_push: Option<Instant>,
+    // Whether the current batch contains a message that must not wait for the batch timeout
+    flush: bool,
 }
 
 impl StageIn {
@@ -111,6 +115,29 @@ impl StageIn {
             inner,
             bytes_topull,
             fragbuf: Some(WBuf::new(batch_size as usize, false)),
+            first_push: None,
+            flush: false,
+        }
+    }
+
+    fn pushed(&mut self, flush: bool) {
+        if self.first_push.is_none() {
+            self.first_push = Some(Instant::now());
+        }
+        self.flush |= flush;
+    }
+
+    // Returns how long the current incomplete batch still waits for more messages
+    // before being transmitted, if it has to wait.
+    fn linger(&self, batch_timeout: Duration) -> Option<Duration> {
+        if self.flush {
+            return None;
+        }
+        let elapsed = self.first_push?.elapsed();
+        if elapsed < batch_timeout {
+            Some(batch_timeout - elapsed)
+        } else {
+            None
         }
     }
 
@@ -118,6 +145,8 @@ impl StageIn {
         if let Some(batch) = self.inner.front_mut() {
             if !batch.is_empty() {
                 self.bytes_topull[self.priority].store(0, Ordering::Release);
+                self.first_push = None;
+                self.flush = false;
                 // Write the batch len before removing the batch
                 batch.write_len();
                 // There is an incomplete batch, pop it
@@ -210,6 +239,8 @@ pub(crate) struct TransmissionPipeline {
     // A single conditional variable for all the conduit queues
     // The conditional variable requires a MutexGuard from stage_out
     cond_canpull: AsyncCondvar,
+    // How long an incomplete batch waits for more messages before being transmitted
+    batch_timeout: Duration,
 }
 
 impl TransmissionPipeline {
@@ -218,6 +249,7 @@ impl TransmissionPipeline {
         batch_size: u16,
         is_streamed: bool,
         conduit: Arc<[TransportConduitTx]>,
+        batch_timeout: Duration,
     ) -> TransmissionPipeline {
         macro_rules! zcapacity {
             ($conduit:expr) => {
@@ -291,6 +323,7 @@ impl TransmissionPipeline {
             stage_refill,
             cond_canrefill,
             cond_canpull,
+            batch_timeout,
         }
     }
 
@@ -315,6 +348,8 @@ impl TransmissionPipeline {
                 let batch = zgetbatch!(self, priority, in_guard, false);
                 if batch.serialize_transport_message(&mut message) {
                     self.bytes_in[priority].store(batch.len(), Ordering::Release);
+                    // Transport messages are never delayed by the batch timeout
+                    in_guard.pushed(true);
                     self.cond_canpull.notify_one();
                     return true;
                 }
@@ -347,8 +382,21 @@ impl TransmissionPipeline {
         false
     }
 
+    // Transmits the current incomplete batch of the priority without waiting for the batch timeout
+    pub(crate) fn flush(&self, priority: Priority) {
+        let priority = if self.is_qos() { priority as usize } else { 0 };
+        let mut in_guard = zlock!(self.stage_in[priority]);
+        if in_guard.first_push.is_some() {
+            in_guard.flush = true;
+            drop(in_guard);
+            self.cond_canpull.notify_one();
+        }
+    }
+
     #[inline]
     pub(crate) fn push_zenoh_message(&self, mut message: ZenohMessage) -> bool {
+        // Control and RealTime messages are never delayed by the batch timeout
+        let flush = message.channel.priority as u8 <= Priority::RealTime as u8;
         // If the queue is not QoS, it means that we only have one priority with index 0.
         let priority = if self.is_qos() {
             message.channel.priority as usize
@@ -373,6 +421,7 @@ impl TransmissionPipeline {
                 let mp = message.channel.priority;
                 if batch.serialize_zenoh_message(&mut message, mp, &mut ch_guard.sn) {
                     self.bytes_in[priority].store(batch.len(), Ordering::Release);
+                    in_guard.pushed(flush);
                     self.cond_canpull.notify_one();
                     return true;
                 }
@@ -493,6 +542,10 @@ impl TransmissionPipeline {
 
                 // An incomplete (non-empty) batch is available in the state IN pipeline.
                 if let Ok(mut in_guard) = self.stage_in[priority].try_lock() {
+                    if in_guard.linger(self.batch_timeout).is_some() {
+                        // The batch waits for more messages
+                        return None;
+                    }
                     return in_guard.try_pull();
                 }
             }
@@ -507,6 +560,7 @@ impl TransmissionPipeline {
     pub(crate) async fn pull(&self) -> Option<(SerializationBatch, usize)> {
         enum Action {
             Wait(AsyncCondvarWaiter),
+            Linger(AsyncCondvarWaiter, Duration),
             Sleep,
         }
 
@@ -521,6 +575,7 @@ impl TransmissionPipeline {
             let action = {
                 let mut out_guard = zlock!(self.stage_out);
                 let mut is_pipeline_really_empty = true;
+                let mut linger: Option<Duration> = None;
                 for conduit in 0..out_guard.len() {
                     if let Some(batch) = out_guard[conduit].try_pull() {
                         return Some((batch, conduit));
@@ -528,8 +583,16 @@ impl TransmissionPipeline {
 
                     // Check if an incomplete (non-empty) batch is available in the state IN pipeline.
                     if let Ok(mut in_guard) = self.stage_in[conduit].try_lock() {
-                        if let Some(batch) = in_guard.try_pull() {
-                            return Some((batch, conduit));
+                        match in_guard.linger(self.batch_timeout) {
+                            // The batch waits for more messages
+                            Some(remaining) => {
+                                linger = Some(linger.map_or(remaining, |l| l.min(remaining)));
+                            }
+                            None => {
+                                if let Some(batch) = in_guard.try_pull() {
+                                    return Some((batch, conduit));
+                                }
+                            }
                         }
                     } else {
                         is_pipeline_really_empty = false
@@ -538,7 +601,10 @@ impl TransmissionPipeline {
 
                 if is_pipeline_really_empty {
                     let waiter = self.cond_canpull.waiter(out_guard);
-                    Action::Wait(waiter)
+                    match linger {
+                        Some(remaining) => Action::Linger(waiter, remaining),
+                        None => Action::Wait(waiter),
+                    }
                 } else {
                     Action::Sleep
                 }
@@ -558,6 +624,20 @@ impl TransmissionPipeline {
                         return None;
                     }
                 }
+                Action::Linger(waiter, remaining) => {
+                    // Check if the pipeline is still active
+                    if !self.active.load(Ordering::Acquire) {
+                        return None;
+                    }
+
+                    // Wait for new messages, at most until the batch timeout expires
+                    let _ = async_std::future::timeout(remaining, waiter).await;
+
+                    // Check if the pipeline is still active
+                    if !self.active.load(Ordering::Acquire) {
+                        return None;
+                    }
+                }
                 Action::Sleep => {
                     // Batches are being filled up, let's backoff and retry
                     task::sleep(backoff).await;
@@ -737,6 +817,7 @@ mod tests {
             batch_size,
             is_streamed,
             conduit.into(),
+            Duration::from_secs(0),
         ));
 
         // Total amount of bytes to send in each test
@@ -835,6 +916,7 @@ mod tests {
             batch_size,
             is_streamed,
             conduit.into(),
+            Duration::from_secs(0),
         ));
 
         let counter = Arc::new(AtomicUsize::new(0));
@@ -945,6 +1027,7 @@ mod tests {
             batch_size,
             is_streamed,
             conduit.into(),
+            Duration::from_secs(0),
         ));
 
         let counter = Arc::new(AtomicUsize::new(0));
@@ -993,6 +1076,62 @@ mod tests {
         });
     }
 
+    #[test]
+    fn tx_pipeline_batch_timeout() {
+        fn message(priority: Priority) -> ZenohMessage {
+            ZenohMessage::make_data(
+                ResKey::RName("test".to_string()),
+                ZBuf::from(vec![0u8; 8]),
+                Channel {
+                    priority,
+                    reliability: Reliability::Reliable,
+                },
+                CongestionControl::Block,
+                None,
+                None,
+                None,
+                None,
+            )
+        }
+
+        let batch_timeout = Duration::from_millis(200);
+        let conduit = vec![TransportConduitTx::new(
+            Priority::default(),
+            SEQ_NUM_RES,
+            ConduitSn::default(),
+        )]
+        .into_boxed_slice();
+        let queue = TransmissionPipeline::new(BATCH_SIZE, true, conduit.into(), batch_timeout);
+
+        task::block_on(async {
+            // A data message waits for the batch timeout
+            let start = Instant::now();
+            queue.push_zenoh_message(message(Priority::Data));
+            let (batch, priority) = queue.pull().timeout(TIMEOUT).await.unwrap().unwrap();
+            assert!(
+                start.elapsed() >= batch_timeout / 2,
+                "{:?}",
+                start.elapsed()
+            );
+            queue.refill(batch, priority);
+
+            // A real-time message is sent immediately
+            let start = Instant::now();
+            queue.push_zenoh_message(message(Priority::RealTime));
+            let (batch, priority) = queue.pull().timeout(TIMEOUT).await.unwrap().unwrap();
+            assert!(start.elapsed() < batch_timeout / 2, "{:?}", start.elapsed());
+            queue.refill(batch, priority);
+
+            // A flushed data message is sent immediately
+            let start = Instant::now();
+            queue.push_zenoh_message(message(Priority::Data));
+            queue.flush(Priority::Data);
+            let (batch, priority) = queue.pull().timeout(TIMEOUT).await.unwrap().unwrap();
+            assert!(start.elapsed() < batch_timeout / 2, "{:?}", start.elapsed());
+            queue.refill(batch, priority);
+        });
+    }
+
     #[test]
     #[ignore]
     fn tx_pipeline_thr() {
@@ -1009,6 +1148,7 @@ mod tests {
             batch_size,
             is_streamed,
             conduit.into(),
+            Duration::from_secs(0),
         ));
         let count = Arc::new(AtomicUsize::new(0));
         let size = Arc::new(AtomicUsize::new(0));
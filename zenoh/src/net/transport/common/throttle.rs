@@ -0,0 +1,142 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::task;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use zenoh_util::zlock;
+
+// The window over which the egress rate is measured
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// The egress of a link: a token bucket limiting its bandwidth (if configured) and the measure of its rate.
+///
+/// The bucket is refilled at the maximum bandwidth and holds at most the bytes of 100 ms at this bandwidth,
+/// which bounds the bursts. A batch larger than the available tokens is sent anyway once the bucket
+/// is not empty, leaving it in debt: the following batches wait for the debt to be paid back.
+/// Each wait is capped (at the keep-alive interval of the link) so that a large debt never delays
+/// the next message beyond the lease of the link: the debt is then paid back over several batches.
+pub(crate) struct EgressThrottle {
+    max_bandwidth: Option<u64>,
+    state: Mutex<EgressState>,
+}
+
+struct EgressState {
+    tokens: f64,
+    refilled: Instant,
+    window_start: Instant,
+    window_bytes: u64,
+    rate: u64,
+}
+
+impl EgressThrottle {
+    pub(crate) fn new(max_bandwidth: Option<u64>) -> EgressThrottle {
+        let now = Instant::now();
+        EgressThrottle {
+            max_bandwidth: max_bandwidth.filter(|b| *b > 0),
+            state: Mutex::new(EgressState {
+                tokens: 0.0,
+                refilled: now,
+                window_start: now,
+                window_bytes: 0,
+                rate: 0,
+            }),
+        }
+    }
+
+    // Waits until `len` bytes can be sent within the maximum bandwidth, but no longer than `max_wait`,
+    // and accounts them in the rate.
+    pub(crate) async fn acquire(&self, len: usize, max_wait: Duration) {
+        let wait = {
+            let mut state = zlock!(self.state);
+            let now = Instant::now();
+            if now.duration_since(state.window_start) >= RATE_WINDOW {
+                state.rate = (state.window_bytes as f64
+                    / now.duration_since(state.window_start).as_secs_f64())
+                    as u64;
+                state.window_start = now;
+                state.window_bytes = 0;
+            }
+            state.window_bytes += len as u64;
+            match self.max_bandwidth {
+                Some(max) => {
+                    let burst = (max as f64 / 10.0).max(1.0);
+                    let elapsed = now.duration_since(state.refilled).as_secs_f64();
+                    state.tokens = (state.tokens + elapsed * max as f64).min(burst);
+                    state.refilled = now;
+                    let wait = if state.tokens < 0.0 {
+                        Duration::from_secs_f64(-state.tokens / max as f64).min(max_wait)
+                    } else {
+                        Duration::from_secs(0)
+                    };
+                    state.tokens -= len as f64;
+                    wait
+                }
+                None => Duration::from_secs(0),
+            }
+        };
+        if wait > Duration::from_secs(0) {
+            task::sleep(wait).await;
+        }
+    }
+
+    // Returns the egress rate in bytes per second, measured over the last second.
+    pub(crate) fn rate(&self) -> u64 {
+        let state = zlock!(self.state);
+        let elapsed = state.window_start.elapsed();
+        if elapsed >= 2 * RATE_WINDOW {
+            // nothing sent during the last window
+            0
+        } else if elapsed >= RATE_WINDOW {
+            (state.window_bytes as f64 / elapsed.as_secs_f64()) as u64
+        } else {
+            state.rate
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn egress_throttle() {
+        task::block_on(async {
+            // 10 KB/s: 5 KB take about 0.5 s
+            let throttle = EgressThrottle::new(Some(10_000));
+            let start = Instant::now();
+            for _ in 0..50 {
+                throttle.acquire(100, Duration::from_secs(10)).await;
+            }
+            let elapsed = start.elapsed();
+            assert!(elapsed >= Duration::from_millis(350), "{:?}", elapsed);
+
+            // a large debt (10 s at 10 KB/s) is paid back by waits capped at max_wait
+            let throttle = EgressThrottle::new(Some(10_000));
+            throttle.acquire(100_000, Duration::from_millis(50)).await;
+            let start = Instant::now();
+            throttle.acquire(100, Duration::from_millis(50)).await;
+            let elapsed = start.elapsed();
+            assert!(elapsed >= Duration::from_millis(40), "{:?}", elapsed);
+            assert!(elapsed < Duration::from_millis(500), "{:?}", elapsed);
+
+            // no limit
+            let throttle = EgressThrottle::new(None);
+            let start = Instant::now();
+            for _ in 0..50 {
+                throttle.acquire(100_000, Duration::from_secs(10)).await;
+            }
+            assert!(start.elapsed() < Duration::from_millis(100));
+        });
+    }
+}
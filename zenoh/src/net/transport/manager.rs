@@ -12,20 +12,22 @@
 //   ADLINK zenoh team, <zenoh@adlink-labs.tech>
 //
 use super::multicast::manager::{TransportManagerConfigMulticast, TransportManagerStateMulticast};
-use super::protocol::core::{whatami, PeerId, WhatAmI, ZInt};
+use super::protocol::core::{whatami, PeerId, Priority, WhatAmI, ZInt};
 #[cfg(feature = "zero-copy")]
 use super::protocol::io::SharedMemoryReader;
 use super::protocol::proto::defaults::{BATCH_SIZE, SEQ_NUM_RES, VERSION};
 use super::unicast::manager::{TransportManagerConfigUnicast, TransportManagerStateUnicast};
 use super::unicast::TransportUnicast;
 use super::TransportEventHandler;
-use crate::net::link::{EndPoint, Locator, LocatorConfig, LocatorProtocol};
+use crate::net::link::{EndPoint, Locator, LocatorConfig, LocatorProtocol, TransportFactory};
 use async_std::sync::{Arc as AsyncArc, Mutex as AsyncMutex};
 use rand::{RngCore, SeedableRng};
 use std::collections::HashMap;
+use std::net::IpAddr;
 use std::sync::Arc;
 #[cfg(feature = "zero-copy")]
 use std::sync::RwLock;
+use std::time::Duration;
 use zenoh_util::core::{ZError, ZErrorKind, ZResult};
 use zenoh_util::crypto::{BlockCipher, PseudoRng};
 use zenoh_util::properties::{config::*, Properties};
@@ -89,11 +91,16 @@ pub struct TransportManagerConfig {
     pub whatami: WhatAmI,
     pub sn_resolution: ZInt,
     pub batch_size: u16,
+    pub batch_timeout: Duration,
     pub defrag_buff_size: usize,
     pub link_rx_buff_size: usize,
     pub unicast: TransportManagerConfigUnicast,
     pub multicast: TransportManagerConfigMulticast,
     pub endpoint: HashMap<LocatorProtocol, Properties>,
+    pub transports: HashMap<String, Arc<dyn TransportFactory>>,
+    pub bind_address: Option<IpAddr>,
+    pub enabled_transports: Option<Vec<String>>,
+    pub max_egress_bandwidth: Option<u64>,
     pub handler: Arc<dyn TransportEventHandler>,
 }
 
@@ -101,6 +108,44 @@ impl TransportManagerConfig {
     pub fn builder() -> TransportManagerConfigBuilder {
         TransportManagerConfigBuilder::default()
     }
+
+    /// Returns an error if the protocol of `locator` is not enabled (see [`ZN_ENABLED_TRANSPORTS_KEY`]).
+    pub fn check_enabled(&self, locator: &Locator) -> ZResult<()> {
+        match &self.enabled_transports {
+            Some(enabled) => check_enabled(enabled, locator),
+            None => Ok(()),
+        }
+    }
+}
+
+// The protocols built in zenoh (with the enabled features)
+const BUILTIN_PROTOCOLS: &[&str] = &[
+    #[cfg(feature = "transport_tcp")]
+    crate::net::link::STR_TCP,
+    #[cfg(feature = "transport_udp")]
+    crate::net::link::STR_UDP,
+    #[cfg(feature = "transport_tls")]
+    crate::net::link::STR_TLS,
+    #[cfg(feature = "transport_quic")]
+    crate::net::link::STR_QUIC,
+    #[cfg(all(feature = "transport_unixsock-stream", target_family = "unix"))]
+    crate::net::link::STR_UNIXSOCK_STREAM,
+    #[cfg(feature = "transport_ws")]
+    crate::net::link::STR_WS,
+];
+
+fn check_enabled(enabled: &[String], locator: &Locator) -> ZResult<()> {
+    if enabled.contains(&locator.address.get_proto().to_string()) {
+        Ok(())
+    } else {
+        zerror!(ZErrorKind::InvalidLocator {
+            descr: format!(
+                "The protocol of {} is disabled (enabled transports: {})",
+                locator,
+                enabled.join(",")
+            )
+        })
+    }
 }
 
 pub struct TransportManagerConfigBuilder {
@@ -109,11 +154,16 @@ pub struct TransportManagerConfigBuilder {
     whatami: WhatAmI,
     sn_resolution: ZInt,
     batch_size: u16,
+    batch_timeout: Duration,
     defrag_buff_size: usize,
     link_rx_buff_size: usize,
     unicast: TransportManagerConfigUnicast,
     multicast: TransportManagerConfigMulticast,
     endpoint: HashMap<LocatorProtocol, Properties>,
+    transports: HashMap<String, Arc<dyn TransportFactory>>,
+    bind_address: Option<IpAddr>,
+    enabled_transports: Option<Vec<String>>,
+    max_egress_bandwidth: Option<u64>,
 }
 
 impl TransportManagerConfigBuilder {
@@ -142,6 +192,13 @@ impl TransportManagerConfigBuilder {
         self
     }
 
+    /// Sets the maximum time an incomplete batch waits for more messages before being sent
+    /// (see [`ZN_BATCH_TIMEOUT_KEY`]).
+    pub fn batch_timeout(mut self, batch_timeout: Duration) -> Self {
+        self.batch_timeout = batch_timeout;
+        self
+    }
+
     pub fn defrag_buff_size(mut self, defrag_buff_size: usize) -> Self {
         self.defrag_buff_size = defrag_buff_size;
         self
@@ -167,6 +224,34 @@ impl TransportManagerConfigBuilder {
         self
     }
 
+    /// Uses `factory` to create the links of its protocol, instead of the built-in implementation
+    /// if the protocol is built in zenoh.
+    pub fn transport(mut self, factory: Arc<dyn TransportFactory>) -> Self {
+        self.transports
+            .insert(factory.protocol().to_string(), factory);
+        self
+    }
+
+    /// Sets the local IP address the outbound TCP and TLS links are bound to.
+    pub fn bind_address(mut self, bind_address: Option<IpAddr>) -> Self {
+        self.bind_address = bind_address;
+        self
+    }
+
+    /// Restricts the protocols of the listeners and of the outgoing links to `enabled_transports`
+    /// (all the protocols are enabled if `None`).
+    pub fn enabled_transports(mut self, enabled_transports: Option<Vec<String>>) -> Self {
+        self.enabled_transports = enabled_transports;
+        self
+    }
+
+    /// Limits the egress bandwidth of each unicast link to `max_egress_bandwidth` bytes per second
+    /// (no limit if `None`).
+    pub fn max_egress_bandwidth(mut self, max_egress_bandwidth: Option<u64>) -> Self {
+        self.max_egress_bandwidth = max_egress_bandwidth;
+        self
+    }
+
     pub fn build(self, handler: Arc<dyn TransportEventHandler>) -> TransportManagerConfig {
         TransportManagerConfig {
             version: self.version,
@@ -174,11 +259,16 @@ impl TransportManagerConfigBuilder {
             whatami: self.whatami,
             sn_resolution: self.sn_resolution,
             batch_size: self.batch_size,
+            batch_timeout: self.batch_timeout,
             defrag_buff_size: self.defrag_buff_size,
             link_rx_buff_size: self.link_rx_buff_size,
             unicast: self.unicast,
             multicast: self.multicast,
             endpoint: self.endpoint,
+            transports: self.transports,
+            bind_address: self.bind_address,
+            enabled_transports: self.enabled_transports,
+            max_egress_bandwidth: self.max_egress_bandwidth,
             handler,
         }
     }
@@ -187,6 +277,49 @@ impl TransportManagerConfigBuilder {
         mut self,
         properties: &ConfigProperties,
     ) -> ZResult<TransportManagerConfigBuilder> {
+        // Validate the enabled transports before applying any other key
+        if let Some(v) = properties.get(&ZN_ENABLED_TRANSPORTS_KEY) {
+            let enabled: Vec<String> = v
+                .split(',')
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect();
+            if enabled.is_empty() {
+                return zerror!(ZErrorKind::Other {
+                    descr: format!(
+                        "Invalid {}: no transport enabled",
+                        ZN_ENABLED_TRANSPORTS_STR
+                    )
+                });
+            }
+            // Reject the unknown protocols (e.g. a typo), that would silently disable the intended one
+            if let Some(unknown) = enabled.iter().find(|p| {
+                !BUILTIN_PROTOCOLS.contains(&p.as_str()) && !self.transports.contains_key(*p)
+            }) {
+                return zerror!(ZErrorKind::Other {
+                    descr: format!(
+                        "Invalid {}: unknown transport '{}' (known transports: {})",
+                        ZN_ENABLED_TRANSPORTS_STR,
+                        unknown,
+                        BUILTIN_PROTOCOLS
+                            .iter()
+                            .copied()
+                            .chain(self.transports.keys().map(String::as_str))
+                            .collect::<Vec<&str>>()
+                            .join(",")
+                    )
+                });
+            }
+            // Reject the configured locators of a disabled protocol now, rather than at connection
+            for key in &[ZN_LISTENER_KEY, ZN_PEER_KEY] {
+                for locator in properties.get_or(key, "").split(',').map(str::trim) {
+                    if let Ok(locator) = locator.parse::<Locator>() {
+                        check_enabled(&enabled, &locator)?;
+                    }
+                }
+            }
+            self = self.enabled_transports(Some(enabled));
+        }
         if let Some(v) = properties.get(&ZN_VERSION_KEY) {
             self = self.version(zparse!(v)?);
         }
@@ -202,12 +335,32 @@ impl TransportManagerConfigBuilder {
         if let Some(v) = properties.get(&ZN_BATCH_SIZE_KEY) {
             self = self.batch_size(zparse!(v)?);
         }
+        if let Some(v) = properties.get(&ZN_BATCH_TIMEOUT_KEY) {
+            self = self.batch_timeout(Duration::from_micros(zparse!(v)?));
+        }
         if let Some(v) = properties.get(&ZN_DEFRAG_BUFF_SIZE_KEY) {
             self = self.defrag_buff_size(zparse!(v)?);
         }
         if let Some(v) = properties.get(&ZN_LINK_RX_BUFF_SIZE_KEY) {
             self = self.link_rx_buff_size(zparse!(v)?);
         }
+        if let Some(v) = properties.get(&ZN_BIND_ADDRESS_KEY) {
+            let addr: IpAddr = zparse!(v)?;
+            // Check the address exists on this host, not to fail later on each outbound link
+            if !addr.is_unspecified() && !zenoh_util::net::get_local_addresses()?.contains(&addr) {
+                return zerror!(ZErrorKind::Other {
+                    descr: format!(
+                        "Invalid {}: {} is not an address of the local host",
+                        ZN_BIND_ADDRESS_STR, addr
+                    )
+                });
+            }
+            self = self.bind_address(Some(addr));
+        }
+        if let Some(v) = properties.get(&ZN_MAX_EGRESS_BANDWIDTH_KEY) {
+            let max: u64 = zparse!(v)?;
+            self = self.max_egress_bandwidth(if max > 0 { Some(max) } else { None });
+        }
 
         self = self.endpoint(LocatorConfig::from_config(properties)?);
         self = self.unicast(
@@ -229,9 +382,14 @@ impl Default for TransportManagerConfigBuilder {
             whatami: whatami::parse(ZN_MODE_DEFAULT).unwrap(),
             sn_resolution: SEQ_NUM_RES,
             batch_size: BATCH_SIZE,
+            batch_timeout: Duration::from_micros(zparse!(ZN_BATCH_TIMEOUT_DEFAULT).unwrap()),
             defrag_buff_size: zparse!(ZN_DEFRAG_BUFF_SIZE_DEFAULT).unwrap(),
             link_rx_buff_size: zparse!(ZN_LINK_RX_BUFF_SIZE_DEFAULT).unwrap(),
             endpoint: HashMap::new(),
+            transports: HashMap::new(),
+            bind_address: None,
+            enabled_transports: None,
+            max_egress_bandwidth: None,
             unicast: TransportManagerConfigUnicast::default(),
             multicast: TransportManagerConfigMulticast::default(),
         }
@@ -288,6 +446,7 @@ impl TransportManager {
     /*              LISTENER             */
     /*************************************/
     pub async fn add_listener(&self, endpoint: EndPoint) -> ZResult<Locator> {
+        self.config.check_enabled(&endpoint.locator)?;
         if endpoint.locator.address.is_multicast() {
             // @TODO: multicast
             unimplemented!();
@@ -328,7 +487,18 @@ impl TransportManager {
         // @TODO: multicast
     }
 
+    // Transmits the messages of the priority waiting for the batch timeout on all the transports
+    pub(crate) fn flush(&self, priority: Priority) {
+        for transport in self.get_transports_unicast() {
+            let _ = transport.flush(priority);
+        }
+        for transport in self.get_transports_multicast() {
+            let _ = transport.flush(priority);
+        }
+    }
+
     pub async fn open_transport(&self, endpoint: EndPoint) -> ZResult<TransportUnicast> {
+        self.config.check_enabled(&endpoint.locator)?;
         if endpoint.locator.address.is_multicast() {
             // @TODO: multicast
             unimplemented!();
@@ -105,6 +105,7 @@ impl TransportLinkMulticast {
                 config.batch_size.min(self.inner.get_mtu()),
                 false,
                 conduit_tx,
+                self.transport.manager.config.batch_timeout,
             ));
             self.pipeline = Some(pipeline.clone());
 
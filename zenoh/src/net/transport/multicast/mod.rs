@@ -20,7 +20,7 @@ pub(crate) mod tx;
 
 use super::common;
 use super::protocol;
-use super::protocol::core::ZInt;
+use super::protocol::core::{Priority, ZInt};
 use super::protocol::proto::{tmsg, ZenohMessage};
 use crate::net::link::Link;
 use crate::net::transport::{TransportMulticastEventHandler, TransportPeer};
@@ -108,6 +108,13 @@ impl TransportMulticast {
         Ok(())
     }
 
+    #[inline(always)]
+    pub(crate) fn flush(&self, priority: Priority) -> ZResult<()> {
+        let transport = self.get_transport()?;
+        transport.flush(priority);
+        Ok(())
+    }
+
     #[inline(always)]
     pub fn handle_message(&self, message: ZenohMessage) -> ZResult<()> {
         self.schedule(message)
@@ -304,6 +304,13 @@ impl TransportMulticastInner {
         self.schedule_first_fit(message);
     }
 
+    /// Transmits the messages of the priority waiting for the batch timeout
+    pub(crate) fn flush(&self, priority: Priority) {
+        if let Some(pipeline) = zread!(self.link).as_ref().and_then(|l| l.get_pipeline()) {
+            pipeline.flush(priority);
+        }
+    }
+
     /*************************************/
     /*               LINK                */
     /*************************************/
@@ -11,13 +11,16 @@
 // Contributors:
 //   ADLINK zenoh team, <zenoh@adlink-labs.tech>
 //
-use super::common::{conduit::TransportConduitTx, pipeline::TransmissionPipeline};
+use super::common::{
+    conduit::TransportConduitTx, pipeline::TransmissionPipeline, throttle::EgressThrottle,
+};
 use super::protocol::core::Priority;
 use super::protocol::io::{ZBuf, ZSlice};
 use super::protocol::proto::TransportMessage;
 use super::transport::TransportUnicastInner;
 #[cfg(feature = "stats")]
 use super::transport::TransportUnicastStatsInner;
+use crate::net::health::Heartbeat;
 use crate::net::link::LinkUnicast;
 use async_std::prelude::*;
 use async_std::task;
@@ -38,6 +41,8 @@ pub(super) struct TransportLinkUnicast {
     transport: TransportUnicastInner,
     // The transmission pipeline
     pipeline: Option<Arc<TransmissionPipeline>>,
+    // The egress bandwidth limit and rate
+    throttle: Arc<EgressThrottle>,
     // The signals to stop TX/RX tasks
     handle_tx: Option<Arc<JoinHandle<()>>>,
     active_rx: Arc<AtomicBool>,
@@ -47,10 +52,14 @@ pub(super) struct TransportLinkUnicast {
 
 impl TransportLinkUnicast {
     pub(super) fn new(transport: TransportUnicastInner, link: LinkUnicast) -> TransportLinkUnicast {
+        let throttle = Arc::new(EgressThrottle::new(
+            transport.manager.config.max_egress_bandwidth,
+        ));
         TransportLinkUnicast {
             transport,
             inner: link,
             pipeline: None,
+            throttle,
             handle_tx: None,
             active_rx: Arc::new(AtomicBool::new(false)),
             signal_rx: Signal::new(),
@@ -70,6 +79,12 @@ impl TransportLinkUnicast {
         self.pipeline.clone()
     }
 
+    // Returns the egress rate of this link in bytes per second, measured over the last second.
+    #[inline]
+    pub(super) fn get_tx_rate(&self) -> u64 {
+        self.throttle.rate()
+    }
+
     pub(super) fn start_tx(
         &mut self,
         keep_alive: Duration,
@@ -82,17 +97,20 @@ impl TransportLinkUnicast {
                 batch_size.min(self.inner.get_mtu()),
                 self.inner.is_streamed(),
                 conduit_tx,
+                self.transport.manager.config.batch_timeout,
             ));
             self.pipeline = Some(pipeline.clone());
 
             // Spawn the TX task
             let c_link = self.inner.clone();
             let c_transport = self.transport.clone();
+            let c_throttle = self.throttle.clone();
             let handle = task::spawn(async move {
                 let res = tx_task(
                     pipeline,
                     c_link.clone(),
                     keep_alive,
+                    c_throttle,
                     #[cfg(feature = "stats")]
                     c_transport.stats.clone(),
                 )
@@ -179,14 +197,19 @@ async fn tx_task(
     pipeline: Arc<TransmissionPipeline>,
     link: LinkUnicast,
     keep_alive: Duration,
+    throttle: Arc<EgressThrottle>,
     #[cfg(feature = "stats")] stats: TransportUnicastStatsInner,
 ) -> ZResult<()> {
+    let heartbeat = Heartbeat::new(format!("tx {}", link));
     loop {
+        heartbeat.beat();
         match pipeline.pull().timeout(keep_alive).await {
             Ok(res) => match res {
                 Some((batch, priority)) => {
-                    // Send the buffer on the link
+                    // Send the buffer on the link, within the egress bandwidth
+                    // (without delaying it beyond the keep-alive interval, not to expire the lease)
                     let bytes = batch.as_bytes();
+                    throttle.acquire(bytes.len(), keep_alive).await;
                     let _ = link.write_all(bytes).await?;
 
                     #[cfg(feature = "stats")]
@@ -264,7 +287,9 @@ async fn rx_task_stream(
     let mtu = link.get_mtu() as usize;
     let n = 1 + (rx_buff_size / mtu);
     let pool = RecyclingObjectPool::new(n, || vec![0u8; mtu].into_boxed_slice());
+    let heartbeat = Heartbeat::new(format!("rx {}", link));
     while active.load(Ordering::Acquire) {
+        heartbeat.beat();
         // Clear the ZBuf
         zbuf.clear();
 
@@ -337,7 +362,9 @@ async fn rx_task_dgram(
     let mtu = link.get_mtu() as usize;
     let n = 1 + (rx_buff_size / mtu);
     let pool = RecyclingObjectPool::new(n, || vec![0u8; mtu].into_boxed_slice());
+    let heartbeat = Heartbeat::new(format!("rx {}", link));
     while active.load(Ordering::Acquire) {
+        heartbeat.beat();
         // Clear the zbuf
         zbuf.clear();
         // Retrieve one buffer
@@ -239,7 +239,10 @@ impl TransportManager {
         match w_guard.get(protocol) {
             Some(lm) => Ok(lm.clone()),
             None => {
-                let lm = LinkManagerBuilderUnicast::make(self.clone(), protocol)?;
+                let lm = match self.config.transports.get(&protocol.to_string()) {
+                    Some(factory) => factory.make(self.clone())?,
+                    None => LinkManagerBuilderUnicast::make(self.clone(), protocol)?,
+                };
                 w_guard.insert(protocol.clone(), lm.clone());
                 Ok(lm)
             }
@@ -469,7 +472,10 @@ impl TransportManager {
         Ok(())
     }
 
-    pub(crate) async fn handle_new_link_unicast(&self, link: LinkUnicast) {
+    /// Handles a new link accepted by a listener, initiating a transport on it.  
+    /// This is to be called by the [`LinkManagerUnicastTrait`](crate::net::link::LinkManagerUnicastTrait)
+    /// implementations for each accepted link.
+    pub async fn handle_new_link_unicast(&self, link: LinkUnicast) {
         let mut guard = zasynclock!(self.state.unicast.incoming);
         if guard.len() >= self.config.unicast.open_pending {
             // We reached the limit of concurrent incoming transport, this means two things:
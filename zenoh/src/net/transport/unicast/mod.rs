@@ -21,7 +21,7 @@ pub(crate) mod tx;
 
 use super::common;
 use super::protocol;
-use super::protocol::core::{PeerId, WhatAmI, ZInt};
+use super::protocol::core::{PeerId, Priority, WhatAmI, ZInt};
 use super::protocol::proto::{tmsg, ZenohMessage};
 use super::{TransportPeer, TransportPeerEventHandler};
 use crate::net::link::Link;
@@ -38,10 +38,10 @@ use zenoh_util::zerror2;
 #[cfg(feature = "stats")]
 #[derive(Clone, Copy, Debug)]
 pub struct TransportStatsUnicast {
-    tx_msgs: usize,
-    tx_bytes: usize,
-    rx_msgs: usize,
-    rx_bytes: usize,
+    pub tx_msgs: usize,
+    pub tx_bytes: usize,
+    pub rx_msgs: usize,
+    pub rx_bytes: usize,
 }
 
 #[derive(Clone, Copy)]
@@ -132,6 +132,24 @@ impl TransportUnicast {
             .collect())
     }
 
+    /// Returns the egress rate of each link in bytes per second, measured over the last second
+    /// (see [`ZN_MAX_EGRESS_BANDWIDTH_KEY`](crate::net::config::ZN_MAX_EGRESS_BANDWIDTH_KEY)).
+    ///
+    /// When the maximum egress bandwidth is exceeded, the sending on the link is delayed, which applies
+    /// backpressure to the publishers (see the `CongestionControl` of the puts): a blocking put waits,
+    /// a droppable one is dropped once the transmission queue is full.
+    /// The limit applies to each unicast link independently (the multicast links are not limited):
+    /// a peer with several links or several transports may exceed it in total. On a link, the batches are sent
+    /// in the order of their priority, so a saturated link delays the lower priorities first and may starve them.
+    pub fn get_links_tx_rate(&self) -> ZResult<Vec<(Link, u64)>> {
+        let transport = self.get_transport()?;
+        Ok(transport
+            .get_links_tx_rate()
+            .into_iter()
+            .map(|(l, rate)| (l.into(), rate))
+            .collect())
+    }
+
     #[inline(always)]
     pub fn schedule(&self, message: ZenohMessage) -> ZResult<()> {
         let transport = self.get_transport()?;
@@ -139,6 +157,13 @@ impl TransportUnicast {
         Ok(())
     }
 
+    #[inline(always)]
+    pub(crate) fn flush(&self, priority: Priority) -> ZResult<()> {
+        let transport = self.get_transport()?;
+        transport.flush(priority);
+        Ok(())
+    }
+
     #[inline(always)]
     pub async fn close_link(&self, link: &Link) -> ZResult<()> {
         let transport = self.get_transport()?;
@@ -503,6 +503,20 @@ impl TransportUnicastInner {
         self.schedule_first_fit(message);
     }
 
+    /// Transmits the messages of the priority waiting for the batch timeout on all the links
+    pub(crate) fn flush(&self, priority: Priority) {
+        for pipeline in zread!(self.links).iter().filter_map(|sl| sl.get_pipeline()) {
+            pipeline.flush(priority);
+        }
+    }
+
+    pub(crate) fn get_links_tx_rate(&self) -> Vec<(LinkUnicast, u64)> {
+        zread!(self.links)
+            .iter()
+            .map(|l| (l.get_link().clone(), l.get_tx_rate()))
+            .collect()
+    }
+
     pub(crate) fn get_links(&self) -> Vec<LinkUnicast> {
         zread!(self.links)
             .iter()
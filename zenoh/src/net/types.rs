@@ -18,6 +18,7 @@ use flume::*;
 use std::collections::HashMap;
 use std::fmt;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::RwLock;
 use std::task::{Context, Poll};
 use uhlc::Timestamp;
@@ -67,6 +68,9 @@ pub use super::protocol::core::CongestionControl;
 /// The kind of reliability.
 pub use super::protocol::core::Reliability;
 
+/// The priority of a message, i.e. the conduit it is sent on.
+pub use super::protocol::core::Priority;
+
 /// A resource key.
 pub use super::protocol::core::ResKey;
 
@@ -208,6 +212,10 @@ pub(crate) struct QueryState {
     pub(crate) reception_mode: ConsolidationMode,
     pub(crate) replies: Option<HashMap<String, Reply>>,
     pub(crate) rep_sender: Sender<Reply>,
+    // if set, the replies from any other replier are dropped
+    pub(crate) replier: Option<PeerId>,
+    // set when a replier rejected the query because of overload (shared with the ReplyReceiver)
+    pub(crate) overloaded: Arc<AtomicBool>,
 }
 
 pub(crate) type Id = usize;
@@ -274,6 +282,7 @@ pub(crate) struct SubscriberState {
     pub(crate) reskey: ResKey,
     pub(crate) resname: String,
     pub(crate) invoker: SubscriberInvoker,
+    pub(crate) paused: AtomicBool,
 }
 
 impl fmt::Debug for SubscriberState {
@@ -331,6 +340,26 @@ impl Subscriber<'_> {
         self.session.pull(&self.state.reskey)
     }
 
+    /// Pauses the delivery of data to this [Subscriber](Subscriber), without undeclaring it.
+    ///
+    /// While paused, the received samples are buffered in the [receiver](Subscriber::receiver) channel
+    /// up to its capacity. Once this channel is full, the further received samples are dropped
+    /// (unlike when not paused, where the session waits for the receiver to consume the samples).
+    pub fn pause(&self) {
+        self.state.paused.store(true, Ordering::Relaxed);
+    }
+
+    /// Resumes the delivery of data to this [Subscriber](Subscriber) after a [pause](Subscriber::pause).
+    /// The samples buffered while paused are received first.
+    pub fn resume(&self) {
+        self.state.paused.store(false, Ordering::Relaxed);
+    }
+
+    /// Returns true if this [Subscriber](Subscriber) is paused.
+    pub fn is_paused(&self) -> bool {
+        self.state.paused.load(Ordering::Relaxed)
+    }
+
     /// Undeclare a [Subscriber](Subscriber) previously declared with [declare_subscriber](Session::declare_subscriber).
     ///
     /// Subscribers are automatically undeclared when dropped, but you may want to use this function to handle errors or
@@ -449,7 +478,20 @@ impl fmt::Debug for CallbackSubscriber<'_> {
 
 zreceiver! {
     #[derive(Clone)]
-    pub struct ReplyReceiver : Receiver<Reply> {}
+    pub struct ReplyReceiver : Receiver<Reply> {
+        pub(crate) overloaded: Arc<AtomicBool>,
+    }
+}
+
+impl ReplyReceiver {
+    /// Returns true if a session hosting matching queryables rejected the query because of overload
+    /// (see [`ZN_MAX_CONCURRENT_QUERIES_KEY`](super::config::ZN_MAX_CONCURRENT_QUERIES_KEY)):
+    /// the replies received by this receiver are then incomplete.  
+    /// Such a rejection is not received as a [`Reply`]. It's only known once it's received,
+    /// i.e. for sure at the end of the stream.
+    pub fn is_overloaded(&self) -> bool {
+        self.overloaded.load(Ordering::Relaxed)
+    }
 }
 
 pub(crate) struct QueryableState {
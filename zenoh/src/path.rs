@@ -12,10 +12,14 @@
 //   ADLINK zenoh team, <zenoh@adlink-labs.tech>
 //
 use crate::net::ResKey;
+use crate::utils::{percent_decode, percent_encode};
 use regex::Regex;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Borrow;
 use std::convert::TryFrom;
 use std::fmt;
 use std::ops::Div;
+use std::sync::Arc;
 use zenoh_util::core::{ZError, ZErrorKind, ZResult};
 use zenoh_util::zerror;
 
@@ -25,15 +29,42 @@ use zenoh_util::zerror;
 /// `"/demo/example/hello"` , `"/org/eclipse/building/be/floor/1/office/2"` ...
 ///
 /// A path can be absolute (i.e. starting with a `'/'`) or relative to a [`Workspace`](super::Workspace).
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+///
+/// A Path is immutable and its string is shared, so cloning a Path is cheap
+/// (e.g. to use it as a key in a map built from received [`Change`](super::Change)s).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Path {
-    pub(crate) p: String,
+    pub(crate) p: Arc<str>,
 }
 
 impl Path {
     fn is_valid(path: &str) -> bool {
-        !path.is_empty()
-            && !path.contains(|c| c == '?' || c == '#' || c == '[' || c == ']' || c == '*')
+        Self::is_valid_const(path)
+    }
+
+    // Checks the validity of a path in a const context (used by the path! macro at compile time).
+    #[doc(hidden)]
+    pub const fn is_valid_const(path: &str) -> bool {
+        let bytes = path.as_bytes();
+        if bytes.is_empty() {
+            return false;
+        }
+        let mut i = 0;
+        while i < bytes.len() {
+            if matches!(bytes[i], b'?' | b'#' | b'[' | b']' | b'*') {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    // Creates a Path from a string already validated at compile time by the path! macro.
+    #[doc(hidden)]
+    pub fn from_validated(p: &'static str) -> Path {
+        Path {
+            p: Self::remove_useless_slashes(p).into(),
+        }
     }
 
     pub(crate) fn remove_useless_slashes(path: &str) -> String {
@@ -58,14 +89,14 @@ impl Path {
             })
         } else {
             Ok(Path {
-                p: Self::remove_useless_slashes(p),
+                p: Self::remove_useless_slashes(p).into(),
             })
         }
     }
 
     /// Returns the Path as a &str.
     pub fn as_str(&self) -> &str {
-        self.p.as_str()
+        &self.p
     }
 
     /// Returns true is this Path is relative (i.e. not starting with `'/'`).
@@ -78,7 +109,7 @@ impl Path {
     pub fn last_segment(&self) -> &str {
         match self.p.rfind('/') {
             Some(i) => &self.p[i + 1..],
-            None => self.p.as_str(),
+            None => &self.p,
         }
     }
 
@@ -86,11 +117,11 @@ impl Path {
     pub fn with_prefix(&self, prefix: &Path) -> Self {
         if self.is_relative() {
             Self {
-                p: format!("{}/{}", prefix.p, self.p),
+                p: format!("{}/{}", prefix.p, self.p).into(),
             }
         } else {
             Self {
-                p: format!("{}{}", prefix.p, self.p),
+                p: format!("{}{}", prefix.p, self.p).into(),
             }
         }
     }
@@ -99,8 +130,72 @@ impl Path {
     /// Otherwise, returns `None`.
     pub fn strip_prefix(&self, prefix: &Path) -> Option<Self> {
         self.p
-            .strip_prefix(&prefix.p)
-            .map(|p| Path { p: p.to_string() })
+            .strip_prefix(&*prefix.p)
+            .map(|p| Path { p: p.into() })
+    }
+
+    /// Creates a Path from a hierarchical key which segments are separated by `separator` instead of `'/'`
+    /// (e.g. `"a.b.c"` with `'.'` as separator gives the relative Path `"a/b/c"`).  
+    /// A key starting with `separator` gives an absolute Path.
+    ///
+    /// A literal `separator` or `'\'` character within a segment must be escaped with a `'\'`
+    /// (e.g. `"a\.b.c"` with `'.'` as separator gives `"a.b/c"`).
+    /// A key segment can't contain a `'/'`. If `separator` is `'/'`, this is equivalent to [`Path::new()`].
+    pub fn from_mapped_key(key: &str, separator: char) -> ZResult<Path> {
+        if separator == '/' {
+            return Path::new(key);
+        }
+        let mut path = String::with_capacity(key.len());
+        let mut chars = key.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some(c) if c == separator || c == '\\' => path.push(c),
+                    _ => return zerror!(ZErrorKind::InvalidPath { path: key.into() }),
+                },
+                '/' => return zerror!(ZErrorKind::InvalidPath { path: key.into() }),
+                c if c == separator => path.push('/'),
+                c => path.push(c),
+            }
+        }
+        Path::new(&path)
+    }
+
+    /// Converts this Path into a hierarchical key which segments are separated by `separator` instead of `'/'`,
+    /// escaping with a `'\'` the literal `separator` and `'\'` characters.  
+    /// This is the reverse of [`Path::from_mapped_key()`].
+    pub fn to_mapped_key(&self, separator: char) -> String {
+        if separator == '/' {
+            return self.p.to_string();
+        }
+        let mut key = String::with_capacity(self.p.len());
+        for c in self.p.chars() {
+            match c {
+                '/' => key.push(separator),
+                c if c == separator || c == '\\' => {
+                    key.push('\\');
+                    key.push(c);
+                }
+                c => key.push(c),
+            }
+        }
+        key
+    }
+
+    /// Converts this Path into a string usable as the path of a URL, percent-encoding all the characters
+    /// other than `'/'` and the URL unreserved characters (e.g. `"/a b/c"` gives `"/a%20b/c"`).  
+    /// This is the reverse of [`Path::from_url_encoded()`].
+    pub fn to_url_encoded(&self) -> String {
+        percent_encode(&self.p, b"/")
+    }
+
+    /// Creates a Path from the percent-encoded path of a URL (e.g. `"/a%20b/c"` gives `"/a b/c"`).  
+    /// Returns an error if the decoded string is not a valid Path.
+    pub fn from_url_encoded(s: &str) -> ZResult<Path> {
+        match percent_decode(s) {
+            Some(p) => Path::new(&p),
+            None => zerror!(ZErrorKind::InvalidPath { path: s.into() }),
+        }
     }
 }
 
@@ -136,6 +231,18 @@ impl Div<&str> for &Path {
     }
 }
 
+impl AsRef<str> for Path {
+    fn as_ref(&self) -> &str {
+        &self.p
+    }
+}
+
+impl Borrow<str> for Path {
+    fn borrow(&self) -> &str {
+        &self.p
+    }
+}
+
 impl fmt::Display for Path {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.p)
@@ -156,15 +263,29 @@ impl TryFrom<&str> for Path {
     }
 }
 
+impl Serialize for Path {
+    /// Serializes the Path as its string form.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Path {
+    /// Deserializes the Path from its string form, failing if this string is not a valid Path.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Path::try_from(String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
 impl From<Path> for ResKey {
     fn from(path: Path) -> Self {
-        ResKey::from(path.p.as_str())
+        ResKey::from(path.as_str())
     }
 }
 
 impl From<&Path> for ResKey {
     fn from(path: &Path) -> Self {
-        ResKey::from(path.p.as_str())
+        ResKey::from(path.as_str())
     }
 }
 
@@ -176,11 +297,52 @@ pub fn path(path: impl AsRef<str>) -> Path {
     Path::try_from(path.as_ref()).unwrap()
 }
 
+/// Creates a [`Path`] from a string literal, checking its validity at compile time.
+///
+/// Unlike the [`path()`] function, an invalid path is a compilation error rather than a panic at runtime.
+/// Only string literals (or constant `&str` expressions) are supported.
+/// The compile-time check covers the validity rules of a [`Path`]: it must not be empty and must not
+/// contain the forbidden characters `'?'`, `'#'`, `'['`, `']'`, `'*'`.
+/// The useless `'/'` are still removed at runtime, as for [`Path::new()`].
+///
+/// # Examples
+/// ```
+/// use zenoh::*;
+///
+/// let path = path!("/demo/example/eval");
+/// assert_eq!(path.as_str(), "/demo/example/eval");
+/// ```
+///
+/// ```compile_fail
+/// use zenoh::*;
+///
+/// let path = path!("/demo/example/*");
+/// ```
+#[macro_export]
+macro_rules! path {
+    ($p:expr) => {{
+        const _: () = assert!(
+            $crate::Path::is_valid_const($p),
+            "invalid zenoh Path: must not be empty and must not contain '?', '#', '[', ']' or '*'"
+        );
+        $crate::Path::from_validated($p)
+    }};
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::convert::TryInto;
 
+    #[test]
+    fn path_serde() {
+        let path: Path = "/a/b/c".try_into().unwrap();
+        let json = serde_json::to_string(&path).unwrap();
+        assert_eq!(json, r#""/a/b/c""#);
+        assert_eq!(serde_json::from_str::<Path>(&json).unwrap(), path);
+        assert!(serde_json::from_str::<Path>(r#""/a/*/c""#).is_err());
+    }
+
     #[test]
     fn path_div() {
         assert_eq!(Path::try_from("a").unwrap() / "b", Path { p: "a/b".into() });
@@ -190,6 +352,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn path_mapped_key() {
+        assert_eq!(
+            Path::from_mapped_key("a.b.c", '.').unwrap(),
+            Path { p: "a/b/c".into() }
+        );
+        assert_eq!(
+            Path::from_mapped_key(":a:b", ':').unwrap(),
+            Path { p: "/a/b".into() }
+        );
+        assert_eq!(
+            Path::from_mapped_key(r"a\.b.c\\d", '.').unwrap(),
+            Path {
+                p: r"a.b/c\d".into()
+            }
+        );
+        assert!(Path::from_mapped_key("a/b.c", '.').is_err());
+        assert!(Path::from_mapped_key(r"a\b.c", '.').is_err());
+        assert!(Path::from_mapped_key("a.*.c", '.').is_err());
+
+        for key in &["a.b.c", ".a.b", r"a\.b.c\\d"] {
+            let path = Path::from_mapped_key(key, '.').unwrap();
+            assert_eq!(&path.to_mapped_key('.'), key);
+        }
+        assert_eq!(Path { p: "/a/b".into() }.to_mapped_key('/'), "/a/b");
+    }
+
+    #[test]
+    fn path_url_encoded() {
+        assert_eq!(path("/a b/c").to_url_encoded(), "/a%20b/c");
+        assert_eq!(Path::from_url_encoded("/a%20b/c").unwrap(), path("/a b/c"));
+        for p in &["/a b/c", "/a%b/c+d", "/é/&=;,", "rel/x~y_z.w-v"] {
+            let p = path(p);
+            assert_eq!(Path::from_url_encoded(&p.to_url_encoded()).unwrap(), p);
+        }
+        assert!(Path::from_url_encoded("/a/%2A").is_err());
+        assert!(Path::from_url_encoded("/a/%2").is_err());
+        assert!(Path::from_url_encoded("/a/%FF").is_err());
+    }
+
+    #[test]
+    fn path_macro() {
+        assert_eq!(path!("/a/b"), Path { p: "/a/b".into() });
+        assert_eq!(path!("a//b/"), Path { p: "a/b".into() });
+        const P: &str = "/demo/example";
+        assert_eq!(path!(P), Path::try_from(P).unwrap());
+        assert!(Path::is_valid_const("/a/b"));
+        assert!(!Path::is_valid_const(""));
+        assert!(!Path::is_valid_const("/a/*"));
+        assert!(!Path::is_valid_const("/a?b"));
+    }
+
+    #[test]
+    fn path_clone_shares_str() {
+        let path = Path::try_from("/a/b").unwrap();
+        let clone = path.clone();
+        assert!(Arc::ptr_eq(&path.p, &clone.p));
+
+        let mut map = std::collections::HashMap::new();
+        map.insert(clone, 1);
+        assert_eq!(map.get("/a/b"), Some(&1));
+    }
+
     #[test]
     fn test_path() {
         assert_eq!(Path::try_from("a/b").unwrap(), Path { p: "a/b".into() });
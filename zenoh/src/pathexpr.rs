@@ -14,6 +14,7 @@
 use crate::net::utils::resource_name;
 use crate::net::ResKey;
 use crate::Path;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::convert::{From, TryFrom};
 use std::{fmt, ops::Div};
 use zenoh_util::core::{ZError, ZErrorKind, ZResult};
@@ -27,14 +28,75 @@ use zenoh_util::zerror;
 ///  - while `"**"` matches any set of characters in a path, including `'/'`.
 ///
 /// A Path Expression can be absolute (i.e. starting with a `'/'`) or relative to a [`Workspace`](super::Workspace).
+///
+/// A Path Expression can also be followed by exclusions, each one being a Path Expression prefixed with `" !"`
+/// (e.g. `"/sensors/** !/sensors/debug/**"`). A [`Path`] matches such a Path Expression if it matches
+/// its included part and none of its exclusions: the exclusions always take precedence over the included part.
+/// As zenoh has no union of Path Expressions, a union has to be expressed as several subscriptions or `get`,
+/// each one applying its own exclusions.
+///
+/// Only the included part is declared to the zenoh network (see [`included()`](PathExpr::included)):
+/// the exclusions are applied client-side by the [`Workspace`](super::Workspace) operations,
+/// filtering out the excluded changes, replies and `get` requests after their reception.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct PathExpr {
     pub(crate) p: String,
+    pub(crate) excludes: Vec<String>,
+    // the complete PathExpr (i.e. the included part followed by the exclusions)
+    full: String,
 }
 
 impl PathExpr {
     fn is_valid(path: &str) -> bool {
-        !path.is_empty() && !path.contains(|c| c == '?' || c == '#' || c == '[' || c == ']')
+        Self::is_valid_const(path) && {
+            let (include, excludes) = Self::split(path);
+            !include.is_empty()
+                && !include.starts_with('!')
+                && excludes.iter().all(|e| !e.is_empty())
+        }
+    }
+
+    fn from_parts(p: String, excludes: Vec<String>) -> PathExpr {
+        let mut full = p.clone();
+        for e in &excludes {
+            full.push_str(" !");
+            full.push_str(e);
+        }
+        PathExpr { p, excludes, full }
+    }
+
+    // Splits a path expression into its included part and its exclusions (each one prefixed with " !").
+    fn split(path: &str) -> (String, Vec<String>) {
+        let mut parts = path.split(" !");
+        let include = Path::remove_useless_slashes(parts.next().unwrap_or("").trim());
+        let excludes = parts
+            .map(|e| Path::remove_useless_slashes(e.trim()))
+            .collect();
+        (include, excludes)
+    }
+
+    // Checks the validity of a path expression in a const context (used by the selector! macro at compile time).
+    #[doc(hidden)]
+    pub const fn is_valid_const(path: &str) -> bool {
+        let bytes = path.as_bytes();
+        if bytes.is_empty() {
+            return false;
+        }
+        let mut i = 0;
+        while i < bytes.len() {
+            if matches!(bytes[i], b'?' | b'#' | b'[' | b']') {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    // Creates a PathExpr from a string already validated at compile time by the selector! macro.
+    #[doc(hidden)]
+    pub fn from_validated(p: &'static str) -> PathExpr {
+        let (p, excludes) = Self::split(p);
+        Self::from_parts(p, excludes)
     }
 
     /// Creates a new PathExpr from a String, checking its validity.  
@@ -43,51 +105,92 @@ impl PathExpr {
         if !Self::is_valid(&p) {
             zerror!(ZErrorKind::InvalidPathExpr { path: p })
         } else {
-            Ok(PathExpr {
-                p: Path::remove_useless_slashes(&p),
-            })
+            let (p, excludes) = Self::split(&p);
+            Ok(Self::from_parts(p, excludes))
         }
     }
 
-    /// Returns the Path as a &str.
+    /// Returns the PathExpr as a &str (including its exclusions, if any).
     pub fn as_str(&self) -> &str {
+        self.full.as_str()
+    }
+
+    /// Returns the included part of this PathExpr (i.e. without its exclusions) as a &str.
+    pub fn included(&self) -> &str {
         self.p.as_str()
     }
 
+    /// Returns the exclusions of this PathExpr (without their `'!'` prefix).
+    pub fn exclusions(&self) -> &[String] {
+        &self.excludes
+    }
+
     /// Returns true is this PathExpr is relative (i.e. not starting with `'/'`).
     pub fn is_relative(&self) -> bool {
         !self.p.starts_with('/')
     }
 
-    /// Returns true is this PathExpr is a [`Path`] (i.e. not containing any `'*'`).
+    /// Returns true is this PathExpr is a [`Path`] (i.e. not containing any `'*'` nor exclusion).
     pub fn is_a_path(&self) -> bool {
-        !self.p.contains('*')
+        !self.p.contains('*') && self.excludes.is_empty()
     }
 
-    /// Returns the concatenation of `prefix` with this PathExpr.
+    /// Returns the concatenation of `prefix` with this PathExpr (and with each of its relative exclusions).
     pub fn with_prefix(&self, prefix: &Path) -> Self {
-        if self.is_relative() {
-            Self {
-                p: format!("{}/{}", prefix.p, self.p),
+        let with_prefix = |p: &str| {
+            if p.starts_with('/') {
+                format!("{}{}", prefix.p, p)
+            } else {
+                format!("{}/{}", prefix.p, p)
             }
-        } else {
-            Self {
-                p: format!("{}{}", prefix.p, self.p),
-            }
-        }
+        };
+        Self::from_parts(
+            with_prefix(&self.p),
+            self.excludes.iter().map(|e| with_prefix(e)).collect(),
+        )
     }
 
     /// If this PathExpr starts with `prefix` returns a copy of this PathExpr with the prefix removed.  
-    /// Otherwise, returns `None`.
+    /// Otherwise, returns `None`.  
+    /// The exclusions not starting with `prefix` are dropped.
     pub fn strip_prefix(&self, prefix: &Path) -> Option<Self> {
-        self.p
-            .strip_prefix(&prefix.p)
-            .map(|p| PathExpr { p: p.to_string() })
+        self.p.strip_prefix(&*prefix.p).map(|p| {
+            PathExpr::from_parts(
+                p.to_string(),
+                self.excludes
+                    .iter()
+                    .filter_map(|e| e.strip_prefix(&*prefix.p).map(str::to_string))
+                    .collect(),
+            )
+        })
     }
 
-    /// Returns true if `path` matches this PathExpr.
+    /// Returns true if `path` matches this PathExpr (i.e. matches its included part but none of its exclusions).
     pub fn matches(&self, path: &Path) -> bool {
-        resource_name::intersect(&self.p, &path.p)
+        resource_name::intersect(&self.p, &path.p) && !self.is_excluded(&path.p)
+    }
+
+    /// Returns true if some [`Path`]s may match both this PathExpr and `other`.  
+    /// This is the case if their included parts intersect and if none of their exclusions includes
+    /// all the included part of the other PathExpr. Note that this is conservative: it can return true
+    /// while all the common paths are excluded by several exclusions together.
+    pub fn intersects(&self, other: &PathExpr) -> bool {
+        resource_name::intersect(&self.p, &other.p)
+            && !self
+                .excludes
+                .iter()
+                .any(|e| resource_name::include(e, &other.p))
+            && !other
+                .excludes
+                .iter()
+                .any(|e| resource_name::include(e, &self.p))
+    }
+
+    // Returns true if the resource `name` matches one of the exclusions of this PathExpr.
+    pub(crate) fn is_excluded(&self, name: &str) -> bool {
+        self.excludes
+            .iter()
+            .any(|e| resource_name::intersect(e, name))
     }
 }
 
@@ -111,7 +214,7 @@ impl Div<String> for &PathExpr {
     type Output = PathExpr;
 
     fn div(self, rhs: String) -> Self::Output {
-        PathExpr::try_from(format!("{}/{}", self, rhs)).unwrap()
+        self / rhs.as_str()
     }
 }
 
@@ -119,13 +222,17 @@ impl Div<&str> for &PathExpr {
     type Output = PathExpr;
 
     fn div(self, rhs: &str) -> Self::Output {
-        PathExpr::try_from(format!("{}/{}", self, rhs)).unwrap()
+        // the exclusions are kept unchanged
+        let expr = PathExpr::try_from(format!("{}/{}", self.p, rhs)).unwrap();
+        let mut excludes = self.excludes.clone();
+        excludes.extend(expr.excludes);
+        PathExpr::from_parts(expr.p, excludes)
     }
 }
 
 impl fmt::Display for PathExpr {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.p)
+        write!(f, "{}", self.full)
     }
 }
 
@@ -143,20 +250,35 @@ impl TryFrom<&str> for PathExpr {
     }
 }
 
+impl Serialize for PathExpr {
+    /// Serializes the PathExpr as its string form.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for PathExpr {
+    /// Deserializes the PathExpr from its string form, failing if this string is not a valid PathExpr.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        PathExpr::try_from(String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
 impl From<&Path> for PathExpr {
     fn from(path: &Path) -> Self {
         // No need to check validity as PathExpr is valid
-        PathExpr { p: path.p.clone() }
+        PathExpr::from_parts(path.p.to_string(), vec![])
     }
 }
 
 impl From<Path> for PathExpr {
     fn from(path: Path) -> Self {
         // No need to check validity as PathExpr is valid
-        PathExpr { p: path.p }
+        PathExpr::from_parts(path.p.to_string(), vec![])
     }
 }
 
+// Only the included part of a PathExpr can be declared to the zenoh network.
 impl From<PathExpr> for ResKey {
     fn from(path: PathExpr) -> Self {
         ResKey::from(path.p.as_str())
@@ -181,6 +303,15 @@ pub fn pathexpr(path: impl AsRef<str>) -> PathExpr {
 mod tests {
     use super::*;
 
+    #[test]
+    fn pathexpr_serde() {
+        let expr = PathExpr::try_from("/a/**/c").unwrap();
+        let json = serde_json::to_string(&expr).unwrap();
+        assert_eq!(json, r#""/a/**/c""#);
+        assert_eq!(serde_json::from_str::<PathExpr>(&json).unwrap(), expr);
+        assert!(serde_json::from_str::<PathExpr>(r#""/a?b""#).is_err());
+    }
+
     #[test]
     fn pathexpr_div() {
         assert_eq!(
@@ -192,4 +323,49 @@ mod tests {
             PathExpr::try_from("a/b").unwrap()
         );
     }
+
+    #[test]
+    fn pathexpr_exclusions() {
+        let expr = PathExpr::try_from("/sensors/**  !/sensors/debug/** !/sensors/*/raw").unwrap();
+        assert_eq!(expr.included(), "/sensors/**");
+        assert_eq!(
+            expr.as_str(),
+            "/sensors/** !/sensors/debug/** !/sensors/*/raw"
+        );
+        assert_eq!(expr.exclusions(), ["/sensors/debug/**", "/sensors/*/raw"]);
+        assert_eq!(
+            expr.to_string(),
+            "/sensors/** !/sensors/debug/** !/sensors/*/raw"
+        );
+        assert!(!expr.is_a_path());
+        assert_eq!(ResKey::from(&expr), ResKey::from("/sensors/**"));
+
+        assert!(expr.matches(&Path::try_from("/sensors/temp").unwrap()));
+        assert!(!expr.matches(&Path::try_from("/sensors/debug/temp").unwrap()));
+        assert!(!expr.matches(&Path::try_from("/sensors/temp/raw").unwrap()));
+        assert!(!expr.matches(&Path::try_from("/other/temp").unwrap()));
+
+        assert!(expr.intersects(&PathExpr::try_from("/sensors/*").unwrap()));
+        assert!(expr.intersects(&PathExpr::try_from("/sensors/*/x !/sensors/a/x").unwrap()));
+        assert!(!expr.intersects(&PathExpr::try_from("/sensors/debug/a/*").unwrap()));
+        assert!(!PathExpr::try_from("/sensors/debug/*")
+            .unwrap()
+            .intersects(&expr));
+
+        let relative = PathExpr::try_from("a/** !a/b/**").unwrap();
+        let prefix = Path::try_from("/p").unwrap();
+        let absolute = relative.with_prefix(&prefix);
+        assert_eq!(absolute.to_string(), "/p/a/** !/p/a/b/**");
+        assert_eq!(
+            absolute.strip_prefix(&prefix).unwrap().to_string(),
+            "/a/** !/a/b/**"
+        );
+        assert_eq!((relative / "c").to_string(), "a/**/c !a/b/**");
+
+        let json = serde_json::to_string(&expr).unwrap();
+        assert_eq!(serde_json::from_str::<PathExpr>(&json).unwrap(), expr);
+
+        assert!(PathExpr::try_from("!/sensors/debug/**").is_err());
+        assert!(PathExpr::try_from("/sensors/** !").is_err());
+    }
 }
@@ -0,0 +1,135 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use crate::{Change, ChangeKind, Data, Path, Timestamp, Value};
+use std::convert::TryFrom;
+use zenoh_util::core::{ZError, ZErrorKind, ZResult};
+use zenoh_util::zerror;
+
+/// A [`Path`]/[`Value`] with its [`Timestamp`] and [`ChangeKind`], that can be converted from and into
+/// a [`Change`] (received via [`Workspace::subscribe()`](crate::Workspace::subscribe)) or a [`Data`]
+/// (received via [`Workspace::get()`](crate::Workspace::get)).
+///
+/// This allows a generic code to process uniformly the results of queries and the live changes.
+///
+/// # Examples
+/// ```
+/// use zenoh::*;
+///
+/// fn display(sample: impl Into<Sample>) {
+///     let sample = sample.into();
+///     println!(">> {} for {} : {:?} at {}", sample.kind, sample.path, sample.value, sample.timestamp);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Sample {
+    /// the [`Path`] of the sample.
+    pub path: Path,
+    /// the [`Value`] of the sample. `None` if the kind is `DELETE`.
+    pub value: Option<Value>,
+    /// the [`Timestamp`] of the sample.
+    pub timestamp: Timestamp,
+    /// the kind of the sample (always `PUT` for a sample converted from a [`Data`]).
+    pub kind: ChangeKind,
+}
+
+impl From<Change> for Sample {
+    fn from(change: Change) -> Self {
+        Sample {
+            path: change.path,
+            value: change.value,
+            timestamp: change.timestamp,
+            kind: change.kind,
+        }
+    }
+}
+
+impl From<Data> for Sample {
+    fn from(data: Data) -> Self {
+        Sample {
+            path: data.path,
+            value: Some(data.value),
+            timestamp: data.timestamp,
+            kind: ChangeKind::Put,
+        }
+    }
+}
+
+impl From<Sample> for Change {
+    fn from(sample: Sample) -> Self {
+        Change::new(sample.path, sample.value, sample.timestamp, sample.kind)
+    }
+}
+
+/// A [`Data`] always has a [`Value`]: the conversion of a [`Sample`] without value (i.e. a `DELETE`) fails.
+impl TryFrom<Sample> for Data {
+    type Error = ZError;
+
+    fn try_from(sample: Sample) -> ZResult<Self> {
+        match sample.value {
+            Some(value) => Ok(Data {
+                path: sample.path,
+                value,
+                timestamp: sample.timestamp,
+                stale: false,
+            }),
+            None => zerror!(ZErrorKind::Other {
+                descr: format!(
+                    "Cannot convert a {} Sample without value for {} into a Data",
+                    sample.kind, sample.path
+                )
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TimestampId;
+    use std::time::Duration;
+
+    fn timestamp() -> Timestamp {
+        Timestamp::new(
+            Duration::from_secs(1).into(),
+            TimestampId::new(1, [0u8; TimestampId::MAX_SIZE]),
+        )
+    }
+
+    #[test]
+    fn sample_conversions() {
+        let data = Data {
+            path: Path::try_from("/a/b").unwrap(),
+            value: Value::Integer(1),
+            timestamp: timestamp(),
+            stale: false,
+        };
+        let sample = Sample::from(data);
+        assert_eq!(sample.kind, ChangeKind::Put);
+        let change = Change::from(sample.clone());
+        assert_eq!(change.path, sample.path);
+        assert!(matches!(change.value, Some(Value::Integer(1))));
+        assert!(change.checksum.is_none());
+        let data = Data::try_from(sample).unwrap();
+        assert!(matches!(data.value, Value::Integer(1)));
+
+        let delete = Sample::from(Change::new(
+            Path::try_from("/a/b").unwrap(),
+            None,
+            timestamp(),
+            ChangeKind::Delete,
+        ));
+        assert_eq!(delete.kind, ChangeKind::Delete);
+        assert!(Data::try_from(delete).is_err());
+    }
+}
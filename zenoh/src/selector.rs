@@ -11,18 +11,167 @@
 // Contributors:
 //   ADLINK zenoh team, <zenoh@adlink-labs.tech>
 //
-use crate::net::Query;
-use crate::{Path, PathExpr, Properties};
+use crate::net::{PeerId, Query};
+use crate::utils::{percent_decode, percent_encode};
+use crate::{Path, PathExpr, Properties, Timestamp, TimestampId, Value};
 use regex::Regex;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::convert::TryFrom;
 use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use zenoh_util::core::{ZError, ZErrorKind, ZResult};
-use zenoh_util::zerror;
+use zenoh_util::{zerror, zerror2};
 
 /// The "starttime" property key for time-range selection
 pub const PROP_STARTTIME: &str = "starttime";
 /// The "stoptime" property key for time-range selection
 pub const PROP_STOPTIME: &str = "stoptime";
+/// The "_increment" property key used by [`Workspace::increment()`](super::Workspace::increment)
+/// to request an atomic increment from a storage
+pub const PROP_INCREMENT: &str = "_increment";
+/// The "_append" property key used by [`Workspace::append()`](super::Workspace::append)
+/// to request an atomic append from a storage (see [`Selector::append()`])
+pub const PROP_APPEND: &str = "_append";
+/// The "_method" property key used to tell an eval if a `get` is a read or a computation
+/// (see [`GetMethod`])
+pub const PROP_METHOD: &str = "_method";
+/// The "_at" property key for a point-in-time selection (see [`Selector::at()`])
+pub const PROP_AT: &str = "_at";
+
+/// The "_time" property key for a time-range selection (see [`Selector::time_range()`])
+pub const PROP_TIME: &str = "_time";
+
+/// The "_accept" property key for the encodings accepted by the requester of a `get`
+/// (see [`Selector::accepted_encodings()`])
+pub const PROP_ACCEPT: &str = "_accept";
+
+/// The "_transform" property key for the transformations applied to the received values
+/// (see [`Transform`])
+pub const PROP_TRANSFORM: &str = "_transform";
+
+/// The "_limit" property key for the maximum number of values replied by a storage (see [`Selector::limit()`])
+pub const PROP_LIMIT: &str = "_limit";
+
+/// The "_after" property key for the path after which a storage replies its values (see [`Selector::after()`])
+pub const PROP_AFTER: &str = "_after";
+
+/// The "_target" property key for the id of the only responder accepted by a `get` (see [`Selector::target()`])
+pub const PROP_TARGET: &str = "_target";
+
+/// The method of a `get`, specified in its [`Selector`] with the `_method` property
+/// (e.g. `/demo/example/eval?(_method=compute)`).
+///
+/// This is advisory: it's up to the eval receiving the [`GetRequest`](super::GetRequest) to decide
+/// how to behave according to the method (e.g. replying a cached value for [`GetMethod::Get`]
+/// and performing a fresh computation for [`GetMethod::Compute`]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GetMethod {
+    /// An idempotent read (`_method=get`). This is the default if the property is not specified.
+    Get,
+    /// A computation which may have side effects (`_method=compute`).
+    Compute,
+}
+
+impl fmt::Display for GetMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GetMethod::Get => write!(f, "get"),
+            GetMethod::Compute => write!(f, "compute"),
+        }
+    }
+}
+
+impl FromStr for GetMethod {
+    type Err = ZError;
+    fn from_str(s: &str) -> ZResult<GetMethod> {
+        match s {
+            "get" => Ok(GetMethod::Get),
+            "compute" => Ok(GetMethod::Compute),
+            _ => zerror!(ZErrorKind::Other {
+                descr: format!(
+                    "Invalid value for '{}' property: '{}' (expected 'get' or 'compute')",
+                    PROP_METHOD, s
+                )
+            }),
+        }
+    }
+}
+
+/// A transformation of the numeric values, specified in a [`Selector`] with the `_transform` property
+/// (e.g. `/demo/sensors/**?(_transform=scale:0.1|offset:-273.15)`).
+///
+/// Several transformations can be chained with `|`, and are applied in order.  
+/// The transformations are applied by the subscriber or the querier on reception of the values
+/// (i.e. via [`Workspace::subscribe()`](super::Workspace::subscribe) or
+/// [`Workspace::get()`](super::Workspace::get)), and not by the publishers nor the storages.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Transform {
+    /// Multiplies the value by a factor (`scale:<factor>`).
+    Scale(f64),
+    /// Adds an offset to the value (`offset:<offset>`).
+    Offset(f64),
+}
+
+impl Transform {
+    /// Applies this transformation to a [`Value::Integer`] or a [`Value::Float`], returning a [`Value::Float`].
+    /// Any other [`Value`] is returned unchanged.
+    pub fn apply(&self, value: Value) -> Value {
+        let f = match value {
+            Value::Integer(i) => i as f64,
+            Value::Float(f) => f,
+            _ => return value,
+        };
+        match self {
+            Transform::Scale(factor) => Value::Float(f * factor),
+            Transform::Offset(offset) => Value::Float(f + offset),
+        }
+    }
+
+    /// Applies a chain of transformations to a [`Value`], in order.
+    pub fn apply_all(transforms: &[Transform], value: Value) -> Value {
+        transforms.iter().fold(value, |v, t| t.apply(v))
+    }
+}
+
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Transform::Scale(factor) => write!(f, "scale:{}", factor),
+            Transform::Offset(offset) => write!(f, "offset:{}", offset),
+        }
+    }
+}
+
+impl FromStr for Transform {
+    type Err = ZError;
+    fn from_str(s: &str) -> ZResult<Transform> {
+        let (name, arg) = match s.find(':') {
+            Some(i) => (&s[..i], &s[i + 1..]),
+            None => (s, ""),
+        };
+        let parse_arg = || {
+            arg.parse::<f64>().map_err(|_| {
+                zerror2!(ZErrorKind::Other {
+                    descr: format!(
+                        "Invalid argument for '{}' transform in '{}' property: '{}' (expected a number)",
+                        name, PROP_TRANSFORM, arg
+                    )
+                })
+            })
+        };
+        match name {
+            "scale" => Ok(Transform::Scale(parse_arg()?)),
+            "offset" => Ok(Transform::Offset(parse_arg()?)),
+            _ => zerror!(ZErrorKind::Other {
+                descr: format!(
+                    "Unknown transform in '{}' property: '{}' (expected 'scale:<factor>' or 'offset:<offset>')",
+                    PROP_TRANSFORM, s
+                )
+            }),
+        }
+    }
+}
 
 #[derive(Clone, Debug, PartialEq)]
 /// A zenoh Selector is the conjunction of a [path expression](super::PathExpr) identifying a set
@@ -132,10 +281,328 @@ impl Selector {
         self.path_expr.matches(path)
     }
 
+    /// Returns the [`GetMethod`] specified by the `_method` property of this Selector,
+    /// or [`GetMethod::Get`] if not specified.
+    /// Returns an error if the property has an invalid value.
+    pub fn method(&self) -> ZResult<GetMethod> {
+        self.properties
+            .get(PROP_METHOD)
+            .map_or(Ok(GetMethod::Get), |m| m.parse())
+    }
+
+    /// Returns the chain of [`Transform`]s specified by the `_transform` property of this Selector,
+    /// or an empty Vec if not specified.
+    /// Returns an error if the property has an invalid or unknown transformation.
+    pub fn transforms(&self) -> ZResult<Vec<Transform>> {
+        self.properties
+            .get(PROP_TRANSFORM)
+            .map_or(Ok(vec![]), |t| t.split('|').map(str::parse).collect())
+    }
+
+    /// Returns the point in time specified by the `_at` property of this Selector, if any
+    /// (e.g. `/demo/example/**?(_at=2024-01-01T00:00:00Z)`).  
+    /// Returns an error if the property has an invalid value.
+    ///
+    /// Such selector requests the values as they were at this time. A storage keeping an history of the values
+    /// replies the last value before (or at) this time. A storage without history replies its latest value,
+    /// logging a warning if it's more recent than this time.
+    ///
+    /// The accepted time formats are:
+    ///  * an RFC 3339 date and time in UTC, with an optional fractional part of seconds
+    ///    (e.g. `2024-01-01T00:00:00Z` or `2024-01-01T00:00:00.250Z`). The `T` can be replaced with a space
+    ///    and the trailing `Z` can be omitted.
+    ///  * a number of seconds since the UNIX epoch, with an optional fractional part (e.g. `1704067200.25`).
+    pub fn at(&self) -> ZResult<Option<Timestamp>> {
+        self.properties
+            .get(PROP_AT)
+            .map(|t| parse_time(t, PROP_AT))
+            .transpose()
+    }
+
+    /// Returns the encodings specified by the `_accept` property of this Selector, in the order of preference
+    /// of the requester (e.g. `["application/json", "text/plain"]` for
+    /// `/demo/example/eval?(_accept=application/json,text/plain)`), or an empty Vec if not specified.  
+    /// An encoding can be a wildcard: `*/*` for any encoding, or `<type>/*` for any encoding of a type
+    /// (e.g. `text/*`).
+    ///
+    /// This is advisory: it's up to the eval receiving the [`GetRequest`](super::GetRequest) to choose the encoding
+    /// of its replies accordingly (see [`GetRequest::best_match()`](super::GetRequest::best_match)).
+    pub fn accepted_encodings(&self) -> Vec<String> {
+        self.properties.get(PROP_ACCEPT).map_or_else(Vec::new, |a| {
+            a.split(',')
+                .map(|e| e.trim().to_ascii_lowercase())
+                .filter(|e| !e.is_empty())
+                .collect()
+        })
+    }
+
+    /// Returns the [`TimeRange`] specified by the `_time` property of this Selector, if any
+    /// (e.g. `/demo/example/**?(_time=[now()-5m..now()])`).  
+    /// Returns an error if the property has an invalid value.
+    ///
+    /// Such selector requests all the values of the matching paths between the start and the stop times
+    /// (both included), and not only the latest ones. A storage keeping an history of the values replies all
+    /// of them, ordered by timestamp. A storage without history replies its latest value,
+    /// logging a warning if it's out of the range.  
+    /// The replies of a `get` with such selector are not consolidated (see [`Selector::has_time_range()`]).
+    ///
+    /// The range has the form `[start..stop]`, where `start` and `stop` are optional (e.g. `[..stop]`
+    /// for all the values until `stop`). Each of them is either:
+    ///  * a time in one of the formats accepted by [`Selector::at()`] (e.g. `2024-01-01T00:00:00Z`).
+    ///  * `now()` optionally followed by `-` or `+` and a duration (e.g. `now()-5m` or `now()-1h30m`),
+    ///    relative to the time the selector is evaluated (i.e. by the storage).
+    pub fn time_range(&self) -> ZResult<Option<TimeRange>> {
+        self.properties
+            .get(PROP_TIME)
+            .map(|r| r.parse())
+            .transpose()
+    }
+
+    /// Returns the id of the only responder (i.e. a zenoh instance, see [`Zenoh::id()`](crate::Zenoh::id))
+    /// specified by the `_target` property of this Selector, if any (e.g. `/demo/example/**?(_target=A0B1C2D3)`).  
+    /// Returns an error if the property is not a valid id (an hexadecimal string of at most 16 bytes).
+    ///
+    /// With such selector, [`Workspace::get()`](crate::Workspace::get) only accepts the replies from the
+    /// targeted responder (e.g. to diagnose a divergence between replicated storages). The query is still
+    /// routed to all the matching queryables, but the replies from the other responders are dropped.  
+    /// If the targeted responder is unreachable (or has no matching queryable), the `get` completes
+    /// without any [`Data`](crate::Data), as for a selection without any value.
+    pub fn target(&self) -> ZResult<Option<PeerId>> {
+        self.properties
+            .get(PROP_TARGET)
+            .map(|t| {
+                t.parse().map_err(|_| {
+                    zerror2!(ZErrorKind::Other {
+                        descr: format!(
+                            "Invalid value for '{}' property: '{}' (expected an hexadecimal id)",
+                            PROP_TARGET, t
+                        )
+                    })
+                })
+            })
+            .transpose()
+    }
+
+    /// Returns the maximum number of values to be replied by each storage specified by the `_limit` property
+    /// of this Selector, if any.  
+    /// Returns an error if the property is not a valid unsigned integer.
+    ///
+    /// With such property, a storage replies the matching values with the smallest paths (in lexicographic
+    /// order), up to the limit. Combined with the `_after` property (see [`Selector::after()`]), this allows
+    /// to fetch the values page per page, as does [`Workspace::get_paged()`](crate::Workspace::get_paged).
+    pub fn limit(&self) -> ZResult<Option<usize>> {
+        self.properties
+            .get(PROP_LIMIT)
+            .map(|l| {
+                l.parse().map_err(|_| {
+                    zerror2!(ZErrorKind::Other {
+                        descr: format!(
+                            "Invalid value for '{}' property: '{}' (expected an unsigned integer)",
+                            PROP_LIMIT, l
+                        )
+                    })
+                })
+            })
+            .transpose()
+    }
+
+    /// Returns the path specified by the `_after` property of this Selector, if any.  
+    /// Returns an error if the property is not a valid [`Path`].
+    ///
+    /// With such property, a storage only replies the matching values with a path greater than this path
+    /// (in lexicographic order).
+    pub fn after(&self) -> ZResult<Option<Path>> {
+        self.properties
+            .get(PROP_AFTER)
+            .map(|p| Path::try_from(p.as_str()))
+            .transpose()
+    }
+
+    /// Returns the element to be appended by a storage specified by the `_append` property of this Selector,
+    /// if any (see [`Workspace::append()`](crate::Workspace::append)), as a [`Value::Json`](crate::Value::Json).  
+    /// The property is the JSON text of the element encoded in URL-safe base64 without padding, as the JSON text
+    /// may contain the separators of the properties.
+    /// Returns an error if the property is not valid base64 or its content is not valid JSON.
+    pub fn append(&self) -> ZResult<Option<crate::Value>> {
+        self.properties
+            .get(PROP_APPEND)
+            .map(|a| {
+                let invalid = || {
+                    zerror2!(ZErrorKind::Other {
+                        descr: format!(
+                            "Invalid value for '{}' property: '{}' (expected a base64 encoded JSON)",
+                            PROP_APPEND, a
+                        )
+                    })
+                };
+                let json = base64::decode_config(a, base64::URL_SAFE_NO_PAD)
+                    .ok()
+                    .and_then(|bytes| serde_json::from_slice::<serde_json::Value>(&bytes).ok())
+                    .ok_or_else(invalid)?;
+                Ok(crate::Value::Json(json.to_string()))
+            })
+            .transpose()
+    }
+
+    // Returns a copy of this Selector with the property `key` set to `value`.
+    pub(crate) fn with_property(&self, key: &str, value: &str) -> Selector {
+        let mut properties = self.properties.clone();
+        properties.insert(key.to_string(), value.to_string());
+        let predicate = format!(
+            "?{}({}){}",
+            self.filter.as_deref().unwrap_or(""),
+            properties,
+            self.fragment
+                .as_ref()
+                .map_or_else(String::new, |f| format!("[{}]", f))
+        );
+        // the predicate is built from the valid parts of this Selector
+        Selector::new(&self.path_expr.to_string(), &predicate).unwrap()
+    }
+
     /// Returns true if the Selector specifies a time-range in its properties
-    /// (i.e. using `"starttime"` or `"stoptime"`)
+    /// (i.e. using `"_time"`, `"starttime"` or `"stoptime"`)
     pub fn has_time_range(&self) -> bool {
-        self.properties.contains_key(PROP_STARTTIME) || self.properties.contains_key(PROP_STOPTIME)
+        self.properties.contains_key(PROP_TIME)
+            || self.properties.contains_key(PROP_STARTTIME)
+            || self.properties.contains_key(PROP_STOPTIME)
+    }
+}
+
+/// A range of time specified by the `_time` property of a [`Selector`] (see [`Selector::time_range()`]).  
+/// The relative times (e.g. `now()-5m`) are resolved when the range is parsed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimeRange {
+    /// the start of the range (included), or `None` if unbounded.
+    pub start: Option<Timestamp>,
+    /// the stop of the range (included), or `None` if unbounded.
+    pub stop: Option<Timestamp>,
+}
+
+impl TimeRange {
+    /// Returns true if `timestamp` is within this range.
+    /// Only the times are compared (not the ids of the timestamps).
+    pub fn contains(&self, timestamp: &Timestamp) -> bool {
+        self.start
+            .as_ref()
+            .map_or(true, |start| timestamp.get_time() >= start.get_time())
+            && self
+                .stop
+                .as_ref()
+                .map_or(true, |stop| timestamp.get_time() <= stop.get_time())
+    }
+}
+
+impl FromStr for TimeRange {
+    type Err = ZError;
+
+    fn from_str(s: &str) -> ZResult<TimeRange> {
+        let invalid = || {
+            zerror2!(ZErrorKind::Other {
+                descr: format!(
+                    "Invalid value for '{}' property: '{}' (expected '[start..stop]')",
+                    PROP_TIME, s
+                )
+            })
+        };
+        let (start, stop) = s
+            .strip_prefix('[')
+            .and_then(|s| s.strip_suffix(']'))
+            .and_then(|s| s.split_once(".."))
+            .ok_or_else(invalid)?;
+        let bound = |t: &str| match t.trim() {
+            "" => Ok(None),
+            t => parse_time(t, PROP_TIME).map(Some),
+        };
+        Ok(TimeRange {
+            start: bound(start)?,
+            stop: bound(stop)?,
+        })
+    }
+}
+
+// Parses a time as an RFC 3339 date and time, as a number of seconds since the UNIX epoch,
+// or as `now()` optionally followed by `-` or `+` and a duration.
+fn parse_time(s: &str, property: &str) -> ZResult<Timestamp> {
+    if let Some(relative) = s.strip_prefix("now()") {
+        return parse_relative_time(relative, property, s);
+    }
+    let since_epoch = match s.parse::<f64>() {
+        Ok(secs) if secs.is_finite() && secs >= 0.0 => Duration::from_secs_f64(secs),
+        _ => humantime::parse_rfc3339_weak(s)
+            .ok()
+            .and_then(|t: SystemTime| t.duration_since(UNIX_EPOCH).ok())
+            .ok_or_else(|| {
+                zerror2!(ZErrorKind::Other {
+                    descr: format!(
+                        "Invalid value for '{}' property: '{}' (expected an RFC 3339 time or a number of seconds since the UNIX epoch)",
+                        property, s
+                    )
+                })
+            })?,
+    };
+    Ok(time_to_timestamp(since_epoch))
+}
+
+// Parses the `-<duration>` or `+<duration>` following `now()` (or nothing), `s` being the whole time
+fn parse_relative_time(relative: &str, property: &str, s: &str) -> ZResult<Timestamp> {
+    let now = SystemTime::now();
+    let time = match relative.trim() {
+        "" => Some(now),
+        r => {
+            let mut chars = r.chars();
+            match (
+                chars.next(),
+                humantime::parse_duration(chars.as_str().trim()),
+            ) {
+                (Some('-'), Ok(d)) => now.checked_sub(d),
+                (Some('+'), Ok(d)) => now.checked_add(d),
+                _ => None,
+            }
+        }
+    };
+    time.and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(time_to_timestamp)
+        .ok_or_else(|| {
+            zerror2!(ZErrorKind::Other {
+                descr: format!(
+                    "Invalid value for '{}' property: '{}' (expected 'now()' optionally followed by '-' or '+' and a duration, e.g. 'now()-5m')",
+                    property, s
+                )
+            })
+        })
+}
+
+fn time_to_timestamp(since_epoch: Duration) -> Timestamp {
+    Timestamp::new(
+        since_epoch.into(),
+        TimestampId::new(1, [0u8; TimestampId::MAX_SIZE]),
+    )
+}
+
+impl Selector {
+    /// Converts this Selector into a string usable in a URL, percent-encoding all the characters of its
+    /// path expression other than `'/'`, `'*'` and the URL unreserved characters, and all the characters
+    /// of its predicate (after the `'?'`) other than `'/'`, `'*'`, `'('`, `')'`, `'='`, `';'`, `','`, `':'`
+    /// and the URL unreserved characters (e.g. `"/a b/**?(k=v w)"` gives `"/a%20b/**?(k=v%20w)"`).  
+    /// This is the reverse of [`Selector::from_url_encoded()`].
+    pub fn to_url_encoded(&self) -> String {
+        let mut url = percent_encode(&self.path_expr.to_string(), b"/*");
+        if let Some(predicate) = self.predicate.strip_prefix('?') {
+            url.push('?');
+            url.push_str(&percent_encode(predicate, b"/*()=;,:"));
+        }
+        url
+    }
+
+    /// Creates a Selector from a percent-encoded string of a URL
+    /// (e.g. `"/a%20b/**?(k=v%20w)"` gives `"/a b/**?(k=v w)"`).  
+    /// Returns an error if the decoded string is not a valid Selector.
+    pub fn from_url_encoded(s: &str) -> ZResult<Selector> {
+        match percent_decode(s) {
+            Some(selector) => Selector::try_from(selector.as_str()),
+            None => zerror!(ZErrorKind::InvalidSelector { selector: s.into() }),
+        }
     }
 }
 
@@ -164,6 +631,20 @@ impl TryFrom<String> for Selector {
     }
 }
 
+impl Serialize for Selector {
+    /// Serializes the Selector as its string form.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Selector {
+    /// Deserializes the Selector from its string form, failing if this string is not a valid Selector.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Selector::try_from(String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
 impl TryFrom<&Query> for Selector {
     type Error = ZError;
     fn try_from(q: &Query) -> Result<Self, Self::Error> {
@@ -179,6 +660,38 @@ pub fn selector(selector: impl AsRef<str>) -> Selector {
     Selector::try_from(selector.as_ref()).unwrap()
 }
 
+/// Creates a [`Selector`] from a path expression string literal, checking its validity at compile time.
+///
+/// Unlike the [`selector()`] function, an invalid path expression is a compilation error rather than a panic
+/// at runtime. Only string literals (or constant `&str` expressions) consisting of a [`PathExpr`] are supported:
+/// it must not be empty and must not contain the forbidden characters `'?'`, `'#'`, `'['`, `']'`.
+/// Hence a selector with a predicate, a filter, properties or a fragment must be created with the [`selector()`]
+/// function or with [`Selector::try_from()`].
+///
+/// # Examples
+/// ```
+/// use zenoh::*;
+///
+/// let selector = selector!("/demo/example/**");
+/// assert_eq!(selector.path_expr.as_str(), "/demo/example/**");
+/// ```
+///
+/// ```compile_fail
+/// use zenoh::*;
+///
+/// let selector = selector!("/demo/example/**?(starttime=now()-1h)");
+/// ```
+#[macro_export]
+macro_rules! selector {
+    ($p:expr) => {{
+        const _: () = assert!(
+            $crate::PathExpr::is_valid_const($p),
+            "invalid zenoh path expression: must not be empty and must not contain '?', '#', '[' or ']'"
+        );
+        $crate::Selector::from($crate::PathExpr::from_validated($p))
+    }};
+}
+
 impl From<&PathExpr> for Selector {
     fn from(from: &PathExpr) -> Self {
         Self {
@@ -232,6 +745,230 @@ mod tests {
     use super::*;
     use std::convert::TryInto;
 
+    #[test]
+    fn selector_at() {
+        assert!(Selector::try_from("/a/b").unwrap().at().unwrap().is_none());
+        let expected: uhlc::NTP64 = Duration::from_secs(1_704_067_200).into();
+        for t in &["2024-01-01T00:00:00Z", "2024-01-01 00:00:00", "1704067200"] {
+            let at = Selector::try_from(format!("/a/b?(_at={})", t).as_str())
+                .unwrap()
+                .at()
+                .unwrap()
+                .unwrap();
+            assert_eq!(at.get_time(), &expected);
+        }
+        let at = Selector::try_from("/a/b?(_at=2024-01-01T00:00:00.5Z)")
+            .unwrap()
+            .at()
+            .unwrap()
+            .unwrap();
+        let expected: uhlc::NTP64 = Duration::from_millis(1_704_067_200_500).into();
+        assert_eq!(at.get_time(), &expected);
+        assert!(Selector::try_from("/a/b?(_at=yesterday)")
+            .unwrap()
+            .at()
+            .is_err());
+    }
+
+    #[test]
+    fn selector_time_range() {
+        assert!(Selector::try_from("/a/b")
+            .unwrap()
+            .time_range()
+            .unwrap()
+            .is_none());
+        let range = Selector::try_from("/a/b?(_time=[2024-01-01T00:00:00Z..1704067260])")
+            .unwrap()
+            .time_range()
+            .unwrap()
+            .unwrap();
+        let start: uhlc::NTP64 = Duration::from_secs(1_704_067_200).into();
+        let stop: uhlc::NTP64 = Duration::from_secs(1_704_067_260).into();
+        assert_eq!(range.start.as_ref().unwrap().get_time(), &start);
+        assert_eq!(range.stop.as_ref().unwrap().get_time(), &stop);
+        assert!(range.contains(&time_to_timestamp(Duration::from_secs(1_704_067_230))));
+        assert!(range.contains(&time_to_timestamp(Duration::from_secs(1_704_067_260))));
+        assert!(!range.contains(&time_to_timestamp(Duration::from_secs(1_704_067_261))));
+
+        // relative and unbounded
+        let range = Selector::try_from("/a/b?(_time=[now()-5m..])")
+            .unwrap()
+            .time_range()
+            .unwrap()
+            .unwrap();
+        assert!(range.stop.is_none());
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        assert!(range.contains(&time_to_timestamp(now - Duration::from_secs(60))));
+        assert!(!range.contains(&time_to_timestamp(now - Duration::from_secs(600))));
+        assert!(range.contains(&time_to_timestamp(now + Duration::from_secs(600))));
+        let range = Selector::try_from("/a/b?(_time=[..now()+1h])")
+            .unwrap()
+            .time_range()
+            .unwrap()
+            .unwrap();
+        assert!(range.start.is_none());
+        assert!(range.contains(&time_to_timestamp(now + Duration::from_secs(1800))));
+        assert!(Selector::try_from("/a/b?(_time=[now()-5m..now()])")
+            .unwrap()
+            .has_time_range());
+
+        for r in &[
+            "now()-5m",
+            "[now()-5m]",
+            "[yesterday..]",
+            "[now()*5m..]",
+            "[now()-5x..]",
+        ] {
+            assert!(
+                Selector::try_from(format!("/a/b?(_time={})", r).as_str())
+                    .unwrap()
+                    .time_range()
+                    .is_err(),
+                "{}",
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn selector_accepted_encodings() {
+        assert!(Selector::try_from("/a/b")
+            .unwrap()
+            .accepted_encodings()
+            .is_empty());
+        assert_eq!(
+            Selector::try_from("/a/b?(_accept=application/json, Text/Plain,;name=Bob)")
+                .unwrap()
+                .accepted_encodings(),
+            vec!["application/json", "text/plain"]
+        );
+    }
+
+    #[test]
+    fn selector_url_encoded() {
+        let selector = Selector::try_from("/a b/**?(k=v w)").unwrap();
+        assert_eq!(selector.to_url_encoded(), "/a%20b/**?(k=v%20w)");
+        for s in &[
+            "/a b/**",
+            "/a%b/*/c?x>1(k=v#w;k2=?&)[frag]",
+            "/é/c?(starttime=now()-1h)",
+        ] {
+            let selector = Selector::try_from(*s).unwrap();
+            assert_eq!(
+                Selector::from_url_encoded(&selector.to_url_encoded()).unwrap(),
+                selector
+            );
+        }
+        assert!(Selector::from_url_encoded("/a/%zz").is_err());
+    }
+
+    #[test]
+    fn selector_transforms() {
+        assert!(Selector::try_from("/a/b")
+            .unwrap()
+            .transforms()
+            .unwrap()
+            .is_empty());
+        let transforms = Selector::try_from("/a/b?(_transform=scale:0.1|offset:-3)")
+            .unwrap()
+            .transforms()
+            .unwrap();
+        assert_eq!(
+            transforms,
+            vec![Transform::Scale(0.1), Transform::Offset(-3.0)]
+        );
+        assert!(matches!(
+            Transform::apply_all(&transforms, Value::Integer(250)),
+            Value::Float(f) if (f - 22.0).abs() < 1e-9
+        ));
+        assert!(matches!(
+            Transform::apply_all(&transforms, Value::StringUtf8("x".into())),
+            Value::StringUtf8(s) if s == "x"
+        ));
+        for t in &["downsample:10", "scale", "scale:abc", "scale:1|"] {
+            assert!(
+                Selector::try_from(format!("/a/b?(_transform={})", t).as_str())
+                    .unwrap()
+                    .transforms()
+                    .is_err(),
+                "{}",
+                t
+            );
+        }
+    }
+
+    #[test]
+    fn selector_target() {
+        assert_eq!(Selector::try_from("/a/b").unwrap().target().unwrap(), None);
+        let target = Selector::try_from("/a/b?(_target=a0b1c2d3)")
+            .unwrap()
+            .target()
+            .unwrap()
+            .unwrap();
+        assert_eq!(target.to_string(), "A0B1C2D3");
+        assert!(Selector::try_from("/a/b?(_target=xyz)")
+            .unwrap()
+            .target()
+            .is_err());
+    }
+
+    #[test]
+    fn selector_paging() {
+        let selector = Selector::try_from("/a/**?x>1(_limit=10)").unwrap();
+        assert_eq!(selector.limit().unwrap(), Some(10));
+        assert_eq!(selector.after().unwrap(), None);
+        let next = selector.with_property(PROP_AFTER, "/a/b/c");
+        assert_eq!(next.path_expr, selector.path_expr);
+        assert_eq!(next.filter.as_deref(), Some("x>1"));
+        assert_eq!(next.limit().unwrap(), Some(10));
+        assert_eq!(
+            next.after().unwrap(),
+            Some(Path::try_from("/a/b/c").unwrap())
+        );
+        assert!(Selector::try_from("/a/**?(_limit=-1)")
+            .unwrap()
+            .limit()
+            .is_err());
+    }
+
+    #[test]
+    fn selector_macro() {
+        assert_eq!(
+            selector!("/a//*/**"),
+            Selector::try_from("/a/*/**").unwrap()
+        );
+        assert!(PathExpr::is_valid_const("/a/*/**"));
+        assert!(!PathExpr::is_valid_const("/a/b?x"));
+    }
+
+    #[test]
+    fn selector_method() {
+        assert_eq!(
+            Selector::try_from("/a/b").unwrap().method().unwrap(),
+            GetMethod::Get
+        );
+        assert_eq!(
+            Selector::try_from("/a/b?(_method=compute)")
+                .unwrap()
+                .method()
+                .unwrap(),
+            GetMethod::Compute
+        );
+        assert!(Selector::try_from("/a/b?(_method=put)")
+            .unwrap()
+            .method()
+            .is_err());
+    }
+
+    #[test]
+    fn selector_serde() {
+        let selector = Selector::try_from("/a/**?x>1(p=v)[f]").unwrap();
+        let json = serde_json::to_string(&selector).unwrap();
+        assert_eq!(json, r#""/a/**?x>1(p=v)[f]""#);
+        assert_eq!(serde_json::from_str::<Selector>(&json).unwrap(), selector);
+        assert!(serde_json::from_str::<Selector>(r#""/a#b""#).is_err());
+    }
+
     #[test]
     fn test_selector() {
         assert_eq!(
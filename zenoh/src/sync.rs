@@ -0,0 +1,167 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+
+//! A blocking API over the zenoh API, for the callers which are not async
+//! (e.g. a sensor poller in a synchronous control loop).
+//!
+//! A [`SyncWorkspace`] wraps a [`Workspace`]: its operations block until completion, and its
+//! subscriptions and evals are [`Iterator`]s which `next()` blocks until the next item.
+//! The operations of [`Zenoh`] itself can be blocked on with [`ZFuture::wait()`](crate::ZFuture::wait)
+//! (e.g. `Zenoh::new(config).wait()`), without any async runtime nor the `futures` crate.
+//!
+//! # Examples
+//! ```no_run
+//! use zenoh::*;
+//! use zenoh::sync::SyncWorkspace;
+//! use std::convert::TryInto;
+//!
+//! let zenoh = Zenoh::new(net::config::default()).wait().unwrap();
+//! let workspace = SyncWorkspace::new(&zenoh, Some("/demo/sensors".try_into().unwrap())).unwrap();
+//! loop {
+//!     let temperature = 21.5; // e.g. read from the sensor
+//!     workspace.put(&"temperature".try_into().unwrap(), temperature.into()).unwrap();
+//!     std::thread::sleep(std::time::Duration::from_secs(1));
+//! }
+//! ```
+use crate::net::{Receiver, ZFuture};
+use crate::{
+    Change, ChangeReceiver, Data, DataReceiver, GetRequest, GetRequestStream, Path, PathExpr,
+    Selector, Value, Workspace, ZResult, Zenoh,
+};
+use async_std::task;
+use futures_lite::StreamExt;
+
+/// A [`Workspace`] which operations are blocking (see the [`sync`](self) module).
+pub struct SyncWorkspace<'a> {
+    workspace: Workspace<'a>,
+}
+
+impl<'a> SyncWorkspace<'a> {
+    /// Creates a SyncWorkspace with an optional [`Path`] as prefix (see [`Zenoh::workspace()`]).
+    pub fn new(zenoh: &'a Zenoh, prefix: Option<Path>) -> ZResult<SyncWorkspace<'a>> {
+        zenoh.workspace(prefix).wait().map(SyncWorkspace::from)
+    }
+
+    /// Returns the wrapped [`Workspace`], for the operations not provided by this SyncWorkspace.
+    /// Each of them can be blocked on with [`ZFuture::wait()`](crate::ZFuture::wait).
+    pub fn workspace(&self) -> &Workspace<'a> {
+        &self.workspace
+    }
+
+    /// Put a [`Path`]/[`Value`] into zenoh, blocking until it's sent (see [`Workspace::put()`]).
+    pub fn put(&self, path: &Path, value: Value) -> ZResult<()> {
+        self.workspace.put(path, value).wait()
+    }
+
+    /// Delete a [`Path`] and its [`Value`] from zenoh, blocking until it's sent (see [`Workspace::delete()`]).
+    pub fn delete(&self, path: &Path) -> ZResult<()> {
+        self.workspace.delete(path).wait()
+    }
+
+    /// Get a selection of [`Path`]/[`Value`] from zenoh (see [`Workspace::get()`]).
+    /// The selection is returned as a [`SyncDataIter`] which `next()` blocks until the next [`Data`]
+    /// is received, or returns `None` once the query is complete.
+    pub fn get(&self, selector: &Selector) -> ZResult<SyncDataIter> {
+        self.workspace
+            .get(selector)
+            .wait()
+            .map(|receiver| SyncDataIter { receiver })
+    }
+
+    /// Subscribe to changes for a selection of [`Path`]/[`Value`] (see [`Workspace::subscribe()`]).
+    /// The changes are returned as a [`SyncChangeIter`] which `next()` blocks until the next [`Change`].
+    pub fn subscribe(&self, selector: &Selector) -> ZResult<SyncChangeIter<'_>> {
+        self.workspace
+            .subscribe(selector)
+            .wait()
+            .map(|receiver| SyncChangeIter { receiver })
+    }
+
+    /// Registers an evaluation function under the provided [`PathExpr`] (see [`Workspace::register_eval()`]).
+    /// The get requests are returned as a [`SyncGetRequestIter`] which `next()` blocks until the next [`GetRequest`],
+    /// which can be replied to with [`GetRequest::reply()`].
+    pub fn register_eval(&self, path_expr: &PathExpr) -> ZResult<SyncGetRequestIter<'_>> {
+        self.workspace
+            .register_eval(path_expr)
+            .wait()
+            .map(|stream| SyncGetRequestIter { stream })
+    }
+
+    /// Closes this SyncWorkspace (see [`Workspace::close()`]).
+    pub fn close(&self) -> ZResult<()> {
+        self.workspace.close().wait()
+    }
+}
+
+impl<'a> From<Workspace<'a>> for SyncWorkspace<'a> {
+    fn from(workspace: Workspace<'a>) -> Self {
+        SyncWorkspace { workspace }
+    }
+}
+
+/// An [`Iterator`] of the [`Data`] returned by [`SyncWorkspace::get()`], which `next()` blocks until
+/// the next [`Data`] is received. It ends once the query is complete.
+pub struct SyncDataIter {
+    receiver: DataReceiver,
+}
+
+impl Iterator for SyncDataIter {
+    type Item = Data;
+
+    fn next(&mut self) -> Option<Data> {
+        self.receiver.recv().ok()
+    }
+}
+
+/// An [`Iterator`] of the [`Change`] returned by [`SyncWorkspace::subscribe()`], which `next()` blocks until
+/// the next [`Change`] is received. It ends only once closed, or if the session is closed.
+pub struct SyncChangeIter<'a> {
+    receiver: ChangeReceiver<'a>,
+}
+
+impl SyncChangeIter<'_> {
+    /// Closes the subscription.
+    pub fn close(self) -> ZResult<()> {
+        self.receiver.close().wait()
+    }
+}
+
+impl Iterator for SyncChangeIter<'_> {
+    type Item = Change;
+
+    fn next(&mut self) -> Option<Change> {
+        self.receiver.recv().ok()
+    }
+}
+
+/// An [`Iterator`] of the [`GetRequest`] returned by [`SyncWorkspace::register_eval()`], which `next()`
+/// blocks until the next [`GetRequest`] is received. It ends only once closed, or if the session is closed.
+pub struct SyncGetRequestIter<'a> {
+    stream: GetRequestStream<'a>,
+}
+
+impl SyncGetRequestIter<'_> {
+    /// Closes the iterator and unregister the evaluation function.
+    pub fn close(self) -> ZResult<()> {
+        self.stream.close().wait()
+    }
+}
+
+impl Iterator for SyncGetRequestIter<'_> {
+    type Item = GetRequest;
+
+    fn next(&mut self) -> Option<GetRequest> {
+        task::block_on(self.stream.next())
+    }
+}
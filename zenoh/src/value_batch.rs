@@ -0,0 +1,236 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use crate::Value;
+use arrow::array::{ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::Arc;
+use zenoh_util::core::{ZError, ZErrorKind, ZResult};
+use zenoh_util::{zerror, zerror2};
+
+/// The name of the column used for the scalar [`Value`]s (i.e. not a Json object nor Properties).
+pub const VALUE_COLUMN: &str = "value";
+
+/// Conversion of a batch of [`Value`]s into an [Arrow](https://arrow.apache.org) [`RecordBatch`]
+/// (requires the `arrow` feature).
+///
+/// Each [`Value`] becomes a row of the batch:
+///  * a [`Value::Json`] object has a column per field. Only the fields with a null, boolean, number or string
+///    value are supported (nested objects and arrays are not).
+///  * a [`Value::Properties`] has a `Utf8` column per property.
+///  * a [`Value::Integer`], [`Value::Float`], [`Value::StringUtf8`] or a [`Value::Json`] scalar has a single
+///    column named [`VALUE_COLUMN`].
+///
+/// The schema is inferred from all the rows: the columns are in the order of their first appearance and are
+/// all nullable (a field missing in a row is null). A column with integers and floats is a `Float64` column.
+/// Any other type conflict between the rows for a same column is an error.
+pub struct ValueBatch;
+
+impl ValueBatch {
+    /// Converts `values` into a [`RecordBatch`], inferring its schema.
+    ///
+    /// # Examples
+    /// ```
+    /// use zenoh::*;
+    ///
+    /// let values = vec![
+    ///     Value::Json(r#"{"sensor": "s1", "temp": 21}"#.to_string()),
+    ///     Value::Json(r#"{"sensor": "s2", "temp": 20.5, "ok": true}"#.to_string()),
+    /// ];
+    /// let batch = ValueBatch::try_from_values(&values).unwrap();
+    /// assert_eq!(batch.num_rows(), 2);
+    /// assert_eq!(batch.num_columns(), 3);
+    ///
+    /// // "temp" can't be both a number and a string
+    /// let values = vec![
+    ///     Value::Json(r#"{"temp": 21}"#.to_string()),
+    ///     Value::Json(r#"{"temp": "hot"}"#.to_string()),
+    /// ];
+    /// assert!(ValueBatch::try_from_values(&values).is_err());
+    /// ```
+    pub fn try_from_values(values: &[Value]) -> ZResult<RecordBatch> {
+        let mut columns: Vec<Column> = vec![];
+        let mut indexes: HashMap<String, usize> = HashMap::new();
+        for (row, value) in values.iter().enumerate() {
+            for (name, field) in to_fields(row, value)? {
+                let i = *indexes.entry(name.clone()).or_insert_with(|| {
+                    columns.push(Column::new(name, values.len()));
+                    columns.len() - 1
+                });
+                columns[i].set(row, field)?;
+            }
+        }
+
+        let fields = columns
+            .iter()
+            .map(|c| Field::new(&c.name, c.kind.data_type(), true))
+            .collect::<Vec<_>>();
+        let arrays = columns.into_iter().map(Column::into_array).collect();
+        RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays).map_err(|e| {
+            zerror2!(ZErrorKind::ValueEncodingFailed {
+                descr: format!("Failed to build the Arrow RecordBatch: {}", e)
+            })
+        })
+    }
+}
+
+// Returns the (column name, field value) pairs of a Value
+fn to_fields(row: usize, value: &Value) -> ZResult<Vec<(String, JsonValue)>> {
+    match value {
+        Value::Json(s) => match serde_json::from_str::<JsonValue>(s) {
+            Ok(JsonValue::Object(map)) => Ok(map.into_iter().collect()),
+            Ok(v) => Ok(vec![(VALUE_COLUMN.to_string(), v)]),
+            Err(e) => zerror!(ZErrorKind::ValueEncodingFailed {
+                descr: format!("Invalid Json value at row {}: {}", row, e)
+            }),
+        },
+        Value::Properties(p) => {
+            // sort the properties for a deterministic order of the columns
+            let mut fields: Vec<(String, JsonValue)> = p
+                .iter()
+                .map(|(k, v)| (k.clone(), JsonValue::String(v.clone())))
+                .collect();
+            fields.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
+            Ok(fields)
+        }
+        Value::StringUtf8(s) => Ok(vec![(
+            VALUE_COLUMN.to_string(),
+            JsonValue::from(s.as_str()),
+        )]),
+        Value::Integer(i) => Ok(vec![(VALUE_COLUMN.to_string(), JsonValue::from(*i))]),
+        Value::Float(f) => Ok(vec![(VALUE_COLUMN.to_string(), JsonValue::from(*f))]),
+        _ => zerror!(ZErrorKind::ValueEncodingFailed {
+            descr: format!(
+                "Unsupported Value at row {} for an Arrow RecordBatch (encoding: {})",
+                row,
+                value.encoding_descr()
+            )
+        }),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ColumnKind {
+    Null,
+    Boolean,
+    Int64,
+    Float64,
+    Utf8,
+}
+
+impl ColumnKind {
+    fn of(value: &JsonValue) -> Option<ColumnKind> {
+        match value {
+            JsonValue::Null => Some(ColumnKind::Null),
+            JsonValue::Bool(_) => Some(ColumnKind::Boolean),
+            JsonValue::Number(n) if n.is_i64() => Some(ColumnKind::Int64),
+            JsonValue::Number(_) => Some(ColumnKind::Float64),
+            JsonValue::String(_) => Some(ColumnKind::Utf8),
+            JsonValue::Array(_) | JsonValue::Object(_) => None,
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        match self {
+            // a column of nulls only is typed as Utf8
+            ColumnKind::Null | ColumnKind::Utf8 => DataType::Utf8,
+            ColumnKind::Boolean => DataType::Boolean,
+            ColumnKind::Int64 => DataType::Int64,
+            ColumnKind::Float64 => DataType::Float64,
+        }
+    }
+}
+
+struct Column {
+    name: String,
+    kind: ColumnKind,
+    kind_row: usize,
+    values: Vec<JsonValue>,
+}
+
+impl Column {
+    fn new(name: String, len: usize) -> Column {
+        Column {
+            name,
+            kind: ColumnKind::Null,
+            kind_row: 0,
+            values: vec![JsonValue::Null; len],
+        }
+    }
+
+    fn set(&mut self, row: usize, value: JsonValue) -> ZResult<()> {
+        let kind = match ColumnKind::of(&value) {
+            Some(kind) => kind,
+            None => {
+                return zerror!(ZErrorKind::ValueEncodingFailed {
+                    descr: format!(
+                        "Unsupported nested value for field '{}' at row {}",
+                        self.name, row
+                    )
+                })
+            }
+        };
+        match (self.kind, kind) {
+            (_, ColumnKind::Null) => (),
+            (ColumnKind::Null, _) => {
+                self.kind = kind;
+                self.kind_row = row;
+            }
+            (ColumnKind::Int64, ColumnKind::Float64) => self.kind = ColumnKind::Float64,
+            (ColumnKind::Float64, ColumnKind::Int64) => (),
+            (current, _) if current == kind => (),
+            (current, _) => {
+                return zerror!(ZErrorKind::ValueEncodingFailed {
+                    descr: format!(
+                        "Conflicting types for field '{}': {:?} at row {} and {:?} at row {}",
+                        self.name, current, self.kind_row, kind, row
+                    )
+                })
+            }
+        }
+        self.values[row] = value;
+        Ok(())
+    }
+
+    fn into_array(self) -> ArrayRef {
+        match self.kind {
+            ColumnKind::Boolean => Arc::new(BooleanArray::from(
+                self.values
+                    .iter()
+                    .map(JsonValue::as_bool)
+                    .collect::<Vec<_>>(),
+            )),
+            ColumnKind::Int64 => Arc::new(Int64Array::from(
+                self.values
+                    .iter()
+                    .map(JsonValue::as_i64)
+                    .collect::<Vec<_>>(),
+            )),
+            ColumnKind::Float64 => Arc::new(Float64Array::from(
+                self.values
+                    .iter()
+                    .map(JsonValue::as_f64)
+                    .collect::<Vec<_>>(),
+            )),
+            ColumnKind::Null | ColumnKind::Utf8 => Arc::new(StringArray::from(
+                self.values
+                    .iter()
+                    .map(JsonValue::as_str)
+                    .collect::<Vec<_>>(),
+            )),
+        }
+    }
+}
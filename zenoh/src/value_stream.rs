@@ -0,0 +1,271 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use crate::net::encoding::APP_STREAM;
+use crate::net::{QueryConsolidation, QueryTarget, ResKey, Session, WBuf, ZBuf};
+use crate::{Path, PathExpr, Selector, Value, Workspace, ZError, ZErrorKind, ZResult};
+use async_std::pin::Pin;
+use async_std::task::{Context, Poll};
+use futures::io::AsyncRead;
+use futures_lite::StreamExt;
+use std::convert::TryFrom;
+use std::future::Future;
+use zenoh_util::{zerror, zerror2};
+
+/// The segment appended to the path of a value put with [`Workspace::put_stream()`], under which its chunks
+/// are put (e.g. `/demo/camera/frame/@chunks/0`, `/demo/camera/frame/@chunks/1`, etc.).
+pub const STREAM_CHUNKS_SEGMENT: &str = "@chunks";
+
+// The size of the id of a stream
+pub(crate) const STREAM_ID_SIZE: usize = 16;
+
+// Returns the path of the chunk `index` of the stream put on `path` (an absolute path).
+pub(crate) fn chunk_path(path: &str, index: u64) -> String {
+    format!("{}/{}/{}", path, STREAM_CHUNKS_SEGMENT, index)
+}
+
+// Returns the manifest of a stream, put on the path of the stream once all its chunks are put.
+pub(crate) fn manifest(
+    id: &[u8; STREAM_ID_SIZE],
+    encoding_descr: &str,
+    chunks: u64,
+    size: u64,
+) -> Value {
+    let mut buf = WBuf::new(64, false);
+    buf.write_bytes(id);
+    buf.write_string(encoding_descr);
+    buf.write_u64_as_zint(chunks);
+    buf.write_u64_as_zint(size);
+    Value::Raw(APP_STREAM, buf.into())
+}
+
+// Returns the stream id, the encoding, the number of chunks and the size read from the payload of a manifest,
+// or None if it's malformed.
+fn parse_manifest(payload: &ZBuf) -> Option<([u8; STREAM_ID_SIZE], String, u64, u64)> {
+    let mut payload = payload.clone();
+    let mut id = [0u8; STREAM_ID_SIZE];
+    if !payload.read_bytes(&mut id) {
+        return None;
+    }
+    let encoding_descr = payload.read_string()?;
+    let chunks = payload.read_zint_as_u64()?;
+    let size = payload.read_zint_as_u64()?;
+    Some((id, encoding_descr, chunks, size))
+}
+
+// Returns the number of chunks of the stream currently stored on `path` (an absolute path), or 0 if there's none.
+// A new stream put on `path` overwrites the chunks of the same index: the chunks beyond its own ones must be deleted.
+pub(crate) async fn stored_chunks(session: &Session, path: &str) -> u64 {
+    let mut replies = match session
+        .query(
+            &ResKey::from(path),
+            "",
+            QueryTarget::default(),
+            QueryConsolidation::default(),
+        )
+        .await
+    {
+        Ok(replies) => replies,
+        Err(_) => return 0,
+    };
+    let mut chunks = 0;
+    // several storages may reply: keep the largest stream
+    while let Some(reply) = replies.next().await {
+        if let Ok(Some(Value::Raw(APP_STREAM, payload))) = Value::from_sample(&reply.data, false) {
+            if let Some((_, _, n, _)) = parse_manifest(&payload) {
+                chunks = chunks.max(n);
+            }
+        }
+    }
+    chunks
+}
+
+// Returns the payload of a chunk: the id of its stream followed by its bytes.
+pub(crate) fn chunk_payload(id: &[u8; STREAM_ID_SIZE], bytes: Vec<u8>) -> ZBuf {
+    let mut payload = ZBuf::new();
+    payload.add_zslice(id.to_vec().into());
+    payload.add_zslice(bytes.into());
+    payload
+}
+
+type ChunkFuture<'a> = Pin<Box<dyn Future<Output = ZResult<Vec<u8>>> + 'a>>;
+
+/// An [`AsyncRead`] over the payload of a value, returned by [`Data::value_reader()`](crate::Data::value_reader)
+/// and [`Change::value_reader()`](crate::Change::value_reader).
+///
+/// For a value put with [`Workspace::put_stream()`], the chunks are fetched one by one (with a `get` on their path
+/// via the Workspace) as the reader is read: at most one chunk is held in memory, whatever the total size of the
+/// value. The chunks are not kept by the subscribers: a storage matching `<path>/@chunks/**` is required (e.g. a
+/// storage on `/demo/camera/**` for a value streamed on `/demo/camera/frame`). Reading fails if a chunk can't be
+/// found (e.g. no storage kept it), or if it was overwritten by another stream put on the same path since.
+/// For any other value, it reads its encoded payload (see [`Value::encode()`]).
+///
+/// # Format
+/// The chunks are put as [`Value::Raw`] under the [`STREAM_CHUNKS_SEGMENT`] of the path, numbered from `0`.
+/// Once all the chunks are put, a manifest is put on the path itself as a [`Value::Raw`] with the [`APP_STREAM`]
+/// encoding, and the chunks of the previous stream put on the path beyond the new ones are deleted. The payloads are made of zenoh integers (_zint_), strings (a zint length and UTF-8 bytes) and bytes:
+/// ```text
+/// manifest: 16 bytes: the stream id (an UUID)
+///           string:   the encoding of the value
+///           zint:     the number of chunks
+///           zint:     the total size of the value
+/// chunk:    16 bytes: the stream id
+///           the bytes of the chunk
+/// ```
+pub struct ValueReader<'a> {
+    workspace: Option<&'a Workspace<'a>>,
+    path: String,
+    id: [u8; STREAM_ID_SIZE],
+    encoding_descr: String,
+    size: u64,
+    chunks: u64,
+    next_chunk: u64,
+    buffer: Vec<u8>,
+    pos: usize,
+    fetching: Option<ChunkFuture<'a>>,
+}
+
+impl<'a> ValueReader<'a> {
+    pub(crate) fn new(
+        workspace: &'a Workspace<'a>,
+        path: &Path,
+        value: &Value,
+    ) -> ZResult<ValueReader<'a>> {
+        match value {
+            Value::Raw(APP_STREAM, payload) => {
+                let (id, encoding_descr, chunks, size) =
+                    parse_manifest(payload).ok_or_else(|| {
+                        zerror2!(ZErrorKind::ValueDecodingFailed {
+                            descr: format!("Malformed stream manifest for {}", path)
+                        })
+                    })?;
+                Ok(ValueReader {
+                    workspace: Some(workspace),
+                    path: path.to_string(),
+                    id,
+                    encoding_descr,
+                    size,
+                    chunks,
+                    next_chunk: 0,
+                    buffer: vec![],
+                    pos: 0,
+                    fetching: None,
+                })
+            }
+            value => {
+                let buffer = value.clone().encode().1.to_vec();
+                Ok(ValueReader {
+                    workspace: None,
+                    path: path.to_string(),
+                    id: [0u8; STREAM_ID_SIZE],
+                    encoding_descr: value.encoding_descr(),
+                    size: buffer.len() as u64,
+                    chunks: 0,
+                    next_chunk: 0,
+                    buffer,
+                    pos: 0,
+                    fetching: None,
+                })
+            }
+        }
+    }
+
+    /// Returns the encoding of the read value (e.g. the `encoding_descr` of [`Workspace::put_stream()`]).
+    pub fn encoding_descr(&self) -> &str {
+        &self.encoding_descr
+    }
+
+    /// Returns the total size of the read value, in bytes.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns true if the read value was put with [`Workspace::put_stream()`].
+    pub fn is_stream(&self) -> bool {
+        self.workspace.is_some()
+    }
+}
+
+// Fetches the chunk `index` of the stream `id` put on `path`, returning its bytes (without the stream id).
+async fn fetch_chunk(
+    workspace: &Workspace<'_>,
+    path: String,
+    index: u64,
+    id: [u8; STREAM_ID_SIZE],
+) -> ZResult<Vec<u8>> {
+    let chunk_path = Path::try_from(chunk_path(&path, index))?;
+    let mut receiver = workspace
+        .get(&Selector::from(PathExpr::from(&chunk_path)))
+        .await?;
+    // several storages may reply: keep the chunk of this stream
+    while let Some(data) = receiver.next().await {
+        let payload = data.value.encode().1.to_vec();
+        if payload.len() >= STREAM_ID_SIZE && payload[..STREAM_ID_SIZE] == id {
+            return Ok(payload[STREAM_ID_SIZE..].to_vec());
+        }
+    }
+    zerror!(ZErrorKind::Other {
+        descr: format!(
+            "Chunk {} of the value streamed on {} not found (not stored, or overwritten by another stream)",
+            index, path
+        )
+    })
+}
+
+impl AsyncRead for ValueReader<'_> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        loop {
+            if this.pos < this.buffer.len() || buf.is_empty() {
+                let n = buf.len().min(this.buffer.len() - this.pos);
+                buf[..n].copy_from_slice(&this.buffer[this.pos..this.pos + n]);
+                this.pos += n;
+                return Poll::Ready(Ok(n));
+            }
+            let workspace = match this.workspace {
+                Some(workspace) if this.next_chunk < this.chunks => workspace,
+                // end of the value
+                _ => return Poll::Ready(Ok(0)),
+            };
+            // release the read chunk before fetching the next one
+            this.buffer = vec![];
+            this.pos = 0;
+            let (path, index, id) = (this.path.clone(), this.next_chunk, this.id);
+            let fetching = this
+                .fetching
+                .get_or_insert_with(|| Box::pin(fetch_chunk(workspace, path, index, id)));
+            match fetching.as_mut().poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(result) => {
+                    this.fetching = None;
+                    match result {
+                        Ok(bytes) => {
+                            this.buffer = bytes;
+                            this.next_chunk += 1;
+                        }
+                        Err(e) => {
+                            return Poll::Ready(Err(std::io::Error::new(
+                                std::io::ErrorKind::Other,
+                                e.to_string(),
+                            )))
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -12,13 +12,62 @@
 //   ADLINK zenoh team, <zenoh@adlink-labs.tech>
 //
 use crate::net::encoding::*;
-use crate::net::{Sample, WBuf, ZBuf, ZInt};
+use crate::net::protocol::io::ZSliceBuffer;
+use crate::net::{Sample, WBuf, ZBuf, ZInt, ZSlice};
 use crate::workspace::ChangeKind;
-use crate::Properties;
+use crate::{Path, PathExpr, Properties, Selector, Workspace};
+use bytes::Bytes;
+use futures_lite::StreamExt;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use zenoh_util::collections::{RecyclingObject, RecyclingObjectPool};
 use zenoh_util::core::{ZError, ZErrorKind, ZResult};
 use zenoh_util::{zerror, zerror2};
 
 /// A user value that is associated with a [Path](super::Path) in zenoh.
+///
+/// # Equality and hashing
+/// The equality and the hashing of Values are based on their canonical encoding, making Value usable as
+/// a `HashMap` key (e.g. for de-duplication or caching):
+///  * a [`Value::Raw`] with the encoding flag of a built-in type is compared as its decoded Value
+///    (e.g. `Raw(APP_INTEGER, "42")` equals `Integer(42)`).
+///  * the numbers are compared by value, independently of their textual representation
+///    (e.g. `Raw(APP_FLOAT, "1.50")` equals `Float(1.5)`). `0.0` equals `-0.0`, and NaN equals NaN.
+///    Note that an [`Value::Integer`] never equals a [`Value::Float`].
+///  * the [`Value::Properties`] are compared independently of the order of the properties.
+///  * the [`Value::Json`] are compared independently of the whitespaces and of the order of the objects' fields
+///    (a string which is not a valid Json is compared as-is).
+///
+/// ```
+/// use zenoh::Value;
+/// use zenoh::net::encoding::*;
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::{Hash, Hasher};
+///
+/// fn hash(value: &Value) -> u64 {
+///     let mut hasher = DefaultHasher::new();
+///     value.hash(&mut hasher);
+///     hasher.finish()
+/// }
+///
+/// let equivalents = [
+///     (Value::Integer(42), Value::Raw(APP_INTEGER, "042".as_bytes().into())),
+///     (Value::Float(1.5), Value::Raw(APP_FLOAT, "1.50".as_bytes().into())),
+///     (Value::Float(0.0), Value::Float(-0.0)),
+///     (Value::Float(f64::NAN), Value::Float(f64::NAN)),
+///     (Value::Json(r#"{"a": 1, "b": [true]}"#.into()), Value::Json(r#"{"b":[true],"a":1}"#.into())),
+///     (Value::Json("[1]".into()), Value::Raw(TEXT_JSON, "[ 1 ]".as_bytes().into())),
+///     (Value::Properties("a=1;b=2".into()), Value::Properties("b=2;a=1".into())),
+///     (Value::StringUtf8("x".into()), Value::Raw(STRING, "x".as_bytes().into())),
+/// ];
+/// for (v1, v2) in equivalents.iter() {
+///     assert_eq!(v1, v2);
+///     assert_eq!(hash(v1), hash(v2));
+/// }
+/// assert_ne!(Value::Integer(1), Value::Float(1.0));
+/// assert_ne!(Value::StringUtf8("1".into()), Value::Integer(1));
+/// ```
 #[derive(Clone, Debug)]
 pub enum Value {
     /// A value as a bytes buffer (_ZBuf_) and an encoding flag.  
@@ -42,9 +91,78 @@ pub enum Value {
     /// An Float value.  
     /// Note: this is equivalent to `Raw(APP_FLOAT, buf)` where buf contains the float encoded as a String
     Float(f64),
+    /// A reference to the value stored under another [`Path`], with the encoding of this value
+    /// (e.g. a small index value referencing large blobs, fetched on demand with [`Value::resolve()`]).  
+    /// Note: this is equivalent to `Raw(APP_REFERENCE, buf)` where buf contains the encoding as a ZInt
+    /// followed by the path as a String
+    Reference { path: Path, encoding: ZInt },
 }
 
+/// The maximum number of [`Value::Reference`] followed by [`Value::resolve()`].
+pub const MAX_REFERENCE_DEPTH: usize = 8;
+
 impl Value {
+    /// Creates a [`Value::Raw`] from a [`PooledBuffer`] taken from a [`BufferPool`], without copying it.
+    /// The buffer will return to its pool once the Value (and all the messages sharing its payload) are dropped.
+    pub fn from_pooled_buffer(encoding: ZInt, buf: PooledBuffer) -> Value {
+        Value::Raw(encoding, buf.into())
+    }
+
+    /// Creates a [`Value::Raw`] backed by a read-only memory mapping of the file at `path`
+    /// (requires the `mmap` feature).  
+    /// The file is not read into memory: its pages are loaded on demand by the OS when the Value is sent,
+    /// allowing to put a file larger than the available RAM (e.g. model weights).
+    ///
+    /// The memory mapping is supported on Unix and Windows platforms. It's kept alive as long as the Value
+    /// (and all the messages sharing its payload, e.g. in a transmission queue) are not dropped.
+    /// **The file must not be modified nor truncated while mapped**: the content of the Value would change,
+    /// and accessing a truncated part of the mapping crashes the process (e.g. with a `SIGBUS` on Unix).
+    ///
+    /// Note that putting a large Value requires to raise the
+    /// [`ZN_MAX_PUT_SIZE_KEY`](crate::net::config::ZN_MAX_PUT_SIZE_KEY) limit of the publisher, and the
+    /// [`ZN_DEFRAG_BUFF_SIZE_KEY`](crate::net::config::ZN_DEFRAG_BUFF_SIZE_KEY) and
+    /// [`ZN_MAX_VALUE_SIZE_KEY`](crate::net::config::ZN_MAX_VALUE_SIZE_KEY) limits of the subscribers.
+    ///
+    /// # Examples
+    /// ```
+    /// use zenoh::*;
+    ///
+    /// let path = std::env::temp_dir().join("zenoh_from_mmap_example.bin");
+    /// std::fs::write(&path, vec![42u8; 4096]).unwrap();
+    /// let value = Value::from_mmap(&path, net::encoding::APP_OCTET_STREAM).unwrap();
+    /// assert_eq!(value.encode().1.to_vec(), vec![42u8; 4096]);
+    /// ```
+    #[cfg(feature = "mmap")]
+    pub fn from_mmap(path: impl AsRef<std::path::Path>, encoding: ZInt) -> ZResult<Value> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path).map_err(|e| {
+            zerror2!(ZErrorKind::IoError {
+                descr: format!("Failed to open {}: {}", path.display(), e)
+            })
+        })?;
+        let len = file
+            .metadata()
+            .map_err(|e| {
+                zerror2!(ZErrorKind::IoError {
+                    descr: format!("Failed to read the metadata of {}: {}", path.display(), e)
+                })
+            })?
+            .len();
+        // an empty file can't be mapped
+        if len == 0 {
+            return Ok(Value::Raw(encoding, ZBuf::new()));
+        }
+        // Safety: the file is mapped read-only and must not be modified while mapped (see above)
+        let mmap = unsafe { memmap2::Mmap::map(&file) }.map_err(|e| {
+            zerror2!(ZErrorKind::IoError {
+                descr: format!("Failed to map {}: {}", path.display(), e)
+            })
+        })?;
+        let len = mmap.len();
+        let buf = ZSliceBuffer::from(std::sync::Arc::new(mmap));
+        Ok(Value::Raw(encoding, ZSlice::new(buf, 0, len).into()))
+    }
+
     /// Returns the encoding flag of the Value.
     pub fn encoding(&self) -> ZInt {
         use Value::*;
@@ -59,6 +177,7 @@ impl Value {
             Json(_) => APP_JSON,
             Integer(_) => APP_INTEGER,
             Float(_) => APP_FLOAT,
+            Reference { .. } => APP_REFERENCE,
         }
     }
 
@@ -74,6 +193,451 @@ impl Value {
         }
     }
 
+    /// Returns the MIME type to use as the HTTP `Content-Type` of the Value (e.g. by a REST gateway).  
+    /// The encodings that are not standard MIME types are mapped to the MIME type of their textual or binary
+    /// representation, according to this table:
+    ///
+    /// | Value                                              | MIME type                          |
+    /// |----------------------------------------------------|------------------------------------|
+    /// | [`Value::Json`]                                    | `application/json`                 |
+    /// | [`Value::StringUtf8`]                              | `text/plain; charset=utf-8`        |
+    /// | [`Value::Properties`]                              | `text/plain; charset=utf-8`        |
+    /// | [`Value::Integer`], [`Value::Float`]               | `text/plain; charset=utf-8`        |
+    /// | [`Value::Custom`] with a `type/subtype` encoding   | its `encoding_descr`               |
+    /// | [`Value::Custom`] with another encoding            | `application/octet-stream`         |
+    /// | [`Value::Raw`] with a standard encoding            | the encoding (e.g. `image/png`)    |
+    /// | [`Value::Raw`] with `STRING`, `APP_PROPERTIES`, `APP_INTEGER`, `APP_FLOAT` or `APP_DECIMAL` | `text/plain; charset=utf-8` |
+    /// | [`Value::Raw`] with another encoding               | `application/octet-stream`         |
+    /// | [`Value::Reference`]                               | `application/octet-stream`         |
+    ///
+    /// The standard encodings are `application/octet-stream`, `application/json`, `application/sql`,
+    /// `application/xml`, `application/xhtml+xml`, `application/x-www-form-urlencoded`, `application/toml`,
+    /// `application/cbor`, `text/json`, `text/html`, `text/xml`, `text/css`, `text/csv`, `text/javascript`, `image/jpeg`,
+    /// `image/png` and `image/gif`.
+    ///
+    /// # Examples
+    /// ```
+    /// use zenoh::Value;
+    /// use zenoh::net::encoding::IMG_PNG;
+    ///
+    /// assert_eq!(Value::Json("{}".to_string()).mime_type(), "application/json");
+    /// assert_eq!(Value::Integer(42).mime_type(), "text/plain; charset=utf-8");
+    /// assert_eq!(Value::Raw(IMG_PNG, vec![0u8].into()).mime_type(), "image/png");
+    /// let custom = Value::Custom { encoding_descr: "application/cbor".to_string(), data: vec![0u8].into() };
+    /// assert_eq!(custom.mime_type(), "application/cbor");
+    /// ```
+    pub fn mime_type(&self) -> &str {
+        const TEXT: &str = "text/plain; charset=utf-8";
+        const BINARY: &str = "application/octet-stream";
+        use Value::*;
+        match self {
+            Json(_) => "application/json",
+            StringUtf8(_) | Properties(_) | Integer(_) | Float(_) => TEXT,
+            Reference { .. } => BINARY,
+            Custom { encoding_descr, .. } => {
+                let essence = encoding_descr.split(';').next().unwrap_or("").trim();
+                match essence.split_once('/') {
+                    Some((t, st)) if !t.is_empty() && !st.is_empty() && !st.contains('/') => {
+                        encoding_descr.as_str()
+                    }
+                    _ => BINARY,
+                }
+            }
+            Raw(encoding, _) => match *encoding {
+                APP_JSON => "application/json",
+                APP_SQL => "application/sql",
+                APP_XML => "application/xml",
+                APP_XHTML_XML => "application/xhtml+xml",
+                APP_X_WWW_FORM_URLENCODED => "application/x-www-form-urlencoded",
+                APP_TOML => "application/toml",
+                TEXT_JSON => "text/json",
+                TEXT_HTML => "text/html",
+                TEXT_XML => "text/xml",
+                TEXT_CSS => "text/css",
+                TEXT_CSV => "text/csv",
+                TEXT_JAVASCRIPT => "text/javascript",
+                IMG_JPG => "image/jpeg",
+                IMG_PNG => "image/png",
+                IMG_GIF => "image/gif",
+                APP_CBOR => "application/cbor",
+                STRING | APP_PROPERTIES | APP_INTEGER | APP_FLOAT | APP_DECIMAL => TEXT,
+                _ => BINARY,
+            },
+        }
+    }
+
+    /// Creates a Value from the `Content-Type` and the body of an HTTP request or response
+    /// (e.g. to ingest an HTTP body into zenoh).  
+    /// The parameters of the content type (e.g. `charset`) and the case of its type are ignored to select the
+    /// encoding, according to this table:
+    ///
+    /// | Content type                                    | Value                                           |
+    /// |-------------------------------------------------|-------------------------------------------------|
+    /// | `application/json`, `text/json`                 | [`Value::Json`]                                 |
+    /// | `text/plain`                                    | [`Value::StringUtf8`]                           |
+    /// | none (empty), `application/octet-stream`        | [`Value::Raw`] with `APP_OCTET_STREAM`          |
+    /// | another encoding of [`encoding`](crate::net::encoding) (e.g. `image/png`) | the Value decoded with this encoding (see [`Value::decode()`]) |
+    /// | any other content type (e.g. `application/x-protobuf`) | [`Value::Custom`] with the content type as `encoding_descr` |
+    ///
+    /// An error is returned if the body of a textual Value isn't valid UTF-8 (or if it isn't a number for
+    /// `application/integer` and `application/float`).
+    /// Note that the content type of a [`Value::Custom`] is kept as is, including its parameters,
+    /// so that [`Value::mime_type()`] returns it back.
+    ///
+    /// # Examples
+    /// ```
+    /// use zenoh::Value;
+    ///
+    /// let value = Value::from_http("application/json; charset=utf-8", br#"{"temp": 21.5}"#.to_vec()).unwrap();
+    /// assert_eq!(value, Value::Json(r#"{"temp": 21.5}"#.to_string()));
+    /// let value = Value::from_http("text/plain", b"hello".to_vec()).unwrap();
+    /// assert_eq!(value, Value::StringUtf8("hello".to_string()));
+    /// let value = Value::from_http("application/x-protobuf", vec![0x08, 0x96, 0x01]).unwrap();
+    /// assert_eq!(value.mime_type(), "application/x-protobuf");
+    /// ```
+    pub fn from_http(content_type: &str, body: impl Into<ZBuf>) -> ZResult<Value> {
+        let body = body.into();
+        let essence = content_type
+            .split(';')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_ascii_lowercase();
+        if essence.is_empty() {
+            return Ok(Value::Raw(APP_OCTET_STREAM, body));
+        }
+        match from_str(&essence) {
+            // a Custom payload is prefixed with its encoding description, which an HTTP body isn't
+            Ok(APP_CUSTOM) | Err(_) => Ok(Value::Custom {
+                encoding_descr: content_type.trim().to_string(),
+                data: body,
+            }),
+            Ok(encoding) => Value::decode(encoding, body),
+        }
+    }
+
+    /// Decodes the data of a [`Value::Custom`] as a `T`, after checking that its encoding description
+    /// is `expected_encoding`. The decoding itself is the `T`'s implementation of [`TryFrom<ZBuf>`](TryFrom).
+    ///
+    /// An error is returned, without attempting to decode, if the Value is not a [`Value::Custom`]
+    /// or if its encoding description differs from `expected_encoding`.
+    ///
+    /// # Examples
+    /// ```
+    /// use zenoh::Value;
+    /// use zenoh::net::ZBuf;
+    /// use std::convert::TryFrom;
+    ///
+    /// struct Point(u8, u8);
+    /// impl TryFrom<ZBuf> for Point {
+    ///     type Error = String;
+    ///     fn try_from(buf: ZBuf) -> Result<Self, Self::Error> {
+    ///         match &buf.to_vec()[..] {
+    ///             [x, y] => Ok(Point(*x, *y)),
+    ///             _ => Err("expected 2 bytes".to_string()),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let value = Value::Custom { encoding_descr: "my/point".to_string(), data: vec![1u8, 2].into() };
+    /// assert!(value.decode_custom::<Point>("my/point").is_ok());
+    /// assert!(value.decode_custom::<Point>("my/other").is_err());
+    /// ```
+    pub fn decode_custom<T>(&self, expected_encoding: &str) -> ZResult<T>
+    where
+        T: TryFrom<ZBuf>,
+        T::Error: fmt::Display,
+    {
+        match self {
+            Value::Custom {
+                encoding_descr,
+                data,
+            } if encoding_descr == expected_encoding => T::try_from(data.clone()).map_err(|e| {
+                zerror2!(ZErrorKind::ValueDecodingFailed {
+                    descr: format!(
+                        "Failed to decode Custom Value with encoding '{}': {}",
+                        encoding_descr, e
+                    )
+                })
+            }),
+            _ => zerror!(ZErrorKind::ValueDecodingFailed {
+                descr: format!(
+                    "Expected a Custom Value with encoding '{}' but got a Value with encoding '{}'",
+                    expected_encoding,
+                    self.encoding_descr()
+                )
+            }),
+        }
+    }
+
+    /// Creates a multipart Value made of several named parts (e.g. a JSON header plus a binary blob),
+    /// with the [`APP_MULTIPART`] encoding.  
+    /// Each part is encoded with its own encoding, which is preserved. The parts' payloads are not copied.
+    /// Use [`Value::as_multipart()`] to get back the parts.
+    ///
+    /// # Examples
+    /// ```
+    /// use zenoh::Value;
+    ///
+    /// let value = Value::multipart(vec![
+    ///     ("header".to_string(), Value::Json(r#"{"width":640,"height":480}"#.to_string())),
+    ///     ("frame".to_string(), Value::from(vec![0u8; 640 * 480])),
+    /// ]);
+    /// let parts = value.as_multipart().unwrap();
+    /// assert_eq!(parts[0].0, "header");
+    /// assert!(matches!(&parts[0].1, Value::Json(s) if s == r#"{"width":640,"height":480}"#));
+    /// assert_eq!(parts[1].0, "frame");
+    /// assert_eq!(parts[1].1.clone().encode().1.len(), 640 * 480);
+    /// ```
+    pub fn multipart(parts: Vec<(String, Value)>) -> Value {
+        let mut buf = WBuf::new(64, false);
+        buf.write_usize_as_zint(parts.len());
+        for (name, value) in parts {
+            let (encoding, payload) = value.encode();
+            buf.write_string(&name);
+            buf.write_zint(encoding);
+            buf.write_usize_as_zint(payload.len());
+            buf.write_zbuf_slices(&payload);
+        }
+        Value::Raw(APP_MULTIPART, buf.into())
+    }
+
+    /// Returns the named parts of a Value created with [`Value::multipart()`] (i.e. a [`Value::Raw`]
+    /// with the [`APP_MULTIPART`] encoding), each part being decoded according to its encoding.  
+    /// The parts' payloads are not copied.
+    pub fn as_multipart(&self) -> ZResult<Vec<(String, Value)>> {
+        let mut payload = match self {
+            Value::Raw(APP_MULTIPART, payload) => payload.clone(),
+            _ => {
+                return zerror!(ZErrorKind::ValueDecodingFailed {
+                    descr: format!(
+                        "Expected a multipart Value but got a Value with encoding '{}'",
+                        self.encoding_descr()
+                    )
+                })
+            }
+        };
+        let malformed = || {
+            zerror2!(ZErrorKind::ValueDecodingFailed {
+                descr: "Malformed multipart Value".to_string()
+            })
+        };
+        let count = payload.read_zint_as_usize().ok_or_else(malformed)?;
+        let mut parts = Vec::with_capacity(count.min(payload.readable()));
+        for _ in 0..count {
+            let name = payload.read_string().ok_or_else(malformed)?;
+            let encoding = payload.read_zint().ok_or_else(malformed)?;
+            let len = payload.read_zint_as_usize().ok_or_else(malformed)?;
+            let mut data = ZBuf::new();
+            if !payload.read_into_zbuf(&mut data, len) {
+                return Err(malformed());
+            }
+            parts.push((name, Value::decode(encoding, data)?));
+        }
+        Ok(parts)
+    }
+
+    /// Returns the part of a JSON Value (i.e. a [`Value::Json`] or a [`Value::Raw`] with the [`APP_JSON`] encoding)
+    /// referenced by a JSON pointer, as defined in [RFC 6901](https://tools.ietf.org/html/rfc6901)
+    /// (e.g. `"/sensors/0/temperature"`).  
+    /// Returns `Ok(None)` if the pointer doesn't reference any part of the JSON document,
+    /// and an error if the Value is not JSON, if its JSON is invalid or if the pointer is invalid
+    /// (i.e. not empty and not starting with `'/'`).
+    ///
+    /// # Examples
+    /// ```
+    /// use zenoh::*;
+    ///
+    /// let value = Value::Json(r#"{"sensors": [{"temperature": 21.5}]}"#.to_string());
+    /// assert_eq!(
+    ///     value.json_pointer("/sensors/0/temperature").unwrap(),
+    ///     Some(serde_json::json!(21.5))
+    /// );
+    /// assert_eq!(value.json_pointer("/sensors/1").unwrap(), None);
+    /// assert!(Value::Integer(3).json_pointer("/a").is_err());
+    /// ```
+    pub fn json_pointer(&self, ptr: &str) -> ZResult<Option<serde_json::Value>> {
+        if !ptr.is_empty() && !ptr.starts_with('/') {
+            return zerror!(ZErrorKind::ValueDecodingFailed {
+                descr: format!(
+                    "Invalid JSON pointer '{}': it must be empty or start with '/'",
+                    ptr
+                )
+            });
+        }
+        let json = match self {
+            Value::Json(s) => serde_json::from_str::<serde_json::Value>(s),
+            Value::Raw(APP_JSON, payload) => {
+                serde_json::from_slice::<serde_json::Value>(&payload.to_vec())
+            }
+            _ => {
+                return zerror!(ZErrorKind::ValueDecodingFailed {
+                    descr: format!(
+                        "Expected a JSON Value but got a Value with encoding '{}'",
+                        self.encoding_descr()
+                    )
+                })
+            }
+        };
+        let mut json = json.map_err(|e| {
+            zerror2!(ZErrorKind::ValueDecodingFailed {
+                descr: format!("Invalid JSON: {}", e)
+            })
+        })?;
+        Ok(json.pointer_mut(ptr).map(serde_json::Value::take))
+    }
+
+    /// Returns the elements of a JSON array Value (i.e. a [`Value::Json`] or a [`Value::Raw`] with the
+    /// [`APP_JSON`] encoding, as created from a `Vec`), each element being converted into a Value:
+    ///  * an integer number into a [`Value::Integer`] and another number into a [`Value::Float`],
+    ///  * a string into a [`Value::StringUtf8`],
+    ///  * `null`, a boolean, an array or an object into a [`Value::Json`].
+    ///
+    /// Returns an error if the Value is not JSON or is not a JSON array.
+    ///
+    /// # Examples
+    /// ```
+    /// use zenoh::*;
+    ///
+    /// let value = Value::from(vec![Value::Integer(1), Value::Float(2.5), "three".into()]);
+    /// assert_eq!(value, Value::Json(r#"[1,2.5,"three"]"#.to_string()));
+    /// assert_eq!(
+    ///     value.as_array().unwrap(),
+    ///     vec![Value::Integer(1), Value::Float(2.5), Value::StringUtf8("three".into())]
+    /// );
+    /// assert!(Value::Json("{}".to_string()).as_array().is_err());
+    /// ```
+    pub fn as_array(&self) -> ZResult<Vec<Value>> {
+        match self.json_pointer("")? {
+            Some(serde_json::Value::Array(array)) => Ok(array
+                .into_iter()
+                .map(|json| match json {
+                    serde_json::Value::Number(n) => match n.as_i64() {
+                        Some(i) => Value::Integer(i),
+                        None => Value::Float(n.as_f64().unwrap_or(f64::NAN)),
+                    },
+                    serde_json::Value::String(s) => Value::StringUtf8(s),
+                    json => Value::from(json),
+                })
+                .collect()),
+            _ => zerror!(ZErrorKind::ValueDecodingFailed {
+                descr: "Expected a JSON array Value".to_string()
+            }),
+        }
+    }
+
+    /// Renders a JSON Value (i.e. a [`Value::Json`] or a [`Value::Raw`] with the [`APP_JSON`] encoding) or a
+    /// [`Value::Properties`] (as a JSON object of strings) as a human-readable JSON, indented with `indent` spaces
+    /// (e.g. for logging).  
+    /// Returns an error describing the Value's encoding if it is not a JSON nor a Properties,
+    /// or if its JSON is invalid.
+    ///
+    /// # Examples
+    /// ```
+    /// use zenoh::*;
+    ///
+    /// let value = Value::Json(r#"{"temp": 21.5}"#.to_string());
+    /// assert_eq!(value.to_pretty_json(2).unwrap(), "{\n  \"temp\": 21.5\n}");
+    /// let value = Value::Properties("a=1".into());
+    /// assert_eq!(value.to_pretty_json(1).unwrap(), "{\n \"a\": \"1\"\n}");
+    /// assert!(Value::Integer(3).to_pretty_json(2).is_err());
+    /// ```
+    pub fn to_pretty_json(&self, indent: usize) -> ZResult<String> {
+        let json: serde_json::Value = match self {
+            Value::Properties(p) => p
+                .iter()
+                .map(|(k, v)| (k.clone(), serde_json::Value::from(v.as_str())))
+                .collect::<serde_json::Map<_, _>>()
+                .into(),
+            Value::Json(_) | Value::Raw(APP_JSON, _) => self.json_pointer("")?.unwrap(),
+            _ => {
+                return zerror!(ZErrorKind::ValueEncodingFailed {
+                    descr: format!(
+                        "Can't render as JSON a Value with the non-JSON encoding '{}'",
+                        self.encoding_descr()
+                    )
+                })
+            }
+        };
+        let indent = vec![b' '; indent];
+        let mut buf = Vec::new();
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(&indent);
+        let mut serializer = serde_json::Serializer::with_formatter(&mut buf, formatter);
+        serde::Serialize::serialize(&json, &mut serializer).map_err(|e| {
+            zerror2!(ZErrorKind::ValueEncodingFailed {
+                descr: format!("Failed to render the Value as JSON: {}", e)
+            })
+        })?;
+        // serde_json only writes valid UTF-8
+        Ok(String::from_utf8(buf).unwrap())
+    }
+
+    /// Returns a copy of this Value with the fields referenced by the JSON pointers (see [`Value::json_pointer()`])
+    /// replaced by `"***"`, to log a Value that may contain secrets (e.g. passwords or tokens).
+    /// It applies to a JSON Value (i.e. a [`Value::Json`] or a [`Value::Raw`] with the [`APP_JSON`] encoding),
+    /// and to a [`Value::Properties`] where `"/<key>"` references the property `<key>`. The pointers referencing
+    /// nothing are ignored. The empty pointer `""` redacts the whole Value, whatever its encoding, as does any
+    /// pointer on a JSON Value whose JSON is invalid. Any other Value is returned unchanged.
+    ///
+    /// It operates on a copy: this Value, and the data stored or published with it, are not modified.
+    ///
+    /// # Examples
+    /// ```
+    /// use zenoh::*;
+    ///
+    /// let value = Value::Json(r#"{"user": "bob", "auth": {"token": "s3cr3t"}}"#.to_string());
+    /// assert_eq!(
+    ///     value.redacted(&["/auth/token", "/missing"]),
+    ///     Value::Json(r#"{"user": "bob", "auth": {"token": "***"}}"#.to_string())
+    /// );
+    /// let value = Value::Properties("user=bob;password=s3cr3t".into());
+    /// assert_eq!(
+    ///     value.redacted(&["/password"]),
+    ///     Value::Properties("user=bob;password=***".into())
+    /// );
+    /// assert_eq!(Value::Integer(3).redacted(&["/a"]), Value::Integer(3));
+    /// assert_eq!(Value::Integer(3).redacted(&[""]), Value::StringUtf8("***".into()));
+    /// ```
+    pub fn redacted(&self, json_pointers: &[&str]) -> Value {
+        const REDACTED: &str = "***";
+        if json_pointers.is_empty() {
+            return self.clone();
+        }
+        if json_pointers.contains(&"") {
+            return Value::StringUtf8(REDACTED.to_string());
+        }
+        match self {
+            Value::Json(_) | Value::Raw(APP_JSON, _) => match self.json_pointer("") {
+                Ok(Some(mut json)) => {
+                    for ptr in json_pointers {
+                        if let Some(field) = json.pointer_mut(ptr) {
+                            *field = serde_json::Value::from(REDACTED);
+                        }
+                    }
+                    Value::Json(json.to_string())
+                }
+                _ => Value::StringUtf8(REDACTED.to_string()),
+            },
+            Value::Properties(p) => {
+                let mut p = p.clone();
+                for ptr in json_pointers {
+                    if let Some(field) = ptr.strip_prefix('/').and_then(|key| p.get_mut(key)) {
+                        *field = REDACTED.to_string();
+                    }
+                }
+                Value::Properties(p)
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Encodes the Value and returns the resulting buffer as [`Bytes`].  
+    /// If the Value was created from [`Bytes`] (e.g. a payload relayed by a proxy), those are returned without copy.
+    /// Otherwise, the encoded buffer is copied.
+    pub fn into_bytes(self) -> Bytes {
+        Bytes::from(&self.encode().1)
+    }
+
     /// Encodes the Value and return the resulting buffer and its encoding flag.
     pub fn encode(self) -> (ZInt, ZBuf) {
         use Value::*;
@@ -93,6 +657,12 @@ impl Value {
             Json(s) => (APP_JSON, ZBuf::from(s.as_bytes())),
             Integer(i) => (APP_INTEGER, ZBuf::from(i.to_string().as_bytes())),
             Float(f) => (APP_FLOAT, ZBuf::from(f.to_string().as_bytes())),
+            Reference { path, encoding } => {
+                let mut buf = WBuf::new(64, false);
+                buf.write_zint(encoding);
+                buf.write_string(path.as_str());
+                (APP_REFERENCE, buf.into())
+            }
         }
     }
 
@@ -187,6 +757,16 @@ impl Value {
                     })
                 })
                 .map(Float),
+            APP_REFERENCE => match (payload.read_zint(), payload.read_string()) {
+                (Some(encoding), Some(path)) => Ok(Reference {
+                    path: Path::try_from(path)?,
+                    encoding,
+                }),
+                _ => zerror!(ZErrorKind::ValueDecodingFailed {
+                    descr: "Failed to read the encoding and the path of a Reference Value"
+                        .to_string()
+                }),
+            },
             _ => Ok(Raw(encoding, payload)),
         }
     }
@@ -226,6 +806,9 @@ impl Value {
             Json(s) => (APP_JSON, false, s),
             Integer(i) => (APP_INTEGER, false, i.to_string()),
             Float(f) => (APP_FLOAT, false, f.to_string()),
+            Reference { path, encoding } => {
+                (APP_REFERENCE, false, format!("{}:{}", encoding, path))
+            }
         }
     }
 
@@ -280,6 +863,25 @@ impl Value {
                     e
                 )
             }),
+            APP_REFERENCE => match s.split_once(':') {
+                Some((encoding, path)) => Ok(Reference {
+                    path: Path::try_from(path)?,
+                    encoding: encoding.parse().map_err(|e| {
+                        zerror2!(
+                            ZErrorKind::ValueDecodingFailed {
+                                descr: format!("Invalid encoding in Reference Value: {}", s)
+                            },
+                            e
+                        )
+                    })?,
+                }),
+                None => zerror!(ZErrorKind::ValueDecodingFailed {
+                    descr: format!(
+                        "Failed to read the encoding decoding Reference Value from String: {}",
+                        s
+                    )
+                }),
+            },
             _ => {
                 if base64 {
                     match base64::decode(s) {
@@ -319,6 +921,627 @@ impl Value {
             Ok(Some(Value::Raw(encoding, sample.payload.clone())))
         }
     }
+
+    /// Returns the value referenced by this [`Value::Reference`], fetched with a `get` on its [`Path`]
+    /// via `workspace` (a relative path being relative to this Workspace). Any other Value is returned unchanged.  
+    /// If several values are returned for the path (e.g. by several storages), the one with the most recent
+    /// timestamp is kept. The `encoding` of the reference is only informative: the fetched value is returned
+    /// whatever its encoding.
+    ///
+    /// If the fetched value is itself a reference, it's resolved in turn. To protect against cycles, the
+    /// resolution fails if a path is referenced twice (e.g. `/a` referencing `/b` referencing `/a`), or if more
+    /// than [`MAX_REFERENCE_DEPTH`] references are followed. It also fails if no value is found for a path.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use zenoh::net::encoding::IMG_PNG;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let blob: Path = "/demo/blobs/0a1b2c".try_into().unwrap();
+    /// workspace.put(&blob, Value::Raw(IMG_PNG, vec![0u8; 1024].into())).await.unwrap();
+    /// let index = Value::Reference { path: blob, encoding: IMG_PNG };
+    /// let image = index.resolve(&workspace).await.unwrap();
+    /// assert_eq!(image.encoding(), IMG_PNG);
+    /// # })
+    /// ```
+    pub async fn resolve(&self, workspace: &Workspace<'_>) -> ZResult<Value> {
+        let mut value = self.clone();
+        let mut visited: Vec<Path> = vec![];
+        while let Value::Reference { path, .. } = &value {
+            if visited.contains(path) {
+                return zerror!(ZErrorKind::Other {
+                    descr: format!(
+                        "Cycle in the references of Value: {} referenced twice",
+                        path
+                    )
+                });
+            }
+            if visited.len() == MAX_REFERENCE_DEPTH {
+                return zerror!(ZErrorKind::Other {
+                    descr: format!(
+                        "Too many references to resolve Value (more than {})",
+                        MAX_REFERENCE_DEPTH
+                    )
+                });
+            }
+            visited.push(path.clone());
+            let mut receiver = workspace.get(&Selector::from(PathExpr::from(path))).await?;
+            let mut latest = None;
+            while let Some(data) = receiver.next().await {
+                if latest
+                    .as_ref()
+                    .map_or(true, |(timestamp, _)| data.timestamp > *timestamp)
+                {
+                    latest = Some((data.timestamp, data.value));
+                }
+            }
+            value = match latest {
+                Some((_, value)) => value,
+                None => {
+                    return zerror!(ZErrorKind::Other {
+                        descr: format!("No value found for the referenced path {}", path)
+                    })
+                }
+            };
+        }
+        Ok(value)
+    }
+}
+
+impl Value {
+    // Returns the canonical encoding of the Value, used for its equality and hashing.
+    fn canonical(&self) -> (ZInt, Vec<u8>) {
+        use Value::*;
+        match self {
+            #[cfg(feature = "decimal")]
+            Raw(APP_DECIMAL, buf) => match self.as_decimal() {
+                Ok(d) => (APP_DECIMAL, d.normalize().to_string().into_bytes()),
+                Err(_) => (APP_DECIMAL, buf.to_vec()),
+            },
+            Raw(encoding, buf) => match Value::decode(*encoding, buf.clone()) {
+                Ok(Raw(_, _)) | Err(_) => (*encoding, buf.to_vec()),
+                Ok(decoded) => decoded.canonical(),
+            },
+            Custom { .. } | Reference { .. } => {
+                let (encoding, buf) = self.clone().encode();
+                (encoding, buf.to_vec())
+            }
+            StringUtf8(s) => (STRING, s.as_bytes().to_vec()),
+            Properties(props) => {
+                let mut props: Vec<(&String, &String)> = props.iter().collect();
+                props.sort();
+                let s = props
+                    .iter()
+                    .map(|(k, v)| format!("{}={}", k, v))
+                    .collect::<Vec<String>>()
+                    .join(";");
+                (APP_PROPERTIES, s.into_bytes())
+            }
+            Json(s) => match serde_json::from_str::<serde_json::Value>(s) {
+                Ok(json) => {
+                    let mut out = String::new();
+                    canonical_json(&json, &mut out);
+                    (APP_JSON, out.into_bytes())
+                }
+                Err(_) => (APP_JSON, s.as_bytes().to_vec()),
+            },
+            Integer(i) => (APP_INTEGER, i.to_string().into_bytes()),
+            Float(f) => {
+                // 0.0 and -0.0 are equal (and all the NaN are displayed as "NaN")
+                let f = if *f == 0.0 { 0.0 } else { *f };
+                (APP_FLOAT, f.to_string().into_bytes())
+            }
+        }
+    }
+}
+
+// Writes a Json value with the fields of the objects sorted and without whitespaces.
+fn canonical_json(json: &serde_json::Value, out: &mut String) {
+    match json {
+        serde_json::Value::Object(map) => {
+            let mut fields: Vec<(&String, &serde_json::Value)> = map.iter().collect();
+            fields.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
+            out.push('{');
+            for (i, (k, v)) in fields.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::from(k.as_str()).to_string());
+                out.push(':');
+                canonical_json(v, out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(array) => {
+            out.push('[');
+            for (i, v) in array.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                canonical_json(v, out);
+            }
+            out.push(']');
+        }
+        _ => out.push_str(&json.to_string()),
+    }
+}
+
+/// The encoding of the values serialized from a `serde` type with [`Value::from_typed()`]
+/// (see [`Workspace::put_typed()`]).
+///
+/// Protobuf is not one of them: the protobuf messages are not `serde` types. They can be sent as
+/// [`Value::Custom`] and decoded with [`Value::decode_custom()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypedEncoding {
+    /// A [`Value::Json`].
+    Json,
+    /// A [`Value::Raw`] with the [`APP_CBOR`] encoding (`application/cbor`), requires the `cbor` feature
+    /// (without it, serializing with this encoding returns an error).
+    Cbor,
+}
+
+impl Default for TypedEncoding {
+    fn default() -> Self {
+        TypedEncoding::Json
+    }
+}
+
+impl std::str::FromStr for TypedEncoding {
+    type Err = ZError;
+
+    fn from_str(s: &str) -> ZResult<Self> {
+        match s {
+            "json" => Ok(TypedEncoding::Json),
+            #[cfg(feature = "cbor")]
+            "cbor" => Ok(TypedEncoding::Cbor),
+            #[cfg(not(feature = "cbor"))]
+            "cbor" => zerror!(ZErrorKind::Other {
+                descr: "The 'cbor' typed encoding requires the cbor feature".to_string()
+            }),
+            _ => zerror!(ZErrorKind::Other {
+                descr: format!("Unsupported typed encoding '{}'", s)
+            }),
+        }
+    }
+}
+
+impl Value {
+    /// Creates a Value serializing `value` with the given [`TypedEncoding`].
+    ///
+    /// # Examples
+    /// ```
+    /// use zenoh::*;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut position = HashMap::new();
+    /// position.insert("x".to_string(), 1.5);
+    /// let value = Value::from_typed(&position, TypedEncoding::Json).unwrap();
+    /// assert_eq!(value.encoding_descr(), "application/json");
+    /// assert_eq!(value.decode_typed::<HashMap<String, f64>>().unwrap(), position);
+    /// ```
+    pub fn from_typed<T>(value: &T, encoding: TypedEncoding) -> ZResult<Value>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        match encoding {
+            TypedEncoding::Json => serde_json::to_string(value).map(Value::Json).map_err(|e| {
+                zerror2!(ZErrorKind::ValueEncodingFailed {
+                    descr: format!("Failed to serialize as JSON: {}", e)
+                })
+            }),
+            #[cfg(feature = "cbor")]
+            TypedEncoding::Cbor => serde_cbor::to_vec(&value)
+                .map(|bytes| Value::Raw(APP_CBOR, bytes.into()))
+                .map_err(|e| {
+                    zerror2!(ZErrorKind::ValueEncodingFailed {
+                        descr: format!("Failed to serialize as CBOR: {}", e)
+                    })
+                }),
+            #[cfg(not(feature = "cbor"))]
+            TypedEncoding::Cbor => zerror!(ZErrorKind::ValueEncodingFailed {
+                descr: "Serializing as CBOR requires the cbor feature".to_string()
+            }),
+        }
+    }
+
+    /// Creates a [`Value::Json`] serializing `value` (i.e. [`Value::from_typed()`] with [`TypedEncoding::Json`]).
+    /// The JSON is read back with [`Data::deserialize()`](crate::Data::deserialize) or
+    /// [`Change::deserialize()`](crate::Change::deserialize).
+    ///
+    /// # Examples
+    /// ```
+    /// use zenoh::*;
+    ///
+    /// let value = Value::from_json_typed(&vec![1, 2, 3]).unwrap();
+    /// assert_eq!(value, Value::Json("[1,2,3]".to_string()));
+    /// ```
+    pub fn from_json_typed<T>(value: &T) -> ZResult<Value>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        Value::from_typed(value, TypedEncoding::Json)
+    }
+
+    // Deserializes this Value from JSON, refusing the Values which encoding isn't JSON
+    // (see Data::deserialize() and Change::deserialize())
+    pub(crate) fn deserialize_json<T>(&self) -> ZResult<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        match self {
+            Value::Json(_) | Value::Raw(APP_JSON, _) | Value::Raw(TEXT_JSON, _) => {
+                self.decode_typed()
+            }
+            _ => zerror!(ZErrorKind::ValueDecodingFailed {
+                descr: format!(
+                    "Expected a JSON Value but got a Value with encoding '{}'",
+                    self.encoding_descr()
+                )
+            }),
+        }
+    }
+
+    /// Deserializes this Value into a `T`, dispatching on its encoding (see [`Value::encoding_descr()`]):
+    ///  * a [`Value::Json`] or a [`Value::Raw`] with a JSON encoding is deserialized from JSON
+    ///  * a [`Value::Raw`] with the [`APP_CBOR`] encoding is deserialized from CBOR (requires the `cbor` feature)
+    ///  * a [`Value::Integer`], [`Value::Float`], [`Value::StringUtf8`] or [`Value::Properties`] is deserialized
+    ///    from its JSON equivalent (a number, a string or an object)
+    ///
+    /// Returns an error for any other Value, or if it can't be deserialized into a `T`.
+    pub fn decode_typed<T>(&self) -> ZResult<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let decoding_failed = |e: &dyn fmt::Display| {
+            zerror2!(ZErrorKind::ValueDecodingFailed {
+                descr: format!(
+                    "Failed to deserialize a Value with encoding '{}': {}",
+                    self.encoding_descr(),
+                    e
+                )
+            })
+        };
+        match self {
+            Value::Json(s) => serde_json::from_str(s).map_err(|e| decoding_failed(&e)),
+            Value::Raw(APP_JSON, payload) | Value::Raw(TEXT_JSON, payload) => {
+                serde_json::from_slice(&payload.to_vec()).map_err(|e| decoding_failed(&e))
+            }
+            #[cfg(feature = "cbor")]
+            Value::Raw(APP_CBOR, payload) => {
+                serde_cbor::from_slice(&payload.to_vec()).map_err(|e| decoding_failed(&e))
+            }
+            Value::Integer(_) | Value::Float(_) | Value::StringUtf8(_) | Value::Properties(_) => {
+                serde_json::from_value(json_element(self.clone())).map_err(|e| decoding_failed(&e))
+            }
+            _ => zerror!(ZErrorKind::ValueDecodingFailed {
+                descr: format!(
+                    "Can't deserialize a Value with encoding '{}'",
+                    self.encoding_descr()
+                )
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl Value {
+    /// Creates a Value holding an exact decimal number (requires the `decimal` feature), avoiding the precision
+    /// loss of a [`Value::Float`] (e.g. for financial data).
+    ///
+    /// The Value is a [`Value::Raw`] with the [`APP_DECIMAL`] encoding (`application/decimal`), which payload is
+    /// the canonical string of the decimal: an optional `-` sign, the digits of the integer part and, only if
+    /// non-zero, a `.` followed by the digits of the fractional part without trailing zeros (e.g. `-12.5`).
+    /// There is no exponent. This allows any peer, even without the `decimal` feature or not in Rust,
+    /// to parse it. At most 28 significant digits are supported.
+    ///
+    /// The equality (and hashing) of such Values is exact: `1.5` equals `1.50` (even if not encoded canonically
+    /// by a remote peer), but not `1.5000000000000001`. Use [`Value::as_decimal()`] to order them.
+    ///
+    /// # Examples
+    /// ```
+    /// use zenoh::Value;
+    /// use rust_decimal::Decimal;
+    /// use std::str::FromStr;
+    ///
+    /// let price = Value::decimal(Decimal::from_str("0.10").unwrap());
+    /// assert_eq!(price.encoding_descr(), "application/decimal");
+    /// assert_eq!(price.as_decimal().unwrap().to_string(), "0.1");
+    /// let sum = Decimal::from_str("0.1").unwrap() + Decimal::from_str("0.2").unwrap();
+    /// assert_eq!(Value::from(sum), Value::decimal(Decimal::from_str("0.3").unwrap()));
+    /// ```
+    pub fn decimal(d: rust_decimal::Decimal) -> Value {
+        Value::Raw(APP_DECIMAL, d.normalize().to_string().into_bytes().into())
+    }
+
+    /// Returns the exact decimal number of a Value created with [`Value::decimal()`]
+    /// (i.e. a [`Value::Raw`] with the [`APP_DECIMAL`] encoding) or of a [`Value::Integer`]
+    /// (requires the `decimal` feature).  
+    /// Returns an error for any other Value, including a [`Value::Float`] which may not be exact.
+    pub fn as_decimal(&self) -> ZResult<rust_decimal::Decimal> {
+        match self {
+            Value::Raw(APP_DECIMAL, payload) => String::from_utf8(payload.to_vec())
+                .ok()
+                .and_then(|s| s.parse::<rust_decimal::Decimal>().ok())
+                .ok_or_else(|| {
+                    zerror2!(ZErrorKind::ValueDecodingFailed {
+                        descr: "Invalid decimal Value".to_string()
+                    })
+                }),
+            Value::Integer(i) => Ok(rust_decimal::Decimal::from(*i)),
+            _ => zerror!(ZErrorKind::ValueDecodingFailed {
+                descr: format!(
+                    "Expected a decimal Value but got a Value with encoding '{}'",
+                    self.encoding_descr()
+                )
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl From<rust_decimal::Decimal> for Value {
+    fn from(d: rust_decimal::Decimal) -> Self {
+        Value::decimal(d)
+    }
+}
+
+#[cfg(feature = "toml")]
+impl Value {
+    /// Creates a Value holding a TOML document (requires the `toml` feature), e.g. a configuration authored in TOML.
+    ///
+    /// The document is parsed, so a malformed one is rejected with an error giving the location of the problem.
+    /// The Value is a [`Value::Raw`] with the [`APP_TOML`] encoding (`application/toml`), which payload is the
+    /// document re-serialized by the `toml` crate: the comments and the formatting are not kept, but the result
+    /// is stable (i.e. creating a Value from the TOML returned by [`Value::as_toml()`] gives the same Value).
+    ///
+    /// # Examples
+    /// ```
+    /// use zenoh::Value;
+    ///
+    /// let config = Value::from_toml_str("# the storage\nname = 'demo'\n[limits]\nmax = 10\n").unwrap();
+    /// assert_eq!(config.encoding_descr(), "application/toml");
+    /// let toml = config.as_toml().unwrap();
+    /// assert_eq!(toml["limits"]["max"].as_integer(), Some(10));
+    /// assert_eq!(Value::from_toml_str(&toml.to_string()).unwrap(), config);
+    /// assert!(Value::from_toml_str("name = ").is_err());
+    /// ```
+    pub fn from_toml_str(s: &str) -> ZResult<Value> {
+        let toml = s.parse::<toml::Value>().map_err(|e| {
+            zerror2!(ZErrorKind::ValueEncodingFailed {
+                descr: format!("Invalid TOML document: {}", e)
+            })
+        })?;
+        let s = toml::to_string(&toml).map_err(|e| {
+            zerror2!(ZErrorKind::ValueEncodingFailed {
+                descr: format!("Failed to serialize the TOML document: {}", e)
+            })
+        })?;
+        Ok(Value::Raw(APP_TOML, s.into_bytes().into()))
+    }
+
+    /// Returns the TOML document of a Value created with [`Value::from_toml_str()`]
+    /// (i.e. a [`Value::Raw`] with the [`APP_TOML`] encoding), parsed as a table (requires the `toml` feature).  
+    /// Its `to_string()` gives back the TOML text. Returns an error for any other Value.
+    pub fn as_toml(&self) -> ZResult<toml::Value> {
+        match self {
+            Value::Raw(APP_TOML, payload) => String::from_utf8(payload.to_vec())
+                .map_err(|e| {
+                    zerror2!(
+                        ZErrorKind::ValueDecodingFailed {
+                            descr: "Failed to decode UTF-8 string for a TOML Value".to_string()
+                        },
+                        e
+                    )
+                })
+                .and_then(|s| {
+                    s.parse::<toml::Value>().map_err(|e| {
+                        zerror2!(ZErrorKind::ValueDecodingFailed {
+                            descr: format!("Invalid TOML Value: {}", e)
+                        })
+                    })
+                }),
+            _ => zerror!(ZErrorKind::ValueDecodingFailed {
+                descr: format!(
+                    "Expected a TOML Value but got a Value with encoding '{}'",
+                    self.encoding_descr()
+                )
+            }),
+        }
+    }
+}
+
+// The essence of the encoding of the ndarray Values (see Value::from_ndarray())
+#[cfg(feature = "ndarray")]
+const NDARRAY_ENCODING: &str = "application/x-ndarray";
+
+/// A type of the elements of an ndarray [`Value`] (see [`Value::from_ndarray()`]), with its `dtype` name
+/// (requires the `ndarray` feature).  
+/// It's implemented for `u8`, `i8`, `u16`, `i16`, `u32`, `i32`, `u64`, `i64`, `f32` and `f64`,
+/// whose `dtype` is their Rust name (e.g. `"f64"`).
+#[cfg(feature = "ndarray")]
+pub trait NdarrayElement: Copy {
+    /// the name of the type in the `dtype` attribute of the encoding.
+    const DTYPE: &'static str;
+    /// the size of an element, in bytes.
+    const SIZE: usize;
+    /// Appends the little-endian representation of the element to `buf`.
+    fn write_le(&self, buf: &mut Vec<u8>);
+    /// Reads an element from its little-endian representation (of [`SIZE`](Self::SIZE) bytes).
+    fn read_le(bytes: &[u8]) -> Self;
+}
+
+#[cfg(feature = "ndarray")]
+macro_rules! impl_ndarray_element {
+    ($($t:ty),*) => {
+        $(
+            impl NdarrayElement for $t {
+                const DTYPE: &'static str = stringify!($t);
+                const SIZE: usize = std::mem::size_of::<$t>();
+
+                fn write_le(&self, buf: &mut Vec<u8>) {
+                    buf.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn read_le(bytes: &[u8]) -> Self {
+                    let mut le = [0u8; std::mem::size_of::<$t>()];
+                    le.copy_from_slice(bytes);
+                    <$t>::from_le_bytes(le)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "ndarray")]
+impl_ndarray_element!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
+#[cfg(feature = "ndarray")]
+impl Value {
+    /// Creates a Value holding a n-dimensional array (e.g. a tensor or a matrix) with its shape and the type of
+    /// its elements (requires the `ndarray` feature).
+    ///
+    /// The Value is a [`Value::Custom`] whose encoding description is `application/x-ndarray` followed by the
+    /// `dtype` and `shape` attributes, e.g. `application/x-ndarray; dtype=f64; shape=2,3` for a 2x3 matrix of `f64`
+    /// (`shape=` for a 0-dimensional array). Its data are the elements in row-major order (i.e. the last index
+    /// varying the fastest, whatever the memory layout of `array`), each one in little-endian whatever the
+    /// platform, without any padding. So its size is the number of elements times the size of the `dtype`.
+    /// This allows any peer, even without the `ndarray` feature or not in Rust, to decode it (e.g. with
+    /// `numpy.frombuffer(data, dtype='<f8').reshape(2, 3)`).
+    ///
+    /// # Examples
+    /// ```
+    /// use zenoh::Value;
+    /// use ndarray::array;
+    ///
+    /// let matrix = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+    /// let value = Value::from_ndarray(&matrix);
+    /// assert_eq!(value.encoding_descr(), "application/x-ndarray; dtype=f64; shape=2,3");
+    /// assert_eq!(value.as_ndarray::<f64>().unwrap(), matrix.into_dyn());
+    /// assert!(value.as_ndarray::<f32>().is_err());
+    /// ```
+    pub fn from_ndarray<T, S, D>(array: &ndarray::ArrayBase<S, D>) -> Value
+    where
+        T: NdarrayElement,
+        S: ndarray::Data<Elem = T>,
+        D: ndarray::Dimension,
+    {
+        let shape = array
+            .shape()
+            .iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        let mut data = Vec::with_capacity(array.len() * T::SIZE);
+        for element in array.iter() {
+            element.write_le(&mut data);
+        }
+        Value::Custom {
+            encoding_descr: format!("{}; dtype={}; shape={}", NDARRAY_ENCODING, T::DTYPE, shape),
+            data: data.into(),
+        }
+    }
+
+    /// Returns the n-dimensional array of a Value created with [`Value::from_ndarray()`] (i.e. a [`Value::Custom`]
+    /// with the `application/x-ndarray` encoding), with elements of type `T` (requires the `ndarray` feature).  
+    /// Returns an error for any other Value, if the `dtype` of the Value isn't the one of `T`, or if the size of
+    /// its data doesn't match its shape.
+    pub fn as_ndarray<T: NdarrayElement>(&self) -> ZResult<ndarray::ArrayD<T>> {
+        let invalid = |descr: String| zerror2!(ZErrorKind::ValueDecodingFailed { descr });
+        let (encoding_descr, data) = match self {
+            Value::Custom {
+                encoding_descr,
+                data,
+            } if encoding_descr.split(';').next().map(str::trim) == Some(NDARRAY_ENCODING) => {
+                (encoding_descr, data)
+            }
+            _ => {
+                return Err(invalid(format!(
+                    "Expected an ndarray Value but got a Value with encoding '{}'",
+                    self.encoding_descr()
+                )))
+            }
+        };
+        let mut dtype = None;
+        let mut shape = None;
+        for param in encoding_descr.split(';').skip(1) {
+            match param.split_once('=').map(|(k, v)| (k.trim(), v.trim())) {
+                Some(("dtype", v)) => dtype = Some(v),
+                Some(("shape", v)) => shape = Some(v),
+                _ => (),
+            }
+        }
+        match dtype {
+            Some(dtype) if dtype == T::DTYPE => (),
+            dtype => {
+                return Err(invalid(format!(
+                    "Expected an ndarray of {} but got an ndarray of {}",
+                    T::DTYPE,
+                    dtype.unwrap_or("unknown dtype")
+                )))
+            }
+        }
+        let shape = shape
+            .ok_or_else(|| invalid("Missing shape for an ndarray Value".to_string()))?
+            .split(',')
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(|d| d.parse::<usize>())
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|e| invalid(format!("Invalid shape for an ndarray Value: {}", e)))?;
+        // the shape is untrusted: check that its size doesn't overflow before allocating anything
+        let size = shape
+            .iter()
+            .try_fold(1usize, |len, d| len.checked_mul(*d))
+            .and_then(|len| len.checked_mul(T::SIZE))
+            .ok_or_else(|| {
+                invalid(format!(
+                    "The shape {:?} of an ndarray Value is too large",
+                    shape
+                ))
+            })?;
+        if data.len() != size {
+            return Err(invalid(format!(
+                "The data of an ndarray Value of shape {:?} have {} bytes instead of {}",
+                shape,
+                data.len(),
+                size
+            )));
+        }
+        let bytes = data.to_vec();
+        let elements = bytes.chunks_exact(T::SIZE).map(T::read_le).collect();
+        ndarray::ArrayD::from_shape_vec(ndarray::IxDyn(&shape), elements)
+            .map_err(|e| invalid(format!("Invalid ndarray Value: {}", e)))
+    }
+}
+
+#[cfg(feature = "ndarray")]
+impl<T, S, D> From<&ndarray::ArrayBase<S, D>> for Value
+where
+    T: NdarrayElement,
+    S: ndarray::Data<Elem = T>,
+    D: ndarray::Dimension,
+{
+    fn from(array: &ndarray::ArrayBase<S, D>) -> Self {
+        Value::from_ndarray(array)
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Value) -> bool {
+        self.canonical() == other.canonical()
+    }
+}
+
+impl Eq for Value {}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical().hash(state)
+    }
 }
 
 impl From<ZBuf> for Value {
@@ -333,6 +1556,13 @@ impl From<Vec<u8>> for Value {
     }
 }
 
+impl From<Bytes> for Value {
+    /// Creates a [`Value::Raw`] with `application/octet-stream` encoding holding the [`Bytes`] without copy.
+    fn from(buf: Bytes) -> Self {
+        Value::from(ZBuf::from(buf))
+    }
+}
+
 impl From<&[u8]> for Value {
     fn from(buf: &[u8]) -> Self {
         Value::from(ZBuf::from(buf))
@@ -380,3 +1610,468 @@ impl From<f64> for Value {
         Value::Float(f)
     }
 }
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    /// Creates a [`Value::Json`] array (encoding `application/json`) with an element per item of the `Vec`,
+    /// the items possibly being of different types:
+    ///  * a [`Value::Integer`] or [`Value::Float`] is a number (a NaN or infinite float is `null`),
+    ///  * a [`Value::StringUtf8`] is a string,
+    ///  * a [`Value::Properties`] is an object with a string field per property,
+    ///  * a [`Value::Json`] is included as-is (or as a string if it is not a valid JSON),
+    ///  * a [`Value::Raw`] with the encoding of one of the above types is decoded first. Otherwise, as for
+    ///    a [`Value::Custom`], it is the string returned by [`Value::encode_to_string()`] (which may be base64).
+    ///
+    /// Note that `Vec<u8>` is not converted to a JSON array, but to a [`Value::Raw`] (see `From<Vec<u8>>`).
+    fn from(items: Vec<T>) -> Self {
+        let array = items
+            .into_iter()
+            .map(|item| json_element(item.into()))
+            .collect::<Vec<serde_json::Value>>();
+        Value::from(serde_json::Value::Array(array))
+    }
+}
+
+// Converts a Value into an element of a JSON array.
+pub(crate) fn json_element(value: Value) -> serde_json::Value {
+    match value {
+        Value::Integer(i) => i.into(),
+        Value::Float(f) => f.into(),
+        Value::StringUtf8(s) => s.into(),
+        Value::Properties(p) => p
+            .iter()
+            .map(|(k, v)| (k.clone(), serde_json::Value::from(v.as_str())))
+            .collect::<serde_json::Map<_, _>>()
+            .into(),
+        Value::Json(s) => serde_json::from_str(&s).unwrap_or(serde_json::Value::String(s)),
+        Value::Raw(encoding, payload) => match Value::decode(encoding, payload.clone()) {
+            Ok(Value::Raw(_, _)) | Err(_) => {
+                Value::Raw(encoding, payload).encode_to_string().2.into()
+            }
+            Ok(decoded) => json_element(decoded),
+        },
+        value => value.encode_to_string().2.into(),
+    }
+}
+
+/// A [`Value`] which payload is decoded only on first access, and then cached.
+///
+/// Subscribers and queriers that receive many values but only inspect a few of them can avoid
+/// the decoding cost of the others by requesting raw values (adding the `raw` property to the
+/// selector, e.g. `"/demo/**?(raw)"`) and wrapping them into a LazyValue.
+///
+/// # Examples
+/// ```
+/// use zenoh::*;
+///
+/// let mut lazy = LazyValue::new(net::encoding::APP_INTEGER, "42".as_bytes().to_vec().into());
+/// assert!(!lazy.is_decoded());
+/// assert!(matches!(lazy.value().unwrap(), Value::Integer(42)));
+/// assert!(lazy.is_decoded());
+/// ```
+#[derive(Clone, Debug)]
+pub struct LazyValue {
+    encoding: ZInt,
+    payload: ZBuf,
+    decoded: Option<Value>,
+}
+
+impl LazyValue {
+    /// Creates a LazyValue from an encoding flag and a not yet decoded payload.
+    pub fn new(encoding: ZInt, payload: ZBuf) -> LazyValue {
+        LazyValue {
+            encoding,
+            payload,
+            decoded: None,
+        }
+    }
+
+    /// Returns the encoding flag of the value, without decoding it.
+    pub fn encoding(&self) -> ZInt {
+        self.encoding
+    }
+
+    /// Returns the raw payload of the value, without decoding it.
+    pub fn payload(&self) -> &ZBuf {
+        &self.payload
+    }
+
+    /// Returns true if the payload has already been decoded.
+    pub fn is_decoded(&self) -> bool {
+        self.decoded.is_some()
+    }
+
+    /// Returns the decoded [`Value`], decoding the payload on the first call.  
+    /// Note that a decoding failure is not cached: the next call will try again.
+    pub fn value(&mut self) -> ZResult<&Value> {
+        if self.decoded.is_none() {
+            self.decoded = Some(Value::decode(self.encoding, self.payload.clone())?);
+        }
+        Ok(self.decoded.as_ref().unwrap())
+    }
+
+    /// Converts into the decoded [`Value`], decoding the payload if not already done.
+    pub fn into_value(self) -> ZResult<Value> {
+        match self.decoded {
+            Some(value) => Ok(value),
+            None => Value::decode(self.encoding, self.payload),
+        }
+    }
+}
+
+impl From<Value> for LazyValue {
+    /// A [`Value::Raw`] is decoded lazily, while the other variants are already decoded.
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Raw(encoding, payload) => LazyValue::new(encoding, payload),
+            value => {
+                let (encoding, payload) = value.clone().encode();
+                LazyValue {
+                    encoding,
+                    payload,
+                    decoded: Some(value),
+                }
+            }
+        }
+    }
+}
+
+type BufferAllocator = Box<dyn Fn() -> Box<[u8]> + Send + Sync>;
+
+/// A pool of pre-allocated buffers of the same size, to be used as payloads for [`Value::Raw`]
+/// (see [`Value::from_pooled_buffer()`]).
+///
+/// Publishing at high rate similarly-sized values allocated in such pool avoids the allocator churn
+/// of creating a new `Vec<u8>` for each value: once a value is sent and dropped its buffer is recycled.
+///
+/// # Examples
+/// ```
+/// use zenoh::*;
+///
+/// let pool = BufferPool::new(16, 65_536);
+/// let mut buf = pool.take();
+/// buf.write(&[0u8; 1024]).unwrap();
+/// let value = Value::from_pooled_buffer(net::encoding::APP_OCTET_STREAM, buf);
+/// ```
+pub struct BufferPool {
+    pool: RecyclingObjectPool<Box<[u8]>, BufferAllocator>,
+    buffer_size: usize,
+}
+
+impl BufferPool {
+    /// Creates a pool of `num` buffers of `buffer_size` bytes each.
+    pub fn new(num: usize, buffer_size: usize) -> BufferPool {
+        let allocator: BufferAllocator =
+            Box::new(move || vec![0u8; buffer_size].into_boxed_slice());
+        BufferPool {
+            pool: RecyclingObjectPool::new(num, allocator),
+            buffer_size,
+        }
+    }
+
+    /// Returns the size of the buffers in this pool.
+    pub fn buffer_size(&self) -> usize {
+        self.buffer_size
+    }
+
+    /// Takes a buffer from the pool.  
+    /// If the pool is empty (all its buffers being in use) a new buffer is allocated,
+    /// that won't be recycled in the pool.
+    pub fn take(&self) -> PooledBuffer {
+        PooledBuffer {
+            buf: self.pool.try_take().unwrap_or_else(|| self.pool.alloc()),
+            len: 0,
+        }
+    }
+}
+
+impl fmt::Debug for BufferPool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BufferPool{{ buffer_size: {} }}", self.buffer_size)
+    }
+}
+
+/// A buffer taken from a [`BufferPool`].
+pub struct PooledBuffer {
+    buf: RecyclingObject<Box<[u8]>>,
+    len: usize,
+}
+
+impl PooledBuffer {
+    /// Returns the capacity of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns the length of the data written in the buffer.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no data was written in the buffer.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Sets the length of the data written in the buffer
+    /// (e.g. after a direct write via [`PooledBuffer::as_mut_slice()`]).
+    ///
+    /// # Panics
+    /// Panics if `len` is greater than the capacity.
+    pub fn set_len(&mut self, len: usize) {
+        assert!(len <= self.capacity());
+        self.len = len;
+    }
+
+    /// Returns the whole buffer (up to its capacity) as a mutable slice.
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        &mut self.buf
+    }
+
+    /// Appends `data` to the buffer.  
+    /// Returns `Err(`[`ZError`]`)` if the remaining capacity is not sufficient.
+    pub fn write(&mut self, data: &[u8]) -> ZResult<()> {
+        let end = self.len + data.len();
+        if end > self.capacity() {
+            return zerror!(ZErrorKind::BufferOverflow {
+                missing: end - self.capacity()
+            });
+        }
+        self.buf[self.len..end].copy_from_slice(data);
+        self.len = end;
+        Ok(())
+    }
+}
+
+impl From<PooledBuffer> for ZBuf {
+    fn from(buf: PooledBuffer) -> Self {
+        ZSlice::new(ZSliceBuffer::from(buf.buf), 0, buf.len).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+
+    fn hash(value: &Value) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn assert_same(v1: Value, v2: Value) {
+        assert_eq!(v1, v2);
+        assert_eq!(hash(&v1), hash(&v2));
+    }
+
+    #[test]
+    fn canonical_equality() {
+        assert_same(
+            Value::Json(r#"{"a": 1, "b": [true, null]}"#.to_string()),
+            Value::Json(r#"{ "b":[true,null], "a":1 }"#.to_string()),
+        );
+        assert_same(
+            Value::Json(r#"{"a": 1}"#.to_string()),
+            Value::Raw(APP_JSON, br#"{"a":1}"#.to_vec().into()),
+        );
+        assert_same(
+            Value::Integer(3),
+            Value::Raw(APP_INTEGER, b"3".to_vec().into()),
+        );
+        assert_same(
+            Value::Properties("a=1;b=2".into()),
+            Value::Properties("b=2;a=1".into()),
+        );
+        assert_same(
+            Value::StringUtf8("hello".to_string()),
+            Value::Raw(STRING, b"hello".to_vec().into()),
+        );
+        assert_ne!(Value::StringUtf8("3".to_string()), Value::Integer(3));
+        assert_ne!(
+            Value::Json(r#"{"a": 1}"#.to_string()),
+            Value::Json(r#"{"a": 2}"#.to_string())
+        );
+        assert_ne!(
+            Value::Raw(APP_OCTET_STREAM, vec![1u8].into()),
+            Value::Raw(IMG_PNG, vec![1u8].into())
+        );
+    }
+
+    #[test]
+    fn http_content_types() {
+        assert_eq!(
+            Value::from_http("TEXT/PLAIN; charset=utf-8", b"hello".to_vec()).unwrap(),
+            Value::StringUtf8("hello".to_string())
+        );
+        assert_eq!(
+            Value::from_http("", vec![1u8]).unwrap(),
+            Value::Raw(APP_OCTET_STREAM, vec![1u8].into())
+        );
+        match Value::from_http("image/png", vec![1u8]).unwrap() {
+            Value::Raw(encoding, _) => assert_eq!(encoding, IMG_PNG),
+            value => panic!("Unexpected value: {:?}", value),
+        }
+        let custom = Value::from_http(" application/x-protobuf; v=2 ", vec![0x08]).unwrap();
+        assert_eq!(custom.mime_type(), "application/x-protobuf; v=2");
+        let cbor = Value::from_http("application/cbor", vec![0xa0]).unwrap();
+        assert_eq!(cbor.mime_type(), "application/cbor");
+        assert!(Value::from_http("application/integer", b"x".to_vec()).is_err());
+        assert!(Value::from_http("text/plain", vec![0xff]).is_err());
+
+        let custom = |descr: &str| Value::Custom {
+            encoding_descr: descr.to_string(),
+            data: vec![0u8].into(),
+        };
+        assert_eq!(custom("cbor").mime_type(), "application/octet-stream");
+        assert_eq!(custom("a/b/c").mime_type(), "application/octet-stream");
+        assert_eq!(custom("/cbor").mime_type(), "application/octet-stream");
+        assert_eq!(
+            Value::Raw(APP_DECIMAL, b"1".to_vec().into()).mime_type(),
+            "text/plain; charset=utf-8"
+        );
+    }
+
+    #[test]
+    fn json_pointers() {
+        let value = Value::Raw(APP_JSON, br#"{"a/b": [1, {"c": 2}]}"#.to_vec().into());
+        assert_eq!(
+            value.json_pointer("/a~1b/1/c").unwrap(),
+            Some(serde_json::json!(2))
+        );
+        assert_eq!(
+            value.json_pointer("").unwrap(),
+            Some(serde_json::json!({"a/b": [1, {"c": 2}]}))
+        );
+        assert_eq!(value.json_pointer("/a~1b/2").unwrap(), None);
+        assert!(value.json_pointer("a").is_err());
+        assert!(Value::Json("{".to_string()).json_pointer("").is_err());
+    }
+
+    #[test]
+    fn redaction() {
+        let value = Value::Raw(
+            APP_JSON,
+            br#"{"keys": ["k1", "k2"], "id": 1}"#.to_vec().into(),
+        );
+        assert_eq!(
+            value.redacted(&["/keys/1", "/keys/5", "/id/x"]),
+            Value::Json(r#"{"keys": ["k1", "***"], "id": 1}"#.to_string())
+        );
+        assert_eq!(value.redacted(&[]), value);
+        // an invalid JSON is entirely redacted
+        assert_eq!(
+            Value::Json("{".to_string()).redacted(&["/a"]),
+            Value::StringUtf8("***".to_string())
+        );
+        assert_eq!(
+            Value::Properties("a=1;b=2".into()).redacted(&["/b", "/c", "a"]),
+            Value::Properties("a=1;b=***".into())
+        );
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn decimals() {
+        use rust_decimal::Decimal;
+        use std::str::FromStr;
+
+        // a remote peer may not encode it canonically
+        assert_same(
+            Value::Raw(APP_DECIMAL, b"1.50".to_vec().into()),
+            Value::decimal(Decimal::from_str("1.5").unwrap()),
+        );
+        assert_ne!(
+            Value::decimal(Decimal::from_str("1.5").unwrap()),
+            Value::decimal(Decimal::from_str("1.5000000000000001").unwrap())
+        );
+        assert_eq!(
+            Value::decimal(Decimal::from_str("-12.500").unwrap())
+                .encode()
+                .1
+                .to_vec(),
+            b"-12.5".to_vec()
+        );
+        assert_eq!(Value::Integer(7).as_decimal().unwrap(), Decimal::from(7));
+        assert!(Value::Float(1.5).as_decimal().is_err());
+        assert!(Value::Raw(APP_DECIMAL, b"abc".to_vec().into())
+            .as_decimal()
+            .is_err());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_documents() {
+        let value = Value::from_toml_str("[a]\nb = 'c' # comment\n").unwrap();
+        assert_eq!(value.as_toml().unwrap()["a"]["b"].as_str(), Some("c"));
+        assert_eq!(
+            Value::from_toml_str(&value.as_toml().unwrap().to_string()).unwrap(),
+            value
+        );
+        assert!(Value::from_toml_str("[a").is_err());
+        assert!(Value::Json("{}".to_string()).as_toml().is_err());
+        assert!(Value::Raw(APP_TOML, b"= 1".to_vec().into())
+            .as_toml()
+            .is_err());
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn ndarrays() {
+        let scalar = Value::from_ndarray(&ndarray::arr0(1.5f32));
+        assert_eq!(
+            scalar.encoding_descr(),
+            "application/x-ndarray; dtype=f32; shape="
+        );
+        assert_eq!(
+            scalar.as_ndarray::<f32>().unwrap(),
+            ndarray::arr0(1.5f32).into_dyn()
+        );
+
+        // the elements are in row-major order, whatever the memory layout
+        let matrix = ndarray::arr2(&[[1u16, 2], [3, 4]]);
+        let value = Value::from_ndarray(&matrix.t());
+        match &value {
+            Value::Custom { data, .. } => assert_eq!(data.to_vec(), vec![1, 0, 3, 0, 2, 0, 4, 0]),
+            value => panic!("Unexpected value: {:?}", value),
+        }
+        assert_eq!(
+            value.as_ndarray::<u16>().unwrap(),
+            matrix.t().to_owned().into_dyn()
+        );
+
+        let custom = |descr: &str, data: Vec<u8>| Value::Custom {
+            encoding_descr: descr.to_string(),
+            data: data.into(),
+        };
+        assert!(
+            custom("application/x-ndarray; dtype=u8; shape=2,2", vec![1, 2, 3])
+                .as_ndarray::<u8>()
+                .is_err()
+        );
+        assert!(
+            custom("application/x-ndarray; dtype=u8; shape=2,x", vec![1, 2])
+                .as_ndarray::<u8>()
+                .is_err()
+        );
+        // an overflowing shape is rejected rather than wrapping around
+        let huge = format!(
+            "application/x-ndarray; dtype=u16; shape={},{}",
+            usize::MAX,
+            2
+        );
+        assert!(custom(&huge, vec![]).as_ndarray::<u16>().is_err());
+        let huge = format!("application/x-ndarray; dtype=u64; shape={}", usize::MAX / 4);
+        assert!(custom(&huge, vec![]).as_ndarray::<u64>().is_err());
+        assert!(custom("application/x-ndarray; dtype=u8", vec![1])
+            .as_ndarray::<u8>()
+            .is_err());
+        assert!(custom("application/x-ndarray; dtype=i8; shape=1", vec![1])
+            .as_ndarray::<u8>()
+            .is_err());
+        assert!(
+            custom("application/octet-stream; dtype=u8; shape=1", vec![1])
+                .as_ndarray::<u8>()
+                .is_err()
+        );
+    }
+}
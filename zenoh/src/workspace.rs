@@ -11,25 +11,49 @@
 // Contributors:
 //   ADLINK zenoh team, <zenoh@adlink-labs.tech>
 //
-use crate::net::queryable::EVAL;
+use crate::net::config::{
+    ZN_KEYRING_KEY, ZN_MAX_PUT_SIZE_DEFAULT, ZN_MAX_PUT_SIZE_KEY, ZN_MAX_VALUE_SIZE_DEFAULT,
+    ZN_MAX_VALUE_SIZE_KEY, ZN_STREAM_CHUNK_SIZE_DEFAULT, ZN_STREAM_CHUNK_SIZE_KEY,
+    ZN_STREAM_CHUNK_SIZE_STR, ZN_TYPED_ENCODING_DEFAULT, ZN_TYPED_ENCODING_KEY,
+};
+use crate::net::protocol::core::rname;
+use crate::net::queryable::{ALL_KINDS, COMPLETE, EVAL, STORAGE};
 use crate::net::{
-    data_kind, encoding, CallbackSubscriber, CongestionControl, DataInfo, Query,
-    QueryConsolidation, QueryTarget, Queryable, Receiver, RecvError, RecvTimeoutError, Reliability,
-    RepliesSender, Reply, ReplyReceiver, ResKey, Sample, SampleReceiver, Session, SubInfo, SubMode,
-    Subscriber, TryRecvError, ZBuf, ZFuture, ZInt,
+    data_kind, encoding, CallbackSubscriber, CongestionControl, ConsolidationMode, DataInfo,
+    PeerId, Priority, Query, QueryConsolidation, QueryTarget, Queryable, QueryableState, Receiver,
+    RecvError, RecvTimeoutError, Reliability, RepliesSender, Reply, ReplyReceiver, ResKey, Sample,
+    SampleReceiver, Session, SubInfo, SubMode, Subscriber, SubscriberState, Target, TryRecvError,
+    ZBuf, ZFuture, ZInt, ZPinBoxFuture,
 };
 use crate::utils::new_reception_timestamp;
 use crate::{
-    zready, Path, PathExpr, Selector, Timestamp, Value, ZError, ZErrorKind, ZResult, Zenoh,
+    checksum, value_stream, ChangeStreamExt, ChecksumAlgo, DeltaPublisher, IntegrityError, Journal,
+    JournalOrigin, JsonField, Keyring, Reduce, SnapshotOnTick, TypedEncoding, ValueReader,
+};
+use crate::{
+    zpinbox, zready, CancellationToken, GetMethod, Path, PathExpr, Selector, Timestamp, Transform,
+    Value, ZError, ZErrorKind, ZResult, Zenoh, PROP_AFTER, PROP_APPEND, PROP_INCREMENT, PROP_LIMIT,
 };
+use async_std::channel::TrySendError;
 use async_std::pin::Pin;
 use async_std::task::{Context, Poll};
+use event_listener::EventListener;
+use futures::io::{AsyncRead, AsyncReadExt};
+use futures_lite::future;
 use futures_lite::stream::{Stream, StreamExt};
-use log::{debug, warn};
+use log::{debug, trace, warn};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::convert::TryInto;
 use std::fmt;
-use std::time::{Duration, Instant};
-use zenoh_util::zerror;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+use zenoh_util::{zerror, zerror2};
+
+// The size of the chunks read by Workspace::put_from_async_read()
+const PUT_READ_CHUNK_SIZE: usize = 64 * 1024;
 
 /// A Workspace to operate on zenoh.
 ///
@@ -64,16 +88,54 @@ use zenoh_util::zerror;
 pub struct Workspace<'a> {
     zenoh: &'a Zenoh,
     prefix: Option<Path>,
+    separator: char,
+    // the subscribers and queryables declared via this Workspace (see Workspace::close()),
+    // the undeclared ones being pruned on each declaration
+    subscribers: Mutex<Vec<Weak<SubscriberState>>>,
+    queryables: Mutex<Vec<Weak<QueryableState>>>,
+    // the last result of each selector queried via Workspace::get_stale_on_error(), with its reception time
+    stale_cache: Arc<Mutex<StaleCache>>,
+    // the keys to encrypt and decrypt the values end-to-end (see Workspace::put_encrypted())
+    keyring: Option<Arc<Keyring>>,
+    // the default encoding of the values put with Workspace::put_typed()
+    typed_encoding: TypedEncoding,
+    // the journal of the changes issued and observed via this Workspace (see Workspace::with_journal())
+    journal: Option<Arc<Journal>>,
 }
 
 const LOCAL_ROUTER_PREFIX: &str = "/@/router/local";
 
+// The cache of Workspace::get_stale_on_error(), by selector
+type StaleCache = HashMap<String, (Instant, Vec<Data>)>;
+
 impl Workspace<'_> {
     pub(crate) fn new(
         zenoh: &Zenoh,
         prefix: Option<Path>,
     ) -> impl ZFuture<Output = ZResult<Workspace<'_>>> {
-        zready(Ok(Workspace { zenoh, prefix }))
+        zready_try!({
+            let keyring = match zenoh.session.runtime.config.get(&ZN_KEYRING_KEY) {
+                Some(path) => Some(Arc::new(Keyring::from_file(path)?)),
+                None => None,
+            };
+            let typed_encoding = zenoh
+                .session
+                .runtime
+                .config
+                .get_or(&ZN_TYPED_ENCODING_KEY, ZN_TYPED_ENCODING_DEFAULT)
+                .parse()?;
+            Ok(Workspace {
+                zenoh,
+                prefix,
+                separator: '/',
+                subscribers: Mutex::new(vec![]),
+                queryables: Mutex::new(vec![]),
+                stale_cache: Arc::new(Mutex::new(HashMap::new())),
+                keyring,
+                typed_encoding,
+                journal: None,
+            })
+        })
     }
 
     /// Returns the prefix that was used to create this Workspace (calling [`Zenoh::workspace()`]).
@@ -81,6 +143,113 @@ impl Workspace<'_> {
         &self.prefix
     }
 
+    /// Sets the separator of the hierarchical keys used with the `*_mapped` operations
+    /// (e.g. [`Workspace::put_mapped()`]), for domains using another separator than `'/'`.  
+    /// With `'.'` as separator, the key `"a.b.c"` is mapped to the relative [`Path`] `"a/b/c"`.
+    /// A literal separator or `'\'` character within a key segment must be escaped with a `'\'`
+    /// (e.g. `"a\.b.c"` is mapped to `"a.b/c"`). See [`Path::from_mapped_key()`].
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh
+    ///     .workspace(Some("/demo/example".try_into().unwrap()))
+    ///     .await
+    ///     .unwrap()
+    ///     .with_separator('.');
+    /// // The following it equivalent to a PUT on "/demo/example/a/b/c".
+    /// workspace.put_mapped("a.b.c", "Hello World!".into()).await.unwrap();
+    /// assert_eq!(workspace.unmap_path(&"/demo/example/a/b/c".try_into().unwrap()), "a.b.c");
+    /// # })
+    /// ```
+    pub fn with_separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Sets the [`Keyring`] used to encrypt the values put with [`Workspace::put_encrypted()`], and to decrypt
+    /// the encrypted values received by this Workspace (subscriptions and gets), in place of the keyring
+    /// configured with the `"keyring"` property (see [`ZN_KEYRING_KEY`](crate::net::config::ZN_KEYRING_KEY)).
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    ///
+    /// let mut keyring = Keyring::new();
+    /// keyring.insert("tenant-a", Keyring::generate_key());
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap().with_keyring(keyring);
+    /// # })
+    /// ```
+    pub fn with_keyring(mut self, keyring: Keyring) -> Self {
+        self.keyring = Some(Arc::new(keyring));
+        self
+    }
+
+    /// Sets the default [`TypedEncoding`] of the values put with [`Workspace::put_typed()`], in place of the
+    /// encoding configured with the `"typed_encoding"` property
+    /// (see [`ZN_TYPED_ENCODING_KEY`](crate::net::config::ZN_TYPED_ENCODING_KEY), `"json"` by default).
+    pub fn with_typed_encoding(mut self, encoding: TypedEncoding) -> Self {
+        self.typed_encoding = encoding;
+        self
+    }
+
+    /// Sets a [`Journal`] recording all the changes issued via this Workspace (puts and deletes),
+    /// and all the changes observed by its subscriptions, for auditing and recovery.  
+    /// The journaling doesn't block the operations (see [`Journal`] for the file format and rotation).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let journal = Journal::open("/var/log/zenoh/journal.log", JournalOptions::default()).unwrap();
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap().with_journal(journal);
+    /// workspace.put(&"/demo/example/hello".try_into().unwrap(), "Hello World!".into()).await.unwrap();
+    /// // the journal now contains: {"seq":0,"origin":"issued","kind":"PUT","path":"/demo/example/hello",...}
+    /// # })
+    /// ```
+    pub fn with_journal(mut self, journal: Journal) -> Self {
+        self.journal = Some(Arc::new(journal));
+        self
+    }
+
+    /// Returns the separator of the hierarchical keys used with the `*_mapped` operations
+    /// (`'/'` by default).
+    pub fn separator(&self) -> char {
+        self.separator
+    }
+
+    /// Maps a hierarchical key using this Workspace's separator to a [`Path`] relative to this Workspace.
+    pub fn map_key(&self, key: &str) -> ZResult<Path> {
+        Path::from_mapped_key(key, self.separator)
+    }
+
+    /// Maps back a [`Path`] (e.g. of a received [`Change`] or [`Data`]) to a hierarchical key
+    /// using this Workspace's separator.
+    /// If the Path is within this Workspace's prefix, the returned key is relative to this prefix.
+    pub fn unmap_path(&self, path: &Path) -> String {
+        let relative = match &self.prefix {
+            Some(prefix) => path
+                .as_str()
+                .strip_prefix(prefix.as_str())
+                .filter(|p| p.starts_with('/'))
+                .map(|p| &p[1..]),
+            None => path.as_str().strip_prefix('/'),
+        };
+        match relative {
+            Some(p) if !p.is_empty() => Path { p: p.into() }.to_mapped_key(self.separator),
+            _ => path.to_mapped_key(self.separator),
+        }
+    }
+
     /// Returns the zenoh-net [`Session`] used by this workspace.
     /// This is for advanced use cases requiring fine usage of the zenoh-net API.
     #[inline]
@@ -88,6 +257,49 @@ impl Workspace<'_> {
         &self.zenoh.session
     }
 
+    // Returns an error if the encoded value is larger than the configured `max_put_size`.
+    fn check_put_size(&self, payload: &ZBuf) -> ZResult<()> {
+        check_size(
+            payload.len(),
+            size_limit(self.session(), ZN_MAX_PUT_SIZE_KEY, ZN_MAX_PUT_SIZE_DEFAULT),
+        )
+    }
+
+    // Returns the configured `max_value_size` for the received values.
+    fn max_value_size(&self) -> usize {
+        size_limit(
+            self.session(),
+            ZN_MAX_VALUE_SIZE_KEY,
+            ZN_MAX_VALUE_SIZE_DEFAULT,
+        )
+    }
+
+    // Records a change issued via this Workspace into its journal, if any.
+    fn journal_issued(
+        &self,
+        reskey: &ResKey,
+        kind: ZInt,
+        encoding: ZInt,
+        payload: &ZBuf,
+        timestamp: Option<&Timestamp>,
+    ) {
+        if let (Some(journal), ResKey::RName(name)) = (&self.journal, reskey) {
+            let kind = ChangeKind::from(kind);
+            let payload = match kind {
+                ChangeKind::Delete => None,
+                _ => Some(payload),
+            };
+            journal.record(
+                JournalOrigin::Issued,
+                name,
+                kind,
+                encoding,
+                payload,
+                timestamp,
+            );
+        }
+    }
+
     fn canonicalize(&self, path: &str) -> ZResult<String> {
         let abs_path = if path.starts_with('/') {
             path.to_string()
@@ -118,7 +330,15 @@ impl Workspace<'_> {
     }
 
     fn pathexpr_to_reskey(&self, path: &PathExpr) -> ZResult<ResKey> {
-        self.canonicalize(path.as_str()).map(ResKey::from)
+        self.canonicalize(path.included()).map(ResKey::from)
+    }
+
+    // The exclusions of a PathExpr can't be declared to the network: they're canonicalized to be applied client-side
+    fn pathexpr_exclusions(&self, path: &PathExpr) -> ZResult<Vec<String>> {
+        path.exclusions()
+            .iter()
+            .map(|e| self.canonicalize(e))
+            .collect()
     }
 
     /// Put a [`Path`]/[`Value`] into zenoh.  
@@ -142,165 +362,159 @@ impl Workspace<'_> {
     pub fn put(&self, path: &Path, value: Value) -> impl ZFuture<Output = ZResult<()>> {
         debug!("put on {:?}", path);
         let (encoding, payload) = value.encode();
+        if let Err(e) = self.check_put_size(&payload) {
+            return zready(Err(e));
+        }
         zready(match self.path_to_reskey(path) {
-            Ok(reskey) => self
-                .session()
-                .write_ext(
-                    &reskey,
-                    payload,
-                    encoding,
-                    data_kind::PUT,
-                    CongestionControl::Drop, // @TODO: Define the right congestion control value for the put
-                )
-                .wait(),
+            Ok(reskey) => {
+                self.journal_issued(&reskey, data_kind::PUT, encoding, &payload, None);
+                self.session()
+                    .write_ext(
+                        &reskey,
+                        payload,
+                        encoding,
+                        data_kind::PUT,
+                        CongestionControl::Drop, // @TODO: Define the right congestion control value for the put
+                    )
+                    .wait()
+            }
             Err(e) => Err(e),
         })
     }
 
-    /// Delete a [`Path`] and its [`Value`] from zenoh.  
-    /// The corresponding [`Change`] will be received by all matching subscribers and all matching storages.
+    /// Put a [`Path`] and a `serde` serializable `value` into zenoh, serialized with the default
+    /// [`TypedEncoding`] of this Workspace. This default is configured with the `"typed_encoding"` property
+    /// (see [`ZN_TYPED_ENCODING_KEY`](crate::net::config::ZN_TYPED_ENCODING_KEY), `"json"` by default)
+    /// and can be overridden with [`Workspace::with_typed_encoding()`] for this Workspace,
+    /// or with [`Workspace::put_typed_with()`] for a single put.  
+    /// The receivers deserialize it with [`Data::decode_typed()`] or [`Value::decode_typed()`], whatever its
+    /// encoding.
     /// Note that the [`Path`] can be absolute or relative to this Workspace.
     ///
     /// # Examples
     /// ```
     /// # async_std::task::block_on(async {
     /// use zenoh::*;
+    /// use std::collections::HashMap;
     /// use std::convert::TryInto;
     ///
     /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
     /// let workspace = zenoh.workspace(None).await.unwrap();
-    /// workspace.delete(
-    ///     &"/demo/example/hello".try_into().unwrap()
-    /// ).await.unwrap();
+    /// let mut position = HashMap::new();
+    /// position.insert("x", 1.5);
+    /// position.insert("y", -2.0);
+    /// workspace.put_typed(&"/demo/example/position".try_into().unwrap(), &position).await.unwrap();
     /// # })
     /// ```
-    pub fn delete(&self, path: &Path) -> impl ZFuture<Output = ZResult<()>> {
-        debug!("delete on {:?}", path);
-        zready(match self.path_to_reskey(path) {
-            Ok(reskey) => self
-                .session()
-                .write_ext(
-                    &reskey,
-                    ZBuf::new(),
-                    encoding::NONE,
-                    data_kind::DELETE,
-                    CongestionControl::Drop, // @TODO: Define the right congestion control value for the delete
-                )
-                .wait(),
-            Err(e) => Err(e),
-        })
+    pub fn put_typed<T>(&self, path: &Path, value: &T) -> impl ZFuture<Output = ZResult<()>>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        self.put_typed_with(path, value, self.typed_encoding)
     }
 
-    /// Get a selection of [`Path`]/[`Value`] from zenoh.  
-    /// The selection is returned as a [`async_std::stream::Stream`] of [`Data`].
-    /// Note that the [`Selector`] can be absolute or relative to this Workspace.
+    /// Put a [`Path`] and a `serde` serializable `value` into zenoh, serialized with `encoding`
+    /// rather than the default [`TypedEncoding`] of this Workspace (see [`Workspace::put_typed()`]).
+    /// Note that the [`Path`] can be absolute or relative to this Workspace.
+    pub fn put_typed_with<T>(
+        &self,
+        path: &Path,
+        value: &T,
+        encoding: TypedEncoding,
+    ) -> impl ZFuture<Output = ZResult<()>>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        debug!("put_typed on {:?} as {:?}", path, encoding);
+        zready_try!({ self.put(path, Value::from_typed(value, encoding)?).wait() })
+    }
+
+    /// Put a [`Path`]/[`Value`] into zenoh, the [`Value`] being encrypted end-to-end with the key `key_id`
+    /// of this Workspace's [`Keyring`] (see [`Workspace::with_keyring()`]).  
+    /// The routers and the storages only see the ciphertext (a [`Value::Raw`] with the
+    /// [`APP_ENCRYPTED`](crate::net::encoding::APP_ENCRYPTED) encoding): only the subscribers and the Workspaces
+    /// whose keyring has the key `key_id` receive the decrypted [`Value`]. The others receive it still encrypted.  
+    /// Returns an error if this Workspace has no keyring, or if its keyring has no key `key_id`.
+    /// Note that the [`Path`] can be absolute or relative to this Workspace.
     ///
     /// # Examples
     /// ```
     /// # async_std::task::block_on(async {
     /// use zenoh::*;
     /// use std::convert::TryInto;
-    /// use futures::prelude::*;
     ///
+    /// let mut keyring = Keyring::new();
+    /// keyring.insert("tenant-a", Keyring::generate_key());
     /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
-    /// let workspace = zenoh.workspace(None).await.unwrap();
-    /// let mut data_stream = workspace.get(&"/demo/example/**".try_into().unwrap()).await.unwrap();
-    /// while let Some(data) = data_stream.next().await {
-    ///     println!(">> {} : {:?} at {}",
-    ///         data.path, data.value, data.timestamp
-    ///     )
-    /// }
+    /// let workspace = zenoh.workspace(None).await.unwrap().with_keyring(keyring);
+    /// workspace.put_encrypted(
+    ///     &"/demo/example/secret".try_into().unwrap(),
+    ///     "Hello World!".into(),
+    ///     "tenant-a"
+    /// ).await.unwrap();
     /// # })
     /// ```
-    pub fn get(&self, selector: &Selector) -> impl ZFuture<Output = ZResult<DataReceiver>> {
-        debug!("get on {}", selector);
+    pub fn put_encrypted(
+        &self,
+        path: &Path,
+        value: Value,
+        key_id: &str,
+    ) -> impl ZFuture<Output = ZResult<()>> {
+        debug!("put_encrypted on {:?} with key {}", path, key_id);
         zready_try!({
-            let reskey = self.pathexpr_to_reskey(&selector.path_expr)?;
-            let decode_value = !selector.properties.contains_key("raw");
-            let consolidation = if selector.has_time_range() {
-                QueryConsolidation::none()
-            } else {
-                QueryConsolidation::default()
-            };
-
-            self.session()
-                .query(
-                    &reskey,
-                    &selector.predicate,
-                    QueryTarget::default(),
-                    consolidation,
-                )
-                .wait()
-                .map(|receiver| DataReceiver {
-                    receiver,
-                    decode_value,
+            let keyring = self.keyring.as_ref().ok_or_else(|| {
+                zerror2!(ZErrorKind::Other {
+                    descr: "No keyring configured for this Workspace".to_string()
                 })
+            })?;
+            self.put(path, keyring.encrypt(key_id, value)?).wait()
         })
     }
 
-    /// Subscribe to changes for a selection of [`Path`]/[`Value`] (specified via a [`Selector`]) from zenoh.  
-    /// The changes are returned as [`async_std::stream::Stream`] of [`Change`].
-    /// This Stream will never end unless it's dropped or explicitly closed via [`ChangeReceiver::close()`].
-    /// Note that the [`Selector`] can be absolute or relative to this Workspace.
+    /// Put a [`Value`] into zenoh, unless `token` has been cancelled.  
+    /// Returns an error of kind [`ZErrorKind::Cancelled`] if `token` was cancelled before the put.
+    /// Note that a put doesn't wait for any acknowledgement: once started, it completes without blocking
+    /// and can't be cancelled anymore.
     ///
     /// # Examples
-    /// ```no_run
+    /// ```
     /// # async_std::task::block_on(async {
     /// use zenoh::*;
     /// use std::convert::TryInto;
-    /// use futures::prelude::*;
     ///
     /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
     /// let workspace = zenoh.workspace(None).await.unwrap();
-    /// let mut change_stream =
-    ///     workspace.subscribe(&"/demo/example/**".try_into().unwrap()).await.unwrap();
-    /// while let Some(change) = change_stream.next().await {
-    ///     println!(">> {:?} for {} : {:?} at {}",
-    ///         change.kind, change.path, change.value, change.timestamp
-    ///     )
-    /// }
+    /// let token = CancellationToken::new();
+    /// token.cancel();
+    /// let result = workspace.put_with_cancel(
+    ///     &"/demo/example/hello".try_into().unwrap(),
+    ///     "Hello World!".into(),
+    ///     &token,
+    /// ).await;
+    /// assert_eq!(result.unwrap_err().get_kind(), &ZErrorKind::Cancelled {});
     /// # })
     /// ```
-    pub fn subscribe(
+    pub fn put_with_cancel(
         &self,
-        selector: &Selector,
-    ) -> impl ZFuture<Output = ZResult<ChangeReceiver<'_>>> {
-        debug!("subscribe on {}", selector);
-        zready_try!({
-            if selector.filter.is_some() {
-                return zerror!(ZErrorKind::Other {
-                    descr: "Filter not supported in selector for subscribe()".into()
-                });
-            }
-            if selector.fragment.is_some() {
-                return zerror!(ZErrorKind::Other {
-                    descr: "Fragment not supported in selector for subscribe()".into()
-                });
-            }
-            let decode_value = !selector.properties.contains_key("raw");
-
-            let reskey = self.pathexpr_to_reskey(&selector.path_expr)?;
-            let sub_info = SubInfo {
-                reliability: Reliability::Reliable,
-                mode: SubMode::Push,
-                period: None,
-            };
-
-            self.session()
-                .declare_subscriber(&reskey, &sub_info)
-                .wait()
-                .map(|mut subscriber| ChangeReceiver {
-                    receiver: subscriber.receiver().clone(),
-                    subscriber,
-                    decode_value,
-                })
-        })
+        path: &Path,
+        value: Value,
+        token: &CancellationToken,
+    ) -> impl ZFuture<Output = ZResult<()>> {
+        if token.is_cancelled() {
+            debug!("put on {:?} cancelled", path);
+            return zready(zerror!(ZErrorKind::Cancelled {}));
+        }
+        zready(self.put(path, value).wait())
     }
 
-    /// Subscribe to changes for a selection of [`Path`]/[`Value`] (specified via a [`Selector`]) from zenoh.  
-    /// For each change, the `callback` will be called.
-    /// A [`SubscriberHandle`] is returned, allowing to close the subscription via [`SubscriberHandle::close()`].
-    /// Note that the [`Selector`] can be absolute or relative to this Workspace.
+    /// Put a [`Path`]/[`Value`] into zenoh, without delivering the corresponding [`Change`] to the
+    /// subscribers of this Workspace's [`Session`].  
+    /// By default (i.e. with [`put()`](Workspace::put)) a put is also delivered to the matching subscribers
+    /// of the Session it is made from (unless its `local_routing` config is `false`).
+    /// The subscribers of a same Session (and thus of all the Workspaces of a same [`Zenoh`](crate::Zenoh)
+    /// instance) are identified as "own" subscribers: they are skipped by this operation, while the subscribers
+    /// of any other Session (even in the same process) and the storages still receive the [`Change`].
+    /// Note that the [`Path`] can be absolute or relative to this Workspace.
     ///
     /// # Examples
     /// ```
@@ -310,101 +524,2307 @@ impl Workspace<'_> {
     ///
     /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
     /// let workspace = zenoh.workspace(None).await.unwrap();
-    /// let mut change_stream = workspace.subscribe_with_callback(
-    ///     &"/demo/example/**".try_into().unwrap(),
-    ///     move |change| {
-    ///        println!(">> {:?} for {} : {:?} at {}",
-    ///            change.kind, change.path, change.value, change.timestamp
-    ///        )}
+    /// workspace.put_without_local_echo(
+    ///     &"/demo/example/hello".try_into().unwrap(),
+    ///     "Hello World!".into()
     /// ).await.unwrap();
     /// # })
     /// ```
-    pub fn subscribe_with_callback<SubscribeCallback>(
+    pub fn put_without_local_echo(
         &self,
-        selector: &Selector,
-        mut callback: SubscribeCallback,
-    ) -> impl ZFuture<Output = ZResult<SubscriberHandle<'_>>>
-    where
-        SubscribeCallback: FnMut(Change) + Send + Sync + 'static,
-    {
-        debug!("subscribe_with_callback on {}", selector);
-        zready_try!({
-            if selector.filter.is_some() {
-                return zerror!(ZErrorKind::Other {
-                    descr: "Filter not supported in selector for subscribe()".into()
-                });
-            }
-            if selector.fragment.is_some() {
-                return zerror!(ZErrorKind::Other {
-                    descr: "Fragment not supported in selector for subscribe()".into()
-                });
-            }
-            let decode_value = !selector.properties.contains_key("raw");
-
-            let reskey = self.pathexpr_to_reskey(&selector.path_expr)?;
-            let sub_info = SubInfo {
-                reliability: Reliability::Reliable,
-                mode: SubMode::Push,
-                period: None,
-            };
-
-            let subscriber = self
-                .session()
-                .declare_callback_subscriber(&reskey, &sub_info, move |sample| {
-                    match Change::from_sample(sample, decode_value) {
-                        Ok(change) => callback(change),
-                        Err(err) => warn!("Received an invalid Sample (drop it): {}", err),
-                    }
-                })
-                .wait()?;
-            Ok(SubscriberHandle { subscriber })
-        })
+        path: &Path,
+        value: Value,
+    ) -> impl ZFuture<Output = ZResult<()>> {
+        debug!("put_without_local_echo on {:?}", path);
+        let options = PutOptions::default().local_echo(false);
+        zready(self.put_with_timestamp(path, value, options, self.session().new_timestamp()))
     }
 
-    /// Registers an evaluation function under the provided [`PathExpr`].  
-    /// A [`async_std::stream::Stream`] of [`GetRequest`] is returned.
-    /// All `get` requests matching the [`PathExpr`] will be added to this stream as a [`GetRequest`],
-    /// allowing the implementation to send a reply as a result of the evaluation function via [`GetRequest::reply()`].
-    /// This Stream will never end unless it's dropped or explicitly closed via [`GetRequestStream::close()`].
-    /// Note that the [`PathExpr`] can be absolute or relative to this Workspace.
+    /// Put a [`Path`]/[`Value`] into zenoh, sending it immediately on each link instead of letting it wait
+    /// for other messages to fill its batch.  
+    /// When the `batch_timeout` config is set, the messages are batched to improve the throughput, at the cost
+    /// of up to `batch_timeout` of extra latency. This operation bypasses this delay for latency-critical values:
+    /// once the put is queued, the batch containing it is transmitted without waiting (on each transport),
+    /// while keeping the priority of the put (see [`PutOptions::flush()`] to combine it with other options).
+    /// Otherwise, it behaves as [`put()`](Workspace::put).
     ///
     /// # Examples
-    /// ```no_run
+    /// ```
     /// # async_std::task::block_on(async {
     /// use zenoh::*;
     /// use std::convert::TryInto;
-    /// use futures::prelude::*;
     ///
     /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
     /// let workspace = zenoh.workspace(None).await.unwrap();
-    /// let mut get_stream =
-    ///     workspace.register_eval(&"/demo/example/eval".try_into().unwrap()).await.unwrap();
-    /// while let Some(get_request) = get_stream.next().await {
-    ///    println!(
-    ///        ">> [Eval function] received get with selector: {}",
-    ///        get_request.selector
-    ///    );
-    ///    // return the Value as a result of this evaluation function :
-    ///    let v = Value::StringUtf8(format!("Result for get on {}", get_request.selector));
-    ///    get_request.reply_async("/demo/example/eval".try_into().unwrap(), v).await;
-    /// }
+    /// workspace.put_flush_now(
+    ///     &"/demo/example/alarm".try_into().unwrap(),
+    ///     "Emergency stop".into()
+    /// ).await.unwrap();
     /// # })
     /// ```
-    pub fn register_eval(
-        &self,
-        path_expr: &PathExpr,
-    ) -> impl ZFuture<Output = ZResult<GetRequestStream<'_>>> {
-        debug!("eval on {}", path_expr);
-        zready_try!({
-            let reskey = self.pathexpr_to_reskey(path_expr)?;
-
-            self.session()
-                .declare_queryable(&reskey, EVAL)
-                .wait()
-                .map(|queryable| GetRequestStream { queryable })
-        })
+    pub fn put_flush_now(&self, path: &Path, value: Value) -> impl ZFuture<Output = ZResult<()>> {
+        debug!("put_flush_now on {:?}", path);
+        let options = PutOptions::default().flush(true);
+        zready(self.put_with_timestamp(path, value, options, self.session().new_timestamp()))
     }
-}
+
+    /// Put a [`Path`]/[`Value`] into zenoh with the options `options`: the delivery options (congestion control,
+    /// priority, reliability and local echo) and the metadata attached to the [`Change`] (checksum, monotonic
+    /// stamp, sequence number and message id), each one being also set by a `put_*` variant for a single option.  
+    /// With the default [`PutOptions`], it behaves as [`put()`](Workspace::put).
+    /// Note that the [`Path`] can be absolute or relative to this Workspace.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use zenoh::net::CongestionControl;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// // a command which must not be lost, even under congestion
+    /// workspace.put_with(
+    ///     &"/demo/example/command".try_into().unwrap(),
+    ///     "start".into(),
+    ///     PutOptions::default()
+    ///         .congestion_control(CongestionControl::Block)
+    ///         .message_id(Uuid::new_v4())
+    /// ).await.unwrap();
+    /// # })
+    /// ```
+    pub fn put_with(
+        &self,
+        path: &Path,
+        value: Value,
+        options: PutOptions,
+    ) -> impl ZFuture<Output = ZResult<()>> {
+        debug!("put_with on {:?} with {:?}", path, options);
+        zready(self.put_with_timestamp(path, value, options, self.session().new_timestamp()))
+    }
+
+    // Puts a Value with the delivery options and the DataInfo extensions of `options`, and `timestamp`.
+    fn put_with_timestamp(
+        &self,
+        path: &Path,
+        value: Value,
+        options: PutOptions,
+        timestamp: Option<Timestamp>,
+    ) -> ZResult<()> {
+        let (encoding, payload) = value.encode();
+        self.check_put_size(&payload)?;
+        let mut info = DataInfo::new();
+        info.kind = Some(data_kind::PUT);
+        info.encoding = Some(encoding);
+        info.timestamp = timestamp;
+        info.checksum = options.checksum.map(|algo| algo.checksum(&payload));
+        info.monotonic_stamp = options.monotonic_stamp;
+        if let Some(sn) = options.sequence {
+            info.source_id = Some(self.session().runtime.pid);
+            info.source_sn = Some(sn);
+        }
+        info.message_id = options.message_id.map(|id| *id.as_bytes());
+        let reskey = self.path_to_reskey(path)?;
+        self.journal_issued(
+            &reskey,
+            data_kind::PUT,
+            encoding,
+            &payload,
+            info.timestamp.as_ref(),
+        );
+        self.session()
+            .write_with_info_ext(
+                &reskey,
+                payload,
+                info,
+                options.congestion_control,
+                options.priority,
+                options.reliability,
+                options.local_echo,
+            )
+            .wait()?;
+        if options.flush {
+            self.session().flush(options.priority);
+        }
+        Ok(())
+    }
+
+    /// Returns a [`DeltaPublisher`] for the successive values of `path`, sending only their differences
+    /// with the previous value when it's smaller (e.g. for large and slowly changing payloads).  
+    /// A keyframe (i.e. the full value) is sent every `keyframe_interval` values, so that the subscribers
+    /// joining mid-stream, or which missed a frame, get the values again. The subscribers reconstruct the
+    /// values with [`ChangeStreamExt::delta_decode()`](crate::ChangeStreamExt::delta_decode).  
+    /// Note that the [`Path`] can be absolute or relative to this Workspace.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut publisher = workspace.delta_publisher(&"/demo/example/frame".try_into().unwrap(), 100);
+    /// let mut frame = vec![0u8; 64 * 1024];
+    /// for i in 0..10u8 {
+    ///     frame[i as usize * 1000] = i;
+    ///     publisher.put(frame.clone().into()).await.unwrap();
+    /// }
+    /// # })
+    /// ```
+    pub fn delta_publisher(&self, path: &Path, keyframe_interval: usize) -> DeltaPublisher<'_> {
+        DeltaPublisher::new(self, path.clone(), keyframe_interval)
+    }
+
+    /// Put into zenoh a payload read from an asynchronous reader (e.g. a file or a socket), with the given encoding
+    /// (see [`encoding`](crate::net::encoding)).  
+    /// The reader is read until its end, and the payload is then put as a single value.
+    ///
+    /// Note that this is a staged buffering rather than a true streaming: a zenoh value is sent as a single
+    /// message and hence must be entirely read before being put. However, the payload is kept as the
+    /// sequence of the chunks read (up to 64 KiB each), without being copied into a contiguous buffer, and
+    /// the reading stops with an error as soon as the payload exceeds the configured `max_put_size`
+    /// (see [`ZN_MAX_PUT_SIZE_KEY`](crate::net::config::ZN_MAX_PUT_SIZE_KEY)).
+    /// The fragmentation of the large values is then handled by the zenoh transport.
+    /// For a true streaming, bounding the memory used whatever the size of the value, see
+    /// [`Workspace::put_stream()`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let file = async_std::fs::File::open("artifact.tar.gz").await.unwrap();
+    /// workspace.put_from_async_read(
+    ///     &"/demo/example/artifact".try_into().unwrap(),
+    ///     net::encoding::APP_OCTET_STREAM,
+    ///     file,
+    /// ).await.unwrap();
+    /// # })
+    /// ```
+    pub fn put_from_async_read<R>(
+        &self,
+        path: &Path,
+        encoding: ZInt,
+        mut reader: R,
+    ) -> impl ZFuture<Output = ZResult<()>>
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        debug!("put_from_async_read on {:?}", path);
+        let reskey = self.path_to_reskey(path);
+        let max_put_size = size_limit(self.session(), ZN_MAX_PUT_SIZE_KEY, ZN_MAX_PUT_SIZE_DEFAULT);
+        let session = self.session().clone();
+        let journal = self.journal.clone();
+        let path = path.clone();
+        zpinbox(async move {
+            let reskey = reskey?;
+            let mut payload = ZBuf::new();
+            loop {
+                let mut chunk = vec![0u8; PUT_READ_CHUNK_SIZE];
+                let n = reader.read(&mut chunk).await.map_err(|e| {
+                    zerror2!(ZErrorKind::IoError {
+                        descr: format!("Failed to read the value to put on {}: {}", path, e)
+                    })
+                })?;
+                if n == 0 {
+                    break;
+                }
+                check_size(payload.len() + n, max_put_size)?;
+                chunk.truncate(n);
+                chunk.shrink_to_fit();
+                payload.add_zslice(chunk.into());
+            }
+            if let (Some(journal), ResKey::RName(name)) = (&journal, &reskey) {
+                journal.record(
+                    JournalOrigin::Issued,
+                    name,
+                    ChangeKind::Put,
+                    encoding,
+                    Some(&payload),
+                    None,
+                );
+            }
+            session
+                .write_ext(
+                    &reskey,
+                    payload,
+                    encoding,
+                    data_kind::PUT,
+                    CongestionControl::Drop, // @TODO: Define the right congestion control value for the put
+                )
+                .await
+        })
+    }
+
+    /// Put into zenoh a payload read from an asynchronous reader (e.g. a camera frame), with the given encoding,
+    /// sending it incrementally in chunks.  
+    /// The reader is read chunk by chunk, each chunk being put as soon as it's read under the
+    /// [`STREAM_CHUNKS_SEGMENT`](crate::STREAM_CHUNKS_SEGMENT) of the path: only one chunk is held in memory,
+    /// whatever the size of the payload. The chunk size is configured with the `stream_chunk_size` property
+    /// (see [`ZN_STREAM_CHUNK_SIZE_KEY`](crate::net::config::ZN_STREAM_CHUNK_SIZE_KEY), 64 KiB by default).
+    /// Once the reader is read until its end, a manifest of the chunks is put on the path itself.
+    /// The chunks and the manifest are sent reliably, blocking under congestion rather than being dropped.
+    /// Note that the [`Path`] can be absolute or relative to this Workspace.
+    ///
+    /// The subscribers receive the manifest, and read the payload with [`Change::value_reader()`]
+    /// (or [`Data::value_reader()`] for a `get`), which fetches the chunks one by one: the chunks must be kept
+    /// by a storage (e.g. a storage on `/demo/camera/**` for a payload put on `/demo/camera/frame`).
+    /// The chunks of the previous stream put on the same path (found with a `get` of its manifest before putting
+    /// the chunks) beyond the chunks of this stream are deleted once its manifest is put.
+    /// See [`ValueReader`](crate::ValueReader) for the format of the chunks and of the manifest.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let frame = async_std::fs::File::open("frame.jpg").await.unwrap();
+    /// workspace.put_stream(
+    ///     &"/demo/camera/frame".try_into().unwrap(),
+    ///     "image/jpeg",
+    ///     frame,
+    /// ).await.unwrap();
+    /// # })
+    /// ```
+    pub fn put_stream<R>(
+        &self,
+        path: &Path,
+        encoding_descr: &str,
+        mut reader: R,
+    ) -> impl ZFuture<Output = ZResult<()>>
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        debug!("put_stream on {:?}", path);
+        let abs_path = self.canonicalize(path.as_str());
+        let chunk_size = size_limit(
+            self.session(),
+            ZN_STREAM_CHUNK_SIZE_KEY,
+            ZN_STREAM_CHUNK_SIZE_DEFAULT,
+        );
+        let max_put_size = size_limit(self.session(), ZN_MAX_PUT_SIZE_KEY, ZN_MAX_PUT_SIZE_DEFAULT);
+        let session = self.session().clone();
+        let journal = self.journal.clone();
+        let encoding_descr = encoding_descr.to_string();
+        zpinbox(async move {
+            let abs_path = abs_path?;
+            check_size(chunk_size + value_stream::STREAM_ID_SIZE, max_put_size)?;
+            if chunk_size == 0 {
+                return zerror!(ZErrorKind::Other {
+                    descr: format!("Invalid {}: 0", ZN_STREAM_CHUNK_SIZE_STR)
+                });
+            }
+            let write = |name: String, encoding: ZInt, payload: ZBuf| {
+                if let Some(journal) = &journal {
+                    journal.record(
+                        JournalOrigin::Issued,
+                        &name,
+                        ChangeKind::Put,
+                        encoding,
+                        Some(&payload),
+                        None,
+                    );
+                }
+                let mut info = DataInfo::new();
+                info.kind = Some(data_kind::PUT);
+                info.encoding = Some(encoding);
+                info.timestamp = session.new_timestamp();
+                session.write_with_info_ext(
+                    &ResKey::from(name),
+                    payload,
+                    info,
+                    CongestionControl::Block,
+                    Priority::default(),
+                    Reliability::Reliable,
+                    true,
+                )
+            };
+            let previous_chunks = value_stream::stored_chunks(&session, &abs_path).await;
+            let id = *Uuid::new_v4().as_bytes();
+            let mut chunks = 0u64;
+            let mut size = 0u64;
+            loop {
+                // fill a whole chunk, unless the reader ends
+                let mut chunk = vec![0u8; chunk_size];
+                let mut n = 0;
+                while n < chunk_size {
+                    match reader.read(&mut chunk[n..]).await {
+                        Ok(0) => break,
+                        Ok(read) => n += read,
+                        Err(e) => {
+                            return zerror!(ZErrorKind::IoError {
+                                descr: format!(
+                                    "Failed to read the value to put on {}: {}",
+                                    abs_path, e
+                                )
+                            })
+                        }
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+                chunk.truncate(n);
+                write(
+                    value_stream::chunk_path(&abs_path, chunks),
+                    encoding::APP_OCTET_STREAM,
+                    value_stream::chunk_payload(&id, chunk),
+                )
+                .await?;
+                chunks += 1;
+                size += n as u64;
+            }
+            let (encoding, payload) =
+                value_stream::manifest(&id, &encoding_descr, chunks, size).encode();
+            write(abs_path.clone(), encoding, payload).await?;
+            // delete the chunks of the previous stream that were not overwritten
+            for index in chunks..previous_chunks {
+                let name = value_stream::chunk_path(&abs_path, index);
+                if let Some(journal) = &journal {
+                    journal.record(
+                        JournalOrigin::Issued,
+                        &name,
+                        ChangeKind::Delete,
+                        encoding::NONE,
+                        None,
+                        None,
+                    );
+                }
+                session
+                    .write_ext(
+                        &ResKey::from(name),
+                        ZBuf::new(),
+                        encoding::NONE,
+                        data_kind::DELETE,
+                        CongestionControl::Block,
+                    )
+                    .await?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Put a [`Value`] into zenoh for a hierarchical key using this Workspace's separator
+    /// (see [`Workspace::with_separator()`]).  
+    /// This is equivalent to a [`Workspace::put()`] on the [`Path`] returned by [`Workspace::map_key()`].
+    pub fn put_mapped(&self, key: &str, value: Value) -> impl ZFuture<Output = ZResult<()>> {
+        zready(
+            self.map_key(key)
+                .and_then(|path| self.put(&path, value).wait()),
+        )
+    }
+
+    /// Put a [`Path`]/[`Value`] into zenoh and returns the [`Timestamp`] assigned to this put.  
+    /// The corresponding [`Change`] received by all matching subscribers and all matching storages will have
+    /// this same timestamp, allowing to correlate them with this put.
+    /// Note that the [`Path`] can be absolute or relative to this Workspace.
+    ///
+    /// The timestamp is generated by the HLC of this session if timestamping is enabled
+    /// (see [`ZN_ADD_TIMESTAMP_KEY`](crate::net::config::ZN_ADD_TIMESTAMP_KEY)). Otherwise, it's generated from
+    /// the system time, with this session's id.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let timestamp = workspace.put_timestamped(
+    ///     &"/demo/example/hello".try_into().unwrap(),
+    ///     "Hello World!".into()
+    /// ).await.unwrap();
+    /// println!("Put at {}", timestamp);
+    /// # })
+    /// ```
+    pub fn put_timestamped(
+        &self,
+        path: &Path,
+        value: Value,
+    ) -> impl ZFuture<Output = ZResult<Timestamp>> {
+        debug!("put_timestamped on {:?}", path);
+        let timestamp = self.session().new_timestamp_or_system_time();
+        zready(
+            self.put_with_timestamp(path, value, PutOptions::default(), Some(timestamp.clone()))
+                .map(|()| timestamp),
+        )
+    }
+
+    /// Put a [`Path`]/[`Value`] into zenoh, attaching a checksum of the encoded value
+    /// computed with `algo`.  
+    /// The subscribers can check the integrity of the received value calling [`Change::verify_checksum()`].
+    /// Note that the [`Path`] can be absolute or relative to this Workspace.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// workspace.put_with_checksum(
+    ///     &"/demo/example/hello".try_into().unwrap(),
+    ///     "Hello World!".into(),
+    ///     ChecksumAlgo::Crc32
+    /// ).await.unwrap();
+    /// # })
+    /// ```
+    pub fn put_with_checksum(
+        &self,
+        path: &Path,
+        value: Value,
+        algo: ChecksumAlgo,
+    ) -> impl ZFuture<Output = ZResult<()>> {
+        debug!("put_with_checksum on {:?} ({})", path, algo);
+        let options = PutOptions::default().checksum(algo);
+        zready(self.put_with_timestamp(path, value, options, self.session().new_timestamp()))
+    }
+
+    /// Put a [`Path`]/[`Value`] into zenoh, attaching a monotonic `stamp` (e.g. a count of nanoseconds
+    /// since an [`Instant`] taken at startup), received by the subscribers in [`Change::monotonic_stamp`].  
+    /// Unlike the [`Timestamp`] of a change (a HLC time, comparable across nodes), the monotonic stamp
+    /// comes from a clock local to the publisher: it's not comparable across publishers nor across
+    /// restarts of a same publisher, but the intervals between the stamps of a same publisher are
+    /// immune to the adjustments of the system clock.  
+    /// Note that the [`Path`] can be absolute or relative to this Workspace.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use std::time::Instant;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let start = Instant::now();
+    /// workspace.put_with_monotonic_stamp(
+    ///     &"/demo/example/position".try_into().unwrap(),
+    ///     1.5.into(),
+    ///     start.elapsed().as_nanos() as u64
+    /// ).await.unwrap();
+    /// # })
+    /// ```
+    pub fn put_with_monotonic_stamp(
+        &self,
+        path: &Path,
+        value: Value,
+        stamp: u64,
+    ) -> impl ZFuture<Output = ZResult<()>> {
+        debug!("put_with_monotonic_stamp on {:?} ({})", path, stamp);
+        let options = PutOptions::default().monotonic_stamp(stamp);
+        zready(self.put_with_timestamp(path, value, options, self.session().new_timestamp()))
+    }
+
+    /// Put a [`Path`]/[`Value`] into zenoh, attaching the sequence number `sn` together with the zenoh id of
+    /// this Workspace's [`Session`], received by the subscribers in [`Change::sequence`].  
+    /// A publisher numbering its puts consecutively allows its subscribers to detect the puts they missed
+    /// (e.g. with a best-effort subscription), with [`ChangeStreamExt::detect_gaps()`].
+    /// The sequence is tracked per publishing Session, whatever the path: the puts on all the paths matched by
+    /// the subscribers must share a same sequence (e.g. a counter per Session, or per Workspace if a single one
+    /// is used to publish).
+    /// The sequence numbers wrap around: the number following `u64::MAX` is `0`.  
+    /// Note that the [`Path`] can be absolute or relative to this Workspace.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// for sn in 0..3u64 {
+    ///     workspace.put_with_sequence(
+    ///         &"/demo/example/counter".try_into().unwrap(),
+    ///         (sn as i64).into(),
+    ///         sn
+    ///     ).await.unwrap();
+    /// }
+    /// # })
+    /// ```
+    pub fn put_with_sequence(
+        &self,
+        path: &Path,
+        value: Value,
+        sn: u64,
+    ) -> impl ZFuture<Output = ZResult<()>> {
+        debug!("put_with_sequence on {:?} ({})", path, sn);
+        let options = PutOptions::default().sequence(sn);
+        zready(self.put_with_timestamp(path, value, options, self.session().new_timestamp()))
+    }
+
+    /// Put a [`Path`]/[`Value`] into zenoh, attaching the unique `message_id`, received by the subscribers
+    /// in [`Change::message_id`].  
+    /// Combined with an at-least-once delivery (e.g. a replay after a reconnection), this allows the subscribers
+    /// to drop the duplicates with [`ChangeStreamExt::dedup_by_id()`]. A republished message (e.g. after a failed
+    /// put) must keep its message id.  
+    /// Note that the [`Path`] can be absolute or relative to this Workspace.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// workspace.put_with_message_id(
+    ///     &"/demo/example/orders/42".try_into().unwrap(),
+    ///     "Hello World!".into(),
+    ///     Uuid::new_v4()
+    /// ).await.unwrap();
+    /// # })
+    /// ```
+    pub fn put_with_message_id(
+        &self,
+        path: &Path,
+        value: Value,
+        message_id: Uuid,
+    ) -> impl ZFuture<Output = ZResult<()>> {
+        debug!("put_with_message_id on {:?} ({})", path, message_id);
+        let options = PutOptions::default().message_id(message_id);
+        zready(self.put_with_timestamp(path, value, options, self.session().new_timestamp()))
+    }
+
+    /// Put the same [`Value`] into zenoh for each [`Path`] of `paths`.  
+    /// Unlike a put on a path expression, the target paths are explicit. The value is encoded only once
+    /// and all the puts share the same [`Timestamp`] (if timestamping is enabled for this session).
+    /// The corresponding [`Change`]s will be received by all matching subscribers and all matching storages.
+    /// Note that each [`Path`] can be absolute or relative to this Workspace.
+    ///
+    /// Returns the result of the put for each path, in the same order than `paths`.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let results = workspace.put_many(
+    ///     &["/primary/x".try_into().unwrap(), "/backup/x".try_into().unwrap()],
+    ///     "Hello World!".into()
+    /// ).await;
+    /// assert!(results.iter().all(|r| r.is_ok()));
+    /// # })
+    /// ```
+    pub fn put_many(
+        &self,
+        paths: &[Path],
+        value: Value,
+    ) -> impl ZFuture<Output = Vec<ZResult<()>>> {
+        debug!("put_many on {:?}", paths);
+        let (encoding, payload) = value.encode();
+        if self.check_put_size(&payload).is_err() {
+            return zready(
+                paths
+                    .iter()
+                    .map(|_| self.check_put_size(&payload))
+                    .collect(),
+            );
+        }
+        let mut info = DataInfo::new();
+        info.kind = Some(data_kind::PUT);
+        info.encoding = Some(encoding);
+        info.timestamp = self.session().new_timestamp();
+        zready(
+            paths
+                .iter()
+                .map(|path| match self.path_to_reskey(path) {
+                    Ok(reskey) => {
+                        self.journal_issued(
+                            &reskey,
+                            data_kind::PUT,
+                            encoding,
+                            &payload,
+                            info.timestamp.as_ref(),
+                        );
+                        self.session()
+                            .write_with_info(
+                                &reskey,
+                                payload.clone(),
+                                info.clone(),
+                                CongestionControl::Drop, // @TODO: Define the right congestion control value for the put
+                            )
+                            .wait()
+                    }
+                    Err(e) => Err(e),
+                })
+                .collect(),
+        )
+    }
+
+    /// Delete a [`Path`] and its [`Value`] from zenoh.  
+    /// The corresponding [`Change`] will be received by all matching subscribers and all matching storages.
+    /// Note that the [`Path`] can be absolute or relative to this Workspace.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// workspace.delete(
+    ///     &"/demo/example/hello".try_into().unwrap()
+    /// ).await.unwrap();
+    /// # })
+    /// ```
+    pub fn delete(&self, path: &Path) -> impl ZFuture<Output = ZResult<()>> {
+        debug!("delete on {:?}", path);
+        zready(match self.path_to_reskey(path) {
+            Ok(reskey) => {
+                self.journal_issued(
+                    &reskey,
+                    data_kind::DELETE,
+                    encoding::NONE,
+                    &ZBuf::new(),
+                    None,
+                );
+                self.session()
+                    .write_ext(
+                        &reskey,
+                        ZBuf::new(),
+                        encoding::NONE,
+                        data_kind::DELETE,
+                        CongestionControl::Drop, // @TODO: Define the right congestion control value for the delete
+                    )
+                    .wait()
+            }
+            Err(e) => Err(e),
+        })
+    }
+
+    /// Delete a [`Path`] and its [`Value`] from zenoh and returns the [`Timestamp`] assigned to this deletion.  
+    /// The corresponding [`Change`] (of kind [`ChangeKind::Delete`] and without value) received by all matching
+    /// subscribers and all matching storages will have this same timestamp, allowing to correlate them with
+    /// this deletion.
+    /// Deleting a [`Path`] without value succeeds as well (the deletion is still notified to the subscribers).
+    /// Note that the [`Path`] can be absolute or relative to this Workspace.
+    ///
+    /// The timestamp is generated as for [`Workspace::put_timestamped()`].
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let timestamp = workspace.delete_timestamped(
+    ///     &"/demo/example/hello".try_into().unwrap()
+    /// ).await.unwrap();
+    /// println!("Deleted at {}", timestamp);
+    /// # })
+    /// ```
+    pub fn delete_timestamped(&self, path: &Path) -> impl ZFuture<Output = ZResult<Timestamp>> {
+        debug!("delete_timestamped on {:?}", path);
+        let timestamp = self.session().new_timestamp_or_system_time();
+        let mut info = DataInfo::new();
+        info.kind = Some(data_kind::DELETE);
+        info.encoding = Some(encoding::NONE);
+        info.timestamp = Some(timestamp.clone());
+        zready(match self.path_to_reskey(path) {
+            Ok(reskey) => {
+                self.journal_issued(
+                    &reskey,
+                    data_kind::DELETE,
+                    encoding::NONE,
+                    &ZBuf::new(),
+                    info.timestamp.as_ref(),
+                );
+                self.session()
+                    .write_with_info(
+                        &reskey,
+                        ZBuf::new(),
+                        info,
+                        CongestionControl::Drop, // @TODO: Define the right congestion control value for the delete
+                    )
+                    .wait()
+                    .map(|()| timestamp)
+            }
+            Err(e) => Err(e),
+        })
+    }
+
+    /// Delete a hierarchical key using this Workspace's separator (see [`Workspace::with_separator()`]).  
+    /// This is equivalent to a [`Workspace::delete()`] on the [`Path`] returned by [`Workspace::map_key()`].
+    pub fn delete_mapped(&self, key: &str) -> impl ZFuture<Output = ZResult<()>> {
+        zready(self.map_key(key).and_then(|path| self.delete(&path).wait()))
+    }
+
+    /// Atomically increment by `delta` the integer [`Value`] stored for a [`Path`] in zenoh,
+    /// returning the resulting value.  
+    /// The read-modify-write is performed by the matching storage (the best matching one, if several),
+    /// so concurrent increments from different workspaces are never lost.
+    /// If no value is stored yet for the path, the increment starts from `0`.
+    /// The corresponding [`Change`] will be received by all matching subscribers and all matching storages.
+    /// Note that the [`Path`] can be absolute or relative to this Workspace.
+    ///
+    /// An error is returned if no storage replied, or if the stored value is not an integer
+    /// (a [`ZErrorKind::StorageError`] error for the error raised by the storage).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let count = workspace.increment(
+    ///     &"/demo/example/counter".try_into().unwrap(),
+    ///     1
+    /// ).await.unwrap();
+    /// # })
+    /// ```
+    pub fn increment(&self, path: &Path, delta: i64) -> impl ZFuture<Output = ZResult<i64>> {
+        debug!("increment on {:?} by {}", path, delta);
+        let reskey = self.path_to_reskey(path);
+        let session = self.session().clone();
+        zpinbox(async move {
+            let reskey = reskey?;
+            let query_target = QueryTarget {
+                kind: STORAGE,
+                target: Target::BestMatching,
+            };
+            let mut replies = session
+                .query(
+                    &reskey,
+                    &format!("?({}={})", PROP_INCREMENT, delta),
+                    query_target,
+                    QueryConsolidation::none(),
+                )
+                .await?;
+            match replies.next().await {
+                Some(reply) => match Value::from_sample(&reply.data, true)? {
+                    Some(Value::Integer(i)) => Ok(i),
+                    Some(Value::StringUtf8(descr)) => zerror!(ZErrorKind::StorageError {
+                        descr: format!("Increment on {} failed: {}", reskey, descr)
+                    }),
+                    _ => zerror!(ZErrorKind::ValueDecodingFailed {
+                        descr: format!("Increment on {} returned a non-integer value", reskey)
+                    }),
+                },
+                None if replies.is_overloaded() => zerror!(ZErrorKind::Other {
+                    descr: format!("Increment on {} rejected because of overload", reskey)
+                }),
+                None => zerror!(ZErrorKind::Other {
+                    descr: format!("No storage replied to increment on {}", reskey)
+                }),
+            }
+        })
+    }
+
+    /// Atomically append `element` to the JSON array [`Value`] stored for a [`Path`] in zenoh (e.g. an event log),
+    /// returning the resulting number of elements.  
+    /// The read-modify-write is performed by the matching storage (the best matching one, if several),
+    /// so concurrent appends from different workspaces are never lost.
+    /// If no value is stored yet for the path, the array `[element]` is stored.
+    /// Note that the [`Path`] can be absolute or relative to this Workspace.
+    ///
+    /// The element is converted to JSON as an element of an array created from a `Vec` (e.g. a [`Value::Integer`]
+    /// into a JSON number, a [`Value::Json`] into its JSON document, see [`Value::as_array()`]).
+    /// The matching subscribers and the other storages (e.g. replicas) receive a [`Change`] of kind
+    /// [`ChangeKind::Put`] whose value is the whole resulting array (as a [`Value::Json`]),
+    /// with the timestamp of the stored array.
+    ///
+    /// An error is returned if no storage replied, or if the stored value is not a JSON array
+    /// (a [`ZErrorKind::StorageError`] error, the stored value being left unchanged).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let len = workspace.append(
+    ///     &"/demo/example/events".try_into().unwrap(),
+    ///     Value::Json(r#"{"event": "door opened"}"#.to_string())
+    /// ).await.unwrap();
+    /// # })
+    /// ```
+    pub fn append(&self, path: &Path, element: Value) -> impl ZFuture<Output = ZResult<usize>> {
+        debug!("append on {:?}", path);
+        let reskey = self.path_to_reskey(path);
+        let element = base64::encode_config(
+            crate::values::json_element(element).to_string(),
+            base64::URL_SAFE_NO_PAD,
+        );
+        let session = self.session().clone();
+        zpinbox(async move {
+            let reskey = reskey?;
+            let query_target = QueryTarget {
+                kind: STORAGE,
+                target: Target::BestMatching,
+            };
+            let mut replies = session
+                .query(
+                    &reskey,
+                    &format!("?({}={})", PROP_APPEND, element),
+                    query_target,
+                    QueryConsolidation::none(),
+                )
+                .await?;
+            match replies.next().await {
+                Some(reply) => match Value::from_sample(&reply.data, true)? {
+                    Some(Value::Integer(len)) if len >= 0 => Ok(len as usize),
+                    Some(Value::StringUtf8(descr)) => zerror!(ZErrorKind::StorageError {
+                        descr: format!("Append on {} failed: {}", reskey, descr)
+                    }),
+                    _ => zerror!(ZErrorKind::ValueDecodingFailed {
+                        descr: format!("Append on {} returned an invalid length", reskey)
+                    }),
+                },
+                None if replies.is_overloaded() => zerror!(ZErrorKind::Other {
+                    descr: format!("Append on {} rejected because of overload", reskey)
+                }),
+                None => zerror!(ZErrorKind::Other {
+                    descr: format!("No storage replied to append on {}", reskey)
+                }),
+            }
+        })
+    }
+
+    /// Get a selection of [`Path`]/[`Value`] from zenoh.  
+    /// The selection is returned as a [`async_std::stream::Stream`] of [`Data`].
+    /// Note that the [`Selector`] can be absolute or relative to this Workspace.
+    ///
+    /// If the [`Selector`] has a `_target` property, only the replies from the specified responder are
+    /// returned (see [`Selector::target()`]).
+    ///
+    /// If the [`Selector`] has a `_time` property (e.g. `/demo/example/**?(_time=[now()-5m..now()])`),
+    /// the storages keeping an history reply all the values within this time range, ordered by timestamp,
+    /// and the other storages reply their latest value (see [`Selector::time_range()`]).
+    /// Returns an error if the time range is invalid.
+    ///
+    /// If the [`PathExpr`] of the [`Selector`] has exclusions (e.g. `/demo/** !/demo/debug/**`),
+    /// the replies for the excluded paths are dropped on reception.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut data_stream = workspace.get(&"/demo/example/**".try_into().unwrap()).await.unwrap();
+    /// while let Some(data) = data_stream.next().await {
+    ///     println!(">> {} : {:?} at {}",
+    ///         data.path, data.value, data.timestamp
+    ///     )
+    /// }
+    ///
+    /// // the history of the last 5 minutes
+    /// let mut history = workspace
+    ///     .get(&"/demo/example/**?(_time=[now()-5m..now()])".try_into().unwrap())
+    ///     .await
+    ///     .unwrap();
+    /// while let Some(data) = history.next().await {
+    ///     println!(">> {} : {:?} at {}", data.path, data.value, data.timestamp)
+    /// }
+    /// # })
+    /// ```
+    pub fn get(&self, selector: &Selector) -> impl ZFuture<Output = ZResult<DataReceiver>> {
+        debug!("get on {}", selector);
+        let consolidation = if selector.has_time_range() {
+            QueryConsolidation::none()
+        } else {
+            QueryConsolidation::default()
+        };
+        // the time range is evaluated by the storages, but an invalid one is reported to the caller
+        zready(
+            selector
+                .time_range()
+                .and_then(|_| self.query_data(selector, consolidation)),
+        )
+    }
+
+    /// Get a selection of [`Path`]/[`Value`] from zenoh, collecting all the [`Data`] unless `token` is cancelled.  
+    /// If `token` is cancelled before all the replies are received, the query is aborted (the further replies
+    /// are dropped) and an error of kind [`ZErrorKind::Cancelled`] is returned.
+    /// Note that the [`Selector`] can be absolute or relative to this Workspace.
+    ///
+    /// This can be combined with a timeout (e.g. [`async_std::future::timeout()`]), a timeout
+    /// and a cancellation being handled independently.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let token = CancellationToken::new();
+    /// // e.g. cancel on application shutdown
+    /// let shutdown = token.clone();
+    /// # shutdown.cancel();
+    /// match workspace.get_with_cancel(&"/demo/example/**".try_into().unwrap(), &token).await {
+    ///     Ok(data) => println!("received {} values", data.len()),
+    ///     Err(e) if e.get_kind() == &ZErrorKind::Cancelled {} => println!("cancelled"),
+    ///     Err(e) => println!("error: {}", e),
+    /// }
+    /// # })
+    /// ```
+    pub fn get_with_cancel(
+        &self,
+        selector: &Selector,
+        token: &CancellationToken,
+    ) -> impl ZFuture<Output = ZResult<Vec<Data>>> {
+        debug!("get_with_cancel on {}", selector);
+        let receiver = if token.is_cancelled() {
+            zerror!(ZErrorKind::Cancelled {})
+        } else {
+            let consolidation = if selector.has_time_range() {
+                QueryConsolidation::none()
+            } else {
+                QueryConsolidation::default()
+            };
+            self.query_data(selector, consolidation)
+        };
+        let token = token.clone();
+        zpinbox(async move {
+            let mut receiver = receiver?;
+            let collect = async {
+                let mut data = vec![];
+                while let Some(d) = receiver.next().await {
+                    data.push(d);
+                }
+                Ok(data)
+            };
+            let cancelled = async {
+                token.cancelled().await;
+                debug!("get_with_cancel: cancelled");
+                zerror!(ZErrorKind::Cancelled {})
+            };
+            future::or(cancelled, collect).await
+        })
+    }
+
+    /// Get a selection of [`Path`]/[`Value`] from zenoh, consolidating the replies with a custom function.  
+    /// All the replies are received without any consolidation by zenoh. For each [`Path`], the first received
+    /// [`Data`] is retained and each subsequent [`Data`] for the same path is folded into it calling
+    /// `consolidate(&mut retained, data)`.
+    /// Once all the replies are received, the consolidated [`Data`] are returned, one per [`Path`],
+    /// in the order in which each path was first received.
+    /// Note that the [`Selector`] can be absolute or relative to this Workspace.
+    ///
+    /// Note that the order in which the replies are received from multiple storages or evals is not deterministic.
+    /// For a deterministic result, `consolidate` should be commutative and associative (e.g. a sum)
+    /// or it should decide on the Data's content (e.g. keep the one with the greatest timestamp).
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// // sum all the integer values replied for each path
+    /// let sums = workspace.get_consolidated_with(
+    ///     &"/demo/example/counters/**".try_into().unwrap(),
+    ///     |sum, data| if let (Value::Integer(s), Value::Integer(i)) = (&mut sum.value, data.value) {
+    ///         *s += i
+    ///     }
+    /// ).await.unwrap();
+    /// for data in sums {
+    ///     println!(">> {} : {:?}", data.path, data.value)
+    /// }
+    /// # })
+    /// ```
+    pub fn get_consolidated_with<F>(
+        &self,
+        selector: &Selector,
+        mut consolidate: F,
+    ) -> impl ZFuture<Output = ZResult<Vec<Data>>>
+    where
+        F: FnMut(&mut Data, Data) + Send + 'static,
+    {
+        debug!("get_consolidated_with on {}", selector);
+        let receiver = self.query_data(selector, QueryConsolidation::none());
+        zpinbox(async move {
+            let mut receiver = receiver?;
+            let mut results: Vec<Data> = vec![];
+            let mut indexes: HashMap<Path, usize> = HashMap::new();
+            while let Some(data) = receiver.next().await {
+                match indexes.get(&data.path) {
+                    Some(i) => consolidate(&mut results[*i], data),
+                    None => {
+                        indexes.insert(data.path.clone(), results.len());
+                        results.push(data);
+                    }
+                }
+            }
+            Ok(results)
+        })
+    }
+
+    /// Get a selection of [`Path`]/[`Value`] from zenoh, collecting all the [`Data`]
+    /// together with a [`QuerySummary`].  
+    /// The [`QuerySummary`] allows to distinguish a selection for which no storage or eval replied
+    /// (`responders == 0`) from a selection whose replies were all filtered out client-side (`responders > 0`
+    /// and `matched == 0`, e.g. by the exclusions of the [`PathExpr`] or the filter of the [`Selector`]).  
+    /// Note that a storage or eval without any matching value doesn't reply, and so isn't counted.
+    /// Note that the [`Selector`] can be absolute or relative to this Workspace.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let (data, summary) = workspace.get_collect(&"/demo/example/**".try_into().unwrap()).await.unwrap();
+    /// if summary.responders == 0 {
+    ///     println!("No storage or eval replied for /demo/example/**");
+    /// } else if data.is_empty() {
+    ///     println!("All the values for /demo/example/** were filtered out");
+    /// }
+    /// # })
+    /// ```
+    pub fn get_collect(
+        &self,
+        selector: &Selector,
+    ) -> impl ZFuture<Output = ZResult<(Vec<Data>, QuerySummary)>> {
+        debug!("get_collect on {}", selector);
+        let consolidation = if selector.has_time_range() {
+            QueryConsolidation::none()
+        } else {
+            QueryConsolidation::default()
+        };
+        let receiver = self.query_data(selector, consolidation);
+        zpinbox(async move {
+            let mut receiver = receiver?;
+            let mut data = vec![];
+            let mut repliers = HashSet::new();
+            while let Some(reply) = receiver.receiver.next().await {
+                repliers.insert((reply.replier_id, reply.replier_kind));
+                if !receiver.accept(&reply) {
+                    continue;
+                }
+                match receiver.transcode(reply) {
+                    Ok(d) => data.push(d),
+                    Err(err) if is_too_large(&err) => return Err(err),
+                    Err(err) => warn!("Received an invalid Reply (drop it): {}", err),
+                }
+            }
+            let summary = QuerySummary {
+                responders: repliers.len(),
+                matched: data.len(),
+                overloaded: receiver.is_overloaded(),
+            };
+            Ok((data, summary))
+        })
+    }
+
+    /// Get a selection of [`Path`]/[`Value`] from zenoh, merged into a single JSON document mirroring the
+    /// hierarchy of the paths: each segment of a path is a nested object, the values being the leaves
+    /// (e.g. `/conf/a/b` and `/conf/c` give `{"conf": {"a": {"b": ...}, "c": ...}}`).  
+    /// The [`Value::Json`], [`Value::Integer`], [`Value::Float`], [`Value::StringUtf8`] and [`Value::Properties`]
+    /// are decoded as JSON. Any other value is embedded as a string with its encoding noted, as
+    /// `{"@value": <string>, "@encoding": <encoding>}` (plus `"@base64": true` if the string is base64 encoded).  
+    /// When a path is both a leaf and a branch (e.g. `/conf/a` and `/conf/a/b` both have a value), the value
+    /// of the leaf is kept in the object of the branch under the `"@value"` key. If several values are received
+    /// for a same path, the one with the greatest timestamp is kept.  
+    /// Note that the [`Selector`] can be absolute or relative to this Workspace, but the JSON document
+    /// always mirrors the absolute paths.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let tree = workspace.get_json_tree(&"/demo/example/**".try_into().unwrap()).await.unwrap();
+    /// println!("{}", serde_json::to_string_pretty(&tree).unwrap());
+    /// # })
+    /// ```
+    pub fn get_json_tree(
+        &self,
+        selector: &Selector,
+    ) -> impl ZFuture<Output = ZResult<serde_json::Value>> {
+        debug!("get_json_tree on {}", selector);
+        let receiver = self.query_data(selector, QueryConsolidation::default());
+        zpinbox(async move {
+            let mut receiver = receiver?;
+            let mut data = vec![];
+            while let Some(d) = receiver.next().await {
+                data.push(d);
+            }
+            data.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+            let mut tree = JsonTree::default();
+            for d in data {
+                tree.insert(&d.path, d.value.to_json_leaf());
+            }
+            Ok(tree.into_json())
+        })
+    }
+
+    /// Get a selection of [`Path`]/[`Value`] from zenoh, collecting the [`Data`] received before `timeout`.  
+    /// If some storages or evals didn't reply in time, the [`Data`] collected so far are returned
+    /// with [`Completeness::PartialTimeout`]. Otherwise, they're returned with [`Completeness::Complete`].
+    /// Note that the [`Selector`] can be absolute or relative to this Workspace.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use std::time::Duration;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let (data, completeness) = workspace
+    ///     .get_collect_timeout(&"/demo/example/**".try_into().unwrap(), Duration::from_secs(1))
+    ///     .await
+    ///     .unwrap();
+    /// if completeness == Completeness::PartialTimeout {
+    ///     println!("showing {} results (timed out waiting for more)", data.len());
+    /// }
+    /// # })
+    /// ```
+    pub fn get_collect_timeout(
+        &self,
+        selector: &Selector,
+        timeout: Duration,
+    ) -> impl ZFuture<Output = ZResult<(Vec<Data>, Completeness)>> {
+        debug!("get_collect_timeout on {}", selector);
+        // the replies must be delivered as they arrive (the default Full consolidation holds them
+        // until the end of the query, so nothing would be collected on timeout)
+        let history = selector.has_time_range();
+        let consolidation = if history {
+            QueryConsolidation::none()
+        } else {
+            QueryConsolidation {
+                reception: ConsolidationMode::Lazy,
+                ..QueryConsolidation::default()
+            }
+        };
+        let receiver = self.query_data(selector, consolidation);
+        zpinbox(async move {
+            let mut receiver = receiver?;
+            let deadline = Instant::now() + timeout;
+            let mut data: Vec<Data> = vec![];
+            loop {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                match async_std::future::timeout(remaining, receiver.next()).await {
+                    Ok(Some(d)) if history => data.push(d),
+                    // without time range, Lazy reception may deliver several Data for a path
+                    Ok(Some(d)) => match data.iter_mut().find(|previous| previous.path == d.path) {
+                        Some(previous) if d.timestamp > previous.timestamp => *previous = d,
+                        Some(_) => (),
+                        None => data.push(d),
+                    },
+                    Ok(None) => return Ok((data, Completeness::Complete)),
+                    Err(_) => {
+                        debug!(
+                            "get_collect_timeout: timed out after {} replies",
+                            data.len()
+                        );
+                        return Ok((data, Completeness::PartialTimeout));
+                    }
+                }
+            }
+        })
+    }
+
+    /// Get a selection of [`Path`]/[`Value`] from zenoh, stopping as soon as `max_results` [`Data`] have been
+    /// received (e.g. for "is there at least one" or "top 10" queries that only need a sample).  
+    /// The [`Data`] are returned in the order they were received, at most one per [`Path`]: if a newer [`Data`]
+    /// is received for an already received [`Path`], it replaces the previous one without counting as a new result.
+    /// If the query completes with fewer results, all of them are returned.
+    /// Note that the [`Selector`] can be absolute or relative to this Workspace.
+    ///
+    /// Once `max_results` [`Data`] are received, the query is finalized: the replies still in flight are
+    /// dropped on reception, without being decoded (zenoh has no message to cancel a query on the responders,
+    /// which still send their replies).  
+    /// Note that early-stop and sorting are mutually exclusive: which [`Data`] are returned depends on the
+    /// order in which the storages and evals reply, and sorting them only sorts this sample. To get the first
+    /// `n` [`Data`] in the order of their paths, the whole selection must be collected (e.g. with
+    /// [`Workspace::get_paged()`]).
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let data = workspace.get_max_results(&"/demo/example/**".try_into().unwrap(), 1).await.unwrap();
+    /// if data.is_empty() {
+    ///     println!("No value for /demo/example/**");
+    /// }
+    /// # })
+    /// ```
+    pub fn get_max_results(
+        &self,
+        selector: &Selector,
+        max_results: usize,
+    ) -> impl ZFuture<Output = ZResult<Vec<Data>>> {
+        debug!("get_max_results({}) on {}", max_results, selector);
+        // the replies must be delivered as they arrive (the default Full consolidation holds them
+        // until the end of the query), and they are de-duplicated per path below
+        let consolidation = QueryConsolidation {
+            reception: ConsolidationMode::Lazy,
+            ..QueryConsolidation::default()
+        };
+        let receiver = self.query_data(selector, consolidation);
+        zpinbox(async move {
+            let mut receiver = receiver?;
+            let mut data: Vec<Data> = vec![];
+            while data.len() < max_results {
+                match receiver.next().await {
+                    Some(d) => match data.iter_mut().find(|previous| previous.path == d.path) {
+                        Some(previous) if d.timestamp > previous.timestamp => *previous = d,
+                        Some(_) => (),
+                        None => data.push(d),
+                    },
+                    None => return Ok(data),
+                }
+            }
+            debug!("get_max_results: stopped after {} results", data.len());
+            // dropping the receiver drops the remaining replies
+            drop(receiver);
+            Ok(data)
+        })
+    }
+
+    /// Get a selection of [`Path`]/[`Value`] from zenoh, falling back to the last known [`Data`] if the query fails
+    /// (e.g. to keep a dashboard showing the last known values during a network outage).
+    /// Note that the [`Selector`] can be absolute or relative to this Workspace.
+    ///
+    /// The query must complete within `timeout`. When it does, the [`Data`] are returned and cached in this
+    /// Workspace (replacing the previous result for the same selector). When the query can't be issued
+    /// (e.g. the session is closed) or doesn't complete within `timeout`, the cached result for the same selector
+    /// is returned if it was received less than `ttl` ago, with each [`Data`] marked as [`stale`](Data::is_stale).
+    /// Otherwise the error is returned (a [`ZErrorKind::Timeout`] error for a timeout).
+    /// Only a timeout or an unreachable infrastructure (a [`ZErrorKind::InvalidSession`], [`ZErrorKind::NoRouter`],
+    /// [`ZErrorKind::InvalidLink`] or [`ZErrorKind::IoError`] error) falls back to the cache: any other error
+    /// (e.g. an invalid selector) is returned as is.
+    ///
+    /// Note that a query completing without any reply (e.g. no storage is reachable) isn't a failure:
+    /// an empty result is returned (and cached).
+    /// The cache is only filled by this operation and is dropped with the Workspace.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use std::time::Duration;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let data = workspace
+    ///     .get_stale_on_error(
+    ///         &"/demo/example/**".try_into().unwrap(),
+    ///         Duration::from_secs(1),
+    ///         Duration::from_secs(60),
+    ///     )
+    ///     .await
+    ///     .unwrap();
+    /// for d in data {
+    ///     println!("{} : {:?}{}", d.path, d.value, if d.is_stale() { " (stale)" } else { "" });
+    /// }
+    /// # })
+    /// ```
+    pub fn get_stale_on_error(
+        &self,
+        selector: &Selector,
+        timeout: Duration,
+        ttl: Duration,
+    ) -> impl ZFuture<Output = ZResult<Vec<Data>>> {
+        debug!("get_stale_on_error on {}", selector);
+        let key = selector.to_string();
+        let consolidation = if selector.has_time_range() {
+            QueryConsolidation::none()
+        } else {
+            QueryConsolidation::default()
+        };
+        let receiver = self.query_data(selector, consolidation);
+        let cache = self.stale_cache.clone();
+        zpinbox(async move {
+            let result = match receiver {
+                Ok(mut receiver) => {
+                    let collect = async {
+                        let mut data = vec![];
+                        while let Some(d) = receiver.next().await {
+                            data.push(d);
+                        }
+                        data
+                    };
+                    async_std::future::timeout(timeout, collect)
+                        .await
+                        .map_err(|_| zerror2!(ZErrorKind::Timeout {}))
+                }
+                Err(e) => Err(e),
+            };
+            let mut cache = cache.lock().unwrap();
+            match result {
+                Ok(data) => {
+                    cache.insert(key, (Instant::now(), data.clone()));
+                    Ok(data)
+                }
+                Err(e) => match cache.get(&key) {
+                    Some((received, data)) if is_unreachable(&e) && received.elapsed() < ttl => {
+                        debug!("get_stale_on_error: {}, returning the cached result", e);
+                        Ok(data
+                            .iter()
+                            .cloned()
+                            .map(|d| Data { stale: true, ..d })
+                            .collect())
+                    }
+                    _ => Err(e),
+                },
+            }
+        })
+    }
+
+    /// Get a selection of [`Path`]/[`Value`] from zenoh, collecting the [`Data`] together with
+    /// per-responder timing metrics ([`GetMetrics`]).  
+    /// This allows to identify the storages or evals that are slow to reply (see [`GetMetrics::slow_responders()`]).
+    /// Note that the [`Selector`] can be absolute or relative to this Workspace.
+    ///
+    /// To measure the time at which each responder replies, the replies are not consolidated by zenoh.
+    /// Instead, they're consolidated once all received, keeping for each [`Path`] the [`Data`] with the greatest
+    /// timestamp (unless the selector specifies a time-range, in which case all the [`Data`] are returned).
+    /// Prefer [`Workspace::get()`] when the metrics are not needed.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use std::time::Duration;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let (data, metrics) = workspace
+    ///     .get_with_metrics(&"/demo/example/**".try_into().unwrap())
+    ///     .await
+    ///     .unwrap();
+    /// for slow in metrics.slow_responders(Duration::from_millis(100)) {
+    ///     println!("{} replied after {:?}", slow.zid, slow.elapsed);
+    /// }
+    /// # })
+    /// ```
+    pub fn get_with_metrics(
+        &self,
+        selector: &Selector,
+    ) -> impl ZFuture<Output = ZResult<(Vec<Data>, GetMetrics)>> {
+        debug!("get_with_metrics on {}", selector);
+        let consolidate = !selector.has_time_range();
+        let receiver = self.query_data(selector, QueryConsolidation::none());
+        zpinbox(async move {
+            let mut receiver = receiver?;
+            let start = Instant::now();
+            let mut data: Vec<Data> = vec![];
+            let mut indexes: HashMap<Path, usize> = HashMap::new();
+            let mut responders: Vec<ResponderMetrics> = vec![];
+            while let Some(reply) = receiver.receiver.next().await {
+                let elapsed = start.elapsed();
+                match responders.iter_mut().find(|r| r.zid == reply.replier_id) {
+                    Some(r) => {
+                        r.replies += 1;
+                        r.last_reply = elapsed;
+                    }
+                    None => responders.push(ResponderMetrics {
+                        zid: reply.replier_id,
+                        replies: 1,
+                        first_reply: elapsed,
+                        last_reply: elapsed,
+                    }),
+                }
+                if !receiver.accept(&reply) {
+                    continue;
+                }
+                let d = match receiver.transcode(reply) {
+                    Ok(d) => d,
+                    Err(err) if is_too_large(&err) => return Err(err),
+                    Err(err) => {
+                        warn!("Received an invalid Reply (drop it): {}", err);
+                        continue;
+                    }
+                };
+                match indexes.get(&d.path) {
+                    Some(i) if consolidate => {
+                        if d.timestamp > data[*i].timestamp {
+                            data[*i] = d;
+                        }
+                    }
+                    _ => {
+                        indexes.insert(d.path.clone(), data.len());
+                        data.push(d);
+                    }
+                }
+            }
+            let metrics = GetMetrics {
+                elapsed: start.elapsed(),
+                responders,
+            };
+            Ok((data, metrics))
+        })
+    }
+
+    /// Get a selection of [`Path`]/[`Value`] from zenoh, requiring that at least `quorum` responders (e.g. the replicas
+    /// of a storage) agree on the value of each [`Path`] (for reads needing consistency over replicas).  
+    /// Note that the [`Selector`] can be absolute or relative to this Workspace.
+    ///
+    /// All the matching storages and evals are queried, and their replies are collected until they all replied or
+    /// `timeout` expires. If the selector is a single [`Path`] (without wildcards), the replies are no longer
+    /// collected as soon as `quorum` responders agree on its value.
+    /// Two responders agree on a value if they replied the same encoding and payload (whatever its timestamp).
+    /// A responder is identified by its zenoh id and its kind (e.g. a storage and an eval declared by the
+    /// same zenoh runtime are 2 responders). For each responder, only its latest value for a path is considered.
+    /// A [`QuorumData`] is then returned for each replied [`Path`], in the order of their first reply:
+    ///  * [`QuorumData::Agreed`] if at least `quorum` responders replied the same value. If several values
+    ///    reach the quorum (with a `quorum` lower than half of the responders), the most replied one is returned.
+    ///  * [`QuorumData::Conflict`] if the responders diverge and no value reaches the quorum. All the
+    ///    replied values are reported, to let the application resolve the conflict (e.g. by keeping the most
+    ///    recent one, or by repairing the diverging replicas).
+    ///  * [`QuorumData::Partial`] if all the responders agree, but they're less than `quorum`
+    ///    (e.g. if some replicas didn't reply before `timeout`, or don't have the path).
+    ///
+    /// Note that a path that no responder replied before `timeout` isn't returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use std::time::Duration;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let results = workspace
+    ///     .get_quorum(&"/demo/example/**".try_into().unwrap(), 2, Duration::from_secs(1))
+    ///     .await
+    ///     .unwrap();
+    /// for result in results {
+    ///     match result {
+    ///         QuorumData::Agreed { data, responders } => {
+    ///             println!("{} : {:?} ({} responders)", data.path, data.value, responders)
+    ///         }
+    ///         QuorumData::Conflict { path, versions } => {
+    ///             println!("{} : conflict between {} values", path, versions.len())
+    ///         }
+    ///         QuorumData::Partial { data, responders } => {
+    ///             println!("{} : {:?} (only {} responders)", data.path, data.value, responders)
+    ///         }
+    ///     }
+    /// }
+    /// # })
+    /// ```
+    pub fn get_quorum(
+        &self,
+        selector: &Selector,
+        quorum: usize,
+        timeout: Duration,
+    ) -> impl ZFuture<Output = ZResult<Vec<QuorumData>>> {
+        debug!("get_quorum on {} with a quorum of {}", selector, quorum);
+        let target = QueryTarget {
+            kind: ALL_KINDS,
+            target: Target::All,
+        };
+        let single_path = selector.path_expr.is_a_path();
+        let receiver = self.query_data_ext(selector, target, QueryConsolidation::none());
+        zpinbox(async move {
+            let mut receiver = receiver?;
+            // for each path, in the order of their first reply, the latest value of each responder
+            let mut paths: Vec<(Path, QuorumReplies)> = vec![];
+            let collect = async {
+                while let Some(reply) = receiver.receiver.next().await {
+                    if !receiver.accept(&reply) {
+                        continue;
+                    }
+                    receiver.check_size(&reply)?;
+                    let replier = (reply.replier_id, reply.replier_kind);
+                    let hash = content_hash(&reply);
+                    let d = match receiver.transcode(reply) {
+                        Ok(d) => d,
+                        Err(err) => {
+                            warn!("Received an invalid Reply (drop it): {}", err);
+                            continue;
+                        }
+                    };
+                    let i = match paths.iter().position(|(path, _)| *path == d.path) {
+                        Some(i) => i,
+                        None => {
+                            paths.push((d.path.clone(), HashMap::new()));
+                            paths.len() - 1
+                        }
+                    };
+                    match paths[i].1.get(&replier) {
+                        Some((_, latest)) if latest.timestamp >= d.timestamp => (),
+                        _ => {
+                            paths[i].1.insert(replier, (hash, d));
+                        }
+                    }
+                    // no other path can be replied: stop as soon as the quorum is met
+                    if single_path && quorum_met(&paths[i].1, quorum) {
+                        debug!("get_quorum: quorum met on {}", paths[i].0);
+                        break;
+                    }
+                }
+                Ok(())
+            };
+            match async_std::future::timeout(timeout, collect).await {
+                Ok(result) => result?,
+                Err(_) => debug!(
+                    "get_quorum: timed out after replies for {} paths",
+                    paths.len()
+                ),
+            }
+            Ok(paths
+                .into_iter()
+                .map(|(path, replies)| {
+                    // group the values by content, keeping the latest Data for each one
+                    let mut versions: Vec<(Vec<u8>, Data, usize)> = vec![];
+                    for (hash, d) in replies.into_values() {
+                        match versions.iter_mut().find(|(h, _, _)| *h == hash) {
+                            Some((_, data, count)) => {
+                                *count += 1;
+                                if d.timestamp > data.timestamp {
+                                    *data = d;
+                                }
+                            }
+                            None => versions.push((hash, d, 1)),
+                        }
+                    }
+                    versions.sort_by(|(_, d1, c1), (_, d2, c2)| {
+                        c2.cmp(c1).then_with(|| d2.timestamp.cmp(&d1.timestamp))
+                    });
+                    let mut versions: Vec<(Data, usize)> =
+                        versions.into_iter().map(|(_, d, c)| (d, c)).collect();
+                    if versions[0].1 >= quorum {
+                        let (data, responders) = versions.swap_remove(0);
+                        QuorumData::Agreed { data, responders }
+                    } else if versions.len() > 1 {
+                        QuorumData::Conflict { path, versions }
+                    } else {
+                        let (data, responders) = versions.swap_remove(0);
+                        QuorumData::Partial { data, responders }
+                    }
+                })
+                .collect())
+        })
+    }
+
+    /// Get a selection of [`Path`]/[`Value`] from zenoh with a `PreferComplete` consolidation, collecting
+    /// the resulting [`Data`] (one per path).  
+    /// For each path, if at least one complete eval (registered via [`Workspace::register_eval_complete()`])
+    /// replied, only the replies of the complete evals are kept and the other replies (e.g. from storages) are
+    /// discarded. Otherwise, all the replies are kept.
+    /// Note that the [`Selector`] can be absolute or relative to this Workspace.
+    ///
+    /// The timestamp-based consolidation applies after this selection: among the kept replies for a path,
+    /// the one with the greatest timestamp is returned. Hence a value replied by a complete eval is returned
+    /// even if a storage replied a more recent value for the same path.
+    /// As all the replies must be received before deciding, the [`Data`] are returned once the query is complete.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let data = workspace
+    ///     .get_prefer_complete(&"/demo/example/**".try_into().unwrap())
+    ///     .await
+    ///     .unwrap();
+    /// for data in data {
+    ///     println!(">> {} : {:?} at {}", data.path, data.value, data.timestamp)
+    /// }
+    /// # })
+    /// ```
+    pub fn get_prefer_complete(
+        &self,
+        selector: &Selector,
+    ) -> impl ZFuture<Output = ZResult<Vec<Data>>> {
+        debug!("get_prefer_complete on {}", selector);
+        let target = QueryTarget {
+            kind: ALL_KINDS,
+            target: Target::All,
+        };
+        let receiver = self.query_data_ext(selector, target, QueryConsolidation::none());
+        zpinbox(async move {
+            let mut receiver = receiver?;
+            // for each path, in the order of their first reply, the selected Data
+            // and if it was replied by a complete eval
+            let mut results: Vec<(Data, bool)> = vec![];
+            let mut indexes: HashMap<Path, usize> = HashMap::new();
+            while let Some(reply) = receiver.receiver.next().await {
+                if !receiver.accept(&reply) {
+                    continue;
+                }
+                let complete = reply.replier_kind & COMPLETE != 0;
+                let d = match receiver.transcode(reply) {
+                    Ok(d) => d,
+                    Err(err) if is_too_large(&err) => return Err(err),
+                    Err(err) => {
+                        warn!("Received an invalid Reply (drop it): {}", err);
+                        continue;
+                    }
+                };
+                match indexes.get(&d.path) {
+                    Some(i) => {
+                        let (selected, selected_complete) = &mut results[*i];
+                        if (complete, &d.timestamp) > (*selected_complete, &selected.timestamp) {
+                            *selected = d;
+                            *selected_complete = complete;
+                        }
+                    }
+                    None => {
+                        indexes.insert(d.path.clone(), results.len());
+                        results.push((d, complete));
+                    }
+                }
+            }
+            Ok(results.into_iter().map(|(d, _)| d).collect())
+        })
+    }
+
+    /// Get a selection of [`Path`]/[`Value`] from zenoh page per page, as a continuous [`Stream`] of [`Data`]
+    /// in the order of their paths.  
+    /// The pages are fetched on demand, with at most `page_size` [`Data`] per page, allowing to iterate
+    /// over a huge selection with a bounded memory. An error is returned if a page can't be queried
+    /// (e.g. an invalid selector), ending the stream.
+    /// Note that the [`Selector`] can be absolute or relative to this Workspace.
+    ///
+    /// Each page is queried with the `_limit` and `_after` properties (see [`Selector::limit()`] and
+    /// [`Selector::after()`]), the next page starting after the last path of the previous one.
+    /// A storage not supporting those properties replies all its values for each page: the result is the same,
+    /// but the memory is not bounded anymore.
+    ///
+    /// There is no snapshot across pages: each page reflects the values at the time of its own query.
+    /// A path put during the iteration is returned only if it comes after the current page, and a path deleted
+    /// during the iteration is not returned if it comes after the current page. Within a page, the latest value
+    /// of each path is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut data_stream = workspace.get_paged(&"/demo/example/**".try_into().unwrap(), 100);
+    /// while let Some(data) = data_stream.next().await {
+    ///     match data {
+    ///         Ok(data) => println!(">> {} : {:?} at {}", data.path, data.value, data.timestamp),
+    ///         Err(e) => println!("Error: {}", e),
+    ///     }
+    /// }
+    /// # })
+    /// ```
+    ///
+    /// [`Stream`]: async_std::stream::Stream
+    pub fn get_paged(&self, selector: &Selector, page_size: usize) -> PagedDataStream<'_> {
+        debug!("get_paged on {} (page size: {})", selector, page_size);
+        PagedDataStream {
+            workspace: self,
+            selector: selector.clone(),
+            page_size: page_size.max(1),
+            after: None,
+            page: VecDeque::new(),
+            fetching: None,
+            done: false,
+        }
+    }
+
+    fn query_data(
+        &self,
+        selector: &Selector,
+        consolidation: QueryConsolidation,
+    ) -> ZResult<DataReceiver> {
+        self.query_data_ext(selector, QueryTarget::default(), consolidation)
+    }
+
+    fn query_data_ext(
+        &self,
+        selector: &Selector,
+        target: QueryTarget,
+        consolidation: QueryConsolidation,
+    ) -> ZResult<DataReceiver> {
+        let reskey = self.pathexpr_to_reskey(&selector.path_expr)?;
+        let excludes = self.pathexpr_exclusions(&selector.path_expr)?;
+        let decode_value = !selector.properties.contains_key("raw");
+        let transforms = selector.transforms()?;
+        let replier = selector.target()?;
+        let max_value_size = self.max_value_size();
+        self.session()
+            .query_ext(&reskey, &selector.predicate, target, consolidation, replier)
+            .wait()
+            .map(|receiver| DataReceiver {
+                receiver,
+                excludes,
+                decode_value,
+                keyring: self.keyring.clone(),
+                transforms,
+                max_value_size,
+            })
+    }
+
+    /// Subscribe to changes for a selection of [`Path`]/[`Value`] (specified via a [`Selector`]) from zenoh.  
+    /// The changes are returned as [`async_std::stream::Stream`] of [`Change`].
+    /// This Stream will never end unless it's dropped or explicitly closed via [`ChangeReceiver::close()`].
+    /// Note that the [`Selector`] can be absolute or relative to this Workspace.
+    ///
+    /// If the [`Selector`] has a `_transform` property, the specified [`Transform`]s are applied to the
+    /// decoded values of the changes (e.g. `/demo/sensors/**?(_transform=scale:0.1)`).
+    ///
+    /// If the [`PathExpr`] of the [`Selector`] has exclusions (e.g. `/demo/** !/demo/debug/**`),
+    /// the changes for the excluded paths are dropped on reception.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut change_stream =
+    ///     workspace.subscribe(&"/demo/example/**".try_into().unwrap()).await.unwrap();
+    /// while let Some(change) = change_stream.next().await {
+    ///     println!(">> {:?} for {} : {:?} at {}",
+    ///         change.kind, change.path, change.value, change.timestamp
+    ///     )
+    /// }
+    /// # })
+    /// ```
+    pub fn subscribe(
+        &self,
+        selector: &Selector,
+    ) -> impl ZFuture<Output = ZResult<ChangeReceiver<'_>>> {
+        debug!("subscribe on {}", selector);
+        zready_try!({
+            if selector.filter.is_some() {
+                return zerror!(ZErrorKind::Other {
+                    descr: "Filter not supported in selector for subscribe()".into()
+                });
+            }
+            if selector.fragment.is_some() {
+                return zerror!(ZErrorKind::Other {
+                    descr: "Fragment not supported in selector for subscribe()".into()
+                });
+            }
+            let decode_value = !selector.properties.contains_key("raw");
+            let transforms = selector.transforms()?;
+            let max_value_size = self.max_value_size();
+
+            let reskey = self.pathexpr_to_reskey(&selector.path_expr)?;
+            let excludes = self.pathexpr_exclusions(&selector.path_expr)?;
+            let sub_info = SubInfo {
+                reliability: Reliability::Reliable,
+                mode: SubMode::Push,
+                period: None,
+            };
+
+            self.session()
+                .declare_subscriber(&reskey, &sub_info)
+                .wait()
+                .map(|mut subscriber| {
+                    track_declared(&self.subscribers, &subscriber.state);
+                    ChangeReceiver {
+                        receiver: subscriber.receiver().clone(),
+                        subscriber,
+                        excludes,
+                        decode_value,
+                        keyring: self.keyring.clone(),
+                        journal: self.journal.clone(),
+                        transforms,
+                        max_value_size,
+                        counters: Arc::new(SubscriberCounters::default()),
+                    }
+                })
+        })
+    }
+
+    /// Subscribe to changes for a selection of [`Path`]/[`Value`] (specified via a [`Selector`]) from zenoh,
+    /// maintaining the latest value of each [`Path`] and yielding on each change the aggregate of all those values,
+    /// computed by folding them with `fold`, starting from `init`.  
+    /// A deleted [`Path`] is removed from the aggregate. This is equivalent to
+    /// [`subscribe()`](Workspace::subscribe) followed by [`ChangeStreamExt::reduce()`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// // the number of sensors detecting an obstacle
+    /// let mut count_stream = workspace
+    ///     .subscribe_reduce(
+    ///         &"/demo/sensors/*/obstacle".try_into().unwrap(),
+    ///         0,
+    ///         |count, _path, value| match value {
+    ///             Value::StringUtf8(s) if s == "true" => count + 1,
+    ///             _ => count,
+    ///         },
+    ///     )
+    ///     .await
+    ///     .unwrap();
+    /// while let Some(count) = count_stream.next().await {
+    ///     println!(">> {} obstacles", count)
+    /// }
+    /// # })
+    /// ```
+    pub fn subscribe_reduce<A, F>(
+        &self,
+        selector: &Selector,
+        init: A,
+        fold: F,
+    ) -> impl ZFuture<Output = ZResult<Reduce<ChangeReceiver<'_>, A, F>>>
+    where
+        A: Clone + Send + Unpin,
+        F: FnMut(A, &Path, &Value) -> A + Send + Unpin,
+    {
+        zready(
+            self.subscribe(selector)
+                .wait()
+                .map(|receiver| receiver.reduce(init, fold)),
+        )
+    }
+
+    /// Subscribe to changes for a selection of [`Path`]/[`Value`] (specified via a [`Selector`]) from zenoh,
+    /// maintaining the latest value of each [`Path`] and yielding at each `interval` a [`Snapshot`](crate::Snapshot)
+    /// of all those values. The snapshots are seeded with the values returned by a [`get()`](Workspace::get)
+    /// on the same [`Selector`] right after the subscription (e.g. the values of the storages).  
+    /// This is equivalent to [`subscribe()`](Workspace::subscribe) followed by [`ChangeStreamExt::snapshot_on_tick()`]
+    /// and [`SnapshotOnTick::seed()`] (see them for the details, and for the memory usage).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use std::time::Duration;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut snapshots = workspace
+    ///     .subscribe_snapshot(&"/demo/example/**".try_into().unwrap(), Duration::from_millis(100))
+    ///     .await
+    ///     .unwrap();
+    /// while let Some(snapshot) = snapshots.next().await {
+    ///     println!(">> {} paths", snapshot.values.len())
+    /// }
+    /// # })
+    /// ```
+    pub fn subscribe_snapshot(
+        &self,
+        selector: &Selector,
+        interval: Duration,
+    ) -> impl ZFuture<Output = ZResult<SnapshotOnTick<ChangeReceiver<'_>>>> {
+        // the subscription and the query are declared right away (so no change is missed between them),
+        // while the seed values are received asynchronously
+        let receiver = self.subscribe(selector).wait();
+        let seed = self.get(selector);
+        SnapshotFuture {
+            receiver: Some(receiver),
+            interval,
+            seed: zpinbox(async move { Ok(seed.await?.collect::<Vec<Data>>().await) }),
+        }
+    }
+
+    /// Subscribe to the changes of a part of the JSON values for a selection of [`Path`] (specified via a [`Selector`])
+    /// from zenoh: the part referenced by the JSON pointer `ptr` (see [`Value::json_pointer()`]).  
+    /// A change is delivered only when the part differs from the one previously delivered for the same [`Path`],
+    /// and its [`Value`] is just this part. When the part is absent (including when the value is not JSON),
+    /// a [`ChangeKind::Delete`] change is delivered if a part was previously delivered for the [`Path`].
+    /// This is equivalent to [`subscribe()`](Workspace::subscribe) followed by [`ChangeStreamExt::json_field()`]
+    /// (see it for the details).  
+    /// Returns an error if `ptr` is not a valid JSON pointer (i.e. not empty and not starting with `'/'`).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut change_stream = workspace
+    ///     .subscribe_field(&"/demo/robots/*/state".try_into().unwrap(), "/battery/level")
+    ///     .await
+    ///     .unwrap();
+    /// while let Some(change) = change_stream.next().await {
+    ///     match change.value {
+    ///         Some(level) => println!(">> battery of {} : {:?}", change.path, level),
+    ///         None => println!(">> battery of {} : unknown", change.path),
+    ///     }
+    /// }
+    /// # })
+    /// ```
+    pub fn subscribe_field(
+        &self,
+        selector: &Selector,
+        ptr: &str,
+    ) -> impl ZFuture<Output = ZResult<JsonField<ChangeReceiver<'_>>>> {
+        if !ptr.is_empty() && !ptr.starts_with('/') {
+            return zready(zerror!(ZErrorKind::ValueDecodingFailed {
+                descr: format!(
+                    "Invalid JSON pointer '{}': it must be empty or start with '/'",
+                    ptr
+                )
+            }));
+        }
+        zready(
+            self.subscribe(selector)
+                .wait()
+                .map(|receiver| receiver.json_field(ptr)),
+        )
+    }
+
+    /// Subscribe to changes for a selection of [`Path`]/[`Value`] (specified via a [`Selector`]) from zenoh.  
+    /// For each change, the `callback` will be called.
+    /// A [`SubscriberHandle`] is returned, allowing to close the subscription via [`SubscriberHandle::close()`].
+    /// Note that the [`Selector`] can be absolute or relative to this Workspace.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut change_stream = workspace.subscribe_with_callback(
+    ///     &"/demo/example/**".try_into().unwrap(),
+    ///     move |change| {
+    ///        println!(">> {:?} for {} : {:?} at {}",
+    ///            change.kind, change.path, change.value, change.timestamp
+    ///        )}
+    /// ).await.unwrap();
+    /// # })
+    /// ```
+    pub fn subscribe_with_callback<SubscribeCallback>(
+        &self,
+        selector: &Selector,
+        mut callback: SubscribeCallback,
+    ) -> impl ZFuture<Output = ZResult<SubscriberHandle<'_>>>
+    where
+        SubscribeCallback: FnMut(Change) + Send + Sync + 'static,
+    {
+        debug!("subscribe_with_callback on {}", selector);
+        zready_try!({
+            if selector.filter.is_some() {
+                return zerror!(ZErrorKind::Other {
+                    descr: "Filter not supported in selector for subscribe()".into()
+                });
+            }
+            if selector.fragment.is_some() {
+                return zerror!(ZErrorKind::Other {
+                    descr: "Fragment not supported in selector for subscribe()".into()
+                });
+            }
+            let decode_value = !selector.properties.contains_key("raw");
+            let transforms = selector.transforms()?;
+            let max_value_size = self.max_value_size();
+
+            let reskey = self.pathexpr_to_reskey(&selector.path_expr)?;
+            let excludes = self.pathexpr_exclusions(&selector.path_expr)?;
+            let sub_info = SubInfo {
+                reliability: Reliability::Reliable,
+                mode: SubMode::Push,
+                period: None,
+            };
+
+            let keyring = self.keyring.clone();
+            let journal = self.journal.clone();
+            let counters = Arc::new(SubscriberCounters::default());
+            let sample_counters = counters.clone();
+            let subscriber = self
+                .session()
+                .declare_callback_subscriber(&reskey, &sub_info, move |sample| {
+                    if is_excluded(&excludes, &sample.res_name) {
+                        return;
+                    }
+                    sample_counters.count(sample.payload.len());
+                    match check_size(sample.payload.len(), max_value_size)
+                        .map(|()| journal_observed(&journal, &sample))
+                        .and_then(|()| Change::from_sample(sample, decode_value))
+                        .map(|change| change.decrypt(&keyring).transform(&transforms))
+                    {
+                        Ok(change) => callback(change),
+                        Err(err) => warn!("Received an invalid Sample (drop it): {}", err),
+                    }
+                })
+                .wait()?;
+            track_declared(&self.subscribers, &subscriber.state);
+            Ok(SubscriberHandle {
+                subscriber,
+                counters,
+            })
+        })
+    }
+
+    /// Subscribe to changes for several [`PathExpr`], dispatching each change to the handler of the first
+    /// matching [`PathExpr`] of `routes`.  
+    /// Each handler is called on its own task, in the order of the changes it receives.
+    /// A [`SubscriberRouter`] is returned, allowing to close all the subscriptions via [`SubscriberRouter::close()`].
+    /// Note that the [`PathExpr`] can be absolute or relative to this Workspace.
+    ///
+    /// When several [`PathExpr`] match a change, the most specific one is selected (and only its handler is called):
+    ///  * a [`PathExpr`] without wildcard (i.e. a [`Path`]) is more specific than one with wildcards,
+    ///  * otherwise the one with the most characters outside of the wildcards is the most specific
+    ///    (e.g. `/a/*/c` is more specific than `/a/**`),
+    ///  * and in case of equality, the first one in `routes` is selected.
+    ///
+    /// Each handler has a queue of `capacity` changes (at least 1). When a handler doesn't keep up and its queue
+    /// is full, the changes for this handler are dropped (with a warning) until there is room in its queue:
+    /// the dispatch never blocks the session's callbacks, so a slow handler doesn't delay the other ones.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let router = workspace.subscribe_router(
+    ///     vec![
+    ///         ("/demo/example/**".try_into().unwrap(), Box::new(|change: Change| {
+    ///             println!(">> [default] {:?} for {}", change.kind, change.path)
+    ///         })),
+    ///         ("/demo/example/alarms/*".try_into().unwrap(), Box::new(|change: Change| {
+    ///             println!(">> [alarm] {:?} for {}", change.kind, change.path)
+    ///         })),
+    ///     ],
+    ///     16
+    /// ).await.unwrap();
+    /// router.close().await.unwrap();
+    /// # })
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn subscribe_router(
+        &self,
+        routes: Vec<(PathExpr, Box<dyn FnMut(Change) + Send + 'static>)>,
+        capacity: usize,
+    ) -> impl ZFuture<Output = ZResult<SubscriberRouter<'_>>> {
+        debug!("subscribe_router on {} routes", routes.len());
+        zready_try!({
+            // the canonicalized PathExpr with their exclusions, from the most to the least specific
+            let mut routes = routes
+                .into_iter()
+                .map(|(path_expr, handler)| {
+                    let expr = self.canonicalize(path_expr.included())?;
+                    let excludes = self.pathexpr_exclusions(&path_expr)?;
+                    Ok((path_expr, expr, excludes, handler))
+                })
+                .collect::<ZResult<Vec<_>>>()?;
+            routes.sort_by_key(|(_, expr, _, _)| std::cmp::Reverse(specificity(expr)));
+            let matchers: Arc<Vec<(String, Vec<String>)>> = Arc::new(
+                routes
+                    .iter()
+                    .map(|(_, expr, excludes, _)| (expr.clone(), excludes.clone()))
+                    .collect(),
+            );
+
+            let mut handles = Vec::with_capacity(routes.len());
+            for (i, (path_expr, _, _, mut handler)) in routes.into_iter().enumerate() {
+                let (sender, receiver) = async_std::channel::bounded::<Change>(capacity.max(1));
+                async_std::task::spawn(async move {
+                    while let Ok(change) = receiver.recv().await {
+                        handler(change)
+                    }
+                });
+                // a change is received by the subscription of each matching route: only the one
+                // of the first matching route dispatches it
+                let matchers = matchers.clone();
+                let handle = self
+                    .subscribe_with_callback(&Selector::from(path_expr), move |change| {
+                        if first_matching_route(&matchers, change.path.as_str()) == Some(i) {
+                            match sender.try_send(change) {
+                                Ok(()) => (),
+                                Err(TrySendError::Full(change)) => warn!(
+                                    "The handler of a subscribe_router() route doesn't keep up (drop the change for {})",
+                                    change.path
+                                ),
+                                Err(TrySendError::Closed(_)) => warn!(
+                                    "The handler of a subscribe_router() route has ended (drop the change)"
+                                ),
+                            }
+                        }
+                    })
+                    .wait()?;
+                handles.push(handle);
+            }
+            Ok(SubscriberRouter { handles })
+        })
+    }
+
+    /// Registers an evaluation function under the provided [`PathExpr`].  
+    /// A [`async_std::stream::Stream`] of [`GetRequest`] is returned.
+    /// All `get` requests matching the [`PathExpr`] will be added to this stream as a [`GetRequest`],
+    /// allowing the implementation to send a reply as a result of the evaluation function via [`GetRequest::reply()`].
+    /// This Stream will never end unless it's dropped or explicitly closed via [`GetRequestStream::close()`].
+    /// Note that the [`PathExpr`] can be absolute or relative to this Workspace.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut get_stream =
+    ///     workspace.register_eval(&"/demo/example/eval".try_into().unwrap()).await.unwrap();
+    /// while let Some(get_request) = get_stream.next().await {
+    ///    println!(
+    ///        ">> [Eval function] received get with selector: {}",
+    ///        get_request.selector
+    ///    );
+    ///    // return the Value as a result of this evaluation function :
+    ///    let v = Value::StringUtf8(format!("Result for get on {}", get_request.selector));
+    ///    get_request.reply_async("/demo/example/eval".try_into().unwrap(), v).await;
+    /// }
+    /// # })
+    /// ```
+    pub fn register_eval(
+        &self,
+        path_expr: &PathExpr,
+    ) -> impl ZFuture<Output = ZResult<GetRequestStream<'_>>> {
+        debug!("eval on {}", path_expr);
+        self.declare_eval(path_expr, EVAL)
+    }
+
+    /// Registers an evaluation function under the provided [`PathExpr`], as [`Workspace::register_eval()`],
+    /// but advertising that it replies the complete value of each path it matches.  
+    /// Its replies are preferred over the ones of the storages and the other evals by
+    /// [`Workspace::get_prefer_complete()`]. The other `get` operations don't distinguish it from a regular eval.
+    /// Note that the [`PathExpr`] can be absolute or relative to this Workspace.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut get_stream = workspace
+    ///     .register_eval_complete(&"/demo/example/config".try_into().unwrap())
+    ///     .await
+    ///     .unwrap();
+    /// while let Some(get_request) = get_stream.next().await {
+    ///    let v = Value::StringUtf8("the complete configuration".to_string());
+    ///    get_request.reply_async("/demo/example/config".try_into().unwrap(), v).await;
+    /// }
+    /// # })
+    /// ```
+    pub fn register_eval_complete(
+        &self,
+        path_expr: &PathExpr,
+    ) -> impl ZFuture<Output = ZResult<GetRequestStream<'_>>> {
+        debug!("complete eval on {}", path_expr);
+        self.declare_eval(path_expr, EVAL | COMPLETE)
+    }
+
+    fn declare_eval(
+        &self,
+        path_expr: &PathExpr,
+        kind: ZInt,
+    ) -> impl ZFuture<Output = ZResult<GetRequestStream<'_>>> {
+        zready_try!({
+            let reskey = self.pathexpr_to_reskey(path_expr)?;
+            let excludes = self.pathexpr_exclusions(path_expr)?;
+
+            self.session()
+                .declare_queryable(&reskey, kind)
+                .wait()
+                .map(|queryable| {
+                    track_declared(&self.queryables, &queryable.state);
+                    GetRequestStream {
+                        queryable,
+                        excludes,
+                    }
+                })
+        })
+    }
+
+    /// Closes this Workspace, undeclaring the subscriptions and the evals declared via this Workspace
+    /// that are still active.  
+    /// The zenoh session remains open, as well as the other Workspaces and their subscriptions and evals.
+    /// This allows to serve several isolated tenants on a single session, each with its own Workspace.
+    ///
+    /// The closed subscriptions and evals won't receive any further change or get request.
+    /// Note that closing their [`ChangeReceiver`], [`SubscriberHandle`] or [`GetRequestStream`] afterwards
+    /// returns an error since they're already undeclared.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let tenant1 = zenoh.workspace(Some("/tenant1".try_into().unwrap())).await.unwrap();
+    /// let tenant2 = zenoh.workspace(Some("/tenant2".try_into().unwrap())).await.unwrap();
+    /// let changes1 = tenant1.subscribe(&"**".try_into().unwrap()).await.unwrap();
+    /// let changes2 = tenant2.subscribe(&"**".try_into().unwrap()).await.unwrap();
+    /// // undeclares the subscription of tenant1 only
+    /// tenant1.close().await.unwrap();
+    /// # })
+    /// ```
+    pub fn close(&self) -> impl ZFuture<Output = ZResult<()>> {
+        debug!("close Workspace with prefix {:?}", self.prefix);
+        // Note: the subscribers and queryables already closed by the user are not found (ignore the error)
+        let subscribers: Vec<Arc<SubscriberState>> = zlock!(self.subscribers)
+            .drain(..)
+            .filter_map(|s| s.upgrade())
+            .collect();
+        for state in subscribers {
+            let _ = self.session().undeclare_subscriber(state.id).wait();
+        }
+        let queryables: Vec<Arc<QueryableState>> = zlock!(self.queryables)
+            .drain(..)
+            .filter_map(|q| q.upgrade())
+            .collect();
+        for state in queryables {
+            let _ = self.session().undeclare_queryable(state.id).wait();
+        }
+        zready(Ok(()))
+    }
+}
 
 impl fmt::Debug for Workspace<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -412,55 +2832,825 @@ impl fmt::Debug for Workspace<'_> {
     }
 }
 
+/// The options of a put made with [`Workspace::put_with()`]: its delivery options, and the metadata
+/// attached to the [`Change`] received by the subscribers.  
+/// The default options are the ones of [`Workspace::put()`]: the put is dropped under congestion,
+/// sent reliably with the [`Priority::Data`] priority, delivered to the subscribers of this Session,
+/// and has no metadata attached.
+///
+/// # Examples
+/// ```
+/// use zenoh::*;
+/// use zenoh::net::{CongestionControl, Priority, Reliability};
+///
+/// // a command which must reach the robot as soon as possible, but is useless if late
+/// let options = PutOptions::default()
+///     .congestion_control(CongestionControl::Drop)
+///     .priority(Priority::RealTime)
+///     .reliability(Reliability::BestEffort);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PutOptions {
+    congestion_control: CongestionControl,
+    priority: Priority,
+    reliability: Reliability,
+    local_echo: bool,
+    flush: bool,
+    checksum: Option<ChecksumAlgo>,
+    monotonic_stamp: Option<u64>,
+    sequence: Option<u64>,
+    message_id: Option<Uuid>,
+}
+
+impl PutOptions {
+    /// Sets the behaviour of the put under congestion: [`CongestionControl::Drop`] drops it (e.g. for a high-rate
+    /// telemetry, where the next value soon replaces it), [`CongestionControl::Block`] waits for the congestion
+    /// to resolve.
+    pub fn congestion_control(mut self, congestion_control: CongestionControl) -> Self {
+        self.congestion_control = congestion_control;
+        self
+    }
+
+    /// Sets the priority of the put: the messages of a higher priority are sent before the others.
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets the reliability of the put: [`Reliability::BestEffort`] is never retransmitted, for the lowest latency.
+    pub fn reliability(mut self, reliability: Reliability) -> Self {
+        self.reliability = reliability;
+        self
+    }
+
+    /// Sets whether the put is delivered to the subscribers of this Workspace's [`Session`]
+    /// (see [`Workspace::put_without_local_echo()`]).
+    pub fn local_echo(mut self, local_echo: bool) -> Self {
+        self.local_echo = local_echo;
+        self
+    }
+
+    /// Sets whether the put is sent immediately, without waiting for other messages to fill its batch
+    /// (see [`Workspace::put_flush_now()`]).
+    pub fn flush(mut self, flush: bool) -> Self {
+        self.flush = flush;
+        self
+    }
+
+    /// Attaches a checksum of the encoded value computed with `algo`
+    /// (see [`Workspace::put_with_checksum()`]).
+    pub fn checksum(mut self, algo: ChecksumAlgo) -> Self {
+        self.checksum = Some(algo);
+        self
+    }
+
+    /// Attaches a monotonic `stamp` (see [`Workspace::put_with_monotonic_stamp()`]).
+    pub fn monotonic_stamp(mut self, stamp: u64) -> Self {
+        self.monotonic_stamp = Some(stamp);
+        self
+    }
+
+    /// Attaches the sequence number `sn`, together with the zenoh id of this Workspace's [`Session`]
+    /// (see [`Workspace::put_with_sequence()`]).
+    pub fn sequence(mut self, sn: u64) -> Self {
+        self.sequence = Some(sn);
+        self
+    }
+
+    /// Attaches the unique `message_id` (see [`Workspace::put_with_message_id()`]).
+    pub fn message_id(mut self, message_id: Uuid) -> Self {
+        self.message_id = Some(message_id);
+        self
+    }
+}
+
+impl Default for PutOptions {
+    fn default() -> Self {
+        PutOptions {
+            congestion_control: CongestionControl::Drop,
+            priority: Priority::default(),
+            reliability: Reliability::Reliable,
+            local_echo: true,
+            flush: false,
+            checksum: None,
+            monotonic_stamp: None,
+            sequence: None,
+            message_id: None,
+        }
+    }
+}
+
 /// A Data returned as a result of a [`Workspace::get()`] operation.
 ///
 /// It contains the [`Path`], its associated [`Value`] and a [`Timestamp`] which corresponds to the time
 /// at which the path/value has been put into zenoh.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Data {
     pub path: Path,
     pub value: Value,
     pub timestamp: Timestamp,
+    // true only for a Data returned from the cache of Workspace::get_stale_on_error()
+    pub(crate) stale: bool,
+}
+
+ztranscoder! {
+    /// A [`Stream`] of [`Data`] returned as a result of the [`Workspace::get()`] operation.
+    ///
+    /// [`Stream`]: async_std::stream::Stream
+    #[derive(Clone)]
+    pub DataReceiver: Receiver<Data> <- ReplyReceiver: Receiver<Reply>
+    with
+        DataIter: Iterator<Data>,
+        DataTryIter: Iterator<Data>,
+    {
+        excludes: Vec<String>,
+        decode_value: bool,
+        keyring: Option<Arc<Keyring>>,
+        transforms: Vec<Transform>,
+        max_value_size: usize,
+    }
+}
+
+impl DataReceiver {
+    fn accept(&self, reply: &Reply) -> bool {
+        !is_excluded(&self.excludes, &reply.data.res_name)
+    }
+
+    /// Returns true if a session hosting matching storages or evals rejected the query because of overload
+    /// (see [`ZN_MAX_CONCURRENT_QUERIES_KEY`](crate::net::config::ZN_MAX_CONCURRENT_QUERIES_KEY)):
+    /// the [`Data`] returned by this stream are then incomplete.  
+    /// Note that it's only known once the rejection is received, i.e. for sure at the end of the stream.
+    pub fn is_overloaded(&self) -> bool {
+        self.receiver.is_overloaded()
+    }
+
+    // Returns an error if the declared length of the replied value exceeds `max_value_size`,
+    // to be checked before the value is processed in any way (hashed, decrypted or decoded).
+    fn check_size(&self, reply: &Reply) -> ZResult<()> {
+        check_size(reply.data.payload.len(), self.max_value_size)
+    }
+
+    fn transcode(&self, reply: Reply) -> ZResult<Data> {
+        self.check_size(&reply)?;
+        let path: Path = reply.data.res_name.try_into().unwrap();
+        let (encoding, timestamp) = if let Some(info) = reply.data.data_info {
+            (
+                info.encoding.unwrap_or(encoding::APP_OCTET_STREAM),
+                info.timestamp.unwrap_or_else(new_reception_timestamp),
+            )
+        } else {
+            (encoding::APP_OCTET_STREAM, new_reception_timestamp())
+        };
+        let value = if self.decode_value {
+            Transform::apply_all(
+                &self.transforms,
+                decrypt(&self.keyring, Value::decode(encoding, reply.data.payload)?),
+            )
+        } else {
+            Value::Raw(encoding, reply.data.payload)
+        };
+        Ok(Data {
+            path,
+            value,
+            timestamp,
+            stale: false,
+        })
+    }
+
+    /// Converts this stream into a [`DataTryStream`] that returns the invalid replies as errors
+    /// instead of dropping them, for use with the [`TryStreamExt`] combinators.
+    ///
+    /// [`TryStreamExt`]: futures::stream::TryStreamExt
+    pub fn into_try_stream(self) -> DataTryStream {
+        DataTryStream { inner: self }
+    }
+
+    /// Converts this stream into a [`DedupDataReceiver`] that drops the replies for a [`Path`] whose content
+    /// (i.e. encoding and payload) is identical to a reply already returned for this [`Path`],
+    /// regardless of their timestamps (e.g. the same value replied by several replicated storages).
+    ///
+    /// Note that with the default consolidation of [`Workspace::get()`], only the latest reply is returned
+    /// for each [`Path`] and there is nothing to de-duplicate. This is useful when the replies are not
+    /// consolidated, i.e. with a selector specifying a time-range (`starttime`/`stoptime` properties),
+    /// where each replica replies its whole history for the selected time-range.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut data_stream = workspace
+    ///     .get(&"/demo/example/**?(starttime=now()-1h)".try_into().unwrap())
+    ///     .await
+    ///     .unwrap()
+    ///     .dedup_by_content();
+    /// while let Some(data) = data_stream.next().await {
+    ///     println!(">> {} : {:?} at {}", data.path, data.value, data.timestamp)
+    /// }
+    /// # })
+    /// ```
+    pub fn dedup_by_content(self) -> DedupDataReceiver {
+        DedupDataReceiver {
+            inner: self,
+            delivered: HashMap::new(),
+        }
+    }
+
+    /// Writes each [`Data`] of this stream into `writer` as it arrives, as a JSON object per line
+    /// ([JSON Lines](https://jsonlines.org) format), until the end of the stream.  
+    /// Returns the number of written [`Data`].
+    ///
+    /// Each JSON object has the fields:
+    ///  * `"path"`: the [`Path`] of the Data
+    ///  * `"value"`: the [`Value`] as JSON. A [`Value::Json`] is embedded as is, a [`Value::Integer`] or
+    ///    [`Value::Float`] is a JSON number and a [`Value::Properties`] a JSON object. Any other Value is a
+    ///    JSON string (base64-encoded if it isn't UTF-8, with an additional `"base64": true` field).
+    ///  * `"encoding"`: the description of the Value's encoding (e.g. `"application/json"`)
+    ///  * `"timestamp"`: the [`Timestamp`] of the Data
+    ///
+    /// Each line is flushed once written, so the output can be piped into a tool as the Data arrive.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// workspace
+    ///     .get(&"/demo/example/**".try_into().unwrap())
+    ///     .await
+    ///     .unwrap()
+    ///     .write_jsonl(std::io::stdout())
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn write_jsonl<W: std::io::Write>(mut self, mut writer: W) -> ZResult<usize> {
+        let mut count = 0;
+        while let Some(data) = self.next().await {
+            let mut line = data.to_json().to_string();
+            line.push('\n');
+            writer
+                .write_all(line.as_bytes())
+                .and_then(|()| writer.flush())
+                .map_err(|e| {
+                    zerror2!(ZErrorKind::IoError {
+                        descr: format!("Failed to write the Data for {}: {}", data.path, e)
+                    })
+                })?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+impl Data {
+    /// Returns true if this Data was returned from the cache of [`Workspace::get_stale_on_error()`]
+    /// rather than received in reply to the query.
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    /// Deserializes the [`Value`] of this Data into a `T`, dispatching on its encoding
+    /// (see [`Value::decode_typed()`]).
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::collections::HashMap;
+    /// use std::convert::TryInto;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut data_stream = workspace.get(&"/demo/example/position".try_into().unwrap()).await.unwrap();
+    /// while let Some(data) = data_stream.next().await {
+    ///     match data.decode_typed::<HashMap<String, f64>>() {
+    ///         Ok(position) => println!(">> {} : {:?}", data.path, position),
+    ///         Err(e) => println!(">> {} : {}", data.path, e),
+    ///     }
+    /// }
+    /// # })
+    /// ```
+    pub fn decode_typed<T>(&self) -> ZResult<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.value.decode_typed()
+    }
+
+    /// Deserializes the JSON [`Value`] of this Data (e.g. put as a [`Value::from_json_typed()`]) into a `T`.  
+    /// Returns an error if the encoding of the Value isn't JSON (see [`Value::encoding_descr()`]),
+    /// or if it can't be deserialized into a `T`.
+    pub fn deserialize<T>(&self) -> ZResult<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.value.deserialize_json()
+    }
+
+    /// Returns an [`AsyncRead`] over the payload of this Data: for a value put with [`Workspace::put_stream()`],
+    /// its chunks are fetched one by one via `workspace` as the reader is read (see [`ValueReader`]).
+    /// Returns an error if the manifest of the chunks is malformed.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    /// use futures::prelude::*;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut data_stream = workspace.get(&"/demo/camera/frame".try_into().unwrap()).await.unwrap();
+    /// if let Some(data) = data_stream.next().await {
+    ///     let mut file = async_std::fs::File::create("frame.jpg").await.unwrap();
+    ///     futures::io::copy(data.value_reader(&workspace).unwrap(), &mut file).await.unwrap();
+    /// }
+    /// # })
+    /// ```
+    pub fn value_reader<'a>(&self, workspace: &'a Workspace<'a>) -> ZResult<ValueReader<'a>> {
+        ValueReader::new(workspace, &self.path, &self.value)
+    }
+
+    // Converts this Data into a JSON object (see DataReceiver::write_jsonl())
+    fn to_json(&self) -> serde_json::Value {
+        let mut json = serde_json::Map::new();
+        json.insert("path".into(), self.path.as_str().into());
+        let value = match &self.value {
+            Value::Json(s) => serde_json::from_str(s).unwrap_or_else(|_| s.as_str().into()),
+            Value::Integer(i) => (*i).into(),
+            Value::Float(f) => (*f).into(),
+            Value::StringUtf8(s) => s.as_str().into(),
+            Value::Properties(p) => p
+                .iter()
+                .map(|(k, v)| (k.clone(), serde_json::Value::from(v.as_str())))
+                .collect::<serde_json::Map<_, _>>()
+                .into(),
+            value => {
+                let (_, base64, s) = value.clone().encode_to_string();
+                if base64 {
+                    json.insert("base64".into(), true.into());
+                }
+                s.into()
+            }
+        };
+        json.insert("value".into(), value);
+        json.insert("encoding".into(), self.value.encoding_descr().into());
+        json.insert("timestamp".into(), self.timestamp.to_string().into());
+        json.into()
+    }
+}
+
+// The hierarchy of the values built by Workspace::get_json_tree()
+#[derive(Default)]
+struct JsonTree {
+    leaf: Option<serde_json::Value>,
+    branches: BTreeMap<String, JsonTree>,
+}
+
+impl JsonTree {
+    fn insert(&mut self, path: &Path, leaf: serde_json::Value) {
+        let node = path
+            .as_str()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .fold(self, |node, segment| {
+                node.branches.entry(segment.to_string()).or_default()
+            });
+        node.leaf = Some(leaf);
+    }
+
+    fn into_json(self) -> serde_json::Value {
+        if self.branches.is_empty() {
+            return self.leaf.unwrap_or_else(|| serde_json::Map::new().into());
+        }
+        let mut json: serde_json::Map<String, serde_json::Value> = self
+            .branches
+            .into_iter()
+            .map(|(segment, branch)| (segment, branch.into_json()))
+            .collect();
+        if let Some(leaf) = self.leaf {
+            json.insert("@value".into(), leaf);
+        }
+        json.into()
+    }
+}
+
+impl Value {
+    // Converts this Value into a leaf of Workspace::get_json_tree()
+    fn to_json_leaf(&self) -> serde_json::Value {
+        match self {
+            Value::Json(s) => match serde_json::from_str(s) {
+                Ok(json) => json,
+                Err(_) => s.as_str().into(),
+            },
+            Value::Integer(i) => (*i).into(),
+            Value::Float(f) => (*f).into(),
+            Value::StringUtf8(s) => s.as_str().into(),
+            Value::Properties(p) => p
+                .iter()
+                .map(|(k, v)| (k.clone(), serde_json::Value::from(v.as_str())))
+                .collect::<serde_json::Map<_, _>>()
+                .into(),
+            value => {
+                let mut json = serde_json::Map::new();
+                let (_, base64, s) = value.clone().encode_to_string();
+                json.insert("@value".into(), s.into());
+                json.insert("@encoding".into(), value.encoding_descr().into());
+                if base64 {
+                    json.insert("@base64".into(), true.into());
+                }
+                json.into()
+            }
+        }
+    }
+}
+
+/// A [`Stream`] of [`Data`] fetched page per page, returned by [`Workspace::get_paged()`].
+///
+/// [`Stream`]: async_std::stream::Stream
+pub struct PagedDataStream<'a> {
+    workspace: &'a Workspace<'a>,
+    selector: Selector,
+    page_size: usize,
+    // the last path of the previous page
+    after: Option<Path>,
+    page: VecDeque<Data>,
+    fetching: Option<Pin<Box<dyn Future<Output = Vec<Data>> + Send>>>,
+    done: bool,
+}
+
+impl PagedDataStream<'_> {
+    // Returns the next page from all the replied Data (replies of several storages being merged),
+    // and whether it's the last page.
+    fn next_page(&self, mut data: Vec<Data>) -> (Vec<Data>, bool) {
+        if let Some(after) = &self.after {
+            // in case a storage doesn't support paging
+            data.retain(|d| d.path.as_str() > after.as_str());
+        }
+        // keep the latest Data of each path
+        data.sort_by(|d1, d2| {
+            d1.path
+                .as_str()
+                .cmp(d2.path.as_str())
+                .then_with(|| d2.timestamp.cmp(&d1.timestamp))
+        });
+        data.dedup_by(|d, previous| d.path == previous.path);
+        // the paths up to the page_size-th one have been replied by all the storages (which reply their
+        // page_size first paths), and fewer paths than page_size means that no storage has more
+        let last = data.len() < self.page_size;
+        data.truncate(self.page_size);
+        (data, last)
+    }
+}
+
+impl Stream for PagedDataStream<'_> {
+    type Item = ZResult<Data>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(data) = this.page.pop_front() {
+                return Poll::Ready(Some(Ok(data)));
+            }
+            if this.done {
+                return Poll::Ready(None);
+            }
+            if this.fetching.is_none() {
+                let mut selector = this
+                    .selector
+                    .with_property(PROP_LIMIT, &this.page_size.to_string());
+                if let Some(after) = &this.after {
+                    selector = selector.with_property(PROP_AFTER, after.as_str());
+                }
+                trace!("get_paged: query page {}", selector);
+                match this
+                    .workspace
+                    .query_data(&selector, QueryConsolidation::default())
+                {
+                    Ok(receiver) => this.fetching = Some(Box::pin(receiver.collect::<Vec<Data>>())),
+                    Err(e) => {
+                        this.done = true;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                }
+            }
+            match this.fetching.as_mut().unwrap().as_mut().poll(cx) {
+                Poll::Ready(data) => {
+                    this.fetching = None;
+                    let (page, last) = this.next_page(data);
+                    this.done = last;
+                    if let Some(data) = page.last() {
+                        this.after = Some(data.path.clone());
+                    }
+                    this.page.extend(page);
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// A [`Stream`] of [`Data`] returned by [`DataReceiver::dedup_by_content()`].
+///
+/// [`Stream`]: async_std::stream::Stream
+pub struct DedupDataReceiver {
+    inner: DataReceiver,
+    // the content hashes of the replies already returned, per path
+    delivered: HashMap<String, HashSet<Vec<u8>>>,
+}
+
+impl DedupDataReceiver {
+    /// Converts back this stream into a [`DataReceiver`].
+    pub fn into_inner(self) -> DataReceiver {
+        self.inner
+    }
+}
+
+// Returns true if `err` means that the queried storages and evals couldn't be reached in time
+// (see Workspace::get_stale_on_error())
+fn is_unreachable(err: &ZError) -> bool {
+    matches!(
+        err.get_kind(),
+        ZErrorKind::Timeout {}
+            | ZErrorKind::InvalidSession { .. }
+            | ZErrorKind::NoRouter { .. }
+            | ZErrorKind::InvalidLink { .. }
+            | ZErrorKind::IoError { .. }
+    )
+}
+
+// Tracks a subscriber or queryable declared via a Workspace, pruning the ones already undeclared
+// (i.e. no longer referenced by the Session nor by their ChangeReceiver, SubscriberHandle or GetRequestStream)
+fn track_declared<T>(declared: &Mutex<Vec<Weak<T>>>, state: &Arc<T>) {
+    let mut declared = zlock!(declared);
+    declared.retain(|d| d.strong_count() > 0);
+    declared.push(Arc::downgrade(state));
+}
+
+// Returns a hash of the encoding and payload of a reply
+fn content_hash(reply: &Reply) -> Vec<u8> {
+    let encoding = reply
+        .data
+        .data_info
+        .as_ref()
+        .and_then(|info| info.encoding)
+        .unwrap_or(encoding::APP_OCTET_STREAM);
+    let mut content = ZBuf::new();
+    content.add_zslice(encoding.to_be_bytes().to_vec().into());
+    for slice in reply.data.payload.as_zslices() {
+        content.add_zslice(slice);
+    }
+    ChecksumAlgo::Sha256.digest(&content)
+}
+
+// The latest reply of each responder (identified by its zenoh id and kind) for a path in Workspace::get_quorum():
+// the hash of its content, and the Data
+type QuorumReplies = HashMap<(PeerId, ZInt), (Vec<u8>, Data)>;
+
+// Returns true if at least `quorum` of the responders replied the same content
+fn quorum_met(replies: &QuorumReplies, quorum: usize) -> bool {
+    let mut counts: HashMap<&[u8], usize> = HashMap::new();
+    for (hash, _) in replies.values() {
+        let count = counts.entry(hash.as_slice()).or_insert(0);
+        *count += 1;
+        if *count >= quorum {
+            return true;
+        }
+    }
+    false
+}
+
+impl Stream for DedupDataReceiver {
+    type Item = Data;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match this.inner.receiver.poll_next(cx) {
+                Poll::Ready(Some(reply)) => {
+                    if !this.inner.accept(&reply) {
+                        continue;
+                    }
+                    if let Err(err) = this.inner.check_size(&reply) {
+                        warn!("Received an invalid Reply (drop it): {}", err);
+                        continue;
+                    }
+                    let hash = content_hash(&reply);
+                    if !this
+                        .delivered
+                        .entry(reply.data.res_name.clone())
+                        .or_insert_with(HashSet::new)
+                        .insert(hash)
+                    {
+                        debug!("Drop duplicate reply for {}", reply.data.res_name);
+                        continue;
+                    }
+                    match this.inner.transcode(reply) {
+                        Ok(data) => return Poll::Ready(Some(data)),
+                        Err(err) => warn!("Received an invalid Reply (drop it): {}", err),
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// A [`TryStream`] of [`Data`] returned by [`DataReceiver::into_try_stream()`].
+///
+/// Unlike [`DataReceiver`], the replies that can't be decoded are returned as errors.
+///
+/// [`TryStream`]: futures::stream::TryStream
+pub struct DataTryStream {
+    inner: DataReceiver,
+}
+
+impl DataTryStream {
+    /// Converts back this stream into a [`DataReceiver`].
+    pub fn into_inner(self) -> DataReceiver {
+        self.inner
+    }
+}
+
+impl Stream for DataTryStream {
+    type Item = ZResult<Data>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let inner = &mut self.get_mut().inner;
+        loop {
+            match inner.receiver.poll_next(cx) {
+                Poll::Ready(Some(reply)) if !inner.accept(&reply) => continue,
+                Poll::Ready(Some(reply)) => return Poll::Ready(Some(inner.transcode(reply))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// A summary of a query, returned with the [`Data`] by [`Workspace::get_collect()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuerySummary {
+    /// the number of distinct storages and evals that replied, counted before the client-side filtering.
+    pub responders: usize,
+    /// the number of [`Data`] returned.
+    pub matched: usize,
+    /// true if a session hosting matching storages or evals rejected the query because of overload:
+    /// the returned [`Data`] are then incomplete (see [`DataReceiver::is_overloaded()`]).
+    pub overloaded: bool,
+}
+
+/// Indicates if the [`Data`] returned by [`Workspace::get_collect_timeout()`] are complete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Completeness {
+    /// all the storages and evals replied before the timeout.
+    Complete,
+    /// the timeout occurred before all the storages and evals replied:
+    /// only the [`Data`] received so far are returned.
+    PartialTimeout,
+}
+
+/// The timing metrics of a query, returned with the [`Data`] by [`Workspace::get_with_metrics()`].
+#[derive(Debug, Clone)]
+pub struct GetMetrics {
+    /// the total duration of the query, until all the responders sent their final reply.
+    pub elapsed: Duration,
+    /// the metrics of each responder that replied, in the order of their first reply.
+    pub responders: Vec<ResponderMetrics>,
+}
+
+impl GetMetrics {
+    /// Returns the responders whose last reply was received after `threshold`,
+    /// from the slowest to the fastest.
+    pub fn slow_responders(&self, threshold: Duration) -> Vec<SlowResponder> {
+        let mut slow: Vec<SlowResponder> = self
+            .responders
+            .iter()
+            .filter(|r| r.last_reply > threshold)
+            .map(|r| SlowResponder {
+                zid: r.zid,
+                elapsed: r.last_reply,
+            })
+            .collect();
+        slow.sort_by(|a, b| b.elapsed.cmp(&a.elapsed));
+        slow
+    }
+}
+
+/// The timing metrics of a responder (storage, eval or router) to a query (see [`GetMetrics`]).
+#[derive(Debug, Clone)]
+pub struct ResponderMetrics {
+    /// the zenoh id of the responder.
+    pub zid: PeerId,
+    /// the number of replies received from the responder.
+    pub replies: usize,
+    /// the time elapsed since the query was issued when its first reply was received.
+    pub first_reply: Duration,
+    /// the time elapsed since the query was issued when its last reply was received.
+    pub last_reply: Duration,
+}
+
+/// A responder to a query that replied after a given threshold (see [`GetMetrics::slow_responders()`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlowResponder {
+    /// the zenoh id of the responder.
+    pub zid: PeerId,
+    /// the time elapsed since the query was issued when its last reply was received.
+    pub elapsed: Duration,
+}
+
+/// The result of [`Workspace::get_quorum()`] for a [`Path`].
+#[derive(Debug, Clone)]
+pub enum QuorumData {
+    /// at least the required quorum of responders replied the same value.
+    Agreed {
+        /// the agreed value (with the most recent timestamp among the responders that replied it).
+        data: Data,
+        /// the number of responders that replied this value.
+        responders: usize,
+    },
+    /// the responders replied different values, and none of them reached the required quorum.
+    Conflict {
+        /// the path of the conflicting values.
+        path: Path,
+        /// each replied value with the number of responders that replied it,
+        /// from the most to the least replied (and then from the most recent to the oldest).
+        versions: Vec<(Data, usize)>,
+    },
+    /// all the responders replied the same value, but they're less than the required quorum.
+    Partial {
+        /// the value replied by all the responders.
+        data: Data,
+        /// the number of responders that replied this value.
+        responders: usize,
+    },
 }
 
-ztranscoder! {
-    /// A [`Stream`] of [`Data`] returned as a result of the [`Workspace::get()`] operation.
-    ///
-    /// [`Stream`]: async_std::stream::Stream
-    #[derive(Clone)]
-    pub DataReceiver: Receiver<Data> <- ReplyReceiver: Receiver<Reply>
-    with
-        DataIter: Iterator<Data>,
-        DataTryIter: Iterator<Data>,
-    {
-        decode_value: bool,
+// Decrypts `value` if it's encrypted with a key of `keyring`. Otherwise it's returned as is (still encrypted
+// if the key is unknown, so that a Workspace without the key still receives the value)
+fn decrypt(keyring: &Option<Arc<Keyring>>, value: Value) -> Value {
+    match (keyring, &value) {
+        (Some(keyring), Value::Raw(encoding::APP_ENCRYPTED, _)) => match keyring.decrypt(&value) {
+            Ok(decrypted) => decrypted,
+            // a Value encrypted with a key missing from the keyring is expected (e.g. for another tenant),
+            // but a malformed or tampered one is worth a warning
+            Err(err) => {
+                if let ZErrorKind::ValueDecodingFailed { .. } = err.get_kind() {
+                    warn!(
+                        "Can't authenticate a received Value (keep it encrypted): {}",
+                        err
+                    );
+                } else {
+                    debug!(
+                        "Can't decrypt a received Value (keep it encrypted): {}",
+                        err
+                    );
+                }
+                value
+            }
+        },
+        _ => value,
     }
 }
 
-impl DataReceiver {
-    fn transcode(&self, reply: Reply) -> ZResult<Data> {
-        let path: Path = reply.data.res_name.try_into().unwrap();
-        let (encoding, timestamp) = if let Some(info) = reply.data.data_info {
-            (
-                info.encoding.unwrap_or(encoding::APP_OCTET_STREAM),
-                info.timestamp.unwrap_or_else(new_reception_timestamp),
-            )
-        } else {
-            (encoding::APP_OCTET_STREAM, new_reception_timestamp())
-        };
-        let value = if self.decode_value {
-            Value::decode(encoding, reply.data.payload)?
-        } else {
-            Value::Raw(encoding, reply.data.payload)
-        };
-        Ok(Data {
-            path,
-            value,
-            timestamp,
-        })
+// Records a change observed by a subscription into the Workspace's journal, if any
+fn journal_observed(journal: &Option<Arc<Journal>>, sample: &Sample) {
+    if let Some(journal) = journal {
+        journal.record_sample(JournalOrigin::Observed, sample);
     }
 }
 
+// Returns true if the resource `name` matches one of the canonicalized `excludes` of a PathExpr
+fn is_excluded(excludes: &[String], name: &str) -> bool {
+    excludes.iter().any(|e| rname::intersect(e, name))
+}
+
+// Returns the specificity of a canonicalized PathExpr for Workspace::subscribe_router(): a PathExpr
+// without wildcard is the most specific, then the more characters outside of the wildcards the more specific
+fn specificity(expr: &str) -> (bool, usize) {
+    (
+        !expr.contains('*'),
+        expr.chars().filter(|c| *c != '*').count(),
+    )
+}
+
+// Returns the index of the first route matching the resource `name`, considering the exclusions
+fn first_matching_route(routes: &[(String, Vec<String>)], name: &str) -> Option<usize> {
+    routes
+        .iter()
+        .position(|(expr, excludes)| rname::intersect(expr, name) && !is_excluded(excludes, name))
+}
+
 /// The kind of a [`Change`].
 #[derive(Debug, Clone, PartialEq)]
 pub enum ChangeKind {
@@ -503,6 +3693,13 @@ impl From<ZInt> for ChangeKind {
 /// The notification of a change occured on a path/value and reported to a subscription.
 ///
 /// See [`Workspace::subscribe()`] and [`Workspace::subscribe_with_callback()`].
+///
+/// Outside of zenoh, a Change is created with [`Change::new()`], as more fields may be added.
+///
+/// **Breaking change:** `Change` is `#[non_exhaustive]` since the addition of the `checksum`,
+/// `monotonic_stamp`, `sequence` and `message_id` fields: outside of zenoh it can no longer be built
+/// with a struct expression (use [`Change::new()`]) nor destructured without a `..` pattern.
+#[non_exhaustive]
 #[derive(Debug, Clone)]
 pub struct Change {
     /// the [`Path`] related to this change.
@@ -513,15 +3710,55 @@ pub struct Change {
     pub timestamp: Timestamp,
     /// the kind of change (`PUT` or `DELETE`).
     pub kind: ChangeKind,
+    /// the result of the verification of the checksum attached by the publisher, if any
+    /// (see [`Workspace::put_with_checksum()`]).
+    pub checksum: Option<Result<ChecksumAlgo, IntegrityError>>,
+    /// the monotonic stamp attached by the publisher, if any (see [`Workspace::put_with_monotonic_stamp()`]).
+    pub monotonic_stamp: Option<u64>,
+    /// the zenoh id of the publisher and the sequence number it attached, if any
+    /// (see [`Workspace::put_with_sequence()`]).
+    pub sequence: Option<(PeerId, u64)>,
+    /// the unique id attached by the publisher, if any (see [`Workspace::put_with_message_id()`]).
+    pub message_id: Option<Uuid>,
 }
 
 impl Change {
+    /// Creates a [`Change`] without the information optionally attached by a publisher
+    /// (checksum, monotonic stamp, sequence number and message id).  
+    /// The value should be `None` for a [`ChangeKind::Delete`].
+    pub fn new(path: Path, value: Option<Value>, timestamp: Timestamp, kind: ChangeKind) -> Change {
+        Change {
+            path,
+            value,
+            timestamp,
+            kind,
+            checksum: None,
+            monotonic_stamp: None,
+            sequence: None,
+            message_id: None,
+        }
+    }
+
     /// Convert a [`Sample`] into a [`Change`].
     /// If the Sample's kind is DELETE, the Change's value is set to `None`.
     /// Otherwise, if decode_value is `true` the payload is decoded as a typed [`Value`].
     /// If decode_value is `false`, the payload is converted into a [`Value::Raw`].
     pub fn from_sample(sample: Sample, decode_value: bool) -> ZResult<Change> {
         let path = sample.res_name.try_into()?;
+        let checksum = checksum::verify(sample.data_info.as_ref(), &sample.payload);
+        let monotonic_stamp = sample
+            .data_info
+            .as_ref()
+            .and_then(|info| info.monotonic_stamp);
+        let sequence = sample
+            .data_info
+            .as_ref()
+            .and_then(|info| info.source_id.zip(info.source_sn));
+        let message_id = sample
+            .data_info
+            .as_ref()
+            .and_then(|info| info.message_id)
+            .map(Uuid::from_bytes);
         let (kind, encoding, timestamp) = if let Some(info) = sample.data_info {
             (
                 info.kind.map_or(ChangeKind::Put, ChangeKind::from),
@@ -548,14 +3785,82 @@ impl Change {
             value,
             timestamp,
             kind,
+            checksum,
+            monotonic_stamp,
+            sequence,
+            message_id,
         })
     }
 
+    // Decrypts the value of this Change if it's encrypted with a key of `keyring`
+    fn decrypt(mut self, keyring: &Option<Arc<Keyring>>) -> Change {
+        if keyring.is_some() {
+            self.value = self.value.map(|v| decrypt(keyring, v));
+        }
+        self
+    }
+
+    // Applies the Transforms to the value of this Change (the checksum still applies to the received payload)
+    fn transform(mut self, transforms: &[Transform]) -> Change {
+        if !transforms.is_empty() {
+            self.value = self.value.map(|v| Transform::apply_all(transforms, v));
+        }
+        self
+    }
+
+    /// Deserializes the JSON [`Value`] of this Change (e.g. put as a [`Value::from_json_typed()`]) into a `T`.  
+    /// Returns an error if the Change has no value (i.e. a `DELETE`), if the encoding of the value isn't JSON
+    /// (see [`Value::encoding_descr()`]), or if it can't be deserialized into a `T`.
+    pub fn deserialize<T>(&self) -> ZResult<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        match &self.value {
+            Some(value) => value.deserialize_json(),
+            None => zerror!(ZErrorKind::ValueDecodingFailed {
+                descr: format!(
+                    "No value to deserialize in {:?} of {}",
+                    self.kind, self.path
+                )
+            }),
+        }
+    }
+
+    /// Returns an [`AsyncRead`] over the payload of this change's value: for a value put with
+    /// [`Workspace::put_stream()`], its chunks are fetched one by one via `workspace` as the reader is read
+    /// (see [`ValueReader`]).
+    /// Returns an error if the change has no value (i.e. a [`ChangeKind::Delete`]), or if the manifest of the
+    /// chunks is malformed.
+    pub fn value_reader<'a>(&self, workspace: &'a Workspace<'a>) -> ZResult<ValueReader<'a>> {
+        match &self.value {
+            Some(value) => ValueReader::new(workspace, &self.path, value),
+            None => zerror!(ZErrorKind::Other {
+                descr: format!("No value to read in {:?} of {}", self.kind, self.path)
+            }),
+        }
+    }
+
+    /// Checks the integrity of this change's value against the checksum attached by the publisher.  
+    /// Returns [`IntegrityError::Missing`] if the publisher didn't attach any checksum.
+    pub fn verify_checksum(&self) -> Result<(), IntegrityError> {
+        match &self.checksum {
+            Some(Ok(_)) => Ok(()),
+            Some(Err(e)) => Err(e.clone()),
+            None => Err(IntegrityError::Missing),
+        }
+    }
+
     /// Convert this [`Change`] into a [`Sample`] to be sent via zenoh-net.
     pub fn into_sample(self) -> Sample {
         let mut info = DataInfo::new();
         info.kind = Some(self.kind as ZInt);
         info.timestamp = Some(self.timestamp);
+        info.monotonic_stamp = self.monotonic_stamp;
+        if let Some((source_id, source_sn)) = self.sequence {
+            info.source_id = Some(source_id);
+            info.source_sn = Some(source_sn);
+        }
+        info.message_id = self.message_id.map(|id| *id.as_bytes());
 
         let payload = match self.value {
             Some(v) => {
@@ -565,6 +3870,10 @@ impl Change {
             }
             None => ZBuf::new(),
         };
+        // re-attach a valid checksum, computed on the payload as re-encoded
+        if let Some(Ok(algo)) = self.checksum {
+            info.checksum = Some(algo.checksum(&payload));
+        }
 
         Sample {
             res_name: self.path.to_string(),
@@ -584,13 +3893,127 @@ ztranscoder! {
         ChangeTryIter: Iterator<Change>,
     {
         subscriber: Subscriber<'a>,
+        excludes: Vec<String>,
         decode_value: bool,
+        keyring: Option<Arc<Keyring>>,
+        journal: Option<Arc<Journal>>,
+        transforms: Vec<Transform>,
+        max_value_size: usize,
+        counters: Arc<SubscriberCounters>,
     }
 }
 
-impl ChangeReceiver<'_> {
+impl<'a> ChangeReceiver<'a> {
+    fn accept(&self, sample: &Sample) -> bool {
+        !is_excluded(&self.excludes, &sample.res_name)
+    }
+
     fn transcode(&self, sample: Sample) -> ZResult<Change> {
+        self.counters.count(sample.payload.len());
+        check_size(sample.payload.len(), self.max_value_size)?;
+        journal_observed(&self.journal, &sample);
         Change::from_sample(sample, self.decode_value)
+            .map(|change| change.decrypt(&self.keyring).transform(&self.transforms))
+    }
+
+    /// Returns the statistics of the changes received by this stream.  
+    /// Note that the changes are accounted when they're returned by this stream (or dropped as invalid),
+    /// and not the ones which are still buffered.
+    pub fn stats(&self) -> SubscriberStats {
+        self.counters.stats()
+    }
+
+    /// Pauses the reception of changes, keeping the subscription declared.
+    ///
+    /// While paused, the received changes are buffered up to the capacity of the stream's channel
+    /// (256 changes by default, configurable at build time with the `API_DATA_RECEPTION_CHANNEL_SIZE`
+    /// environment variable).
+    /// Once this buffer is full, the further changes are dropped until [`resume()`](ChangeReceiver::resume)
+    /// is called. Note that a paused stream may still return the changes buffered before the pause.
+    pub fn pause(&self) {
+        self.subscriber.pause()
+    }
+
+    /// Resumes the reception of changes after a [`pause()`](ChangeReceiver::pause).
+    /// The changes buffered while paused are returned first.
+    pub fn resume(&self) {
+        self.subscriber.resume()
+    }
+
+    /// Returns true if the reception of changes is paused.
+    pub fn is_paused(&self) -> bool {
+        self.subscriber.is_paused()
+    }
+
+    /// Converts this stream into a [`ChangeTryStream`] that returns the invalid samples as errors
+    /// instead of dropping them, for use with the [`TryStreamExt`] combinators.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use futures::prelude::*;
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// workspace
+    ///     .subscribe(&"/demo/example/**".try_into().unwrap())
+    ///     .await
+    ///     .unwrap()
+    ///     .into_try_stream()
+    ///     .try_for_each(|change| async move {
+    ///         println!(">> {:?} for {} : {:?}", change.kind, change.path, change.value);
+    ///         Ok(())
+    ///     })
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    ///
+    /// [`TryStreamExt`]: futures::stream::TryStreamExt
+    pub fn into_try_stream(self) -> ChangeTryStream<'a> {
+        ChangeTryStream { inner: self }
+    }
+
+    /// Converts this stream into a [`GapAwareChangeReceiver`] that also returns a [`ChangeEvent::Gap`]
+    /// each time a connection to a remote zenoh node (router or peer) is lost.
+    ///
+    /// The subscription survives the reconnections: it's transparently re-declared when the connection
+    /// is re-established and the stream resumes. But the changes published in between are lost.
+    /// A [`ChangeEvent::Gap`] tells the consumer that it may have missed some changes
+    /// (and e.g. that it should re-synchronize with a [`Workspace::get()`]).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use futures::prelude::*;
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::client(None)).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let mut events = workspace
+    ///     .subscribe(&"/demo/example/**".try_into().unwrap())
+    ///     .await
+    ///     .unwrap()
+    ///     .with_gap_markers();
+    /// while let Some(event) = events.next().await {
+    ///     match event {
+    ///         ChangeEvent::Change(change) => println!(">> {:?} for {}", change.kind, change.path),
+    ///         ChangeEvent::Gap => println!(">> connection lost: some changes may be missed"),
+    ///         ChangeEvent::Heartbeat | ChangeEvent::SequenceGap { .. } => (),
+    ///     }
+    /// }
+    /// # })
+    /// ```
+    pub fn with_gap_markers(self) -> GapAwareChangeReceiver<'a> {
+        let losses = self.subscriber.session.runtime.connection_losses.count();
+        GapAwareChangeReceiver {
+            inner: self,
+            losses,
+            listener: None,
+        }
     }
 
     // Closes the stream and the subscription.
@@ -599,6 +4022,130 @@ impl ChangeReceiver<'_> {
     }
 }
 
+/// An event returned by a [`GapAwareChangeReceiver`] or a [`Heartbeat`](crate::Heartbeat) stream.
+#[derive(Debug)]
+pub enum ChangeEvent {
+    /// a [`Change`] received by the subscription.
+    Change(Change),
+    /// a connection to a remote zenoh node was lost: some changes may have been missed.
+    Gap,
+    /// no change was received during the heartbeat interval
+    /// (see [`ChangeStreamExt::heartbeat()`](crate::ChangeStreamExt::heartbeat)).
+    Heartbeat,
+    /// the sequence numbers `from` to `to` (inclusive) of the publisher `source` were not received
+    /// (see [`ChangeStreamExt::detect_gaps()`](crate::ChangeStreamExt::detect_gaps)).
+    SequenceGap { source: PeerId, from: u64, to: u64 },
+}
+
+/// A [`Stream`] of [`ChangeEvent`] returned by [`ChangeReceiver::with_gap_markers()`].
+///
+/// [`Stream`]: async_std::stream::Stream
+pub struct GapAwareChangeReceiver<'a> {
+    inner: ChangeReceiver<'a>,
+    losses: usize,
+    listener: Option<EventListener>,
+}
+
+impl<'a> GapAwareChangeReceiver<'a> {
+    /// Converts back this stream into a [`ChangeReceiver`].
+    pub fn into_inner(self) -> ChangeReceiver<'a> {
+        self.inner
+    }
+
+    // Closes the stream and the subscription.
+    pub fn close(self) -> impl ZFuture<Output = ZResult<()>> {
+        self.inner.close()
+    }
+}
+
+impl Stream for GapAwareChangeReceiver<'_> {
+    type Item = ChangeEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let connection_losses = &this.inner.subscriber.session.runtime.connection_losses;
+        loop {
+            let losses = connection_losses.count();
+            if losses != this.losses {
+                this.losses = losses;
+                this.listener = None;
+                return Poll::Ready(Some(ChangeEvent::Gap));
+            }
+            match &mut this.listener {
+                // re-check the count once listening, not to miss a loss notified in between
+                None => this.listener = Some(connection_losses.listen()),
+                Some(listener) => match Pin::new(listener).poll(cx) {
+                    Poll::Ready(()) => this.listener = None,
+                    Poll::Pending => break,
+                },
+            }
+        }
+        match this.inner.poll_next(cx) {
+            Poll::Ready(Some(change)) => Poll::Ready(Some(ChangeEvent::Change(change))),
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// A [`TryStream`] of [`Change`] returned by [`ChangeReceiver::into_try_stream()`].
+///
+/// Unlike [`ChangeReceiver`], the samples that can't be decoded are returned as errors.
+///
+/// [`TryStream`]: futures::stream::TryStream
+pub struct ChangeTryStream<'a> {
+    inner: ChangeReceiver<'a>,
+}
+
+impl<'a> ChangeTryStream<'a> {
+    /// Converts back this stream into a [`ChangeReceiver`].
+    pub fn into_inner(self) -> ChangeReceiver<'a> {
+        self.inner
+    }
+
+    // Closes the stream and the subscription.
+    pub fn close(self) -> impl ZFuture<Output = ZResult<()>> {
+        self.inner.close()
+    }
+}
+
+impl Stream for ChangeTryStream<'_> {
+    type Item = ZResult<Change>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let inner = &mut self.get_mut().inner;
+        loop {
+            match inner.receiver.poll_next(cx) {
+                Poll::Ready(Some(sample)) if !inner.accept(&sample) => continue,
+                Poll::Ready(Some(sample)) => return Poll::Ready(Some(inner.transcode(sample))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+fn size_limit(session: &Session, key: ZInt, default: &str) -> usize {
+    session
+        .runtime
+        .config
+        .get_or(&key, default)
+        .parse()
+        .unwrap_or_else(|_| default.parse().unwrap())
+}
+
+fn is_too_large(err: &ZError) -> bool {
+    matches!(err.get_kind(), ZErrorKind::ValueTooLarge { .. })
+}
+
+fn check_size(size: usize, max: usize) -> ZResult<()> {
+    if size > max {
+        zerror!(ZErrorKind::ValueTooLarge { size, max })
+    } else {
+        Ok(())
+    }
+}
+
 fn path_value_to_sample(path: Path, value: Value) -> Sample {
     let (encoding, payload) = value.encode();
     let mut info = DataInfo::new();
@@ -611,18 +4158,120 @@ fn path_value_to_sample(path: Path, value: Value) -> Sample {
     }
 }
 
+/// The future returned by [`Workspace::subscribe_snapshot()`], resolving once the seed values are received.
+struct SnapshotFuture<'a> {
+    receiver: Option<ZResult<ChangeReceiver<'a>>>,
+    interval: Duration,
+    seed: ZPinBoxFuture<ZResult<Vec<Data>>>,
+}
+
+impl<'a> Future for SnapshotFuture<'a> {
+    type Output = ZResult<SnapshotOnTick<ChangeReceiver<'a>>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let seed = match Pin::new(&mut self.seed).poll(cx) {
+            Poll::Ready(seed) => seed,
+            Poll::Pending => return Poll::Pending,
+        };
+        let receiver = self
+            .receiver
+            .take()
+            .expect("SnapshotFuture polled after completion");
+        let interval = self.interval;
+        Poll::Ready(
+            receiver.and_then(|receiver| Ok(receiver.snapshot_on_tick(interval).seed(seed?))),
+        )
+    }
+}
+
+impl ZFuture for SnapshotFuture<'_> {
+    fn wait(self) -> Self::Output {
+        async_std::task::block_on(self)
+    }
+}
+
 /// A handle returned as result of [`Workspace::subscribe_with_callback()`] operation.
 pub struct SubscriberHandle<'a> {
     subscriber: CallbackSubscriber<'a>,
+    counters: Arc<SubscriberCounters>,
 }
 
 impl SubscriberHandle<'_> {
+    /// Returns the statistics of the changes received by this subscription.
+    pub fn stats(&self) -> SubscriberStats {
+        self.counters.stats()
+    }
+
     /// Closes the subscription.
     pub fn close(self) -> impl ZFuture<Output = ZResult<()>> {
         self.subscriber.undeclare()
     }
 }
 
+/// A handle returned as result of [`Workspace::subscribe_router()`] operation.
+pub struct SubscriberRouter<'a> {
+    handles: Vec<SubscriberHandle<'a>>,
+}
+
+impl SubscriberRouter<'_> {
+    /// Closes the subscriptions of all the routes.  
+    /// The handlers are dropped once they've processed the changes remaining in their queue.
+    pub fn close(self) -> impl ZFuture<Output = ZResult<()>> {
+        zready_try!({
+            for handle in self.handles {
+                handle.close().wait()?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// The statistics of the changes received by a subscription, accounted locally by the subscriber
+/// (see [`SubscriberHandle::stats()`] and [`ChangeReceiver::stats()`]).
+///
+/// A monitoring loop can compute the throughput of a subscription by polling these statistics periodically.
+/// Note that the samples received but dropped because they're invalid (e.g. that can't be decoded) are accounted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubscriberStats {
+    /// The number of received changes.
+    pub received: u64,
+    /// The total size of the payloads of the received changes, in bytes.
+    pub bytes: u64,
+    /// The time of reception of the last change, if any.
+    pub last_at: Option<SystemTime>,
+}
+
+// The lock-free counters of the changes received by a subscription.
+#[derive(Default)]
+struct SubscriberCounters {
+    received: AtomicU64,
+    bytes: AtomicU64,
+    // nanoseconds since UNIX_EPOCH, 0 if no change was received
+    last_at: AtomicU64,
+}
+
+impl SubscriberCounters {
+    fn count(&self, bytes: usize) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        self.received.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.last_at.store(now, Ordering::Relaxed);
+    }
+
+    fn stats(&self) -> SubscriberStats {
+        SubscriberStats {
+            received: self.received.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            last_at: match self.last_at.load(Ordering::Relaxed) {
+                0 => None,
+                nanos => Some(UNIX_EPOCH + Duration::from_nanos(nanos)),
+            },
+        }
+    }
+}
+
 /// A `GET` request received by an evaluation function (see [`Workspace::register_eval()`]).
 #[derive(Clone)]
 pub struct GetRequest {
@@ -631,19 +4280,152 @@ pub struct GetRequest {
 }
 
 impl GetRequest {
-    /// Send a [`Path`]/[`Value`] as a reply to the requester.
+    /// Send a [`Path`]/[`Value`] as a reply to the requester.  
+    /// A warning is logged if the encoding of the value isn't accepted by the requester
+    /// (see [`GetRequest::accepted_encodings()`]).
     #[inline(always)]
     pub fn reply(&self, path: Path, value: Value) {
+        self.check_accepted(&path, &value);
         self.replies_sender.send(path_value_to_sample(path, value))
     }
 
-    /// Send a [`Path`]/[`Value`] as a reply to the requester.
+    /// Send a [`Path`]/[`Value`] as a reply to the requester.  
+    /// A warning is logged if the encoding of the value isn't accepted by the requester
+    /// (see [`GetRequest::accepted_encodings()`]).
     #[inline(always)]
     pub async fn reply_async(&self, path: Path, value: Value) {
+        self.check_accepted(&path, &value);
         self.replies_sender
             .send_async(path_value_to_sample(path, value))
             .await
     }
+
+    /// Returns the encodings accepted by the requester, in its order of preference, as specified by the
+    /// `_accept` property of the selector (see [`Selector::accepted_encodings()`]).
+    /// An empty Vec means that the requester accepts any encoding.
+    #[inline]
+    pub fn accepted_encodings(&self) -> Vec<String> {
+        self.selector.accepted_encodings()
+    }
+
+    /// Returns the encoding preferred by the requester among the `encodings` the eval can reply
+    /// (e.g. `&["text/plain", "application/json"]`), according to the order of the `_accept` property
+    /// of the selector (see [`Selector::accepted_encodings()`]), possibly via a wildcard (e.g. `application/*`).  
+    /// Returns the first of `encodings` (i.e. the eval's default) if the requester didn't specify any accepted
+    /// encoding, and `None` if it accepts none of `encodings`.
+    ///
+    /// This allows an eval to serve several representations of a same value from the same handler.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::*;
+    /// use futures::prelude::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let path: Path = "/demo/example/eval".try_into().unwrap();
+    /// let mut get_stream = workspace.register_eval(&path.clone().into()).await.unwrap();
+    /// while let Some(get_request) = get_stream.next().await {
+    ///     // e.g. a get on "/demo/example/eval?(_accept=application/json,text/plain)"
+    ///     let value = match get_request.best_match(&["text/plain", "application/json"]) {
+    ///         Some("application/json") => Value::Json(r#"{"temperature": 21.5}"#.to_string()),
+    ///         _ => Value::StringUtf8("temperature: 21.5".to_string()),
+    ///     };
+    ///     get_request.reply_async(path.clone(), value).await;
+    /// }
+    /// # })
+    /// ```
+    pub fn best_match<'a>(&self, encodings: &[&'a str]) -> Option<&'a str> {
+        best_match(&self.accepted_encodings(), encodings)
+    }
+
+    /// Returns true if the requester explicitly accepts the `encoding` (e.g. `"application/json"`) with the
+    /// `_accept` property of the selector, possibly via a wildcard (e.g. `application/*`).  
+    /// This allows an eval to serve several representations of a same value from the same handler, falling back
+    /// to its default representation when the requester didn't specify any accepted encoding
+    /// (see [`GetRequest::best_match()`] to take the requester's order of preference into account).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use futures::prelude::*;
+    /// use zenoh::*;
+    /// use std::convert::TryInto;
+    ///
+    /// let zenoh = Zenoh::new(net::config::default()).await.unwrap();
+    /// let workspace = zenoh.workspace(None).await.unwrap();
+    /// let path: Path = "/demo/example/eval".try_into().unwrap();
+    /// let mut get_stream = workspace.register_eval(&path.clone().into()).await.unwrap();
+    /// while let Some(get_request) = get_stream.next().await {
+    ///     // e.g. a get on "/demo/example/eval?(_accept=application/json)"
+    ///     let value = if get_request.prefers("application/json") {
+    ///         Value::Json(r#"{"temperature": 21.5}"#.to_string())
+    ///     } else {
+    ///         Value::StringUtf8("temperature: 21.5".to_string())
+    ///     };
+    ///     get_request.reply_async(path.clone(), value).await;
+    /// }
+    /// # })
+    /// ```
+    pub fn prefers(&self, encoding: &str) -> bool {
+        self.accepted_encodings()
+            .iter()
+            .any(|accepted| encoding_matches(accepted, encoding))
+    }
+
+    /// Returns true if the requester accepts the `encoding` (e.g. `"application/json"`), i.e. if it didn't
+    /// specify any accepted encoding, or if the `_accept` property of the selector includes it, possibly via
+    /// a wildcard (e.g. `application/*`), regardless of its order of preference (see [`GetRequest::best_match()`]).
+    pub fn accepts(&self, encoding: &str) -> bool {
+        let accepted = self.accepted_encodings();
+        accepted.is_empty() || accepted.iter().any(|a| encoding_matches(a, encoding))
+    }
+
+    // Logs a warning if the requester doesn't accept the encoding of `value`
+    fn check_accepted(&self, path: &Path, value: &Value) {
+        let encoding = value.encoding_descr();
+        if !self.accepts(&encoding) {
+            warn!(
+                "Reply on {} with encoding {} not accepted by the requester (accepted: {})",
+                path,
+                encoding,
+                self.accepted_encodings().join(",")
+            );
+        }
+    }
+
+    /// Returns the [`GetMethod`] requested by the `_method` property of the selector
+    /// (see [`Selector::method()`]).
+    #[inline]
+    pub fn method(&self) -> ZResult<GetMethod> {
+        self.selector.method()
+    }
+}
+
+// Returns true if `encoding` matches the `accepted` encoding, which may be a wildcard (`*/*` or `<type>/*`)
+fn encoding_matches(accepted: &str, encoding: &str) -> bool {
+    let encoding = encoding.to_ascii_lowercase();
+    match accepted.strip_suffix('*') {
+        Some("*/") => true,
+        Some(prefix) if prefix.ends_with('/') => encoding.starts_with(prefix),
+        _ => encoding == accepted.to_ascii_lowercase(),
+    }
+}
+
+// Returns the first of `encodings` matching the `accepted` encodings, in their order of preference
+// (or the first of `encodings` if `accepted` is empty)
+fn best_match<'a>(accepted: &[String], encodings: &[&'a str]) -> Option<&'a str> {
+    if accepted.is_empty() {
+        return encodings.first().copied();
+    }
+    accepted.iter().find_map(|accepted| {
+        encodings
+            .iter()
+            .find(|encoding| encoding_matches(accepted, encoding))
+            .copied()
+    })
 }
 
 fn query_to_get(query: Query) -> ZResult<GetRequest> {
@@ -658,6 +4440,7 @@ fn query_to_get(query: Query) -> ZResult<GetRequest> {
 /// [`Stream`]: async_std::stream::Stream
 pub struct GetRequestStream<'a> {
     queryable: Queryable<'a>,
+    excludes: Vec<String>,
 }
 
 impl GetRequestStream<'_> {
@@ -672,16 +4455,66 @@ impl Stream for GetRequestStream<'_> {
 
     #[inline(always)]
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
-        match self.get_mut().queryable.receiver().poll_next(cx) {
-            Poll::Ready(Some(query)) => match query_to_get(query) {
-                Ok(get) => Poll::Ready(Some(get)),
-                Err(err) => {
-                    warn!("Error in receveid get(): {}. Ignore it.", err);
-                    Poll::Pending
+        let this = self.get_mut();
+        loop {
+            match this.queryable.receiver().poll_next(cx) {
+                Poll::Ready(Some(query)) => {
+                    // skip the get requests selecting only excluded paths
+                    if this
+                        .excludes
+                        .iter()
+                        .any(|e| rname::include(e, &query.res_name))
+                    {
+                        continue;
+                    }
+                    match query_to_get(query) {
+                        Ok(get) => return Poll::Ready(Some(get)),
+                        Err(err) => warn!("Error in receveid get(): {}. Ignore it.", err),
+                    }
                 }
-            },
-            Poll::Ready(None) => Poll::Ready(None),
-            Poll::Pending => Poll::Pending,
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepted_encodings_matching() {
+        assert!(encoding_matches("application/json", "application/json"));
+        assert!(encoding_matches("application/json", "Application/JSON"));
+        assert!(!encoding_matches(
+            "application/json",
+            "application/octet-stream"
+        ));
+        assert!(encoding_matches("text/*", "text/plain"));
+        assert!(!encoding_matches("text/*", "application/json"));
+        assert!(encoding_matches("*/*", "application/json"));
+        assert!(!encoding_matches("text*", "text/plain"));
+
+        let accepted = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<String>>();
+        let encodings = ["text/plain", "application/json"];
+        assert_eq!(best_match(&[], &encodings), Some("text/plain"));
+        assert_eq!(
+            best_match(&accepted(&["application/json", "text/plain"]), &encodings),
+            Some("application/json")
+        );
+        assert_eq!(
+            best_match(&accepted(&["text/plain", "application/json"]), &encodings),
+            Some("text/plain")
+        );
+        assert_eq!(
+            best_match(&accepted(&["image/png", "application/*"]), &encodings),
+            Some("application/json")
+        );
+        assert_eq!(best_match(&accepted(&["image/png"]), &encodings), None);
+        assert_eq!(
+            best_match(&accepted(&["*/*"]), &encodings),
+            Some("text/plain")
+        );
+    }
+}
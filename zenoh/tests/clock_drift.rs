@@ -0,0 +1,105 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::prelude::FutureExt;
+use async_std::task;
+use futures::prelude::*;
+use std::convert::TryInto;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use zenoh::net::config::{
+    ZN_ADD_TIMESTAMP_KEY, ZN_LISTENER_KEY, ZN_MAX_CLOCK_DRIFT_KEY, ZN_MULTICAST_SCOUTING_KEY,
+    ZN_PEER_KEY,
+};
+use zenoh::net::{CongestionControl, DataInfo};
+use zenoh::*;
+
+const LISTENER: &str = "tcp/127.0.0.1:17481";
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+fn config() -> net::config::ConfigProperties {
+    let mut config = net::config::peer();
+    config.insert(ZN_MULTICAST_SCOUTING_KEY, "false".to_string());
+    config
+}
+
+fn since_epoch() -> Duration {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap()
+}
+
+#[test]
+fn clock_drift() {
+    task::block_on(async {
+        let zenoh1 = Zenoh::new({
+            let mut config = config();
+            config.insert(ZN_LISTENER_KEY, LISTENER.to_string());
+            config.insert(ZN_ADD_TIMESTAMP_KEY, "true".to_string());
+            config.insert(ZN_MAX_CLOCK_DRIFT_KEY, "50".to_string());
+            config
+        })
+        .await
+        .unwrap();
+        let zenoh2 = Zenoh::new({
+            let mut config = config();
+            config.insert(ZN_PEER_KEY, LISTENER.to_string());
+            config
+        })
+        .await
+        .unwrap();
+
+        let workspace1 = zenoh1.workspace(None).await.unwrap();
+        let mut changes = workspace1
+            .subscribe(&"/test/clock_drift".try_into().unwrap())
+            .await
+            .unwrap();
+
+        // a sensor with a clock one hour ahead
+        let source = TimestampId::new(1, [0xAB; TimestampId::MAX_SIZE]);
+        let ahead = Timestamp::new((since_epoch() + Duration::from_secs(3600)).into(), source);
+        let (encoding, payload) = Value::Integer(1).encode();
+        // publish until the subscription is propagated
+        let receive = async {
+            loop {
+                let mut info = DataInfo::new();
+                info.encoding = Some(encoding);
+                info.timestamp = Some(ahead);
+                zenoh2
+                    .session()
+                    .write_with_info(
+                        &"/test/clock_drift".into(),
+                        payload.clone(),
+                        info,
+                        CongestionControl::Block,
+                    )
+                    .await
+                    .unwrap();
+                if let Ok(Some(change)) = changes.next().timeout(Duration::from_millis(100)).await {
+                    break change;
+                }
+            }
+        };
+        let change = receive.timeout(TIMEOUT).await.unwrap();
+
+        // the timestamp is clamped to the local clock, and the skew reported
+        let bound: uhlc::NTP64 = (since_epoch() + Duration::from_secs(1)).into();
+        assert!(*change.timestamp.get_time() < bound);
+        let skews = zenoh1.clock_skews();
+        assert_eq!(skews.len(), 1);
+        assert_eq!(skews[0].source, source);
+        assert!(skews[0].clamped >= 1);
+        assert!(skews[0].max_skew > Duration::from_secs(3500));
+
+        drop(changes);
+        zenoh2.close().await.unwrap();
+        zenoh1.close().await.unwrap();
+    });
+}
@@ -0,0 +1,72 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::prelude::*;
+use async_std::task;
+use std::convert::TryInto;
+use std::time::Duration;
+use zenoh::net::config::ZN_MULTICAST_SCOUTING_KEY;
+use zenoh::*;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+async fn next_change(changes: &mut ChangeReceiver<'_>) -> Change {
+    changes.next().timeout(TIMEOUT).await.unwrap().unwrap()
+}
+
+#[test]
+fn delete_timestamped() {
+    task::block_on(async {
+        let mut config = net::config::peer();
+        config.insert(ZN_MULTICAST_SCOUTING_KEY, "false".to_string());
+        let zenoh = Zenoh::new(config).await.unwrap();
+        let workspace = zenoh
+            .workspace(Some("/test/delete".try_into().unwrap()))
+            .await
+            .unwrap();
+        let mut changes = workspace
+            .subscribe(&"**".try_into().unwrap())
+            .await
+            .unwrap();
+        let path: Path = "a".try_into().unwrap();
+
+        let put_ts = workspace.put_timestamped(&path, "v".into()).await.unwrap();
+        let delete_ts = workspace.delete_timestamped(&path).await.unwrap();
+        // both are generated by the HLC of the session, which is strictly monotonic
+        assert!(delete_ts > put_ts);
+
+        let change = next_change(&mut changes).await;
+        assert_eq!(change.kind, ChangeKind::Put);
+        assert_eq!(change.path.as_str(), "/test/delete/a");
+        assert_eq!(change.timestamp, put_ts);
+        let change = next_change(&mut changes).await;
+        assert_eq!(change.kind, ChangeKind::Delete);
+        assert_eq!(change.path.as_str(), "/test/delete/a");
+        assert!(change.value.is_none());
+        assert_eq!(change.timestamp, delete_ts);
+
+        // deleting a path without value succeeds, and is notified as well
+        let delete_ts = workspace
+            .delete_timestamped(&"never_put".try_into().unwrap())
+            .await
+            .unwrap();
+        let change = next_change(&mut changes).await;
+        assert_eq!(change.kind, ChangeKind::Delete);
+        assert_eq!(change.path.as_str(), "/test/delete/never_put");
+        assert_eq!(change.timestamp, delete_ts);
+
+        changes.close().await.unwrap();
+        drop(workspace);
+        zenoh.close().await.unwrap();
+    });
+}
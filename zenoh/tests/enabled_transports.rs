@@ -0,0 +1,56 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::task;
+use zenoh::net::config::{
+    ZN_ENABLED_TRANSPORTS_KEY, ZN_LISTENER_KEY, ZN_MULTICAST_SCOUTING_KEY, ZN_PEER_KEY,
+};
+use zenoh::*;
+
+const LISTENER: &str = "tcp/127.0.0.1:17481";
+
+fn config(enabled_transports: &str, listener: &str) -> net::config::ConfigProperties {
+    let mut config = net::config::peer();
+    config.insert(ZN_MULTICAST_SCOUTING_KEY, "false".to_string());
+    config.insert(ZN_ENABLED_TRANSPORTS_KEY, enabled_transports.to_string());
+    if !listener.is_empty() {
+        config.insert(ZN_LISTENER_KEY, listener.to_string());
+    }
+    config
+}
+
+#[test]
+fn enabled_transports() {
+    task::block_on(async {
+        // an unknown protocol (e.g. a typo) or an empty list is a config error
+        assert!(Zenoh::new(config("tcp,tpc", LISTENER)).await.is_err());
+        assert!(Zenoh::new(config(" , ", LISTENER)).await.is_err());
+
+        // a listener of a disabled protocol is rejected at startup
+        assert!(Zenoh::new(config("udp", LISTENER)).await.is_err());
+        let mut with_peer = config("udp", "");
+        with_peer.insert(ZN_PEER_KEY, LISTENER.to_string());
+        assert!(Zenoh::new(with_peer).await.is_err());
+
+        // without configured listener, the default tcp listener is skipped if tcp is disabled
+        let zenoh = Zenoh::new(config("udp", "")).await.unwrap();
+        zenoh.close().await.unwrap();
+
+        let zenoh = Zenoh::new(config("tcp", LISTENER)).await.unwrap();
+        // a peer of a disabled protocol is rejected on reload
+        let mut new_config = config("tcp", LISTENER);
+        new_config.insert(ZN_PEER_KEY, "udp/127.0.0.1:17482".to_string());
+        assert!(zenoh.reload_config(&new_config).await.is_err());
+        zenoh.close().await.unwrap();
+    });
+}
@@ -210,12 +210,18 @@ fn gen_data_info() -> DataInfo {
         kind: option_gen!(gen!(ZInt)),
         encoding: option_gen!(gen!(ZInt)),
         timestamp: option_gen!(gen_timestamp()),
+        checksum: option_gen!(Checksum {
+            algo: gen!(ZInt),
+            digest: gen_buffer(64),
+        }),
+        monotonic_stamp: option_gen!(gen!(ZInt)),
         #[cfg(feature = "zero-copy")]
         sliced: false,
         source_id: option_gen!(gen_pid()),
         source_sn: option_gen!(gen!(ZInt)),
         first_router_id: option_gen!(gen_pid()),
         first_router_sn: option_gen!(gen!(ZInt)),
+        message_id: option_gen!(*uuid::Uuid::new_v4().as_bytes()),
     }
 }
 
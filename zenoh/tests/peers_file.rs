@@ -0,0 +1,81 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::task;
+use std::time::Duration;
+use zenoh::net::config::{ZN_LISTENER_KEY, ZN_MULTICAST_SCOUTING_KEY, ZN_PEERS_FILE_KEY};
+use zenoh::net::info::ZN_INFO_PEER_PID_KEY;
+use zenoh::*;
+
+const LISTENER: &str = "tcp/127.0.0.1:17471";
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+fn config() -> net::config::ConfigProperties {
+    let mut config = net::config::peer();
+    config.insert(ZN_MULTICAST_SCOUTING_KEY, "false".to_string());
+    config
+}
+
+// Waits until `zenoh` is connected to `count` peers.
+async fn wait_peers(zenoh: &Zenoh, count: usize) {
+    let wait = async {
+        loop {
+            let info = zenoh.session().info().await;
+            let peers = info
+                .get(&ZN_INFO_PEER_PID_KEY)
+                .map(|pids| pids.split(',').filter(|pid| !pid.is_empty()).count())
+                .unwrap_or(0);
+            if peers == count {
+                break;
+            }
+            task::sleep(Duration::from_millis(100)).await;
+        }
+    };
+    async_std::future::timeout(TIMEOUT, wait).await.unwrap();
+}
+
+#[test]
+fn peers_file() {
+    task::block_on(async {
+        let path = std::env::temp_dir().join("zenoh_peers_file_test.txt");
+        std::fs::write(&path, "# no peer yet\n").unwrap();
+
+        let zenoh1 = Zenoh::new({
+            let mut config = config();
+            config.insert(ZN_LISTENER_KEY, LISTENER.to_string());
+            config
+        })
+        .await
+        .unwrap();
+        let zenoh2 = Zenoh::new({
+            let mut config = config();
+            config.insert(ZN_PEERS_FILE_KEY, path.to_str().unwrap().to_string());
+            config
+        })
+        .await
+        .unwrap();
+        wait_peers(&zenoh2, 0).await;
+
+        // an added peer is connected
+        std::fs::write(&path, format!("# zenoh1\n{}\n\ninvalid\n", LISTENER)).unwrap();
+        wait_peers(&zenoh2, 1).await;
+
+        // a removed peer is disconnected
+        std::fs::write(&path, "").unwrap();
+        wait_peers(&zenoh2, 0).await;
+
+        zenoh2.close().await.unwrap();
+        zenoh1.close().await.unwrap();
+        let _ = std::fs::remove_file(&path);
+    });
+}
@@ -0,0 +1,62 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::prelude::*;
+use async_std::task;
+use std::convert::TryInto;
+use std::time::Duration;
+use zenoh::net::config::{ZN_MAX_CONCURRENT_QUERIES_KEY, ZN_MULTICAST_SCOUTING_KEY};
+use zenoh::*;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+#[test]
+fn query_overload() {
+    task::block_on(async {
+        let mut config = net::config::peer();
+        config.insert(ZN_MULTICAST_SCOUTING_KEY, "false".to_string());
+        config.insert(ZN_MAX_CONCURRENT_QUERIES_KEY, "1".to_string());
+        let zenoh = Zenoh::new(config).await.unwrap();
+        let workspace = zenoh.workspace(None).await.unwrap();
+        let path: Path = "/test/overload".try_into().unwrap();
+        let mut eval = workspace
+            .register_eval(&PathExpr::from(&path))
+            .await
+            .unwrap();
+
+        // the first query is processed until its request is dropped by the eval
+        let mut first = workspace.get(&Selector::from(&path)).await.unwrap();
+        let request = eval.next().timeout(TIMEOUT).await.unwrap().unwrap();
+
+        // the second query is rejected: the overload is reported, not returned as a Data
+        let mut second = workspace.get(&Selector::from(&path)).await.unwrap();
+        assert!(second.next().timeout(TIMEOUT).await.unwrap().is_none());
+        assert!(second.is_overloaded());
+        let (data, summary) = workspace.get_collect(&Selector::from(&path)).await.unwrap();
+        assert!(data.is_empty());
+        assert!(summary.overloaded);
+
+        request
+            .reply_async(path.clone(), Value::StringUtf8("reply".to_string()))
+            .await;
+        drop(request);
+        let data = first.next().timeout(TIMEOUT).await.unwrap().unwrap();
+        assert_eq!(data.path, path);
+        assert!(first.next().timeout(TIMEOUT).await.unwrap().is_none());
+        assert!(!first.is_overloaded());
+
+        eval.close().await.unwrap();
+        drop(workspace);
+        zenoh.close().await.unwrap();
+    });
+}
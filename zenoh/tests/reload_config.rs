@@ -0,0 +1,69 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::task;
+use zenoh::net::config::{
+    ZN_LISTENER_KEY, ZN_LISTENER_STR, ZN_MAX_SESSIONS_KEY, ZN_MAX_SESSIONS_STR,
+    ZN_MULTICAST_SCOUTING_KEY, ZN_PEER_KEY,
+};
+use zenoh::*;
+
+const LISTENER1: &str = "tcp/127.0.0.1:17461";
+const LISTENER2: &str = "tcp/127.0.0.1:17462";
+
+fn config(listeners: &str) -> net::config::ConfigProperties {
+    let mut config = net::config::peer();
+    config.insert(ZN_MULTICAST_SCOUTING_KEY, "false".to_string());
+    config.insert(ZN_LISTENER_KEY, listeners.to_string());
+    config
+}
+
+#[test]
+fn reload_config() {
+    task::block_on(async {
+        let zenoh = Zenoh::new(config(LISTENER1)).await.unwrap();
+
+        // unchanged config
+        let report = zenoh.reload_config(&config(LISTENER1)).await.unwrap();
+        assert!(report.applied.is_empty());
+        assert!(report.restart_required.is_empty());
+
+        // an added listener is applied, a changed max_sessions requires a restart
+        let mut new_config = config(&format!("{},{}", LISTENER1, LISTENER2));
+        new_config.insert(ZN_MAX_SESSIONS_KEY, "10".to_string());
+        let report = zenoh.reload_config(&new_config).await.unwrap();
+        assert_eq!(report.applied, vec![ZN_LISTENER_STR.to_string()]);
+        assert_eq!(
+            report.restart_required,
+            vec![ZN_MAX_SESSIONS_STR.to_string()]
+        );
+        let zenoh2 = Zenoh::new({
+            let mut config = config("");
+            config.insert(ZN_PEER_KEY, LISTENER2.to_string());
+            config
+        })
+        .await
+        .unwrap();
+
+        // a removed listener requires a restart
+        let report = zenoh.reload_config(&config(LISTENER2)).await.unwrap();
+        assert!(report.applied.is_empty());
+        assert_eq!(report.restart_required, vec![ZN_LISTENER_STR.to_string()]);
+
+        // an invalid locator is an error
+        assert!(zenoh.reload_config(&config("invalid")).await.is_err());
+
+        zenoh2.close().await.unwrap();
+        zenoh.close().await.unwrap();
+    });
+}
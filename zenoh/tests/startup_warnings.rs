@@ -0,0 +1,48 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::task;
+use zenoh::net::config::{
+    ZN_LISTENER_KEY, ZN_LISTENER_STR, ZN_MULTICAST_SCOUTING_KEY, ZN_STRICT_STARTUP_KEY,
+};
+use zenoh::*;
+
+const LISTENER: &str = "tcp/127.0.0.1:17453";
+
+fn config(strict: bool) -> net::config::ConfigProperties {
+    let mut config = net::config::peer();
+    config.insert(ZN_MULTICAST_SCOUTING_KEY, "false".to_string());
+    config.insert(ZN_LISTENER_KEY, LISTENER.to_string());
+    config.insert(ZN_STRICT_STARTUP_KEY, strict.to_string());
+    config
+}
+
+#[test]
+fn startup_warnings() {
+    task::block_on(async {
+        let zenoh1 = Zenoh::new(config(true)).await.unwrap();
+        assert!(zenoh1.startup_warnings().is_empty());
+
+        // The listener's port is already in use
+        assert!(Zenoh::new(config(true)).await.is_err());
+
+        let zenoh2 = Zenoh::new(config(false)).await.unwrap();
+        let warnings = zenoh2.startup_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].key, ZN_LISTENER_STR);
+        assert_eq!(warnings[0].value, LISTENER);
+
+        zenoh2.close().await.unwrap();
+        zenoh1.close().await.unwrap();
+    });
+}
@@ -0,0 +1,64 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::task;
+use std::convert::TryInto;
+use std::time::Duration;
+use zenoh::net::config::{ZN_LISTENER_KEY, ZN_MULTICAST_SCOUTING_KEY, ZN_PEER_KEY};
+use zenoh::net::queryable::EVAL;
+use zenoh::*;
+
+const LISTENER: &str = "tcp/127.0.0.1:17471";
+
+fn config(key: u64, locator: &str) -> net::config::ConfigProperties {
+    let mut config = net::config::peer();
+    config.insert(ZN_MULTICAST_SCOUTING_KEY, "false".to_string());
+    config.insert(key, locator.to_string());
+    config
+}
+
+#[test]
+fn trace_route() {
+    task::block_on(async {
+        let zenoh1 = Zenoh::new(config(ZN_LISTENER_KEY, LISTENER)).await.unwrap();
+        let zenoh2 = Zenoh::new(config(ZN_PEER_KEY, LISTENER)).await.unwrap();
+        let selector: Selector = "/test/trace_route/**".try_into().unwrap();
+
+        // no queryable yet
+        assert!(zenoh1.trace_route(&selector).unwrap().is_empty());
+
+        let queryable = zenoh2
+            .session()
+            .declare_queryable(&"/test/trace_route/eval".into(), EVAL)
+            .await
+            .unwrap();
+        task::sleep(Duration::from_secs(1)).await;
+
+        let hops = zenoh1.trace_route(&selector).unwrap();
+        assert_eq!(hops.len(), 1);
+        assert_eq!(hops[0].pid.to_string(), zenoh2.id());
+        assert_eq!(hops[0].from.to_string(), zenoh1.id());
+        assert_eq!(hops[0].distance, 1);
+        assert!(hops[0].responder);
+
+        // the local queryable is at distance 0
+        let hops = zenoh2.trace_route(&selector).unwrap();
+        assert!(hops
+            .iter()
+            .any(|hop| hop.distance == 0 && hop.responder && hop.pid.to_string() == zenoh2.id()));
+
+        queryable.undeclare().await.unwrap();
+        zenoh2.close().await.unwrap();
+        zenoh1.close().await.unwrap();
+    });
+}
@@ -0,0 +1,176 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::prelude::*;
+use async_std::sync::Arc;
+use async_std::task;
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use zenoh::net::link::{
+    DefaultTransportFactory, EndPoint, LinkManagerUnicast, LinkManagerUnicastTrait, LinkUnicast,
+    Locator, LocatorAddress, LocatorProtocol, TransportFactory,
+};
+use zenoh::net::protocol::core::{whatami, PeerId};
+use zenoh::net::transport::{
+    DummyTransportPeerEventHandler, TransportEventHandler, TransportManager,
+    TransportManagerConfig, TransportMulticast, TransportMulticastEventHandler, TransportPeer,
+    TransportPeerEventHandler, TransportUnicast,
+};
+use zenoh_util::core::ZResult;
+use zenoh_util::zasync_executor_init;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+struct SHFactory;
+
+impl TransportEventHandler for SHFactory {
+    fn new_unicast(
+        &self,
+        _peer: TransportPeer,
+        _transport: TransportUnicast,
+    ) -> ZResult<Arc<dyn TransportPeerEventHandler>> {
+        Ok(Arc::new(DummyTransportPeerEventHandler::default()))
+    }
+
+    fn new_multicast(
+        &self,
+        _transport: TransportMulticast,
+    ) -> ZResult<Arc<dyn TransportMulticastEventHandler>> {
+        panic!();
+    }
+}
+
+// A factory wrapping a built-in transport, counting the opened links
+struct CountingFactory {
+    inner: DefaultTransportFactory,
+    links: Arc<AtomicUsize>,
+}
+
+struct CountingLinkManager {
+    inner: LinkManagerUnicast,
+    links: Arc<AtomicUsize>,
+}
+
+impl TransportFactory for CountingFactory {
+    fn protocol(&self) -> &str {
+        self.inner.protocol()
+    }
+
+    fn make(&self, manager: TransportManager) -> ZResult<LinkManagerUnicast> {
+        Ok(Arc::new(CountingLinkManager {
+            inner: self.inner.make(manager)?,
+            links: self.links.clone(),
+        }))
+    }
+}
+
+#[async_trait]
+impl LinkManagerUnicastTrait for CountingLinkManager {
+    async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
+        let link = self.inner.new_link(endpoint).await?;
+        self.links.fetch_add(1, Ordering::SeqCst);
+        Ok(link)
+    }
+
+    async fn new_listener(&self, endpoint: EndPoint) -> ZResult<Locator> {
+        self.inner.new_listener(endpoint).await
+    }
+
+    async fn del_listener(&self, endpoint: &EndPoint) -> ZResult<()> {
+        self.inner.del_listener(endpoint).await
+    }
+
+    fn get_listeners(&self) -> Vec<EndPoint> {
+        self.inner.get_listeners()
+    }
+
+    fn get_locators(&self) -> Vec<Locator> {
+        self.inner.get_locators()
+    }
+}
+
+#[test]
+fn transport_factory_custom_locator() {
+    task::block_on(async {
+        zasync_executor_init!();
+
+        let endpoint: EndPoint = "mem/test".parse().unwrap();
+        match &endpoint.locator.address {
+            LocatorAddress::Custom(l) => {
+                assert_eq!(l.protocol, "mem");
+                assert_eq!(l.address, "test");
+            }
+            _ => panic!("Not a custom locator: {}", endpoint),
+        }
+        assert_eq!(
+            endpoint.locator.address.get_proto(),
+            LocatorProtocol::Custom("mem".to_string())
+        );
+        assert_eq!(endpoint.to_string(), "mem/test");
+        assert!("m:m/test".parse::<EndPoint>().is_err());
+
+        // Without a factory for the protocol, no listener can be added
+        let config = TransportManagerConfig::builder().build(Arc::new(SHFactory::default()));
+        let manager = TransportManager::new(config);
+        assert!(manager.add_listener(endpoint).await.is_err());
+    });
+}
+
+#[cfg(feature = "transport_tcp")]
+#[test]
+fn transport_factory_instrumented_tcp() {
+    task::block_on(async {
+        zasync_executor_init!();
+
+        let endpoint: EndPoint = "tcp/127.0.0.1:13447".parse().unwrap();
+
+        let router_id = PeerId::new(1, [0u8; PeerId::MAX_SIZE]);
+        let config = TransportManagerConfig::builder()
+            .whatami(whatami::ROUTER)
+            .pid(router_id)
+            .build(Arc::new(SHFactory::default()));
+        let router_manager = TransportManager::new(config);
+
+        let links = Arc::new(AtomicUsize::new(0));
+        let client_id = PeerId::new(1, [1u8; PeerId::MAX_SIZE]);
+        let config = TransportManagerConfig::builder()
+            .whatami(whatami::CLIENT)
+            .pid(client_id)
+            .transport(Arc::new(CountingFactory {
+                inner: DefaultTransportFactory::new(LocatorProtocol::Tcp),
+                links: links.clone(),
+            }))
+            .build(Arc::new(SHFactory::default()));
+        let client_manager = TransportManager::new(config);
+
+        router_manager.add_listener(endpoint.clone()).await.unwrap();
+        let transport = client_manager
+            .open_transport(endpoint.clone())
+            .timeout(TIMEOUT)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(transport.get_pid().unwrap(), router_id);
+        assert_eq!(links.load(Ordering::SeqCst), 1);
+
+        transport.close().timeout(TIMEOUT).await.unwrap().unwrap();
+        router_manager
+            .del_listener(&endpoint)
+            .timeout(TIMEOUT)
+            .await
+            .unwrap()
+            .unwrap();
+    });
+}
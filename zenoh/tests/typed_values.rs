@@ -0,0 +1,103 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::prelude::*;
+use async_std::task;
+use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
+use std::time::Duration;
+use zenoh::net::config::ZN_MULTICAST_SCOUTING_KEY;
+use zenoh::*;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Limits {
+    max: u32,
+    unit: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Storage {
+    kind: String,
+    paths: Vec<String>,
+    limits: Limits,
+    comment: Option<String>,
+}
+
+#[test]
+fn typed_values_roundtrip() {
+    task::block_on(async {
+        let mut config = net::config::peer();
+        config.insert(ZN_MULTICAST_SCOUTING_KEY, "false".to_string());
+        let zenoh = Zenoh::new(config).await.unwrap();
+        let workspace = zenoh.workspace(None).await.unwrap();
+        let path: Path = "/test/typed/storage".try_into().unwrap();
+        let storage = Storage {
+            kind: "memory".to_string(),
+            paths: vec!["/demo/**".to_string(), "/test/**".to_string()],
+            limits: Limits {
+                max: 10,
+                unit: Some("MB".to_string()),
+            },
+            comment: None,
+        };
+
+        // put: received by a subscriber as a Change
+        let mut changes = workspace
+            .subscribe(&"/test/typed/**".try_into().unwrap())
+            .await
+            .unwrap();
+        let value = Value::from_json_typed(&storage).unwrap();
+        assert_eq!(value.encoding_descr(), "application/json");
+        workspace.put(&path, value).await.unwrap();
+        let change = changes.next().timeout(TIMEOUT).await.unwrap().unwrap();
+        assert_eq!(change.deserialize::<Storage>().unwrap(), storage);
+
+        // get: replied by an eval as a Data
+        let mut eval = workspace
+            .register_eval(&"/test/typed/**".try_into().unwrap())
+            .await
+            .unwrap();
+        let reply = async {
+            let request = eval.next().await.unwrap();
+            request
+                .reply_async(path.clone(), change.value.unwrap())
+                .await;
+        };
+        let get = async {
+            workspace
+                .get(&"/test/typed/**".try_into().unwrap())
+                .await
+                .unwrap()
+                .collect::<Vec<Data>>()
+                .await
+        };
+        let (_, data) = reply.join(get).timeout(TIMEOUT).await.unwrap();
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].deserialize::<Storage>().unwrap(), storage);
+
+        // a non-JSON value is refused, whatever its content
+        let mut raw = data[0].clone();
+        raw.value = Value::Raw(
+            net::encoding::APP_OCTET_STREAM,
+            serde_json::to_vec(&storage).unwrap().into(),
+        );
+        assert!(raw.deserialize::<Storage>().is_err());
+
+        eval.close().await.unwrap();
+        changes.close().await.unwrap();
+        drop(workspace);
+        zenoh.close().await.unwrap();
+    });
+}
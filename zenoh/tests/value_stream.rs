@@ -0,0 +1,192 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::prelude::*;
+use async_std::task;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zenoh::net::config::ZN_MULTICAST_SCOUTING_KEY;
+use zenoh::*;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SIZE: usize = 10 * 1024 * 1024;
+
+type Stored = Arc<Mutex<HashMap<Path, Value>>>;
+
+// Replies to the gets with the stored values, until the other future raced with it completes
+async fn serve<T>(eval: &mut GetRequestStream<'_>, stored: &Stored) -> T {
+    while let Some(request) = eval.next().await {
+        let value = stored
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(path, _)| request.selector.matches(path))
+            .map(|(path, value)| (path.clone(), value.clone()));
+        if let Some((path, value)) = value {
+            request.reply_async(path, value).await;
+        }
+    }
+    futures::future::pending().await
+}
+
+#[test]
+fn value_stream_10mb() {
+    task::block_on(async {
+        let mut config = net::config::peer();
+        config.insert(ZN_MULTICAST_SCOUTING_KEY, "false".to_string());
+        let zenoh = Zenoh::new(config).await.unwrap();
+        let workspace = zenoh.workspace(None).await.unwrap();
+        let path: Path = "/test/stream/frame".try_into().unwrap();
+        let frame: Vec<u8> = (0..SIZE).map(|i| (i % 251) as u8).collect();
+
+        // a minimal storage of the values put on /test/stream/** (including the chunks)
+        let stored: Stored = Arc::new(Mutex::new(HashMap::new()));
+        let store = stored.clone();
+        let storage = workspace
+            .subscribe_with_callback(&"/test/stream/**".try_into().unwrap(), move |change| {
+                match change.value {
+                    Some(value) => store.lock().unwrap().insert(change.path, value),
+                    None => store.lock().unwrap().remove(&change.path),
+                };
+            })
+            .await
+            .unwrap();
+        let mut eval = workspace
+            .register_eval(&"/test/stream/**".try_into().unwrap())
+            .await
+            .unwrap();
+
+        let mut changes = workspace.subscribe(&Selector::from(&path)).await.unwrap();
+        // the manifest of a previous stream is looked up before putting the chunks
+        workspace
+            .put_stream(&path, "image/png", futures::io::Cursor::new(frame.clone()))
+            .race(serve(&mut eval, &stored))
+            .timeout(TIMEOUT)
+            .await
+            .unwrap()
+            .unwrap();
+        // the chunks are stored, each within the chunk size
+        assert!(stored.lock().unwrap().len() > SIZE / (64 * 1024));
+        assert!(stored
+            .lock()
+            .unwrap()
+            .values()
+            .all(|value| value.clone().encode().1.len() <= 64 * 1024 + 16));
+
+        // subscriber side: the manifest is received, and the chunks are fetched while reading
+        let change = changes.next().timeout(TIMEOUT).await.unwrap().unwrap();
+        let read = async {
+            let mut reader = change.value_reader(&workspace).unwrap();
+            assert!(reader.is_stream());
+            assert_eq!(reader.encoding_descr(), "image/png");
+            assert_eq!(reader.size(), SIZE as u64);
+            let mut received = vec![];
+            reader.read_to_end(&mut received).await.unwrap();
+            received
+        };
+        let received = read
+            .race(serve(&mut eval, &stored))
+            .timeout(TIMEOUT)
+            .await
+            .unwrap();
+        assert!(received == frame);
+
+        // get side
+        let read = async {
+            let data = workspace
+                .get(&Selector::from(&path))
+                .await
+                .unwrap()
+                .next()
+                .await
+                .unwrap();
+            let mut received = vec![];
+            data.value_reader(&workspace)
+                .unwrap()
+                .read_to_end(&mut received)
+                .await
+                .unwrap();
+            received
+        };
+        let received = read
+            .race(serve(&mut eval, &stored))
+            .timeout(TIMEOUT)
+            .await
+            .unwrap();
+        assert!(received == frame);
+
+        // a missing chunk fails the reading
+        let chunk: Path = "/test/stream/frame/@chunks/3".try_into().unwrap();
+        stored.lock().unwrap().remove(&chunk);
+        let read = async {
+            let mut received = vec![];
+            change
+                .value_reader(&workspace)
+                .unwrap()
+                .read_to_end(&mut received)
+                .await
+        };
+        assert!(read
+            .race(serve(&mut eval, &stored))
+            .timeout(TIMEOUT)
+            .await
+            .unwrap()
+            .is_err());
+
+        // a shorter stream put on the same path deletes the chunks of the previous stream beyond its own ones
+        let thumbnail = frame[..100 * 1024].to_vec();
+        workspace
+            .put_stream(
+                &path,
+                "image/png",
+                futures::io::Cursor::new(thumbnail.clone()),
+            )
+            .race(serve(&mut eval, &stored))
+            .timeout(TIMEOUT)
+            .await
+            .unwrap()
+            .unwrap();
+        let chunks = stored
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|path| path.as_str().starts_with("/test/stream/frame/@chunks/"))
+            .count();
+        assert_eq!(chunks, 2);
+        let change = changes.next().timeout(TIMEOUT).await.unwrap().unwrap();
+        let read = async {
+            let mut received = vec![];
+            change
+                .value_reader(&workspace)
+                .unwrap()
+                .read_to_end(&mut received)
+                .await
+                .unwrap();
+            received
+        };
+        let received = read
+            .race(serve(&mut eval, &stored))
+            .timeout(TIMEOUT)
+            .await
+            .unwrap();
+        assert!(received == thumbnail);
+
+        eval.close().await.unwrap();
+        changes.close().await.unwrap();
+        storage.close().await.unwrap();
+        drop(workspace);
+        zenoh.close().await.unwrap();
+    });
+}
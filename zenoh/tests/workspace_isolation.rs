@@ -0,0 +1,99 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::prelude::*;
+use async_std::task;
+use std::convert::TryInto;
+use std::time::Duration;
+use zenoh::net::config::ZN_MULTICAST_SCOUTING_KEY;
+use zenoh::*;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_millis(500);
+
+async fn next_change(changes: &mut ChangeReceiver<'_>) -> Change {
+    changes.next().timeout(TIMEOUT).await.unwrap().unwrap()
+}
+
+async fn assert_no_change(changes: &mut ChangeReceiver<'_>) {
+    assert!(changes.next().timeout(SLEEP).await.is_err());
+}
+
+#[test]
+fn workspace_isolation() {
+    task::block_on(async {
+        let mut config = net::config::peer();
+        config.insert(ZN_MULTICAST_SCOUTING_KEY, "false".to_string());
+        let zenoh = Zenoh::new(config).await.unwrap();
+
+        let ws1 = zenoh
+            .workspace(Some("/test/isolation/tenant1".try_into().unwrap()))
+            .await
+            .unwrap();
+        let ws2 = zenoh
+            .workspace(Some("/test/isolation/tenant2".try_into().unwrap()))
+            .await
+            .unwrap();
+        let mut changes1 = ws1.subscribe(&"**".try_into().unwrap()).await.unwrap();
+        let mut changes2 = ws2.subscribe(&"**".try_into().unwrap()).await.unwrap();
+
+        // Operate on both workspaces concurrently, with the same relative paths
+        let put1 = async {
+            for i in 0..10i64 {
+                ws1.put(&"a".try_into().unwrap(), i.into()).await.unwrap();
+            }
+        };
+        let put2 = async {
+            for i in 0..10i64 {
+                ws2.put(&"a".try_into().unwrap(), (i + 100).into())
+                    .await
+                    .unwrap();
+            }
+        };
+        put1.join(put2).await;
+
+        for i in 0..10i64 {
+            let change = next_change(&mut changes1).await;
+            assert_eq!(change.path.as_str(), "/test/isolation/tenant1/a");
+            assert!(matches!(change.value, Some(Value::Integer(v)) if v == i));
+            let change = next_change(&mut changes2).await;
+            assert_eq!(change.path.as_str(), "/test/isolation/tenant2/a");
+            assert!(matches!(change.value, Some(Value::Integer(v)) if v == i + 100));
+        }
+        assert_no_change(&mut changes1).await;
+        assert_no_change(&mut changes2).await;
+
+        // Closing a workspace undeclares its subscription only
+        ws1.close().await.unwrap();
+        ws2.put(
+            &"/test/isolation/tenant1/b".try_into().unwrap(),
+            1i64.into(),
+        )
+        .await
+        .unwrap();
+        ws2.put(&"b".try_into().unwrap(), 2i64.into())
+            .await
+            .unwrap();
+        let change = next_change(&mut changes2).await;
+        assert_eq!(change.path.as_str(), "/test/isolation/tenant2/b");
+        assert_no_change(&mut changes1).await;
+
+        // The subscription was already undeclared by the workspace's close
+        assert!(changes1.close().await.is_err());
+        changes2.close().await.unwrap();
+        ws2.close().await.unwrap();
+        drop(ws1);
+        drop(ws2);
+        zenoh.close().await.unwrap();
+    });
+}